@@ -3,6 +3,8 @@ use mlua::prelude::*;
 use std::path::Path;
 use std::process::Command;
 
+use crate::vfox::CmdHook;
+
 pub(crate) fn mod_cmd(lua: &Lua) -> LuaResult<()> {
     let package: Table = lua.globals().get("package")?;
     let loaded: Table = package.get("loaded")?;
@@ -84,6 +86,7 @@ fn exec(lua: &Lua, args: mlua::MultiValue) -> LuaResult<String> {
             // For now, just ignore the timeout option
         }
     }
+    apply_cmd_hook(lua, &mut cmd);
 
     let output = cmd
         .output()
@@ -119,6 +122,13 @@ fn apply_mise_env(lua: &Lua, cmd: &mut Command) -> LuaResult<bool> {
     }
 }
 
+/// Apply the hook registered with `Vfox::set_cmd_hook`, if any.
+fn apply_cmd_hook(lua: &Lua, cmd: &mut Command) {
+    if let Some(hook) = lua.app_data_ref::<CmdHook>() {
+        hook(cmd);
+    }
+}
+
 /// Drop-in replacement for Lua's `os.execute` that applies mise's sanitized env
 /// (see [`apply_mise_env`]) and runs through the same shell as cmd.exec, while
 /// keeping `os.execute`'s streaming stdio (output goes to the terminal rather
@@ -134,6 +144,7 @@ fn os_execute(lua: &Lua, command: Option<String>) -> LuaResult<i64> {
     let mut cmd = command_from_shell(&shell, &command)?;
     let has_mise_env = apply_mise_env(lua, &mut cmd)?;
     debug!("[os.execute] command={command:?} shell={shell:?} has_mise_env={has_mise_env}");
+    apply_cmd_hook(lua, &mut cmd);
     let status = cmd
         .status()
         .map_err(|e| mlua::Error::RuntimeError(format!("Failed to execute command: {e}")))?;
@@ -285,6 +296,27 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_cmd_hook_applies_to_exec_and_os_execute() {
+        let lua = Lua::new();
+        mod_cmd(&lua).unwrap();
+        let hook: CmdHook = std::sync::Arc::new(|cmd: &mut Command| {
+            cmd.env("MISE_CMD_HOOK_MARKER", "yes");
+        });
+        lua.set_app_data(hook);
+        lua.load(
+            r#"
+            local cmd = require("cmd")
+            assert(cmd.exec("echo $MISE_CMD_HOOK_MARKER") == "yes\n")
+            local ok = os.execute('[ "$MISE_CMD_HOOK_MARKER" = yes ]')
+            assert(ok == 0, "hook not applied to os.execute")
+        "#,
+        )
+        .exec()
+        .unwrap();
+    }
+
     #[test]
     fn test_cmd_windows_compatibility() {
         let lua = Lua::new();
@@ -16,7 +16,7 @@ use crate::http::HttpHeadersResolver;
 use crate::metadata::Metadata;
 use crate::runtime::Runtime;
 use crate::sdk_info::SdkInfo;
-use crate::vfox::UrlRewriter;
+use crate::vfox::{CmdHook, UrlRewriter};
 use crate::{VfoxError, config, error, lua_mod};
 
 #[derive(Debug)]
@@ -152,6 +152,11 @@ impl Plugin {
         Ok(())
     }
 
+    /// Register the hook applied to commands spawned by the Lua cmd module.
+    pub(crate) fn set_cmd_hook(&self, hook: CmdHook) {
+        self.lua.set_app_data(hook);
+    }
+
     /// Register the default HTTP headers resolver used by artifact downloads
     /// and the Lua HTTP module.
     pub(crate) fn set_http_headers_resolver(&self, resolver: HttpHeadersResolver) -> Result<()> {
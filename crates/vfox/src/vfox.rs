@@ -71,10 +71,12 @@ pub struct Vfox {
     pub runtime_env_type: Option<String>,
     url_rewriter: Option<UrlRewriter>,
     http_headers_resolver: Option<HttpHeadersResolver>,
+    cmd_hook: Option<CmdHook>,
     log_tx: Option<mpsc::Sender<String>>,
 }
 
 pub(crate) type UrlRewriter = Arc<dyn Fn(&mut Url) + Send + Sync>;
+pub(crate) type CmdHook = Arc<dyn Fn(&mut std::process::Command) + Send + Sync>;
 
 impl std::fmt::Debug for Vfox {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -100,6 +102,7 @@ impl std::fmt::Debug for Vfox {
                 "http_headers_resolver",
                 &self.http_headers_resolver.as_ref().map(|_| "<closure>"),
             )
+            .field("cmd_hook", &self.cmd_hook.as_ref().map(|_| "<closure>"))
             .finish_non_exhaustive()
     }
 }
@@ -129,6 +132,15 @@ impl Vfox {
         self.http_headers_resolver = Some(Arc::new(resolver));
     }
 
+    /// Set a hook that is called on every command spawned by Lua `cmd.exec()` and
+    /// `os.execute()` before it runs, e.g. to sandbox it.
+    pub fn set_cmd_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&mut std::process::Command) + Send + Sync + 'static,
+    {
+        self.cmd_hook = Some(Arc::new(hook));
+    }
+
     fn rewrite_url(&self, url: &mut Url) {
         if let Some(rewriter) = &self.url_rewriter {
             rewriter(url);
@@ -198,6 +210,9 @@ impl Vfox {
         if let Some(resolver) = &self.http_headers_resolver {
             plugin.set_http_headers_resolver(resolver.clone())?;
         }
+        if let Some(hook) = &self.cmd_hook {
+            plugin.set_cmd_hook(hook.clone());
+        }
         Ok(plugin)
     }
 
@@ -842,6 +857,7 @@ impl Default for Vfox {
             runtime_env_type: None,
             url_rewriter: None,
             http_headers_resolver: None,
+            cmd_hook: None,
             log_tx: None,
         }
     }
@@ -892,6 +908,7 @@ mod tests {
                 runtime_env_type: None,
                 url_rewriter: None,
                 http_headers_resolver: None,
+                cmd_hook: None,
                 log_tx: None,
             }
         }
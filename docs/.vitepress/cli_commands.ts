@@ -14,6 +14,17 @@ export const commands: { [key: string]: Command } = {
   asdf: {
     hide: true,
   },
+  audit: {
+    hide: false,
+    subcommands: {
+      scan: {
+        hide: false,
+      },
+      update: {
+        hide: false,
+      },
+    },
+  },
   backends: {
     hide: false,
     subcommands: {
@@ -57,6 +68,17 @@ export const commands: { [key: string]: Command } = {
           },
         },
       },
+      certificates: {
+        hide: false,
+        subcommands: {
+          apply: {
+            hide: false,
+          },
+          status: {
+            hide: false,
+          },
+        },
+      },
       compose: {
         hide: false,
         subcommands: {
@@ -124,6 +146,39 @@ export const commands: { [key: string]: Command } = {
       linux: {
         hide: false,
         subcommands: {
+          environment: {
+            hide: false,
+            subcommands: {
+              apply: {
+                hide: false,
+              },
+              status: {
+                hide: false,
+              },
+            },
+          },
+          "kernel-modules": {
+            hide: false,
+            subcommands: {
+              apply: {
+                hide: false,
+              },
+              status: {
+                hide: false,
+              },
+            },
+          },
+          sysctl: {
+            hide: false,
+            subcommands: {
+              apply: {
+                hide: false,
+              },
+              status: {
+                hide: false,
+              },
+            },
+          },
           "systemd-units": {
             hide: false,
             subcommands: {
@@ -254,6 +309,9 @@ export const commands: { [key: string]: Command } = {
           },
         },
       },
+      rollback: {
+        hide: false,
+      },
       secrets: {
         hide: false,
         subcommands: {
@@ -268,7 +326,23 @@ export const commands: { [key: string]: Command } = {
           apply: {
             hide: false,
           },
-          status: {
+          ssh: {
+        hide: false,
+        subcommands: {
+          "known-hosts": {
+            hide: false,
+            subcommands: {
+              apply: {
+                hide: false,
+              },
+              status: {
+                hide: false,
+              },
+            },
+          },
+        },
+      },
+      status: {
             hide: false,
           },
         },
@@ -298,6 +372,9 @@ export const commands: { [key: string]: Command } = {
           },
         },
       },
+      watch: {
+        hide: false,
+      },
     },
   },
   cache: {
@@ -417,6 +494,9 @@ export const commands: { [key: string]: Command } = {
       bootstrap: {
         hide: true,
       },
+      ci: {
+        hide: false,
+      },
       config: {
         hide: false,
       },
@@ -533,6 +613,14 @@ export const commands: { [key: string]: Command } = {
       },
     },
   },
+  policy: {
+    hide: false,
+    subcommands: {
+      check: {
+        hide: false,
+      },
+    },
+  },
   prune: {
     hide: false,
   },
@@ -629,15 +717,24 @@ export const commands: { [key: string]: Command } = {
       graph: {
         hide: false,
       },
+      history: {
+        hide: false,
+      },
       info: {
         hide: false,
       },
+      logs: {
+        hide: false,
+      },
       ls: {
         hide: false,
       },
       run: {
         hide: false,
       },
+      "update-includes": {
+        hide: false,
+      },
       validate: {
         hide: false,
       },
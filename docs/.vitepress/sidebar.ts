@@ -80,16 +80,20 @@ export const sidebar: SidebarItem[] = [
         items: [
           { text: "aqua", link: "/dev-tools/backends/aqua" },
           { text: "asdf", link: "/dev-tools/backends/asdf" },
+          { text: "brew", link: "/dev-tools/backends/brew" },
           { text: "cargo", link: "/dev-tools/backends/cargo" },
           { text: "conda", link: "/dev-tools/backends/conda" },
           { text: "dotnet", link: "/dev-tools/backends/dotnet" },
           { text: "forgejo", link: "/dev-tools/backends/forgejo" },
           { text: "gem", link: "/dev-tools/backends/gem" },
+          { text: "git", link: "/dev-tools/backends/git" },
           { text: "github", link: "/dev-tools/backends/github" },
           { text: "gitlab", link: "/dev-tools/backends/gitlab" },
           { text: "go", link: "/dev-tools/backends/go" },
           { text: "http", link: "/dev-tools/backends/http" },
+          { text: "maven", link: "/dev-tools/backends/maven" },
           { text: "npm", link: "/dev-tools/backends/npm" },
+          { text: "oci", link: "/dev-tools/backends/oci" },
           { text: "pipx", link: "/dev-tools/backends/pipx" },
           { text: "pkgx", link: "/dev-tools/backends/pkgx" },
           { text: "spm", link: "/dev-tools/backends/spm" },
@@ -168,6 +172,14 @@ export const sidebar: SidebarItem[] = [
         text: "systemd",
         link: "/bootstrap/systemd",
       },
+      {
+        text: "Kernel and /etc/environment",
+        link: "/bootstrap/kernel",
+      },
+      {
+        text: "Certificates and SSH Known Hosts",
+        link: "/bootstrap/trust",
+      },
       {
         text: "User Login Shell",
         link: "/bootstrap/user",
@@ -269,6 +281,7 @@ export const sidebar: SidebarItem[] = [
     items: [
       { text: "Architecture", link: "/architecture" },
       { text: "Paranoid", link: "/paranoid" },
+      { text: "Tool Policy", link: "/tool-policy" },
       { text: "Templates", link: "/templates" },
       { text: "URL Replacements", link: "/url-replacements" },
       { text: "Model Context Protocol", link: "/mcp" },
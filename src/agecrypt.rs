@@ -20,22 +20,7 @@ pub(crate) async fn create_age_directive(
     value: &str,
     recipients: &[Box<dyn Recipient + Send>],
 ) -> Result<EnvDirective> {
-    if recipients.is_empty() {
-        return Err(eyre!(
-            "[experimental] No age recipients provided for encryption"
-        ));
-    }
-
-    let encryptor =
-        match Encryptor::with_recipients(recipients.iter().map(|r| r.as_ref() as &dyn Recipient)) {
-            Ok(encryptor) => encryptor,
-            Err(e) => return Err(eyre!("[experimental] Failed to create encryptor: {}", e)),
-        };
-
-    let mut encrypted = Vec::new();
-    let mut writer = encryptor.wrap_output(&mut encrypted)?;
-    writer.write_all(value.as_bytes())?;
-    writer.finish()?;
+    let encrypted = encrypt_bytes(value.as_bytes(), recipients)?;
 
     // Determine format based on size and compression
     let (encoded, format) = if encrypted.len() > COMPRESSION_THRESHOLD {
@@ -69,35 +54,72 @@ pub(crate) async fn decrypt_age_directive(directive: &EnvDirective) -> Result<St
                 Some(AgeFormat::Raw) | None => decoded,
             };
 
-            let identities = load_all_identities().await?;
-            if identities.is_empty() {
-                return Err(eyre!(
-                    "[experimental] No age identities found for decryption"
-                ));
-            }
+            let decrypted = decrypt_bytes(&ciphertext)?;
+            String::from_utf8(decrypted)
+                .wrap_err("[experimental] Decrypted value is not valid UTF-8")
+        }
+        _ => Err(eyre!("[experimental] Not an Age directive")),
+    }
+}
 
-            let decryptor = Decryptor::new(&ciphertext[..])?;
-            let mut decrypted = Vec::new();
+/// Encrypt `plaintext` to `recipients` in the binary age format.
+pub(crate) fn encrypt_bytes(
+    plaintext: &[u8],
+    recipients: &[Box<dyn Recipient + Send>],
+) -> Result<Vec<u8>> {
+    if recipients.is_empty() {
+        return Err(eyre!(
+            "[experimental] No age recipients provided for encryption"
+        ));
+    }
 
-            let identity_refs: Vec<&dyn Identity> = identities
-                .iter()
-                .map(|i| i.as_ref() as &dyn Identity)
-                .collect();
+    let encryptor =
+        match Encryptor::with_recipients(recipients.iter().map(|r| r.as_ref() as &dyn Recipient)) {
+            Ok(encryptor) => encryptor,
+            Err(e) => return Err(eyre!("[experimental] Failed to create encryptor: {}", e)),
+        };
 
-            match decryptor.decrypt(identity_refs.into_iter()) {
-                Ok(mut reader) => {
-                    reader.read_to_end(&mut decrypted)?;
-                }
-                Err(e) => {
-                    return Err(eyre!("[experimental] Failed to decrypt: {}", e));
-                }
-            }
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor.wrap_output(&mut encrypted)?;
+    writer.write_all(plaintext)?;
+    writer.finish()?;
+    Ok(encrypted)
+}
 
-            String::from_utf8(decrypted)
-                .wrap_err("[experimental] Decrypted value is not valid UTF-8")
+/// Decrypt age ciphertext with the configured identities.
+pub(crate) fn decrypt_bytes(ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let identities = load_all_identities()?;
+    if identities.is_empty() {
+        return Err(eyre!(
+            "[experimental] No age identities found for decryption"
+        ));
+    }
+
+    let decryptor = Decryptor::new(ciphertext)?;
+    let mut decrypted = Vec::new();
+
+    let identity_refs: Vec<&dyn Identity> = identities
+        .iter()
+        .map(|i| i.as_ref() as &dyn Identity)
+        .collect();
+
+    match decryptor.decrypt(identity_refs.into_iter()) {
+        Ok(mut reader) => {
+            reader.read_to_end(&mut decrypted)?;
+        }
+        Err(e) => {
+            return Err(eyre!("[experimental] Failed to decrypt: {}", e));
         }
-        _ => Err(eyre!("[experimental] Not an Age directive")),
     }
+    Ok(decrypted)
+}
+
+/// Decrypt an age-encrypted file into memory; the plaintext is never written
+/// anywhere by this function.
+pub(crate) fn decrypt_file(path: &Path) -> Result<Vec<u8>> {
+    Settings::get().ensure_experimental("age encryption")?;
+    decrypt_bytes(&file::read(path)?)
+        .wrap_err_with(|| format!("[experimental] Failed to decrypt {}", path.display()))
 }
 
 pub(crate) async fn load_recipients_from_defaults() -> Result<Vec<Box<dyn Recipient + Send>>> {
@@ -252,9 +274,9 @@ async fn load_ssh_recipient_from_private_key(path: &Path) -> Result<String> {
     ))
 }
 
-async fn load_all_identities() -> Result<Vec<Box<dyn Identity + Send + Sync>>> {
+fn load_all_identities() -> Result<Vec<Box<dyn Identity + Send + Sync>>> {
     // Get identity files first
-    let identity_files = get_all_identity_files().await;
+    let identity_files = get_all_identity_files();
     let ssh_identity_files = get_all_ssh_identity_files();
 
     // Now process identities without holding them across await points
@@ -352,7 +374,7 @@ async fn get_default_key_file() -> Option<PathBuf> {
         })
 }
 
-async fn get_all_identity_files() -> Vec<PathBuf> {
+fn get_all_identity_files() -> Vec<PathBuf> {
     let mut files = Vec::new();
 
     if let Some(ref identity_files) = Settings::get().age.identity_files {
@@ -472,6 +494,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_age_bytes_round_trip() -> Result<()> {
+        let key = age::x25519::Identity::generate();
+        let recipients: Vec<Box<dyn Recipient + Send>> = vec![Box::new(key.to_public())];
+        let plaintext = b"machine example.com login me password secret\n";
+        let encrypted = encrypt_bytes(plaintext, &recipients)?;
+        assert!(!encrypted.windows(6).any(|w| w == b"secret"));
+
+        use age::secrecy::ExposeSecret;
+        env::set_var("MISE_AGE_KEY", key.to_string().expose_secret());
+        let decrypted = decrypt_bytes(&encrypted);
+        env::remove_var("MISE_AGE_KEY");
+
+        assert_eq!(decrypted?, plaintext);
+        Ok(())
+    }
+
     #[test]
     fn test_parse_recipient() -> Result<()> {
         let age_recipient = "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p";
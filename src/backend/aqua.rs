@@ -200,6 +200,25 @@ impl Backend for AquaBackend {
         count
     }
 
+    async fn release_notes(
+        &self,
+        from: Option<&str>,
+        to: &str,
+    ) -> Result<Vec<backend::release_notes::ReleaseNote>> {
+        let pkg = AQUA_REGISTRY.package(&self.id).await?;
+        if pkg.repo_owner.is_empty() || pkg.repo_name.is_empty() {
+            return Ok(vec![]);
+        }
+        let repo = format!("{}/{}", pkg.repo_owner, pkg.repo_name);
+        let notes = backend::release_notes::github(github::API_URL, &repo).await?;
+        Ok(backend::release_notes::between(
+            notes,
+            from,
+            to,
+            pkg.version_prefix.as_deref(),
+        ))
+    }
+
     async fn security_info(&self) -> Vec<crate::backend::SecurityFeature> {
         use crate::backend::SecurityFeature;
 
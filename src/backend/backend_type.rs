@@ -16,16 +16,20 @@ use std::fmt::{Display, Formatter};
 pub(crate) enum BackendType {
     Aqua,
     Asdf,
+    Brew,
     Cargo,
     Conda,
     Core,
     Dotnet,
     Forgejo,
     Gem,
+    Git,
     Github,
     Gitlab,
     Go,
+    Maven,
     Npm,
+    Oci,
     Pipx,
     Pkgx,
     Spm,
@@ -61,16 +65,20 @@ impl BackendType {
         match prefix {
             "aqua" => BackendType::Aqua,
             "asdf" => BackendType::Asdf,
+            "brew" => BackendType::Brew,
             "cargo" => BackendType::Cargo,
             "conda" => BackendType::Conda,
             "core" => BackendType::Core,
             "dotnet" => BackendType::Dotnet,
             "forgejo" => BackendType::Forgejo,
             "gem" => BackendType::Gem,
+            "git" => BackendType::Git,
             "github" => BackendType::Github,
             "gitlab" => BackendType::Gitlab,
             "go" => BackendType::Go,
+            "maven" => BackendType::Maven,
             "npm" => BackendType::Npm,
+            "oci" => BackendType::Oci,
             "pipx" => BackendType::Pipx,
             "pkgx" => BackendType::Pkgx,
             "spm" => BackendType::Spm,
@@ -84,9 +92,13 @@ impl BackendType {
 
     /// Returns true if this backend is still gated behind experimental mode.
     pub(crate) fn is_experimental(&self) -> bool {
-        use super::{dotnet, pkgx, s3, spm};
+        use super::{brew, dotnet, git, maven, oci, pkgx, s3, spm};
         match self {
+            BackendType::Brew => brew::EXPERIMENTAL,
             BackendType::Dotnet => dotnet::EXPERIMENTAL,
+            BackendType::Git => git::EXPERIMENTAL,
+            BackendType::Maven => maven::EXPERIMENTAL,
+            BackendType::Oci => oci::EXPERIMENTAL,
             BackendType::Pkgx => pkgx::EXPERIMENTAL,
             BackendType::S3 => s3::EXPERIMENTAL,
             BackendType::Spm => spm::EXPERIMENTAL,
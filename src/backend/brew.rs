@@ -0,0 +1,187 @@
+//! Homebrew backend for mise - installs formula bottles as per-project tools
+//!
+//! Unlike `[bootstrap.packages]` brew entries, which pour into the global
+//! Homebrew prefix, each tool version here is poured into its own install
+//! directory together with its runtime dependencies, so projects can pin
+//! different versions without a Homebrew installation.
+//!
+//! ## Configuration
+//!
+//! ```toml
+//! [tools]
+//! "brew:libpq" = "17"
+//!
+//! # versions of a formula's versioned siblings resolve to that formula
+//! "brew:postgresql" = "16"
+//! ```
+//!
+//! Bottles are the only source: Homebrew publishes one per formula for its
+//! current version, so the installable versions are the current versions of
+//! the formula and of its `@`-versioned formulae.
+
+pub(crate) const EXPERIMENTAL: bool = true;
+
+use crate::backend::backend_type::BackendType;
+use crate::backend::platform_target::PlatformTarget;
+use crate::backend::{Backend, VersionInfo};
+use crate::cli::args::BackendArg;
+use crate::config::{Config, Settings};
+use crate::install_context::InstallContext;
+use crate::lockfile::PlatformInfo;
+#[cfg(unix)]
+use crate::system::packages::brew;
+use crate::toolset::ToolVersion;
+use async_trait::async_trait;
+use eyre::Result;
+use itertools::Itertools;
+use std::path::PathBuf;
+use std::sync::Arc;
+use versions::Versioning;
+
+#[derive(Debug)]
+pub(crate) struct BrewBackend {
+    ba: Arc<BackendArg>,
+}
+
+impl BrewBackend {
+    pub(crate) fn from_arg(ba: BackendArg) -> Self {
+        Self { ba: Arc::new(ba) }
+    }
+
+    fn formula(&self) -> &str {
+        &self.ba.tool_name
+    }
+}
+
+#[cfg(not(unix))]
+mod brew {
+    //! Bottles and their relocation code are unix-only.
+    use super::*;
+    use crate::ui::progress_report::SingleReport;
+    use eyre::bail;
+    use std::path::Path;
+
+    pub(crate) struct ToolBottle {
+        pub url: String,
+        pub sha256: String,
+    }
+
+    fn unsupported<T>() -> Result<T> {
+        bail!("the brew backend is not supported on Windows")
+    }
+
+    pub(crate) async fn tool_versions(_name: &str) -> Result<Vec<String>> {
+        unsupported()
+    }
+
+    pub(crate) async fn tool_bottle(
+        _name: &str,
+        _version: &str,
+        _os: &str,
+        _arch: &str,
+        _host: bool,
+    ) -> Result<ToolBottle> {
+        unsupported()
+    }
+
+    pub(crate) async fn install_tool(
+        _name: &str,
+        _version: &str,
+        _prefix: &Path,
+        _locked: Option<&str>,
+        _pr: &dyn SingleReport,
+    ) -> Result<ToolBottle> {
+        unsupported()
+    }
+}
+
+#[async_trait]
+impl Backend for BrewBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Brew
+    }
+
+    fn ba(&self) -> &Arc<BackendArg> {
+        &self.ba
+    }
+
+    async fn _list_remote_versions(&self, _config: &Arc<Config>) -> Result<Vec<VersionInfo>> {
+        Ok(brew::tool_versions(self.formula())
+            .await?
+            .into_iter()
+            .sorted_by_cached_key(|v| Versioning::new(v))
+            .map(|version| VersionInfo {
+                version,
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn resolve_lock_info(
+        &self,
+        tv: &ToolVersion,
+        target: &PlatformTarget,
+    ) -> Result<PlatformInfo> {
+        let bottle = brew::tool_bottle(
+            self.formula(),
+            &tv.version,
+            target.os_name(),
+            target.arch_name(),
+            target.is_current(),
+        )
+        .await?;
+        Ok(PlatformInfo {
+            url: Some(bottle.url),
+            checksum: Some(format!("sha256:{}", bottle.sha256)),
+            ..Default::default()
+        })
+    }
+
+    async fn install_version_(
+        &self,
+        ctx: &InstallContext,
+        mut tv: ToolVersion,
+    ) -> Result<ToolVersion> {
+        ctx.pr
+            .set_message(format!("resolve {}@{}", self.formula(), tv.version));
+        let platform_key = self.get_platform_key();
+        // the locked bottle is picked and verified before anything is poured
+        let locked = tv
+            .lock_platforms
+            .get(&platform_key)
+            .and_then(|p| p.checksum.as_deref())
+            .map(|checksum| {
+                checksum
+                    .strip_prefix("sha256:")
+                    .unwrap_or(checksum)
+                    .to_string()
+            });
+        let bottle = brew::install_tool(
+            self.formula(),
+            &tv.version,
+            &tv.install_path(),
+            locked.as_deref(),
+            ctx.pr.as_ref(),
+        )
+        .await?;
+
+        let platform_info = tv.lock_platforms.entry(platform_key).or_default();
+        platform_info.url = Some(bottle.url);
+        if Settings::get().lockfile_enabled() {
+            platform_info.checksum = Some(format!("sha256:{}", bottle.sha256));
+        }
+        Ok(tv)
+    }
+
+    async fn list_bin_paths(
+        &self,
+        _config: &Arc<Config>,
+        tv: &ToolVersion,
+    ) -> Result<Vec<PathBuf>> {
+        Ok(["bin", "sbin"]
+            .into_iter()
+            .map(|dir| tv.install_path().join(dir))
+            .filter(|dir| dir.exists())
+            .collect())
+    }
+}
@@ -0,0 +1,326 @@
+//! Git backend for mise - builds tools from source with a declared recipe
+//!
+//! For tools that publish no release binaries: versions are the repository's
+//! tags, each install clones the tag's commit and runs the `build` recipe
+//! with the `build_tools` toolchain on PATH. The lockfile pins the commit the
+//! tag resolved to.
+//!
+//! ## Configuration
+//!
+//! ```toml
+//! [tools]
+//! "git:https://git.corp/tools/foo" = { version = "v2.1.0", build = "make install PREFIX={{install_path}}", build_tools = ["go@1.22"] }
+//! ```
+
+pub(crate) const EXPERIMENTAL: bool = true;
+
+use crate::backend::backend_type::BackendType;
+use crate::backend::options::BackendOptions;
+use crate::backend::platform_target::PlatformTarget;
+use crate::backend::static_helpers::template_string;
+use crate::backend::{Backend, VersionInfo, runtime_path_for_install_path};
+use crate::cli::args::{BackendArg, ToolArg};
+use crate::cmd::CmdLineRunner;
+use crate::config::{Config, Settings};
+use crate::env;
+use crate::file;
+use crate::git::{CloneOptions, Git, looks_like_sha, tag_commits};
+use crate::install_context::InstallContext;
+use crate::lockfile::PlatformInfo;
+use crate::toolset::{
+    InstallOptions, ToolRequest, ToolSource, ToolVersion, ToolVersionOptions, Toolset,
+};
+use async_trait::async_trait;
+use eyre::{Result, bail, eyre};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use versions::Versioning;
+use xx::regex;
+
+#[derive(Debug)]
+pub(crate) struct GitBackend {
+    ba: Arc<BackendArg>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct GitOptions<'a> {
+    values: BackendOptions<'a>,
+}
+
+impl<'a> GitOptions<'a> {
+    fn new(raw: &'a ToolVersionOptions) -> Self {
+        Self {
+            values: BackendOptions::new(raw),
+        }
+    }
+
+    fn build(&self) -> Option<String> {
+        self.values.platform_string("build")
+    }
+
+    /// `tool@version` specs, from an array or a comma-separated string.
+    fn build_tools(&self) -> Vec<String> {
+        self.values
+            .comma_joined("build_tools")
+            .map(|tools| {
+                tools
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tool| !tool.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn bin_path(&self) -> Option<String> {
+        self.values.platform_string("bin_path")
+    }
+}
+
+impl GitBackend {
+    pub(crate) fn from_arg(ba: BackendArg) -> Self {
+        Self { ba: Arc::new(ba) }
+    }
+
+    /// The repository URL, e.g. `https://git.corp/tools/foo`.
+    fn repository(&self) -> &str {
+        &self.ba.tool_name
+    }
+
+    /// Commit of each version tag in the repository.
+    fn tags(&self) -> Result<BTreeMap<String, String>> {
+        let refs = Git::ls_remote(self.repository(), &["refs/tags/*"])?;
+        Ok(tag_commits(&refs))
+    }
+
+    /// The commit recorded in the lockfile for this version, if it belongs to
+    /// this repository.
+    fn locked_commit(&self, tv: &ToolVersion) -> Option<String> {
+        let url = tv
+            .lock_platforms
+            .get(&self.get_platform_key())?
+            .url
+            .as_deref()?;
+        let (repo, commit) = Git::split_url_and_ref(url);
+        (repo == self.repository())
+            .then_some(commit)
+            .flatten()
+            .filter(|commit| looks_like_sha(commit))
+    }
+
+    /// Environment for the build: the tool's dependency env with each
+    /// `build_tools` entry installed and activated on top of it. Returns the
+    /// env and the PATH entries to prepend.
+    async fn build_env(
+        &self,
+        ctx: &InstallContext,
+        opts: &GitOptions<'_>,
+    ) -> Result<(BTreeMap<String, String>, Vec<PathBuf>)> {
+        let mut build_env = self.dependency_env(&ctx.config).await?;
+        let specs = opts.build_tools();
+        if specs.is_empty() {
+            return Ok((build_env, vec![]));
+        }
+        let mut ts = Toolset::new(ToolSource::Argument);
+        for spec in &specs {
+            let arg = ToolArg::from_str(spec)?;
+            let tvr = match arg.tvr {
+                Some(tvr) => tvr,
+                None => ToolRequest::new(arg.ba.clone(), "latest", ToolSource::Argument)?,
+            };
+            ts.add_version(tvr);
+        }
+        ctx.pr
+            .set_message(format!("install build tools {}", specs.join(" ")));
+        let mut config = ctx.config.clone();
+        ts.resolve(&config).await?;
+        ts.install_missing_versions(&mut config, &InstallOptions::default())
+            .await?;
+        let (tool_env, mut paths) = ts.env(&config).await?;
+        build_env.extend(tool_env);
+        paths.extend(ts.list_paths(&config).await);
+        Ok((build_env, paths))
+    }
+}
+
+/// Returns install-time-only option keys for the git backend.
+pub(crate) fn install_time_option_keys() -> Vec<String> {
+    vec!["build".into(), "build_tools".into(), "bin_path".into()]
+}
+
+/// Render a build recipe: `{{ version }}` and `{{ install_path }}` on top of
+/// the usual template functions.
+fn render_build(recipe: &str, tv: &ToolVersion) -> Result<String> {
+    let mut tera = crate::tera::get_tera(None);
+    let mut ctx = crate::tera::BASE_CONTEXT.clone();
+    ctx.insert("version", &tv.version);
+    ctx.insert(
+        "install_path",
+        &tv.install_path().to_string_lossy().to_string(),
+    );
+    crate::tera::render_str(&mut tera, recipe, &ctx)
+        .map_err(|err| eyre!("failed to render build recipe '{recipe}': {err}"))
+}
+
+#[async_trait]
+impl Backend for GitBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Git
+    }
+
+    fn ba(&self) -> &Arc<BackendArg> {
+        &self.ba
+    }
+
+    fn mark_prereleases_from_version_pattern(&self) -> bool {
+        true
+    }
+
+    async fn _list_remote_versions(&self, _config: &Arc<Config>) -> Result<Vec<VersionInfo>> {
+        Ok(self
+            .tags()?
+            .into_keys()
+            // skip tags like `nightly` or `release-candidate`
+            .filter(|tag| regex!(r"^v?[0-9]").is_match(tag))
+            .sorted_by_cached_key(|tag| Versioning::new(tag))
+            .map(|version| VersionInfo {
+                version,
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn resolve_lock_info(
+        &self,
+        tv: &ToolVersion,
+        _target: &PlatformTarget,
+    ) -> Result<PlatformInfo> {
+        // a commit is the same for every platform
+        let commit = self
+            .tags()?
+            .remove(&tv.version)
+            .ok_or_else(|| eyre!("tag {} not found in {}", tv.version, self.repository()))?;
+        Ok(PlatformInfo {
+            url: Some(format!("{}#{commit}", self.repository())),
+            ..Default::default()
+        })
+    }
+
+    async fn install_version_(
+        &self,
+        ctx: &InstallContext,
+        mut tv: ToolVersion,
+    ) -> Result<ToolVersion> {
+        let raw_opts = tv.request.options();
+        let opts = GitOptions::new(&raw_opts);
+        let Some(recipe) = opts.build() else {
+            bail!(
+                "{}: the git backend needs a `build` recipe, e.g. {{ version = \"{}\", build = \"make install PREFIX={{{{install_path}}}}\" }}",
+                self.ba.short,
+                tv.version
+            );
+        };
+
+        // A locked commit wins over the tag, which may have moved.
+        let revision = self
+            .locked_commit(&tv)
+            .unwrap_or_else(|| tv.version.clone());
+        let src = tv.download_path().join("src");
+        if src.exists() {
+            file::remove_all(&src)?;
+        }
+        ctx.pr
+            .set_message(format!("clone {}@{revision}", self.repository()));
+        let git = Git::new(&src);
+        git.clone(
+            self.repository(),
+            CloneOptions::default().revision(&revision),
+        )?;
+        git.update_submodules()?;
+        let commit = git.current_sha()?;
+        tv.lock_platforms
+            .entry(self.get_platform_key())
+            .or_default()
+            .url = Some(format!("{}#{commit}", self.repository()));
+
+        ctx.pr.next_operation();
+        let (build_env, paths) = self.build_env(ctx, &opts).await?;
+        let script = render_build(&recipe, &tv)?;
+        file::create_dir_all(tv.install_path())?;
+        ctx.pr.set_message(format!("build {}", &commit[..12]));
+        let shell = Settings::get().default_inline_shell()?;
+        let (program, shell_args) = shell.split_first().ok_or_else(|| {
+            eyre!(
+                "default inline shell is empty; check unix_default_inline_shell_args / windows_default_inline_shell_args"
+            )
+        })?;
+        CmdLineRunner::new(program)
+            .current_dir(&src)
+            .envs(build_env)
+            .env_values(tv.install_env())
+            .env("MISE_TOOL_INSTALL_PATH", tv.install_path())
+            .env(env::MISE_TOOL_VERSION_ENV_VAR, tv.version.clone())
+            .prepend_path(paths)?
+            .with_pr(ctx.pr.as_ref())
+            .cmd_body_args(shell_args, &script)
+            .execute()?;
+
+        Ok(tv)
+    }
+
+    async fn list_bin_paths(
+        &self,
+        _config: &Arc<Config>,
+        tv: &ToolVersion,
+    ) -> Result<Vec<PathBuf>> {
+        let raw_opts = tv.request.options();
+        let opts = GitOptions::new(&raw_opts);
+        if let Some(bin_path_template) = opts.bin_path() {
+            let bin_path = template_string(&bin_path_template, tv);
+            return Ok(vec![runtime_path_for_install_path(
+                tv,
+                tv.install_path().join(bin_path),
+            )]);
+        }
+        let bin_dir = tv.install_path().join("bin");
+        if bin_dir.exists() {
+            return Ok(vec![runtime_path_for_install_path(tv, bin_dir)]);
+        }
+        Ok(vec![tv.runtime_path()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(pairs: &[(&str, toml::Value)]) -> ToolVersionOptions {
+        let mut opts = ToolVersionOptions::default();
+        for (key, value) in pairs {
+            opts.opts.insert(key.to_string(), value.clone());
+        }
+        opts
+    }
+
+    #[test]
+    fn test_build_tools() {
+        let array = options(&[(
+            "build_tools",
+            toml::Value::Array(vec!["go@1.22".into(), "node@22".into()]),
+        )]);
+        assert_eq!(
+            GitOptions::new(&array).build_tools(),
+            vec!["go@1.22", "node@22"]
+        );
+        let string = options(&[("build_tools", "go@1.22, zig".into())]);
+        assert_eq!(
+            GitOptions::new(&string).build_tools(),
+            vec!["go@1.22", "zig"]
+        );
+        assert!(GitOptions::new(&options(&[])).build_tools().is_empty());
+    }
+}
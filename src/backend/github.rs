@@ -8,7 +8,7 @@ use crate::backend::static_helpers::{
     template_string, try_with_v_prefix, try_with_v_prefix_and_repo, verify_artifact,
 };
 use crate::backend::{
-    MISE_BINS_DIR, SecurityFeature, backend_arg_matches_registry_backend,
+    MISE_BINS_DIR, SecurityFeature, backend_arg_matches_registry_backend, release_notes,
     runtime_path_for_install_path,
 };
 use crate::cli::args::{BackendArg, ToolVersionType};
@@ -376,6 +376,30 @@ impl Backend for UnifiedGitBackend {
             * 3
     }
 
+    async fn release_notes(
+        &self,
+        from: Option<&str>,
+        to: &str,
+    ) -> Result<Vec<release_notes::ReleaseNote>> {
+        let repo = self.repo();
+        let raw_opts = self.ba.opts();
+        let opts = self.options(&raw_opts);
+        let api_url = opts.api_url();
+        let notes = if self.is_gitlab() {
+            release_notes::gitlab(&api_url, &repo).await?
+        } else if self.is_forgejo() {
+            release_notes::forgejo(&api_url, &repo).await?
+        } else {
+            release_notes::github(&api_url, &repo).await?
+        };
+        Ok(release_notes::between(
+            notes,
+            from,
+            to,
+            opts.version_prefix(),
+        ))
+    }
+
     async fn security_info(&self) -> Vec<SecurityFeature> {
         // Only report security features for GitHub (not GitLab yet)
         if self.is_gitlab() || self.is_forgejo() {
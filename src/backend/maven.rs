@@ -0,0 +1,388 @@
+//! Maven backend for mise - installs JVM command-line tools from Maven repositories
+//!
+//! The tool is a `group:artifact` coordinate. Versions come from the
+//! artifact's `maven-metadata.xml`; installing resolves the runtime dependency
+//! closure from the POMs, downloads every jar into `lib/` (each checked
+//! against its published `.sha1`), and writes a launcher to `bin/` that runs
+//! the main class with the mise-managed `java`.
+//!
+//! ## Configuration
+//!
+//! ```toml
+//! [tools]
+//! java = "21"
+//! "maven:com.pinterest.ktlint:ktlint-cli" = "1.3"
+//!
+//! # Jars without a Main-Class manifest entry, or an internal repository
+//! "maven:com.corp:deployer" = { version = "1.4", main_class = "com.corp.deployer.Cli", bin = "deploy", repository = "https://nexus.corp/repository/maven-releases" }
+//! ```
+
+pub(crate) const EXPERIMENTAL: bool = true;
+
+mod pom;
+mod resolve;
+
+use crate::backend::backend_type::BackendType;
+use crate::backend::options::BackendOptions;
+use crate::backend::platform_target::PlatformTarget;
+use crate::backend::static_helpers::verify_checksum_str;
+use crate::backend::{Backend, VersionInfo};
+use crate::cli::args::BackendArg;
+use crate::config::{Config, Settings};
+use crate::file;
+use crate::hash;
+use crate::install_context::InstallContext;
+use crate::lockfile::PlatformInfo;
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+use async_trait::async_trait;
+use eyre::{Result, bail, eyre};
+use itertools::Itertools;
+use pom::Gav;
+use resolve::{Jar, Repository, Resolver};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use versions::Versioning;
+
+#[derive(Debug)]
+pub(crate) struct MavenBackend {
+    ba: Arc<BackendArg>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MavenOptions<'a> {
+    values: BackendOptions<'a>,
+}
+
+impl<'a> MavenOptions<'a> {
+    fn new(raw: &'a ToolVersionOptions) -> Self {
+        Self {
+            values: BackendOptions::new(raw),
+        }
+    }
+
+    fn repository(&self) -> String {
+        self.values
+            .platform_string("repository")
+            .unwrap_or_else(|| Settings::get().maven.repository.clone())
+    }
+
+    fn classifier(&self) -> Option<String> {
+        self.values.platform_string("classifier")
+    }
+
+    fn main_class(&self) -> Option<String> {
+        self.values.platform_string("main_class")
+    }
+
+    fn bin(&self) -> Option<String> {
+        self.values.platform_string("bin")
+    }
+
+    fn jvm_args(&self) -> Option<String> {
+        self.values.platform_string("jvm_args")
+    }
+}
+
+impl MavenBackend {
+    pub(crate) fn from_arg(ba: BackendArg) -> Self {
+        Self { ba: Arc::new(ba) }
+    }
+
+    /// `(group, artifact)` from the `group:artifact` tool name.
+    fn coordinates(&self) -> Result<(&str, &str)> {
+        match self.ba.tool_name.split(':').collect_vec().as_slice() {
+            [group, artifact] if !group.is_empty() && !artifact.is_empty() => Ok((group, artifact)),
+            _ => bail!(
+                "invalid maven tool '{}', expected maven:<groupId>:<artifactId>",
+                self.ba.tool_name
+            ),
+        }
+    }
+
+    fn gav(&self, tv: &ToolVersion) -> Result<Gav> {
+        let (group, artifact) = self.coordinates()?;
+        Ok(Gav {
+            group: group.to_string(),
+            artifact: artifact.to_string(),
+            version: tv.version.clone(),
+        })
+    }
+
+    /// Download `jar` into `lib`, checking it against the published sha1.
+    /// Returns the jar's path and that sha1, if there is one.
+    async fn download_jar(
+        &self,
+        ctx: &InstallContext,
+        repo: &Repository,
+        jar: &Jar,
+        lib: &Path,
+    ) -> Result<(PathBuf, Option<String>)> {
+        let url = repo.file_url(&jar.gav, jar.classifier.as_deref(), "jar");
+        let path = lib.join(jar.file_name());
+        ctx.pr.set_message(format!("download {}", jar.file_name()));
+        repo.download(&url, &path, Some(ctx.pr.as_ref())).await?;
+        let sha1 = repo.sha1(&url).await?;
+        match &sha1 {
+            Some(sha1) => hash::ensure_checksum(&path, sha1, Some(ctx.pr.as_ref()), "sha1")?,
+            // Maven itself only warns here; plenty of older artifacts lack one
+            None => warn!("{url} has no published sha1, not verifying"),
+        }
+        Ok((path, sha1))
+    }
+}
+
+/// Returns install-time-only option keys for the maven backend.
+pub(crate) fn install_time_option_keys() -> Vec<String> {
+    vec![
+        "repository".into(),
+        "classifier".into(),
+        "main_class".into(),
+        "bin".into(),
+        "jvm_args".into(),
+    ]
+}
+
+/// The `Main-Class` attribute of a jar manifest.
+fn manifest_main_class(manifest: &str) -> Option<String> {
+    // manifest lines wrap at 72 bytes; continuations start with a space
+    let unwrapped = manifest.replace("\r\n", "\n").replace("\n ", "");
+    unwrapped
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|class| class.trim().to_string())
+        .filter(|class| !class.is_empty())
+}
+
+fn jar_main_class(jar: &Path) -> Result<Option<String>> {
+    let mut archive = zip::ZipArchive::new(file::open(jar)?)?;
+    let Ok(mut entry) = archive.by_name("META-INF/MANIFEST.MF") else {
+        return Ok(None);
+    };
+    let mut manifest = String::new();
+    entry.read_to_string(&mut manifest)?;
+    Ok(manifest_main_class(&manifest))
+}
+
+/// A launcher that runs `main_class` from the jars in `../lib`.
+///
+/// `java` is the mise-managed java found at install time; the launcher falls
+/// back to `$JAVA_HOME` and then `PATH` if it has since been uninstalled.
+fn launcher_script(java: Option<&Path>, jars: &[Jar], main_class: &str, jvm_args: &str) -> String {
+    if cfg!(windows) {
+        let classpath = jars
+            .iter()
+            .map(|jar| format!("%LIB%\\{}", jar.file_name()))
+            .join(";");
+        let java = java.map(|j| j.display().to_string()).unwrap_or_default();
+        format!(
+            "@echo off\r\n\
+             set \"LIB=%~dp0..\\lib\"\r\n\
+             set \"JAVA={java}\"\r\n\
+             if not exist \"%JAVA%\" (if defined JAVA_HOME (set \"JAVA=%JAVA_HOME%\\bin\\java.exe\") else (set \"JAVA=java\"))\r\n\
+             \"%JAVA%\" {jvm_args} -cp \"{classpath}\" {main_class} %*\r\n"
+        )
+    } else {
+        let classpath = jars
+            .iter()
+            .map(|jar| format!("$LIB/{}", jar.file_name()))
+            .join(":");
+        let java = java.map(|j| j.display().to_string()).unwrap_or_default();
+        format!(
+            r#"#!/bin/sh
+LIB="$(cd "$(dirname "$0")/../lib" && pwd)"
+JAVA="{java}"
+if [ ! -x "$JAVA" ]; then
+  if [ -n "$JAVA_HOME" ]; then JAVA="$JAVA_HOME/bin/java"; else JAVA=java; fi
+fi
+exec "$JAVA" {jvm_args} -cp "{classpath}" {main_class} "$@"
+"#
+        )
+    }
+}
+
+#[async_trait]
+impl Backend for MavenBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Maven
+    }
+
+    fn ba(&self) -> &Arc<BackendArg> {
+        &self.ba
+    }
+
+    fn get_dependencies(&self) -> Result<Vec<&str>> {
+        Ok(vec!["java"])
+    }
+
+    fn mark_prereleases_from_version_pattern(&self) -> bool {
+        true
+    }
+
+    async fn _list_remote_versions(&self, _config: &Arc<Config>) -> Result<Vec<VersionInfo>> {
+        let (group, artifact) = self.coordinates()?;
+        let opts = self.ba.opts();
+        let repo = Repository::new(&MavenOptions::new(&opts).repository());
+        Ok(repo
+            .versions(group, artifact)
+            .await?
+            .into_iter()
+            .filter(|v| !v.ends_with("-SNAPSHOT"))
+            .unique()
+            .sorted_by_cached_key(|v| Versioning::new(v))
+            .map(|version| VersionInfo {
+                version,
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn resolve_lock_info(
+        &self,
+        tv: &ToolVersion,
+        _target: &PlatformTarget,
+    ) -> Result<PlatformInfo> {
+        // jars are platform-independent, so every platform locks the same
+        // main jar
+        let raw_opts = tv.request.options();
+        let opts = MavenOptions::new(&raw_opts);
+        let repo = Repository::new(&opts.repository());
+        let url = repo.file_url(&self.gav(tv)?, opts.classifier().as_deref(), "jar");
+        let checksum = match repo.sha1(&url).await? {
+            Some(sha1) => format!("sha1:{sha1}"),
+            None => {
+                let tmp = tempfile::tempdir()?;
+                let path = tmp.path().join("artifact.jar");
+                repo.download(&url, &path, None).await?;
+                format!("sha256:{}", hash::file_hash_sha256(&path, None)?)
+            }
+        };
+        Ok(PlatformInfo {
+            url: Some(url),
+            checksum: Some(checksum),
+            ..Default::default()
+        })
+    }
+
+    async fn install_version_(
+        &self,
+        ctx: &InstallContext,
+        mut tv: ToolVersion,
+    ) -> Result<ToolVersion> {
+        let raw_opts = tv.request.options();
+        let opts = MavenOptions::new(&raw_opts);
+        let repo = Repository::new(&opts.repository());
+        let root = self.gav(&tv)?;
+        let classifier = opts.classifier();
+
+        ctx.pr.set_message(format!("resolve {root}"));
+        let jars = Resolver::new(repo.clone())
+            .runtime_classpath(&root, classifier.as_deref())
+            .await?;
+
+        ctx.pr.next_operation();
+        let lib = tv.install_path().join("lib");
+        file::create_dir_all(&lib)?;
+        let mut main_jar = None;
+        for jar in &jars {
+            let downloaded = self.download_jar(ctx, &repo, jar, &lib).await?;
+            main_jar.get_or_insert(downloaded);
+        }
+        let (main_jar, main_sha1) =
+            main_jar.ok_or_else(|| eyre!("nothing to install for {root}"))?;
+
+        let platform_info = tv
+            .lock_platforms
+            .entry(self.get_platform_key())
+            .or_default();
+        if let Some(checksum) = &platform_info.checksum {
+            verify_checksum_str(&main_jar, checksum, Some(ctx.pr.as_ref()))?;
+        } else if Settings::get().lockfile_enabled() {
+            // same checksum resolve_lock_info would record
+            platform_info.checksum = Some(match main_sha1 {
+                Some(sha1) => format!("sha1:{sha1}"),
+                None => format!(
+                    "sha256:{}",
+                    hash::file_hash_sha256(&main_jar, Some(ctx.pr.as_ref()))?
+                ),
+            });
+        }
+        platform_info.url = Some(repo.file_url(&root, classifier.as_deref(), "jar"));
+
+        ctx.pr.next_operation();
+        let main_class = match opts.main_class() {
+            Some(main_class) => main_class,
+            None => jar_main_class(&main_jar)?.ok_or_else(|| {
+                eyre!("{root} has no Main-Class in its manifest; set the `main_class` option")
+            })?,
+        };
+        let java = self
+            .dependency_path_for_install(&ctx.config, Some(&ctx.ts), "java")
+            .await;
+        let (_, artifact) = self.coordinates()?;
+        let bin_name = opts.bin().unwrap_or_else(|| artifact.to_string());
+        let bin_name = if cfg!(windows) {
+            format!("{bin_name}.cmd")
+        } else {
+            bin_name
+        };
+        ctx.pr.set_message(format!("write launcher {bin_name}"));
+        let bin = tv.install_path().join("bin").join(bin_name);
+        file::create_dir_all(bin.parent().unwrap())?;
+        file::write(
+            &bin,
+            launcher_script(
+                java.as_deref(),
+                &jars,
+                &main_class,
+                &opts.jvm_args().unwrap_or_default(),
+            ),
+        )?;
+        file::make_executable(&bin)?;
+
+        Ok(tv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_main_class() {
+        let manifest = "Manifest-Version: 1.0\r\nCreated-By: Maven JAR Plugin 3.3.0\r\nMain-Class: com.pinterest.ktlint.cli.internal.Main\r\n\r\n";
+        assert_eq!(
+            manifest_main_class(manifest).as_deref(),
+            Some("com.pinterest.ktlint.cli.internal.Main")
+        );
+        let wrapped = "Main-Class: com.corp.a.very.long.package.name.that.wraps.past.seventy-two\n .bytes.Main\n";
+        assert_eq!(
+            manifest_main_class(wrapped).as_deref(),
+            Some("com.corp.a.very.long.package.name.that.wraps.past.seventy-two.bytes.Main")
+        );
+        assert_eq!(manifest_main_class("Manifest-Version: 1.0\n"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_launcher_script() {
+        let jar = |artifact: &str| Jar {
+            gav: Gav {
+                group: "com.corp".into(),
+                artifact: artifact.into(),
+                version: "1.0".into(),
+            },
+            classifier: None,
+        };
+        let script = launcher_script(
+            Some(Path::new("/mise/installs/java/21/bin/java")),
+            &[jar("tool"), jar("core")],
+            "com.corp.Main",
+            "-Xmx512m",
+        );
+        assert!(script.contains(r#"JAVA="/mise/installs/java/21/bin/java""#));
+        assert!(script.contains(
+            r#"exec "$JAVA" -Xmx512m -cp "$LIB/com.corp.tool-1.0.jar:$LIB/com.corp.core-1.0.jar" com.corp.Main "$@""#
+        ));
+    }
+}
@@ -0,0 +1,552 @@
+//! POM and `maven-metadata.xml` reading for the maven backend.
+//!
+//! Only the parts of the POM model that decide a runtime classpath are read:
+//! coordinates, parent, properties, dependencies and dependency management.
+//! The XML reader is deliberately small — POMs don't use namespaces beyond
+//! the default one, DTDs or processing instructions that matter here.
+
+use std::collections::HashMap;
+
+use eyre::{Result, bail, eyre};
+
+/// An XML element with its concatenated text and child elements.
+/// Attributes are not kept; nothing in a POM needs them.
+#[derive(Debug, Default, Clone)]
+pub(super) struct Element {
+    name: String,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Trimmed text of the child `name`, if present and non-empty.
+    fn text_of(&self, name: &str) -> Option<String> {
+        self.child(name)
+            .map(|c| c.text.trim().to_string())
+            .filter(|t| !t.is_empty())
+    }
+}
+
+/// Parse an XML document into its root element.
+pub(super) fn parse_xml(input: &str) -> Result<Element> {
+    // stack[0] is a synthetic document node collecting the root element
+    let mut stack = vec![Element::default()];
+    let mut rest = input;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after
+                .find("-->")
+                .ok_or_else(|| eyre!("unterminated XML comment"))?;
+            rest = &after[end + 3..];
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after
+                .find("]]>")
+                .ok_or_else(|| eyre!("unterminated CDATA section"))?;
+            stack.last_mut().unwrap().text.push_str(&after[..end]);
+            rest = &after[end + 3..];
+        } else if rest.starts_with("<?") || rest.starts_with("<!") {
+            let end = rest
+                .find('>')
+                .ok_or_else(|| eyre!("unterminated XML declaration"))?;
+            rest = &rest[end + 1..];
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after
+                .find('>')
+                .ok_or_else(|| eyre!("unterminated closing tag"))?;
+            let name = local_name(after[..end].trim());
+            if stack.len() < 2 {
+                bail!("unexpected closing tag </{name}>");
+            }
+            let element = stack.pop().unwrap();
+            if element.name != name {
+                bail!(
+                    "mismatched closing tag </{name}>, expected </{}>",
+                    element.name
+                );
+            }
+            stack.last_mut().unwrap().children.push(element);
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('<') {
+            let end = after.find('>').ok_or_else(|| eyre!("unterminated tag"))?;
+            let tag = &after[..end];
+            let self_closing = tag.ends_with('/');
+            let name = local_name(
+                tag.trim_end_matches('/')
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default(),
+            );
+            let element = Element {
+                name,
+                ..Default::default()
+            };
+            if self_closing {
+                stack.last_mut().unwrap().children.push(element);
+            } else {
+                stack.push(element);
+            }
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            stack
+                .last_mut()
+                .unwrap()
+                .text
+                .push_str(&unescape(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    if stack.len() > 1 {
+        bail!("unclosed <{}> element", stack.last().unwrap().name);
+    }
+    stack
+        .pop()
+        .and_then(|document| document.children.into_iter().next())
+        .ok_or_else(|| eyre!("empty XML document"))
+}
+
+/// Drop a namespace prefix (`m:project` → `project`).
+fn local_name(name: &str) -> String {
+    name.rsplit(':').next().unwrap_or(name).to_string()
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Released versions listed in a `maven-metadata.xml`, oldest first.
+pub(super) fn metadata_versions(xml: &str) -> Result<Vec<String>> {
+    let metadata = parse_xml(xml)?;
+    Ok(metadata
+        .child("versioning")
+        .and_then(|v| v.child("versions"))
+        .map(|versions| {
+            versions
+                .children_named("version")
+                .map(|v| v.text.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// `group:artifact:version`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(super) struct Gav {
+    pub group: String,
+    pub artifact: String,
+    pub version: String,
+}
+
+impl std::fmt::Display for Gav {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.group, self.artifact, self.version)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct Dependency {
+    pub group: String,
+    pub artifact: String,
+    pub version: Option<String>,
+    pub scope: Option<String>,
+    pub classifier: Option<String>,
+    pub type_: Option<String>,
+    pub optional: bool,
+    /// `(group, artifact)` pairs; either side may be `*`.
+    pub exclusions: Vec<(String, String)>,
+}
+
+impl Dependency {
+    fn from_element(e: &Element) -> Option<Self> {
+        Some(Self {
+            group: e.text_of("groupId")?,
+            artifact: e.text_of("artifactId")?,
+            version: e.text_of("version"),
+            scope: e.text_of("scope"),
+            classifier: e.text_of("classifier"),
+            type_: e.text_of("type"),
+            optional: e.text_of("optional").as_deref() == Some("true"),
+            exclusions: e
+                .child("exclusions")
+                .map(|exclusions| {
+                    exclusions
+                        .children_named("exclusion")
+                        .filter_map(|x| Some((x.text_of("groupId")?, x.text_of("artifactId")?)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    pub(super) fn scope(&self) -> &str {
+        self.scope.as_deref().unwrap_or("compile")
+    }
+
+    pub(super) fn type_(&self) -> &str {
+        self.type_.as_deref().unwrap_or("jar")
+    }
+
+    pub(super) fn is_import(&self) -> bool {
+        self.scope() == "import" && self.type_() == "pom"
+    }
+
+    fn interpolate(&mut self, properties: &HashMap<String, String>) {
+        for field in [
+            Some(&mut self.group),
+            Some(&mut self.artifact),
+            self.version.as_mut(),
+            self.scope.as_mut(),
+            self.classifier.as_mut(),
+            self.type_.as_mut(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *field = interpolate(field, properties);
+        }
+    }
+}
+
+/// A POM as written, before parent inheritance and interpolation.
+#[derive(Debug, Clone, Default)]
+pub(super) struct RawPom {
+    pub group: Option<String>,
+    pub artifact: String,
+    pub version: Option<String>,
+    pub packaging: Option<String>,
+    pub parent: Option<Gav>,
+    pub properties: Vec<(String, String)>,
+    pub dependencies: Vec<Dependency>,
+    pub managed: Vec<Dependency>,
+}
+
+impl RawPom {
+    pub(super) fn parse(xml: &str) -> Result<Self> {
+        let project = parse_xml(xml)?;
+        if project.name != "project" {
+            bail!("expected a <project> POM, found <{}>", project.name);
+        }
+        let parent = project.child("parent").and_then(|p| {
+            Some(Gav {
+                group: p.text_of("groupId")?,
+                artifact: p.text_of("artifactId")?,
+                version: p.text_of("version")?,
+            })
+        });
+        let dependencies = |e: Option<&Element>| -> Vec<Dependency> {
+            e.map(|deps| {
+                deps.children_named("dependency")
+                    .filter_map(Dependency::from_element)
+                    .collect()
+            })
+            .unwrap_or_default()
+        };
+        Ok(Self {
+            group: project.text_of("groupId"),
+            artifact: project
+                .text_of("artifactId")
+                .ok_or_else(|| eyre!("POM has no artifactId"))?,
+            version: project.text_of("version"),
+            packaging: project.text_of("packaging"),
+            properties: project
+                .child("properties")
+                .map(|props| {
+                    props
+                        .children
+                        .iter()
+                        .map(|p| (p.name.clone(), p.text.trim().to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            dependencies: dependencies(project.child("dependencies")),
+            managed: dependencies(
+                project
+                    .child("dependencyManagement")
+                    .and_then(|m| m.child("dependencies")),
+            ),
+            parent,
+        })
+    }
+}
+
+/// A POM with its parents merged in and `${…}` properties expanded.
+/// `managed` may still contain `import` entries for BOMs.
+#[derive(Debug, Clone, Default)]
+pub(super) struct EffectivePom {
+    pub gav: Gav,
+    pub packaging: String,
+    pub dependencies: Vec<Dependency>,
+    pub managed: Vec<Dependency>,
+}
+
+impl EffectivePom {
+    /// Merge `chain` — a POM followed by its parent, grandparent, … — the
+    /// way Maven builds an effective model: the nearest definition of a
+    /// property, dependency or managed dependency wins.
+    pub(super) fn from_chain(chain: &[RawPom]) -> Result<Self> {
+        let pom = chain.first().ok_or_else(|| eyre!("empty POM chain"))?;
+        let group = pom
+            .group
+            .clone()
+            .or_else(|| pom.parent.as_ref().map(|p| p.group.clone()))
+            .ok_or_else(|| eyre!("POM {} has no groupId", pom.artifact))?;
+        let version = pom
+            .version
+            .clone()
+            .or_else(|| pom.parent.as_ref().map(|p| p.version.clone()))
+            .ok_or_else(|| eyre!("POM {} has no version", pom.artifact))?;
+
+        let mut properties = HashMap::new();
+        for ancestor in chain.iter().rev() {
+            properties.extend(ancestor.properties.iter().cloned());
+        }
+        for (key, value) in [
+            ("project.groupId", &group),
+            ("project.artifactId", &pom.artifact),
+            ("project.version", &version),
+            ("pom.groupId", &group),
+            ("pom.version", &version),
+            ("groupId", &group),
+            ("version", &version),
+        ] {
+            properties.insert(key.to_string(), value.clone());
+        }
+        if let Some(parent) = &pom.parent {
+            properties.insert("project.parent.groupId".into(), parent.group.clone());
+            properties.insert("project.parent.version".into(), parent.version.clone());
+        }
+
+        let merge = |select: fn(&RawPom) -> &Vec<Dependency>| {
+            let mut merged: Vec<Dependency> = vec![];
+            for dep in chain.iter().flat_map(|p| select(p).iter()) {
+                let duplicate = merged.iter().any(|d| {
+                    d.group == dep.group
+                        && d.artifact == dep.artifact
+                        && d.classifier == dep.classifier
+                        && d.type_() == dep.type_()
+                });
+                if !duplicate {
+                    let mut dep = dep.clone();
+                    dep.interpolate(&properties);
+                    merged.push(dep);
+                }
+            }
+            merged
+        };
+        Ok(Self {
+            gav: Gav {
+                group: interpolate(&group, &properties),
+                artifact: pom.artifact.clone(),
+                version: interpolate(&version, &properties),
+            },
+            packaging: pom
+                .packaging
+                .as_deref()
+                .map(|p| interpolate(p, &properties))
+                .unwrap_or_else(|| "jar".to_string()),
+            dependencies: merge(|p| &p.dependencies),
+            managed: merge(|p| &p.managed),
+        })
+    }
+
+    /// The managed entry for `dep`, if this POM's dependency management has one.
+    pub(super) fn managed_for(&self, dep: &Dependency) -> Option<&Dependency> {
+        self.managed.iter().find(|m| {
+            !m.is_import()
+                && m.group == dep.group
+                && m.artifact == dep.artifact
+                && m.classifier == dep.classifier
+        })
+    }
+}
+
+/// Expand `${name}` references, leaving unknown ones as written.
+fn interpolate(value: &str, properties: &HashMap<String, String>) -> String {
+    let mut value = value.to_string();
+    // properties may refer to other properties; bound the passes so a cycle
+    // can't loop forever
+    for _ in 0..8 {
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value.as_str();
+        let mut changed = false;
+        while let Some(start) = rest.find("${") {
+            out.push_str(&rest[..start]);
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 2..start + len];
+            match properties.get(name) {
+                Some(v) => {
+                    out.push_str(v);
+                    changed = true;
+                }
+                None => out.push_str(&rest[start..start + len + 1]),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        out.push_str(rest);
+        value = out;
+        if !changed {
+            break;
+        }
+    }
+    value
+}
+
+/// A concrete version for a dependency version spec: a plain version, a
+/// pinned `[1.2.3]`, or the inclusive lower bound of a range such as
+/// `[1.2,2.0)`.
+pub(super) fn pick_version(spec: &str) -> Result<String> {
+    let spec = spec.trim();
+    if !spec.starts_with(['[', '(']) {
+        return Ok(spec.to_string());
+    }
+    if let Some(lower) = spec.strip_prefix('[') {
+        let lower = lower.split([',', ']']).next().unwrap_or_default().trim();
+        if !lower.is_empty() {
+            return Ok(lower.to_string());
+        }
+    }
+    bail!("unsupported version range {spec}; pin the dependency in the POM's dependencyManagement")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <groupId>com.corp</groupId>
+  <artifactId>parent</artifactId>
+  <version>3</version>
+  <packaging>pom</packaging>
+  <properties>
+    <kotlin.version>2.0.0</kotlin.version>
+    <slf4j.version>1.7.36</slf4j.version>
+  </properties>
+  <dependencyManagement>
+    <dependencies>
+      <dependency>
+        <groupId>org.slf4j</groupId>
+        <artifactId>slf4j-api</artifactId>
+        <version>${slf4j.version}</version>
+      </dependency>
+    </dependencies>
+  </dependencyManagement>
+</project>"#;
+
+    const CHILD: &str = r#"<project>
+  <parent>
+    <groupId>com.corp</groupId>
+    <artifactId>parent</artifactId>
+    <version>3</version>
+  </parent>
+  <artifactId>deployer</artifactId>
+  <version>1.4.2</version>
+  <!-- newer slf4j than the parent -->
+  <properties><slf4j.version>2.0.13</slf4j.version></properties>
+  <dependencies>
+    <dependency>
+      <groupId>org.jetbrains.kotlin</groupId>
+      <artifactId>kotlin-stdlib</artifactId>
+      <version>${kotlin.version}</version>
+      <exclusions>
+        <exclusion><groupId>org.jetbrains</groupId><artifactId>annotations</artifactId></exclusion>
+      </exclusions>
+    </dependency>
+    <dependency>
+      <groupId>org.slf4j</groupId>
+      <artifactId>slf4j-api</artifactId>
+    </dependency>
+    <dependency>
+      <groupId>${project.groupId}</groupId>
+      <artifactId>deployer-core</artifactId>
+      <version>${project.version}</version>
+      <classifier>all</classifier>
+    </dependency>
+    <dependency>
+      <groupId>junit</groupId>
+      <artifactId>junit</artifactId>
+      <version>4.13.2</version>
+      <scope>test</scope>
+    </dependency>
+  </dependencies>
+</project>"#;
+
+    #[test]
+    fn test_parse_xml() {
+        let root = parse_xml(
+            "<?xml version=\"1.0\"?><a x=\"1\"><b>one &amp; two</b><c/><!-- <d/> --><e><![CDATA[<raw>]]></e></a>",
+        )
+        .unwrap();
+        assert_eq!(root.name, "a");
+        assert_eq!(root.text_of("b").as_deref(), Some("one & two"));
+        assert!(root.child("c").is_some());
+        assert!(root.child("d").is_none());
+        assert_eq!(root.text_of("e").as_deref(), Some("<raw>"));
+        assert!(parse_xml("<a><b></a>").is_err());
+        assert!(parse_xml("<a>").is_err());
+        assert!(parse_xml("").is_err());
+    }
+
+    #[test]
+    fn test_metadata_versions() {
+        let xml = r#"<metadata><groupId>com.corp</groupId><artifactId>deployer</artifactId>
+<versioning><latest>1.5.0</latest><versions><version>1.4.2</version><version>1.5.0</version></versions></versioning></metadata>"#;
+        assert_eq!(metadata_versions(xml).unwrap(), vec!["1.4.2", "1.5.0"]);
+    }
+
+    #[test]
+    fn test_effective_pom_inherits_and_interpolates() {
+        let chain = [
+            RawPom::parse(CHILD).unwrap(),
+            RawPom::parse(PARENT).unwrap(),
+        ];
+        let pom = EffectivePom::from_chain(&chain).unwrap();
+        assert_eq!(pom.gav.to_string(), "com.corp:deployer:1.4.2");
+        assert_eq!(pom.packaging, "jar");
+
+        let kotlin = &pom.dependencies[0];
+        assert_eq!(kotlin.version.as_deref(), Some("2.0.0"));
+        assert_eq!(
+            kotlin.exclusions,
+            vec![("org.jetbrains".to_string(), "annotations".to_string())]
+        );
+
+        let slf4j = &pom.dependencies[1];
+        assert_eq!(slf4j.version, None);
+        assert_eq!(
+            pom.managed_for(slf4j).unwrap().version.as_deref(),
+            Some("2.0.13")
+        );
+
+        let core = &pom.dependencies[2];
+        assert_eq!(core.group, "com.corp");
+        assert_eq!(core.version.as_deref(), Some("1.4.2"));
+        assert_eq!(core.classifier.as_deref(), Some("all"));
+
+        assert_eq!(pom.dependencies[3].scope(), "test");
+    }
+
+    #[test]
+    fn test_pick_version() {
+        assert_eq!(pick_version("1.2.3").unwrap(), "1.2.3");
+        assert_eq!(pick_version("[1.2.3]").unwrap(), "1.2.3");
+        assert_eq!(pick_version("[1.2,2.0)").unwrap(), "1.2");
+        assert!(pick_version("(,2.0]").is_err());
+    }
+}
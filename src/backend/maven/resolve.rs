@@ -0,0 +1,505 @@
+//! Maven repository access and runtime classpath resolution.
+//!
+//! Resolution follows Maven's rules closely enough for command-line tools:
+//! breadth-first over compile/runtime dependencies, nearest declaration wins
+//! per `group:artifact`, the root POM's dependency management pins transitive
+//! versions, and exclusions apply to everything below the dependency that
+//! declares them.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use eyre::{Result, bail, eyre};
+use url::Url;
+
+use super::pom::{Dependency, EffectivePom, Gav, RawPom, metadata_versions, pick_version};
+use crate::file;
+use crate::http::{self, HTTP, HTTP_FETCH};
+use crate::ui::progress_report::SingleReport;
+
+/// Parent chains deeper than this are treated as a cycle.
+const MAX_PARENT_DEPTH: usize = 32;
+
+/// A Maven repository laid out as `{base}/{group/path}/{artifact}/{version}/…`,
+/// over http(s) or `file://`.
+#[derive(Debug, Clone)]
+pub(super) struct Repository {
+    base: String,
+}
+
+impl Repository {
+    pub(super) fn new(url: &str) -> Self {
+        Self {
+            base: url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn artifact_dir(&self, group: &str, artifact: &str) -> String {
+        format!("{}/{}/{artifact}", self.base, group.replace('.', "/"))
+    }
+
+    pub(super) fn metadata_url(&self, group: &str, artifact: &str) -> String {
+        format!("{}/maven-metadata.xml", self.artifact_dir(group, artifact))
+    }
+
+    /// URL of `{artifact}-{version}[-{classifier}].{ext}`.
+    pub(super) fn file_url(&self, gav: &Gav, classifier: Option<&str>, ext: &str) -> String {
+        let classifier = classifier.map(|c| format!("-{c}")).unwrap_or_default();
+        format!(
+            "{}/{}/{}-{}{classifier}.{ext}",
+            self.artifact_dir(&gav.group, &gav.artifact),
+            gav.version,
+            gav.artifact,
+            gav.version,
+        )
+    }
+
+    fn local_path(url: &str) -> Option<PathBuf> {
+        url.starts_with("file:")
+            .then(|| Url::parse(url).ok()?.to_file_path().ok())
+            .flatten()
+    }
+
+    /// The text at `url`, or `None` if the repository doesn't have it.
+    pub(super) async fn fetch_text(&self, url: &str) -> Result<Option<String>> {
+        if let Some(path) = Self::local_path(url) {
+            return Ok(path
+                .exists()
+                .then(|| file::read_to_string(&path))
+                .transpose()?);
+        }
+        match HTTP_FETCH.get_text(url).await {
+            Ok(text) => Ok(Some(text)),
+            Err(err) if http::error_code(&err) == Some(404) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub(super) async fn download(
+        &self,
+        url: &str,
+        dest: &Path,
+        pr: Option<&dyn SingleReport>,
+    ) -> Result<()> {
+        if let Some(path) = Self::local_path(url) {
+            if !path.exists() {
+                bail!("{url} not found");
+            }
+            file::copy(&path, dest)?;
+            return Ok(());
+        }
+        HTTP.download_file(url, dest, pr).await
+    }
+
+    /// Released versions of `group:artifact`, oldest first.
+    pub(super) async fn versions(&self, group: &str, artifact: &str) -> Result<Vec<String>> {
+        let url = self.metadata_url(group, artifact);
+        let xml = self
+            .fetch_text(&url)
+            .await?
+            .ok_or_else(|| eyre!("{group}:{artifact} not found in {}", self.base))?;
+        metadata_versions(&xml)
+    }
+
+    /// The published sha1 of a file, if the repository has one.
+    pub(super) async fn sha1(&self, url: &str) -> Result<Option<String>> {
+        Ok(self
+            .fetch_text(&format!("{url}.sha1"))
+            .await?
+            // some repositories publish `sha1sum` style "<hex>  <file>"
+            .and_then(|text| text.split_whitespace().next().map(str::to_lowercase)))
+    }
+}
+
+/// A jar on the runtime classpath.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Jar {
+    pub gav: Gav,
+    pub classifier: Option<String>,
+}
+
+impl Jar {
+    /// Name the jar is stored under in the install's `lib` directory.
+    /// The group is included since artifact ids collide across groups.
+    pub(super) fn file_name(&self) -> String {
+        let classifier = self
+            .classifier
+            .as_ref()
+            .map(|c| format!("-{c}"))
+            .unwrap_or_default();
+        format!(
+            "{}.{}-{}{classifier}.jar",
+            self.gav.group, self.gav.artifact, self.gav.version
+        )
+    }
+}
+
+pub(super) struct Resolver {
+    repo: Repository,
+    raw: HashMap<Gav, RawPom>,
+    effective: HashMap<Gav, Arc<EffectivePom>>,
+}
+
+impl Resolver {
+    pub(super) fn new(repo: Repository) -> Self {
+        Self {
+            repo,
+            raw: HashMap::new(),
+            effective: HashMap::new(),
+        }
+    }
+
+    async fn raw_pom(&mut self, gav: &Gav) -> Result<RawPom> {
+        if let Some(pom) = self.raw.get(gav) {
+            return Ok(pom.clone());
+        }
+        let url = self.repo.file_url(gav, None, "pom");
+        let xml = self
+            .repo
+            .fetch_text(&url)
+            .await?
+            .ok_or_else(|| eyre!("POM for {gav} not found at {url}"))?;
+        let pom = RawPom::parse(&xml).map_err(|err| eyre!("invalid POM {url}: {err}"))?;
+        self.raw.insert(gav.clone(), pom.clone());
+        Ok(pom)
+    }
+
+    /// The POM for `gav` merged with its parents, without BOM imports.
+    async fn merged_pom(&mut self, gav: &Gav) -> Result<EffectivePom> {
+        let mut chain = vec![self.raw_pom(gav).await?];
+        while let Some(parent) = chain.last().unwrap().parent.clone() {
+            if chain.len() > MAX_PARENT_DEPTH {
+                bail!("parent chain of {gav} is too deep (cycle at {parent}?)");
+            }
+            chain.push(self.raw_pom(&parent).await?);
+        }
+        EffectivePom::from_chain(&chain)
+    }
+
+    /// The effective POM for `gav`, with `import`-scoped BOMs in its
+    /// dependency management replaced by their managed entries.
+    pub(super) async fn effective_pom(&mut self, gav: &Gav) -> Result<Arc<EffectivePom>> {
+        if let Some(pom) = self.effective.get(gav) {
+            return Ok(pom.clone());
+        }
+        let mut pom = self.merged_pom(gav).await?;
+        let (imports, mut managed): (Vec<_>, Vec<_>) =
+            pom.managed.drain(..).partition(Dependency::is_import);
+        let mut queue = VecDeque::from(imports);
+        let mut seen = HashSet::new();
+        while let Some(import) = queue.pop_front() {
+            let bom = Gav {
+                group: import.group.clone(),
+                artifact: import.artifact.clone(),
+                version: pick_version(import.version.as_deref().unwrap_or_default())?,
+            };
+            if !seen.insert(bom.clone()) {
+                continue;
+            }
+            // entries declared directly, or imported earlier, take precedence
+            for entry in self.merged_pom(&bom).await?.managed {
+                if entry.is_import() {
+                    queue.push_back(entry);
+                } else if !managed
+                    .iter()
+                    .any(|m| m.group == entry.group && m.artifact == entry.artifact)
+                {
+                    managed.push(entry);
+                }
+            }
+        }
+        pom.managed = managed;
+        let pom = Arc::new(pom);
+        self.effective.insert(gav.clone(), pom.clone());
+        Ok(pom)
+    }
+
+    /// The jars needed to run `root`: its own jar followed by its runtime
+    /// dependency closure.
+    pub(super) async fn runtime_classpath(
+        &mut self,
+        root: &Gav,
+        classifier: Option<&str>,
+    ) -> Result<Vec<Jar>> {
+        let root_pom = self.effective_pom(root).await?;
+        if root_pom.packaging == "pom" {
+            bail!("{root} has pom packaging and no jar to run");
+        }
+        let mut jars = vec![Jar {
+            gav: root.clone(),
+            classifier: classifier.map(str::to_string),
+        }];
+        // classifier variants (e.g. natives) of one artifact are separate jars
+        let mut seen = HashSet::from([(
+            root.group.clone(),
+            root.artifact.clone(),
+            classifier.map(str::to_string),
+            "jar".to_string(),
+        )]);
+        let mut queue = VecDeque::from([(root_pom.clone(), Vec::<(String, String)>::new())]);
+        while let Some((pom, exclusions)) = queue.pop_front() {
+            for dep in &pom.dependencies {
+                // the root's dependency management overrides what the
+                // declaring POM says; its own management fills the gaps
+                let managed = [root_pom.managed_for(dep), pom.managed_for(dep)];
+                let version = managed
+                    .iter()
+                    .flatten()
+                    .find_map(|m| m.version.clone())
+                    .filter(|_| !Arc::ptr_eq(&pom, &root_pom) || dep.version.is_none())
+                    .or_else(|| dep.version.clone());
+                let scope = dep
+                    .scope
+                    .clone()
+                    .or_else(|| managed.iter().flatten().find_map(|m| m.scope.clone()))
+                    .unwrap_or_else(|| "compile".to_string());
+                if !matches!(scope.as_str(), "compile" | "runtime")
+                    || dep.optional
+                    || !matches!(dep.type_(), "jar" | "bundle" | "pom")
+                    || is_excluded(&exclusions, dep)
+                    || !seen.insert((
+                        dep.group.clone(),
+                        dep.artifact.clone(),
+                        dep.classifier.clone(),
+                        dep.type_().to_string(),
+                    ))
+                {
+                    continue;
+                }
+                let Some(version) = version else {
+                    bail!(
+                        "{}: dependency {}:{} has no version",
+                        pom.gav,
+                        dep.group,
+                        dep.artifact
+                    );
+                };
+                let gav = Gav {
+                    group: dep.group.clone(),
+                    artifact: dep.artifact.clone(),
+                    version: pick_version(&version)?,
+                };
+                let child = self.effective_pom(&gav).await?;
+                if dep.type_() != "pom" {
+                    jars.push(Jar {
+                        gav,
+                        classifier: dep.classifier.clone(),
+                    });
+                }
+                let mut child_exclusions = exclusions.clone();
+                child_exclusions.extend(dep.exclusions.iter().cloned());
+                queue.push_back((child, child_exclusions));
+            }
+        }
+        Ok(jars)
+    }
+}
+
+fn is_excluded(exclusions: &[(String, String)], dep: &Dependency) -> bool {
+    exclusions.iter().any(|(group, artifact)| {
+        (group == "*" || group == &dep.group) && (artifact == "*" || artifact == &dep.artifact)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pom(gav: &str, body: &str) -> (String, String) {
+        let mut parts = gav.split(':');
+        let (group, artifact, version) = (
+            parts.next().unwrap(),
+            parts.next().unwrap(),
+            parts.next().unwrap(),
+        );
+        (
+            format!(
+                "{}/{artifact}/{version}/{artifact}-{version}.pom",
+                group.replace('.', "/")
+            ),
+            format!(
+                "<project><groupId>{group}</groupId><artifactId>{artifact}</artifactId><version>{version}</version>{body}</project>"
+            ),
+        )
+    }
+
+    fn dep(gav: &str, extra: &str) -> String {
+        let mut parts = gav.split(':');
+        let group = parts.next().unwrap();
+        let artifact = parts.next().unwrap();
+        let version = parts
+            .next()
+            .map(|v| format!("<version>{v}</version>"))
+            .unwrap_or_default();
+        format!(
+            "<dependency><groupId>{group}</groupId><artifactId>{artifact}</artifactId>{version}{extra}</dependency>"
+        )
+    }
+
+    fn repo(files: &[(String, String)]) -> (tempfile::TempDir, Repository) {
+        let tmp = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = tmp.path().join(path);
+            file::create_dir_all(path.parent().unwrap()).unwrap();
+            file::write(&path, content).unwrap();
+        }
+        let url = Url::from_directory_path(tmp.path()).unwrap().to_string();
+        (tmp, Repository::new(&url))
+    }
+
+    fn names(jars: &[Jar]) -> Vec<String> {
+        jars.iter().map(|j| j.gav.to_string()).collect()
+    }
+
+    #[test]
+    fn test_repository_urls() {
+        let repo = Repository::new("https://repo1.maven.org/maven2/");
+        let gav = Gav {
+            group: "com.pinterest.ktlint".into(),
+            artifact: "ktlint-cli".into(),
+            version: "1.3.1".into(),
+        };
+        assert_eq!(
+            repo.metadata_url(&gav.group, &gav.artifact),
+            "https://repo1.maven.org/maven2/com/pinterest/ktlint/ktlint-cli/maven-metadata.xml"
+        );
+        assert_eq!(
+            repo.file_url(&gav, Some("all"), "jar"),
+            "https://repo1.maven.org/maven2/com/pinterest/ktlint/ktlint-cli/1.3.1/ktlint-cli-1.3.1-all.jar"
+        );
+        let jar = Jar {
+            gav,
+            classifier: None,
+        };
+        assert_eq!(jar.file_name(), "com.pinterest.ktlint.ktlint-cli-1.3.1.jar");
+    }
+
+    #[tokio::test]
+    async fn test_runtime_classpath() {
+        let (_tmp, repo) = repo(&[
+            pom(
+                "com.corp:tool:1.0",
+                &format!(
+                    "<dependencyManagement><dependencies>{}{}</dependencies></dependencyManagement><dependencies>{}{}{}{}{}</dependencies>",
+                    dep("org.lib:shared:2.0", ""),
+                    dep("org.bom:bom:1", "<type>pom</type><scope>import</scope>"),
+                    dep(
+                        "org.lib:a:1.0",
+                        "<exclusions><exclusion><groupId>org.noise</groupId><artifactId>*</artifactId></exclusion></exclusions>"
+                    ),
+                    dep("org.lib:b:1.0", ""),
+                    dep("org.lib:from-bom", ""),
+                    dep("junit:junit:4.13", "<scope>test</scope>"),
+                    dep("org.lib:opt:1.0", "<optional>true</optional>"),
+                ),
+            ),
+            pom(
+                "org.lib:a:1.0",
+                &format!(
+                    "<dependencies>{}{}</dependencies>",
+                    dep("org.lib:shared:1.0", ""),
+                    dep("org.noise:logger:1.0", ""),
+                ),
+            ),
+            pom(
+                "org.lib:b:1.0",
+                &format!(
+                    "<dependencies>{}</dependencies>",
+                    dep("org.lib:deep:1.0", "")
+                ),
+            ),
+            pom("org.lib:deep:1.0", ""),
+            pom("org.lib:shared:2.0", ""),
+            pom("org.lib:from-bom:3.1", ""),
+            pom(
+                "org.bom:bom:1",
+                &format!(
+                    "<packaging>pom</packaging><dependencyManagement><dependencies>{}</dependencies></dependencyManagement>",
+                    dep("org.lib:from-bom:3.1", "")
+                ),
+            ),
+        ]);
+        let root = Gav {
+            group: "com.corp".into(),
+            artifact: "tool".into(),
+            version: "1.0".into(),
+        };
+        let jars = Resolver::new(repo)
+            .runtime_classpath(&root, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            names(&jars),
+            vec![
+                "com.corp:tool:1.0",
+                "org.lib:a:1.0",
+                "org.lib:b:1.0",
+                "org.lib:from-bom:3.1",
+                "org.lib:shared:2.0",
+                "org.lib:deep:1.0",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_runtime_classpath_missing_pom() {
+        let (_tmp, repo) = repo(&[pom(
+            "com.corp:tool:1.0",
+            &format!(
+                "<dependencies>{}</dependencies>",
+                dep("org.lib:gone:1.0", "")
+            ),
+        )]);
+        let root = Gav {
+            group: "com.corp".into(),
+            artifact: "tool".into(),
+            version: "1.0".into(),
+        };
+        let err = Resolver::new(repo)
+            .runtime_classpath(&root, None)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("POM for org.lib:gone:1.0 not found"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_runtime_classpath_classifiers() {
+        let (_tmp, repo) = repo(&[
+            pom(
+                "com.corp:tool:1.0",
+                &format!(
+                    "<dependencies>{}{}{}</dependencies>",
+                    dep("org.lib:native:1.0", ""),
+                    dep(
+                        "org.lib:native:1.0",
+                        "<classifier>linux-x86_64</classifier>"
+                    ),
+                    dep(
+                        "org.lib:native:1.0",
+                        "<classifier>linux-x86_64</classifier>"
+                    ),
+                ),
+            ),
+            pom("org.lib:native:1.0", ""),
+        ]);
+        let root = Gav {
+            group: "com.corp".into(),
+            artifact: "tool".into(),
+            version: "1.0".into(),
+        };
+        let jars = Resolver::new(repo)
+            .runtime_classpath(&root, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            jars.iter().map(Jar::file_name).collect::<Vec<_>>(),
+            vec![
+                "com.corp.tool-1.0.jar",
+                "org.lib.native-1.0.jar",
+                "org.lib.native-1.0-linux-x86_64.jar",
+            ]
+        );
+    }
+}
@@ -62,22 +62,27 @@ pub(crate) mod asdf;
 pub(crate) mod asset_matcher;
 pub(crate) mod aube_host;
 pub(crate) mod backend_type;
+pub(crate) mod brew;
 pub(crate) mod cargo;
 pub(crate) mod conda;
 pub(crate) mod dotnet;
 mod external_plugin_cache;
 pub(crate) mod gem;
+pub(crate) mod git;
 pub(crate) mod github;
 pub(crate) mod go;
 pub(crate) mod http;
 pub(crate) mod jq;
+pub(crate) mod maven;
 pub(crate) mod npm;
 pub(crate) mod npm_registry;
+pub(crate) mod oci;
 pub(crate) mod options;
 pub(crate) mod pipx;
 pub(crate) mod pkgx;
 pub(crate) mod platform_target;
 mod platform_tokens;
+pub(crate) mod release_notes;
 pub(crate) mod s3;
 pub(crate) mod spm;
 pub(crate) mod static_helpers;
@@ -608,15 +613,19 @@ pub(crate) fn arg_to_backend(ba: BackendArg) -> Option<ABackend> {
         }
         BackendType::Aqua => Some(Arc::new(aqua::AquaBackend::from_arg(ba))),
         BackendType::Asdf => Some(Arc::new(asdf::AsdfBackend::from_arg(ba))),
+        BackendType::Brew => Some(Arc::new(brew::BrewBackend::from_arg(ba))),
         BackendType::Cargo => Some(Arc::new(cargo::CargoBackend::from_arg(ba))),
         BackendType::Conda => Some(Arc::new(conda::CondaBackend::from_arg(ba))),
         BackendType::Dotnet => Some(Arc::new(dotnet::DotnetBackend::from_arg(ba))),
         BackendType::Forgejo => Some(Arc::new(github::UnifiedGitBackend::from_arg(ba))),
         BackendType::Gem => Some(Arc::new(gem::GemBackend::from_arg(ba))),
+        BackendType::Git => Some(Arc::new(git::GitBackend::from_arg(ba))),
         BackendType::Github => Some(Arc::new(github::UnifiedGitBackend::from_arg(ba))),
         BackendType::Gitlab => Some(Arc::new(github::UnifiedGitBackend::from_arg(ba))),
         BackendType::Go => Some(Arc::new(go::GoBackend::from_arg(ba))),
+        BackendType::Maven => Some(Arc::new(maven::MavenBackend::from_arg(ba))),
         BackendType::Npm => Some(Arc::new(npm::NPMBackend::from_arg(ba))),
+        BackendType::Oci => Some(Arc::new(oci::OciBackend::from_arg(ba))),
         BackendType::Pipx => Some(Arc::new(pipx::PIPXBackend::from_arg(ba))),
         BackendType::Pkgx => Some(Arc::new(pkgx::PkgxBackend::from_arg(ba))),
         BackendType::Spm => Some(Arc::new(spm::SPMBackend::from_arg(ba))),
@@ -646,8 +655,11 @@ pub(crate) fn install_time_option_keys_for_type(backend_type: &BackendType) -> V
         }
         BackendType::Ubi => ubi::install_time_option_keys(),
         BackendType::Cargo => cargo::install_time_option_keys(),
+        BackendType::Git => git::install_time_option_keys(),
         BackendType::Go => go::install_time_option_keys(),
+        BackendType::Maven => maven::install_time_option_keys(),
         BackendType::Npm => npm::install_time_option_keys(),
+        BackendType::Oci => oci::install_time_option_keys(),
         BackendType::Pipx => pipx::install_time_option_keys(),
         BackendType::Pkgx => pkgx::install_time_option_keys(),
         BackendType::Aqua => aqua::install_time_option_keys(),
@@ -2033,6 +2045,15 @@ pub(crate) trait Backend: Debug + Send + Sync {
     async fn security_info(&self) -> Vec<SecurityFeature> {
         vec![]
     }
+    /// Release notes of the versions after `from` up to `to`, newest first.
+    /// Empty for backends that don't install from releases with notes.
+    async fn release_notes(
+        &self,
+        _from: Option<&str>,
+        _to: &str,
+    ) -> Result<Vec<release_notes::ReleaseNote>> {
+        Ok(vec![])
+    }
     fn get_plugin_type(&self) -> Option<PluginType> {
         None
     }
@@ -3206,6 +3227,8 @@ pub(crate) trait Backend: Debug + Send + Sync {
         // Toolset installs preflight these options before doing any work, but
         // direct callers such as `install-into` must be protected here too.
         tv.request.ensure_safe_install_options()?;
+        crate::policy::enforce(&tv)?;
+        let sandbox_install = crate::sandbox::install_sandbox_enabled(&tv)?;
 
         // Check for --locked mode: if enabled and no lockfile URL exists, fail early
         // Exempt tool stubs from lockfile requirements since they are ephemeral
@@ -3314,8 +3337,19 @@ pub(crate) trait Backend: Debug + Send + Sync {
         ctx.pr.set_message("install".into());
         self.create_install_dirs(&tv)?;
 
+        let install_sandbox = if sandbox_install {
+            if cfg!(target_os = "linux") {
+                Some(crate::sandbox::SandboxConfig::for_install(&tv))
+            } else {
+                warn!("install_sandbox is only supported on Linux, installing {tv} unsandboxed");
+                None
+            }
+        } else {
+            None
+        };
         let old_tv = tv.clone();
-        let tv = match self.install_version_(&ctx, tv).await {
+        let install = self.install_version_(&ctx, tv);
+        let tv = match crate::sandbox::with_install_sandbox(install_sandbox, install).await {
             Ok(tv) => tv,
             Err(e) => {
                 self.cleanup_install_dirs_on_error(&old_tv);
@@ -3323,6 +3357,10 @@ pub(crate) trait Backend: Debug + Send + Sync {
                 return Err(e);
             }
         };
+        if let Err(err) = crate::policy::enforce_provenance(&tv, &self.get_platform_key()) {
+            self.cleanup_install_dirs_on_error(&tv);
+            return Err(err);
+        }
 
         let install_path = tv.install_path();
         let mut update_install_state = false;
@@ -0,0 +1,422 @@
+//! OCI backend for mise - installs tools published as OCI artifacts
+//!
+//! Tools are pushed to a container registry as ORAS-style artifacts: one
+//! manifest per version tag (or an index with one manifest per platform),
+//! whose layer is the tool's archive or binary. Versions come from the
+//! repository's tags, and the lockfile pins the resolved manifest digest.
+//!
+//! ## Configuration
+//!
+//! ```toml
+//! [tools]
+//! "oci:registry.corp/tools/deployer" = "1.4"
+//!
+//! # Artifacts with several files: pick the layer by its title
+//! "oci:registry.corp/tools/infra" = { version = "2.0.0", layer = "infra.tar.gz", bin_path = "bin" }
+//! ```
+//!
+//! Registry credentials are read the same way as `mise oci push`: from the
+//! docker/podman auth files and credential helpers.
+
+pub(crate) const EXPERIMENTAL: bool = true;
+
+use crate::backend::backend_type::BackendType;
+use crate::backend::options::BackendOptions;
+use crate::backend::platform_target::PlatformTarget;
+use crate::backend::static_helpers::{install_artifact, template_string};
+use crate::backend::{Backend, VersionInfo, runtime_path_for_install_path};
+use crate::cli::args::BackendArg;
+use crate::config::{Config, Settings};
+use crate::file;
+use crate::install_context::InstallContext;
+use crate::lockfile::PlatformInfo;
+use crate::oci::manifest::{Descriptor, ImageManifest};
+use crate::oci::registry;
+use crate::toolset::{ToolVersion, ToolVersionOptions};
+use async_trait::async_trait;
+use eyre::{Result, bail, eyre};
+use itertools::Itertools;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use versions::Versioning;
+use xx::regex;
+
+/// Layer annotation ORAS sets to the pushed file's name.
+const ANNOTATION_TITLE: &str = "org.opencontainers.image.title";
+
+/// Lockfile URL scheme for a digest-pinned manifest reference.
+const LOCK_URL_SCHEME: &str = "oci://";
+
+#[derive(Debug)]
+pub(crate) struct OciBackend {
+    ba: Arc<BackendArg>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OciOptions<'a> {
+    values: BackendOptions<'a>,
+}
+
+impl<'a> OciOptions<'a> {
+    fn new(raw: &'a ToolVersionOptions) -> Self {
+        Self {
+            values: BackendOptions::new(raw),
+        }
+    }
+
+    fn raw(&self) -> &'a ToolVersionOptions {
+        self.values.raw()
+    }
+
+    fn layer(&self) -> Option<String> {
+        self.values.platform_string("layer")
+    }
+
+    fn layer_for_target(&self, target: &PlatformTarget) -> Option<String> {
+        self.values.platform_string_for_target("layer", target)
+    }
+
+    fn bin_path(&self) -> Option<String> {
+        self.values.platform_string("bin_path")
+    }
+}
+
+impl OciBackend {
+    pub(crate) fn from_arg(ba: BackendArg) -> Self {
+        Self { ba: Arc::new(ba) }
+    }
+
+    /// The repository reference, e.g. `registry.corp/tools/deployer`.
+    fn repository(&self) -> &str {
+        &self.ba.tool_name
+    }
+
+    /// The digest-pinned reference recorded in the lockfile for this
+    /// version, if it belongs to this repository.
+    fn locked_reference(&self, tv: &ToolVersion) -> Option<String> {
+        let url = tv
+            .lock_platforms
+            .get(&self.get_platform_key())?
+            .url
+            .as_deref()?;
+        let reference = url.strip_prefix(LOCK_URL_SCHEME)?;
+        let digest = reference
+            .strip_prefix(self.repository())?
+            .strip_prefix('@')?;
+        digest.starts_with("sha256:").then(|| reference.to_string())
+    }
+
+    async fn resolve(
+        &self,
+        reference: &str,
+        target: &PlatformTarget,
+        layer: Option<&str>,
+    ) -> Result<(registry::ResolvedManifest, Descriptor)> {
+        let (os, arch) = oci_platform(target);
+        let resolved = registry::resolve_platform_manifest(reference, (os, arch)).await?;
+        let layer = select_layer(&resolved.manifest, layer)
+            .map_err(|err| eyre!("{reference}: {err}"))?
+            .clone();
+        Ok((resolved, layer))
+    }
+}
+
+/// Returns install-time-only option keys for the OCI backend.
+pub(crate) fn install_time_option_keys() -> Vec<String> {
+    vec![
+        "layer".into(),
+        "bin_path".into(),
+        "format".into(),
+        "strip_components".into(),
+        "bin".into(),
+        "rename_exe".into(),
+    ]
+}
+
+/// OCI `(os, architecture)` names for a mise platform.
+fn oci_platform(target: &PlatformTarget) -> (&str, &str) {
+    let os = match target.os_name() {
+        "macos" => "darwin",
+        os => os,
+    };
+    let arch = match target.arch_name() {
+        "x64" => "amd64",
+        "x86" => "386",
+        arch => arch,
+    };
+    (os, arch)
+}
+
+/// The layer holding the tool: the only one, or the one titled `wanted`.
+fn select_layer<'a>(manifest: &'a ImageManifest, wanted: Option<&str>) -> Result<&'a Descriptor> {
+    let title = |layer: &Descriptor| layer.annotations.get(ANNOTATION_TITLE).cloned();
+    if let Some(wanted) = wanted {
+        return manifest
+            .layers
+            .iter()
+            .find(|layer| title(layer).as_deref() == Some(wanted))
+            .ok_or_else(|| {
+                eyre!(
+                    "no layer titled '{wanted}'; available: {}",
+                    manifest.layers.iter().filter_map(title).join(", ")
+                )
+            });
+    }
+    match manifest.layers.as_slice() {
+        [layer] => Ok(layer),
+        [] => bail!("the manifest has no layers"),
+        layers => bail!(
+            "the manifest has {} layers; set the `layer` option to one of: {}",
+            layers.len(),
+            layers.iter().filter_map(title).join(", ")
+        ),
+    }
+}
+
+/// File name to store a layer under, so `install_artifact` can tell an
+/// archive from a bare binary: the ORAS title when present, otherwise the
+/// tool name with an extension derived from the media type.
+fn layer_file_name(layer: &Descriptor, tool: &str) -> String {
+    if let Some(name) = layer
+        .annotations
+        .get(ANNOTATION_TITLE)
+        .and_then(|title| Path::new(title).file_name())
+        .and_then(|name| name.to_str())
+    {
+        return name.to_string();
+    }
+    let base = tool.rsplit('/').next().unwrap_or(tool);
+    let media_type = layer.media_type.as_str();
+    let ext = if media_type.ends_with("tar+gzip") || media_type.ends_with("tar.gzip") {
+        ".tar.gz"
+    } else if media_type.ends_with("tar+zstd") {
+        ".tar.zst"
+    } else if media_type.ends_with(".tar") {
+        ".tar"
+    } else {
+        ""
+    };
+    format!("{base}{ext}")
+}
+
+#[async_trait]
+impl Backend for OciBackend {
+    fn get_type(&self) -> BackendType {
+        BackendType::Oci
+    }
+
+    fn ba(&self) -> &Arc<BackendArg> {
+        &self.ba
+    }
+
+    fn mark_prereleases_from_version_pattern(&self) -> bool {
+        true
+    }
+
+    async fn _list_remote_versions(&self, _config: &Arc<Config>) -> Result<Vec<VersionInfo>> {
+        let tags = registry::list_tags(self.repository()).await?;
+        Ok(tags
+            .into_iter()
+            // skip `latest` and cosign's `sha256-<digest>.sig` style tags
+            .filter(|tag| regex!(r"^v?[0-9]").is_match(tag))
+            .sorted_by_cached_key(|tag| Versioning::new(tag))
+            .map(|version| VersionInfo {
+                version,
+                ..Default::default()
+            })
+            .collect())
+    }
+
+    async fn resolve_lock_info(
+        &self,
+        tv: &ToolVersion,
+        target: &PlatformTarget,
+    ) -> Result<PlatformInfo> {
+        let raw_opts = tv.request.options();
+        let opts = OciOptions::new(&raw_opts);
+        let reference = format!("{}:{}", self.repository(), tv.version);
+        let layer = opts.layer_for_target(target);
+        let (resolved, layer) = self.resolve(&reference, target, layer.as_deref()).await?;
+        Ok(PlatformInfo {
+            url: Some(format!(
+                "{LOCK_URL_SCHEME}{}@{}",
+                self.repository(),
+                resolved.digest
+            )),
+            checksum: Some(layer.digest),
+            size: Some(layer.size),
+            ..Default::default()
+        })
+    }
+
+    async fn install_version_(
+        &self,
+        ctx: &InstallContext,
+        mut tv: ToolVersion,
+    ) -> Result<ToolVersion> {
+        let raw_opts = tv.request.options();
+        let opts = OciOptions::new(&raw_opts);
+        let platform_key = self.get_platform_key();
+
+        // A locked manifest digest wins over the tag, which may have moved.
+        let reference = self
+            .locked_reference(&tv)
+            .unwrap_or_else(|| format!("{}:{}", self.repository(), tv.version));
+        ctx.pr.set_message(format!("resolve {reference}"));
+        let layer = opts.layer();
+        let (resolved, layer) = self
+            .resolve(
+                &reference,
+                &PlatformTarget::from_current(),
+                layer.as_deref(),
+            )
+            .await?;
+
+        let platform_info = tv.lock_platforms.entry(platform_key).or_default();
+        if let Some(checksum) = &platform_info.checksum
+            && checksum != &layer.digest
+        {
+            bail!(
+                "{reference}: layer digest {} does not match the lockfile checksum {checksum}",
+                layer.digest
+            );
+        }
+        platform_info.url = Some(format!(
+            "{LOCK_URL_SCHEME}{}@{}",
+            self.repository(),
+            resolved.digest
+        ));
+        if Settings::get().lockfile_enabled() {
+            platform_info.checksum = Some(layer.digest.clone());
+            platform_info.size = Some(layer.size);
+        }
+
+        ctx.pr.next_operation();
+        let filename = layer_file_name(&layer, self.repository());
+        let file_path = tv.download_path().join(&filename);
+        ctx.pr.set_message(format!("download {filename}"));
+        file::create_dir_all(tv.download_path())?;
+        registry::download_verified_blob(
+            &reference,
+            &layer.digest,
+            &file_path,
+            Some(ctx.pr.as_ref()),
+        )
+        .await?;
+
+        ctx.pr.next_operation();
+        ctx.pr.set_message("extract".into());
+        install_artifact(&tv, &file_path, opts.raw(), Some(ctx.pr.as_ref()))?;
+
+        Ok(tv)
+    }
+
+    async fn list_bin_paths(
+        &self,
+        _config: &Arc<Config>,
+        tv: &ToolVersion,
+    ) -> Result<Vec<PathBuf>> {
+        let raw_opts = tv.request.options();
+        let opts = OciOptions::new(&raw_opts);
+        if let Some(bin_path_template) = opts.bin_path() {
+            let bin_path = template_string(&bin_path_template, tv);
+            return Ok(vec![runtime_path_for_install_path(
+                tv,
+                tv.install_path().join(bin_path),
+            )]);
+        }
+        let bin_dir = tv.install_path().join("bin");
+        if bin_dir.exists() {
+            return Ok(vec![runtime_path_for_install_path(tv, bin_dir)]);
+        }
+        Ok(vec![tv.runtime_path()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::Platform;
+    use indexmap::IndexMap;
+
+    fn layer(media_type: &str, title: Option<&str>) -> Descriptor {
+        Descriptor {
+            media_type: media_type.to_string(),
+            size: 1,
+            digest: format!("sha256:{}", "0".repeat(64)),
+            annotations: title
+                .map(|title| IndexMap::from([(ANNOTATION_TITLE.to_string(), title.to_string())]))
+                .unwrap_or_default(),
+            platform: None,
+        }
+    }
+
+    fn manifest(layers: Vec<Descriptor>) -> ImageManifest {
+        ImageManifest {
+            schema_version: 2,
+            media_type: crate::oci::manifest::MEDIA_TYPE_OCI_MANIFEST.to_string(),
+            config: layer("application/vnd.oci.empty.v1+json", None),
+            layers,
+            annotations: IndexMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_oci_platform_names() {
+        let target = |key: &str| PlatformTarget::new(Platform::parse(key).unwrap());
+        assert_eq!(oci_platform(&target("macos-arm64")), ("darwin", "arm64"));
+        assert_eq!(oci_platform(&target("linux-x64")), ("linux", "amd64"));
+        assert_eq!(oci_platform(&target("windows-x64")), ("windows", "amd64"));
+    }
+
+    #[test]
+    fn test_select_layer() {
+        let single = manifest(vec![layer("application/octet-stream", None)]);
+        assert!(select_layer(&single, None).is_ok());
+
+        let multi = manifest(vec![
+            layer(
+                "application/vnd.oci.image.layer.v1.tar+gzip",
+                Some("infra.tar.gz"),
+            ),
+            layer("text/markdown", Some("README.md")),
+        ]);
+        let err = select_layer(&multi, None).unwrap_err().to_string();
+        assert!(err.contains("infra.tar.gz, README.md"), "{err}");
+        assert_eq!(
+            select_layer(&multi, Some("infra.tar.gz"))
+                .unwrap()
+                .media_type,
+            "application/vnd.oci.image.layer.v1.tar+gzip"
+        );
+        assert!(select_layer(&multi, Some("missing")).is_err());
+        assert!(select_layer(&manifest(vec![]), None).is_err());
+    }
+
+    #[test]
+    fn test_layer_file_name() {
+        let tool = "registry.corp/tools/deployer";
+        assert_eq!(
+            layer_file_name(
+                &layer("application/octet-stream", Some("../deployer-linux")),
+                tool
+            ),
+            "deployer-linux"
+        );
+        assert_eq!(
+            layer_file_name(
+                &layer("application/vnd.oci.image.layer.v1.tar+gzip", None),
+                tool
+            ),
+            "deployer.tar.gz"
+        );
+        assert_eq!(
+            layer_file_name(&layer("application/vnd.oci.image.layer.v1.tar", None), tool),
+            "deployer.tar"
+        );
+        assert_eq!(
+            layer_file_name(&layer("application/vnd.corp.binary", None), tool),
+            "deployer"
+        );
+    }
+}
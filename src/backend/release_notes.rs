@@ -0,0 +1,377 @@
+//! Release notes between two versions of a tool
+//!
+//! Backends that install from forge releases implement
+//! [`Backend::release_notes`](crate::backend::Backend::release_notes) with the
+//! fetchers here; `mise outdated --changelog`, the `mise upgrade
+//! --interactive` picker and `mise upgrade --branch` commits show the result.
+
+use std::cmp::Ordering;
+
+use console::style;
+use eyre::Result;
+use itertools::Itertools;
+use serde::Serialize;
+use versions::Versioning;
+use xx::regex;
+
+use crate::semver::split_version_prefix;
+use crate::{forgejo, github, gitlab};
+
+/// Lines of a release body shown before it is cut off in terminal output.
+const MAX_BODY_LINES: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub(crate) struct ReleaseNote {
+    pub tag: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Whether the notes call out a breaking change, or the release starts a
+    /// new major version.
+    pub breaking: bool,
+}
+
+impl ReleaseNote {
+    fn new(tag: String, title: Option<String>, body: Option<String>, url: Option<String>) -> Self {
+        let body = body.unwrap_or_default().replace("\r\n", "\n");
+        let breaking =
+            title.as_deref().is_some_and(is_breaking_line) || body.lines().any(is_breaking_line);
+        Self {
+            title: title.filter(|t| !t.is_empty() && *t != tag),
+            tag,
+            body,
+            url,
+            breaking,
+        }
+    }
+}
+
+pub(crate) async fn github(api_url: &str, repo: &str) -> Result<Vec<ReleaseNote>> {
+    Ok(github::list_release_notes_from_url(api_url, repo)
+        .await?
+        .into_iter()
+        .filter(|r| !r.prerelease)
+        .map(|r| ReleaseNote::new(r.tag_name, r.name, r.body, r.html_url))
+        .collect())
+}
+
+pub(crate) async fn gitlab(api_url: &str, repo: &str) -> Result<Vec<ReleaseNote>> {
+    Ok(gitlab::list_releases_from_url(api_url, repo)
+        .await?
+        .into_iter()
+        .map(|r| ReleaseNote::new(r.tag_name, None, r.description, None))
+        .collect())
+}
+
+pub(crate) async fn forgejo(api_url: &str, repo: &str) -> Result<Vec<ReleaseNote>> {
+    Ok(forgejo::list_release_notes_from_url(api_url, repo)
+        .await?
+        .into_iter()
+        .filter(|r| !r.prerelease)
+        .map(|r| ReleaseNote::new(r.tag_name, r.name, r.body, r.html_url))
+        .collect())
+}
+
+/// The releases after `from` up to and including `to`, newest first. Tags are
+/// read as versions after removing `version_prefix`, or any leading non-numeric
+/// prefix like `v` or `jq-`. Without `from` only the release of `to` is kept.
+pub(crate) fn between(
+    notes: Vec<ReleaseNote>,
+    from: Option<&str>,
+    to: &str,
+    version_prefix: Option<&str>,
+) -> Vec<ReleaseNote> {
+    let version = |tag: &str| -> String {
+        let tag = version_prefix
+            .and_then(|prefix| tag.strip_prefix(prefix))
+            .unwrap_or(tag);
+        split_version_prefix(tag).1
+    };
+    let to = split_version_prefix(to).1;
+    let from = from.map(|from| split_version_prefix(from).1);
+    let mut notes = notes
+        .into_iter()
+        .map(|note| (version(&note.tag), note))
+        .filter(|(v, _)| {
+            compare(v, &to) != Ordering::Greater
+                && match &from {
+                    Some(from) => compare(v, from) == Ordering::Greater,
+                    None => compare(v, &to) == Ordering::Equal,
+                }
+        })
+        .sorted_by(|(a, _), (b, _)| compare(a, b))
+        .collect::<Vec<_>>();
+    // a release that starts a new major version is breaking whether or not its
+    // notes say so; 0.x versions don't follow that convention
+    let mut prev_major = from.as_deref().and_then(major);
+    for (v, note) in &mut notes {
+        let note_major = major(v);
+        if let (Some(prev), Some(next)) = (prev_major, note_major)
+            && prev >= 1
+            && next > prev
+        {
+            note.breaking = true;
+        }
+        prev_major = note_major.or(prev_major);
+    }
+    notes.into_iter().rev().map(|(_, note)| note).collect()
+}
+
+/// A one-line summary for the upgrade picker, e.g. `3 releases, breaking: v3.0.0`.
+pub(crate) fn summary(notes: &[ReleaseNote]) -> String {
+    let s = if notes.len() == 1 { "" } else { "s" };
+    let mut summary = format!("{} release{s}", notes.len());
+    let breaking = notes
+        .iter()
+        .filter(|n| n.breaking)
+        .map(|n| &n.tag)
+        .join(", ");
+    if !breaking.is_empty() {
+        summary.push_str(&format!(", breaking: {breaking}"));
+    }
+    summary
+}
+
+/// The notes of one tool as a terminal section, with breaking changes in red.
+pub(crate) fn render(name: &str, from: Option<&str>, to: &str, notes: &[ReleaseNote]) -> String {
+    let mut out = match from {
+        Some(from) => format!("{} {from} → {to}\n", style(name).bold()),
+        None => format!("{} {to}\n", style(name).bold()),
+    };
+    if notes.is_empty() {
+        out.push_str(&format!("  {}\n", style("no release notes found").dim()));
+    }
+    for note in notes {
+        let mut heading = format!("  {}", style(&note.tag).bold());
+        if let Some(title) = &note.title {
+            heading.push_str(&format!(" {title}"));
+        }
+        if note.breaking {
+            heading.push_str(&format!(" {}", style("BREAKING").red().bold()));
+        }
+        if let Some(url) = &note.url {
+            heading.push_str(&format!(" {}", style(url).dim()));
+        }
+        out.push_str(&heading);
+        out.push('\n');
+        let lines = note.body.trim().lines().collect::<Vec<_>>();
+        for line in lines.iter().take(MAX_BODY_LINES) {
+            if is_breaking_line(line) {
+                out.push_str(&format!("    {}\n", style(line).red()));
+            } else {
+                out.push_str(&format!("    {line}\n"));
+            }
+        }
+        if lines.len() > MAX_BODY_LINES {
+            let more = lines.len() - MAX_BODY_LINES;
+            out.push_str(&format!(
+                "    {}\n",
+                style(format!("… {more} more lines")).dim()
+            ));
+        }
+    }
+    out
+}
+
+/// The notes of one tool as markdown, for commit messages and pull requests.
+pub(crate) fn markdown(name: &str, from: Option<&str>, to: &str, notes: &[ReleaseNote]) -> String {
+    let mut out = match from {
+        Some(from) => format!("### {name} {from} → {to}\n"),
+        None => format!("### {name} {to}\n"),
+    };
+    for note in notes {
+        let mut heading = match &note.url {
+            Some(url) => format!("\n#### [{}]({url})", note.tag),
+            None => format!("\n#### {}", note.tag),
+        };
+        if let Some(title) = &note.title {
+            heading.push_str(&format!(" {title}"));
+        }
+        if note.breaking {
+            heading.push_str(" ⚠️ BREAKING");
+        }
+        out.push_str(&heading);
+        out.push_str("\n\n");
+        let body = note.body.trim();
+        if !body.is_empty() {
+            out.push_str(body);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Whether a line of release notes announces a breaking change: a
+/// `BREAKING CHANGE(S)` marker, a heading such as `## ⚠️ Breaking changes`, or a
+/// conventional commit like `feat!:`. Prose such as "no breaking changes" or
+/// "non-breaking" doesn't count.
+fn is_breaking_line(line: &str) -> bool {
+    regex!(r"\bBREAKING[ -]CHANGES?\b").is_match(line)
+        || regex!(r"(?i)^\s*(?:#{1,6}|\*\*)\s*(?:[^\w\s]+\s*)?breaking(?:\s+changes?)?\b")
+            .is_match(line)
+        || regex!(r"^\s*(?:[-*]\s+)?(?:\*\*)?\w+(?:\([^)]*\))?!:").is_match(line)
+}
+
+fn major(version: &str) -> Option<u64> {
+    version.split(['.', '-', '+']).next()?.parse().ok()
+}
+
+fn compare(a: &str, b: &str) -> Ordering {
+    match (Versioning::new(a), Versioning::new(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn note(tag: &str, body: &str) -> ReleaseNote {
+        ReleaseNote::new(tag.to_string(), None, Some(body.to_string()), None)
+    }
+
+    fn tags(notes: &[ReleaseNote]) -> Vec<&str> {
+        notes.iter().map(|n| n.tag.as_str()).collect()
+    }
+
+    #[test]
+    fn test_between() {
+        let notes = vec![
+            note("v3.0.1", ""),
+            note("v3.0.0", ""),
+            note("v2.1.0", ""),
+            note("v2.0.1", ""),
+            note("v2.0.0", ""),
+        ];
+        assert_eq!(
+            tags(&between(notes.clone(), Some("2.0.0"), "3.0.0", None)),
+            vec!["v3.0.0", "v2.1.0", "v2.0.1"]
+        );
+        assert_eq!(
+            tags(&between(notes.clone(), None, "2.1.0", None)),
+            vec!["v2.1.0"]
+        );
+        let prefixed = vec![note("cli-v1.2.0", ""), note("cli-v1.1.0", "")];
+        assert_eq!(
+            tags(&between(prefixed, Some("1.1.0"), "1.2.0", Some("cli-v"))),
+            vec!["cli-v1.2.0"]
+        );
+    }
+
+    #[test]
+    fn test_between_marks_new_major_versions_breaking() {
+        let notes = vec![note("v3.0.0", ""), note("v2.1.0", ""), note("v0.2.0", "")];
+        let notes = between(notes, Some("2.0.0"), "3.0.0", None);
+        assert_eq!(
+            notes.iter().map(|n| n.breaking).collect::<Vec<_>>(),
+            vec![true, false]
+        );
+        let zero = between(
+            vec![note("v0.3.0", ""), note("v0.2.0", "")],
+            Some("0.1.0"),
+            "0.3.0",
+            None,
+        );
+        assert!(zero.iter().all(|n| !n.breaking));
+    }
+
+    #[test]
+    fn test_is_breaking_line() {
+        assert!(is_breaking_line("## BREAKING CHANGES"));
+        assert!(is_breaking_line("### ⚠ BREAKING CHANGES"));
+        assert!(is_breaking_line("BREAKING CHANGE: `--foo` was removed"));
+        assert!(is_breaking_line("## 💥 Breaking changes"));
+        assert!(is_breaking_line("**Breaking**"));
+        assert!(is_breaking_line("- feat(cli)!: rename --foo to --bar"));
+        assert!(is_breaking_line("* refactor!: remove deprecated API"));
+        assert!(!is_breaking_line("No breaking changes"));
+        assert!(!is_breaking_line("- This release has no breaking changes."));
+        assert!(!is_breaking_line("## No breaking changes"));
+        assert!(!is_breaking_line(
+            "- refactor: non-breaking cleanup of the parser"
+        ));
+        assert!(!is_breaking_line("- Breaking: the docs were reorganized"));
+        assert!(!is_breaking_line("- fix: handle unbreakable spaces"));
+        assert!(!is_breaking_line("- feat: add --json flag!"));
+    }
+
+    #[test]
+    fn test_summary() {
+        let notes = vec![
+            ReleaseNote::new(
+                "v3.0.0".into(),
+                None,
+                Some("BREAKING CHANGE: drop --old".into()),
+                None,
+            ),
+            note("v2.1.0", "- fix: things"),
+        ];
+        assert_eq!(summary(&notes), "2 releases, breaking: v3.0.0");
+        assert_eq!(summary(&notes[1..]), "1 release");
+    }
+
+    #[tokio::test]
+    async fn test_forgejo_release_notes() {
+        let _config = crate::config::Config::get().await.unwrap();
+        let mut server = mockito::Server::new_async().await;
+        let base = server.url();
+        let repo = "owner/release-notes";
+        let body = serde_json::json!([
+            {"tag_name": "v2.1.0-rc.1", "body": "rc", "draft": false, "prerelease": true},
+            {"tag_name": "v2.0.1", "name": "v2.0.1", "body": "- fix: crash on start\r\n", "draft": false, "prerelease": false},
+            {"tag_name": "v2.0.0", "name": "Two", "body": "## BREAKING CHANGES\n- drop --old", "html_url": "https://codeberg.org/owner/release-notes/releases/tag/v2.0.0", "draft": false, "prerelease": false},
+            {"tag_name": "v1.9.0", "body": "draft notes", "draft": true, "prerelease": false},
+            {"tag_name": "v1.5.0", "body": "", "draft": false, "prerelease": false},
+        ]);
+        let mock = server
+            .mock("GET", format!("/repos/{repo}/releases").as_str())
+            .match_query(mockito::Matcher::UrlEncoded("limit".into(), "100".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let notes = between(
+            forgejo(&base, repo).await.unwrap(),
+            Some("1.5.0"),
+            "2.1.0",
+            None,
+        );
+        mock.assert_async().await;
+        assert_eq!(
+            notes,
+            vec![
+                note("v2.0.1", "- fix: crash on start\n"),
+                ReleaseNote {
+                    tag: "v2.0.0".into(),
+                    title: Some("Two".into()),
+                    body: "## BREAKING CHANGES\n- drop --old".into(),
+                    url: Some(
+                        "https://codeberg.org/owner/release-notes/releases/tag/v2.0.0".into()
+                    ),
+                    breaking: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown() {
+        let notes = vec![ReleaseNote::new(
+            "v3.0.0".into(),
+            Some("Three".into()),
+            Some("- feat!: drop --old\n".into()),
+            Some("https://github.com/o/r/releases/tag/v3.0.0".into()),
+        )];
+        assert_eq!(
+            markdown("github:o/r", Some("2.1.0"), "3.0.0", &notes),
+            "### github:o/r 2.1.0 → 3.0.0\n\n#### [v3.0.0](https://github.com/o/r/releases/tag/v3.0.0) Three ⚠️ BREAKING\n\n- feat!: drop --old\n"
+        );
+    }
+}
@@ -24,6 +24,7 @@ use crate::install_context::InstallContext;
 use crate::lockfile::{PlatformInfo, ProvenanceType};
 use crate::plugins::Plugin;
 use crate::plugins::vfox_plugin::VfoxPlugin;
+use crate::sandbox;
 use crate::toolset::{ToolOptions, ToolVersion, Toolset, install_state};
 use crate::ui::multi_progress_report::MultiProgressReport;
 
@@ -327,19 +328,28 @@ impl Backend for VfoxBackend {
             vfox.cmd_env = Some(cmd_env);
         }
 
+        // The Lua hooks run in-process, so with an install sandbox they run on a confined thread
+        let pathname = self.pathname.clone();
+        let version = tv.version.clone();
+        let (install_path, download_path) = (tv.install_path(), tv.download_path());
+        let options = tool_options.into_backend_options().into_map();
+
         // Use backend methods if the plugin supports them
         if self.is_backend_plugin() {
-            let tool_name = self.get_tool_name()?;
-            vfox.backend_install(
-                &self.pathname,
-                tool_name,
-                &tv.version,
-                tv.install_path(),
-                tv.download_path(),
-                tool_options.into_backend_options().into_map(),
-            )
-            .await
-            .wrap_err("Backend install method failed")?;
+            let tool_name = self.get_tool_name()?.to_string();
+            sandbox::confine(async move {
+                vfox.backend_install(
+                    &pathname,
+                    &tool_name,
+                    &version,
+                    install_path,
+                    download_path,
+                    options,
+                )
+                .await
+                .wrap_err("Backend install method failed")
+            })
+            .await?;
             return Ok(tv);
         }
 
@@ -363,15 +373,18 @@ impl Backend for VfoxBackend {
             .and_then(|pi| pi.provenance.take());
 
         // Use default vfox behavior for traditional plugins
-        let result = vfox
-            .install_with_download_dir_and_options(
-                &self.pathname,
-                &tv.version,
-                tv.install_path(),
-                tv.download_path(),
-                tool_options.into_backend_options().into_map(),
-            )
-            .await?;
+        let result = sandbox::confine(async move {
+            Ok(vfox
+                .install_with_download_dir_and_options(
+                    &pathname,
+                    &version,
+                    install_path,
+                    download_path,
+                    options,
+                )
+                .await?)
+        })
+        .await?;
 
         // Record provenance if attestation verification succeeded
         if let Some(att) = result.verified_attestation {
@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use clap::Subcommand;
+use eyre::Result;
+
+use crate::backend::Backend;
+use crate::cli::args::ToolArg;
+use crate::config::Config;
+use crate::toolset::{ToolVersion, ToolsetBuilder};
+
+mod scan;
+mod update;
+
+/// Check tools for known vulnerabilities
+///
+/// Looks up installed and configured tool versions in a local snapshot of the
+/// OSV database (https://osv.dev), so scanning needs no network. Download the
+/// snapshot with `mise audit update` first.
+///
+/// Bare `mise audit` is the same as `mise audit scan`.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub(crate) struct Audit {
+    #[clap(subcommand)]
+    command: Option<Commands>,
+
+    #[clap(flatten)]
+    scan: scan::AuditScan,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    Scan(scan::AuditScan),
+    Update(update::AuditUpdate),
+}
+
+impl Commands {
+    pub(crate) async fn run(self) -> Result<()> {
+        match self {
+            Self::Scan(cmd) => cmd.run().await,
+            Self::Update(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl Audit {
+    pub(crate) async fn run(self) -> Result<()> {
+        let cmd = self.command.unwrap_or(Commands::Scan(self.scan));
+
+        cmd.run().await
+    }
+}
+
+/// The tool versions to audit: the given tools, or every installed version
+/// and every version the current config resolves to.
+pub(super) async fn tool_versions(
+    config: &Arc<Config>,
+    tools: &[ToolArg],
+) -> Result<Vec<(Arc<dyn Backend>, ToolVersion)>> {
+    let ts = ToolsetBuilder::new().with_args(tools).build(config).await?;
+    if tools.is_empty() {
+        return ts.list_all_versions(config).await;
+    }
+    let requested = tools.iter().map(|t| t.ba.clone()).collect::<HashSet<_>>();
+    Ok(ts
+        .list_current_versions()
+        .into_iter()
+        .filter(|(_, tv)| requested.contains(tv.ba()))
+        .collect())
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise audit update</bold>            # Download the snapshot for the current tools
+    $ <bold>mise audit npm:prettier</bold>      # Scan one tool
+    $ <bold>mise audit --fail-on high</bold>    # Exit 1 on high or critical vulnerabilities
+    $ <bold>mise audit --json</bold>
+
+    $ <bold>mise audit</bold>
+    Tool                   Vulnerability        Severity  Fixed In  Fix
+    npm:next@14.1.0        GHSA-fr5h-rqp8-mj6g  high      14.1.1    mise use npm:next@14.1.1
+    github:cli/cli@2.40.0  GO-2024-2466         unknown   2.40.1    mise use github:cli/cli@2.40.1
+"#
+);
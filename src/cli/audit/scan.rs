@@ -0,0 +1,146 @@
+use std::collections::BTreeSet;
+
+use eyre::Result;
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::cli::args::ToolArg;
+use crate::config::Config;
+use crate::osv::{self, Package, Severity};
+use crate::ui::table::MiseTable;
+
+/// Scan tools for known vulnerabilities
+///
+/// Looks up each tool version in the local OSV snapshot. Tools are matched by
+/// backend: npm, pipx, cargo, gem, go and maven tools as packages of their
+/// ecosystem, and aqua, github and ubi tools by their GitHub repository. Other
+/// tools are listed as not scanned.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub(crate) struct AuditScan {
+    /// Tool(s) to scan
+    /// e.g.: node@20 npm:prettier
+    /// If not specified, installed tools and tools in the current config are scanned
+    #[clap(value_name = "TOOL@VERSION", verbatim_doc_comment)]
+    tool: Vec<ToolArg>,
+
+    /// Exit with status 1 if a vulnerability of this severity or higher is found
+    ///
+    /// Vulnerabilities the database has no severity for count as any severity.
+    #[clap(long, value_name = "SEVERITY", verbatim_doc_comment)]
+    fail_on: Option<Severity>,
+
+    /// Output in JSON format
+    #[clap(short = 'J', long)]
+    json: bool,
+
+    /// Don't show table header
+    #[clap(long)]
+    no_header: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Vulnerability {
+    tool: String,
+    version: String,
+    purl: String,
+    id: String,
+    aliases: Vec<String>,
+    summary: Option<String>,
+    severity: Severity,
+    fixed_in: Option<String>,
+    /// The command that moves the tool to the fixed version.
+    suggestion: Option<String>,
+}
+
+impl AuditScan {
+    pub(crate) async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let mut targets = vec![];
+        let mut tools = vec![];
+        let mut unsupported = BTreeSet::new();
+        let mut missing = BTreeSet::new();
+        for (_, tv) in super::tool_versions(&config, &self.tool).await? {
+            let Some(package) = Package::for_backend(tv.ba()) else {
+                unsupported.insert(tv.ba().short.clone());
+                continue;
+            };
+            if !osv::has_snapshot(package.ecosystem) {
+                missing.insert(package.ecosystem.osv_name());
+                continue;
+            }
+            tools.push(tv.ba().short.clone());
+            targets.push((package, tv.version.clone()));
+        }
+        if !missing.is_empty() {
+            warn!(
+                "no OSV snapshot for {} in {}, run `mise audit update` to download it",
+                missing.iter().join(", "),
+                osv::db_dir().display()
+            );
+        }
+        if !unsupported.is_empty() && !self.json {
+            info!(
+                "not scanned, no OSV ecosystem for: {}",
+                unsupported.iter().join(", ")
+            );
+        }
+
+        let findings = osv::scan(&targets)?;
+        let mut vulnerabilities = vec![];
+        for ((tool, (package, version)), findings) in tools.iter().zip(&targets).zip(findings) {
+            for finding in findings {
+                vulnerabilities.push(Vulnerability {
+                    tool: tool.clone(),
+                    version: version.clone(),
+                    purl: package.purl(version),
+                    suggestion: finding
+                        .fixed_in
+                        .as_ref()
+                        .map(|fixed_in| format!("mise use {tool}@{fixed_in}")),
+                    id: finding.id,
+                    aliases: finding.aliases,
+                    summary: finding.summary,
+                    severity: finding.severity,
+                    fixed_in: finding.fixed_in,
+                });
+            }
+        }
+        vulnerabilities.sort_by(|a, b| {
+            (b.severity, &a.tool, &a.version, &a.id).cmp(&(a.severity, &b.tool, &b.version, &b.id))
+        });
+
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&vulnerabilities)?);
+        } else if vulnerabilities.is_empty() {
+            info!(
+                "no known vulnerabilities in {} tool versions",
+                targets.len()
+            );
+        } else {
+            let mut table = MiseTable::new(
+                self.no_header,
+                &["Tool", "Vulnerability", "Severity", "Fixed In", "Fix"],
+            );
+            for v in &vulnerabilities {
+                table.add_row(vec![
+                    format!("{}@{}", v.tool, v.version),
+                    v.id.clone(),
+                    v.severity.to_string(),
+                    v.fixed_in.clone().unwrap_or_else(|| "-".to_string()),
+                    v.suggestion.clone().unwrap_or_default(),
+                ]);
+            }
+            table.print()?;
+        }
+
+        if let Some(fail_on) = self.fail_on
+            && vulnerabilities
+                .iter()
+                .any(|v| v.severity == Severity::Unknown || v.severity >= fail_on)
+        {
+            return Err(crate::request_exit(1));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::BTreeSet;
+
+use eyre::Result;
+use itertools::Itertools;
+
+use crate::config::Config;
+use crate::osv::{self, Ecosystem, Package};
+
+/// Download the OSV vulnerability database snapshot
+///
+/// Downloads OSV's export of each ecosystem the installed and configured tools
+/// belong to into the `audit.db_dir` directory, replacing the snapshot there.
+/// `mise audit` reads only this snapshot, so run this again to pick up new
+/// advisories.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub(crate) struct AuditUpdate {
+    /// Download every ecosystem mise can scan, not just the ones in use
+    ///
+    /// Useful to prepare a snapshot for machines without network access.
+    #[clap(long, verbatim_doc_comment)]
+    all: bool,
+}
+
+impl AuditUpdate {
+    pub(crate) async fn run(self) -> Result<()> {
+        let ecosystems = if self.all {
+            BTreeSet::from([
+                Ecosystem::CratesIo,
+                Ecosystem::Go,
+                Ecosystem::Maven,
+                Ecosystem::Npm,
+                Ecosystem::PyPI,
+                Ecosystem::RubyGems,
+            ])
+        } else {
+            let config = Config::get().await?;
+            super::tool_versions(&config, &[])
+                .await?
+                .iter()
+                .filter_map(|(_, tv)| Package::for_backend(tv.ba()))
+                .map(|p| p.ecosystem)
+                .collect()
+        };
+        if ecosystems.is_empty() {
+            info!("no tools with an OSV ecosystem, nothing to download");
+            return Ok(());
+        }
+        osv::update(&ecosystems).await?;
+        info!(
+            "updated OSV snapshot for {} in {}",
+            ecosystems.iter().map(|e| e.osv_name()).join(", "),
+            osv::db_dir().display()
+        );
+        Ok(())
+    }
+}
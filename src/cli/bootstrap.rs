@@ -124,11 +124,17 @@ fn deferred_flags(command: clap::Command) -> clap::Command {
 ///
 /// 0. `mise bootstrap accounts apply` — converge `[bootstrap.users]` and
 ///    `[bootstrap.groups]` (Linux)
+///    0.5. `mise bootstrap certificates apply` and
+///    `mise bootstrap ssh known-hosts apply` — trust `[bootstrap.certificates]`
+///    CAs (Linux) and pin `[bootstrap.ssh.known_hosts]` host keys
 /// 1. `mise bootstrap plugins apply` — install `[bootstrap.plugins]`
 ///    1.7. `[bootstrap.hooks.pre-packages]` — optional setup hook
 /// 2. Install built-in-manager entries from `[bootstrap.packages]`
 /// 3. `mise bootstrap files apply` — converge `[bootstrap.files]` and
 ///    `[bootstrap.directories]`
+///    3.5. `mise bootstrap linux kernel-modules apply`, `sysctl apply` and
+///    `environment apply` — converge `[bootstrap.linux.kernel_modules]`,
+///    `[bootstrap.linux.sysctl]` and `[bootstrap.linux.environment]` (Linux)
 /// 4. `mise bootstrap services apply` — converge `[bootstrap.services]`
 ///    systemd system services (Linux)
 /// 5. `mise bootstrap firewall apply` — converge `[bootstrap.linux.firewall]`
@@ -215,7 +221,16 @@ enum BootstrapPart {
     Plugins,
     Packages,
     Accounts,
+    Certificates,
+    #[clap(name = "ssh-known-hosts", alias = "known-hosts")]
+    KnownHosts,
     Files,
+    #[clap(name = "linux-kernel-modules", alias = "kernel-modules")]
+    KernelModules,
+    #[clap(name = "linux-sysctl", alias = "sysctl")]
+    Sysctl,
+    #[clap(name = "linux-environment", alias = "environment")]
+    Environment,
     Services,
     Firewall,
     Compose,
@@ -238,11 +253,16 @@ enum BootstrapPart {
 impl BootstrapPart {
     // Keep this in sync with every enum variant. `--only` computes a
     // complement from ALL, so an omitted variant would always run.
-    const ALL: [Self; 17] = [
+    const ALL: [Self; 22] = [
         Self::Plugins,
         Self::Packages,
         Self::Accounts,
+        Self::Certificates,
+        Self::KnownHosts,
         Self::Files,
+        Self::KernelModules,
+        Self::Sysctl,
+        Self::Environment,
         Self::Services,
         Self::Firewall,
         Self::Compose,
@@ -262,15 +282,23 @@ impl BootstrapPart {
 type BootstrapPredictionGraph = HashMap<ResourceId, (ResourceAction, Vec<ResourceId>)>;
 
 fn bootstrap_resource_is_skipped(resource: &ResourceId, skip: &HashSet<BootstrapPart>) -> bool {
-    let part = match resource.kind.as_str() {
+    bootstrap_resource_part(resource).is_some_and(|part| skip.contains(&part))
+}
+
+fn bootstrap_resource_part(resource: &ResourceId) -> Option<BootstrapPart> {
+    Some(match resource.kind.as_str() {
         "package" => BootstrapPart::Packages,
         "file" | "directory" => BootstrapPart::Files,
+        "certificate" => BootstrapPart::Certificates,
+        "known-host" => BootstrapPart::KnownHosts,
+        "kernel-module" => BootstrapPart::KernelModules,
+        "sysctl" => BootstrapPart::Sysctl,
+        "environment" => BootstrapPart::Environment,
         "service" => BootstrapPart::Services,
         "firewall" | "firewall-rule" => BootstrapPart::Firewall,
         "user" | "group" => BootstrapPart::Accounts,
-        _ => return false,
-    };
-    skip.contains(&part)
+        _ => return None,
+    })
 }
 
 fn bootstrap_prediction_has_skipped_change(
@@ -312,6 +340,7 @@ enum Commands {
     #[clap(name = "__inspect-firewall-plan", hide = true)]
     InspectFirewallPlan(BootstrapInspectFirewallPlan),
     Accounts(BootstrapAccounts),
+    Certificates(BootstrapCertificates),
     Compose(BootstrapCompose),
     Dotfiles(BootstrapDotfiles),
     Files(BootstrapFiles),
@@ -329,12 +358,15 @@ enum Commands {
     Plugins(BootstrapPlugins),
     Remote(Box<BootstrapRemote>),
     Repos(BootstrapRepos),
+    Rollback(BootstrapRollback),
     Secrets(BootstrapSecrets),
     Services(BootstrapServices),
+    Ssh(BootstrapSsh),
     Status(BootstrapStatus),
     #[clap(hide = true)]
     Systemd(BootstrapSystemd),
     User(BootstrapUser),
+    Watch(BootstrapWatch),
 }
 
 /// Show the aggregate bootstrap status
@@ -371,6 +403,85 @@ struct BootstrapPlan {
     prompt_secrets: bool,
 }
 
+/// Undo bootstrap applies from their rollback journals
+///
+/// Every `mise bootstrap` run first records what the resources it is about
+/// to change looked like. Rollback restores managed files and directories,
+/// dotfiles, services and the firewall from the latest apply, or from every
+/// apply back to and including `--to`. Accounts, packages and Compose
+/// projects are reported as irreversible along with their previous state.
+/// Files changed since the apply are only overwritten with `--force`.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = ROLLBACK_AFTER_LONG_HELP)]
+struct BootstrapRollback {
+    /// Roll back every apply since and including this one
+    #[clap(long, value_name = "APPLY_ID", conflicts_with = "list")]
+    to: Option<String>,
+
+    /// List recorded applies instead of rolling back
+    #[clap(long)]
+    list: bool,
+
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+
+    /// Print what would be restored without changing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+
+    /// Overwrite files that were changed since the apply
+    #[clap(long, short)]
+    force: bool,
+
+    /// Skip the confirmation prompt
+    #[clap(long, short)]
+    yes: bool,
+}
+
+/// Periodically check declarative bootstrap resources for drift
+///
+/// Computes the same plan as `mise bootstrap plan` every `--interval` and
+/// writes it as a JSON drift report. When a resource that was unchanged at
+/// the previous check needs a create, update or remove, `--notify` shows a
+/// desktop notification and `--hook` runs a command.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = WATCH_AFTER_LONG_HELP)]
+struct BootstrapWatch {
+    /// How often to check, e.g. 30m or 1h
+    #[clap(long, default_value = "1h", value_parser = crate::duration::parse_duration)]
+    interval: std::time::Duration,
+
+    /// Check once and exit
+    #[clap(long)]
+    once: bool,
+
+    /// Where to write the JSON drift report
+    ///
+    /// Defaults to $MISE_STATE_DIR/bootstrap/drift.json
+    #[clap(long, value_name = "PATH", verbatim_doc_comment)]
+    report: Option<std::path::PathBuf>,
+
+    /// Show a desktop notification on new drift
+    #[clap(long)]
+    notify: bool,
+
+    /// Run this command on new drift
+    ///
+    /// MISE_DRIFT_REPORT is set to the report path and MISE_DRIFT_RESOURCES
+    /// to the newly drifted resources, one per line.
+    #[clap(long, value_name = "COMMAND", verbatim_doc_comment)]
+    hook: Option<String>,
+
+    /// Install a systemd user timer that runs `--once` every `--interval` instead of watching
+    #[clap(long, conflicts_with = "once")]
+    install_timer: bool,
+
+    /// With --install-timer, print the systemd commands without running them
+    #[clap(long, short = 'n', requires = "install_timer")]
+    dry_run: bool,
+}
+
 #[derive(Debug, clap::Args)]
 struct BootstrapApplySystemPlan {}
 
@@ -791,6 +902,24 @@ struct BootstrapDotfilesStatus {
     cmd: DotfilesStatus,
 }
 
+static ROLLBACK_AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise bootstrap rollback --list</bold>
+    $ <bold>mise bootstrap rollback --dry-run</bold>
+    $ <bold>mise bootstrap rollback --to 20261019T120000Z --yes</bold>
+"#
+);
+
+static WATCH_AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise bootstrap watch --interval 30m --notify</bold>
+    $ <bold>mise bootstrap watch --once --hook 'curl -fsS -d @"$MISE_DRIFT_REPORT" https://example.com/drift'</bold>
+    $ <bold>mise bootstrap watch --install-timer --interval 1h --notify</bold>
+"#
+);
+
 static BOOTSTRAP_DOTFILES_APPLY_AFTER_LONG_HELP: &str = color_print::cstr!(
     r#"<bold><underline>Examples:</underline></bold>
 
@@ -945,6 +1074,95 @@ enum BootstrapMacosCommands {
     LaunchdAgents(BootstrapLaunchd),
 }
 
+/// Manage trusted CA certificates from `[bootstrap.certificates]`
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+struct BootstrapCertificates {
+    #[clap(subcommand)]
+    command: BootstrapCertificatesCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum BootstrapCertificatesCommands {
+    Apply(BootstrapCertificatesApply),
+    Status(BootstrapCertificatesStatus),
+}
+
+/// Install configured CA certificates into the system trust store
+#[derive(Debug, clap::Args)]
+struct BootstrapCertificatesApply {
+    /// Print what would change without changing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+
+    /// Skip the confirmation prompt
+    #[clap(long, short)]
+    yes: bool,
+}
+
+/// Show configured CA certificate state
+#[derive(Debug, clap::Args)]
+struct BootstrapCertificatesStatus {
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+
+    /// Exit with code 1 when any certificate is not converged
+    #[clap(long)]
+    missing: bool,
+}
+
+/// Manage SSH client bootstrap config from `[bootstrap.ssh]`
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+struct BootstrapSsh {
+    #[clap(subcommand)]
+    command: BootstrapSshCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum BootstrapSshCommands {
+    KnownHosts(BootstrapKnownHosts),
+}
+
+/// Manage ~/.ssh/known_hosts from `[bootstrap.ssh.known_hosts]`
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+struct BootstrapKnownHosts {
+    #[clap(subcommand)]
+    command: BootstrapKnownHostsCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum BootstrapKnownHostsCommands {
+    Apply(BootstrapKnownHostsApply),
+    Status(BootstrapKnownHostsStatus),
+}
+
+/// Write configured host keys to ~/.ssh/known_hosts
+#[derive(Debug, clap::Args)]
+struct BootstrapKnownHostsApply {
+    /// Print what would change without changing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+
+    /// Skip the confirmation prompt
+    #[clap(long, short)]
+    yes: bool,
+}
+
+/// Show configured known host state
+#[derive(Debug, clap::Args)]
+struct BootstrapKnownHostsStatus {
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+
+    /// Exit with code 1 when any host's keys are not converged
+    #[clap(long)]
+    missing: bool,
+}
+
 /// Manage Linux bootstrap config from `[bootstrap.linux]`
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment)]
@@ -955,10 +1173,127 @@ struct BootstrapLinux {
 
 #[derive(Debug, Subcommand)]
 enum BootstrapLinuxCommands {
+    Environment(BootstrapHostEnvironment),
+    KernelModules(BootstrapKernelModules),
+    Sysctl(BootstrapSysctl),
     #[clap(name = "systemd-units", alias = "systemd")]
     SystemdUnits(BootstrapSystemd),
 }
 
+/// Manage kernel parameters from `[bootstrap.linux.sysctl]`
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+struct BootstrapSysctl {
+    #[clap(subcommand)]
+    command: BootstrapSysctlCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum BootstrapSysctlCommands {
+    Apply(BootstrapSysctlApply),
+    Status(BootstrapSysctlStatus),
+}
+
+/// Persist configured kernel parameters and apply them live
+#[derive(Debug, clap::Args)]
+struct BootstrapSysctlApply {
+    /// Print what would change without changing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+
+    /// Skip the confirmation prompt
+    #[clap(long, short)]
+    yes: bool,
+}
+
+/// Show configured kernel parameter state
+#[derive(Debug, clap::Args)]
+struct BootstrapSysctlStatus {
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+
+    /// Exit with code 1 when any kernel parameter is not converged
+    #[clap(long)]
+    missing: bool,
+}
+
+/// Manage kernel modules from `[bootstrap.linux.kernel_modules]`
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+struct BootstrapKernelModules {
+    #[clap(subcommand)]
+    command: BootstrapKernelModulesCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum BootstrapKernelModulesCommands {
+    Apply(BootstrapKernelModulesApply),
+    Status(BootstrapKernelModulesStatus),
+}
+
+/// Load configured kernel modules now and at boot
+#[derive(Debug, clap::Args)]
+struct BootstrapKernelModulesApply {
+    /// Print what would change without changing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+
+    /// Skip the confirmation prompt
+    #[clap(long, short)]
+    yes: bool,
+}
+
+/// Show configured kernel module state
+#[derive(Debug, clap::Args)]
+struct BootstrapKernelModulesStatus {
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+
+    /// Exit with code 1 when any kernel module is not converged
+    #[clap(long)]
+    missing: bool,
+}
+
+/// Manage /etc/environment from `[bootstrap.linux.environment]`
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+struct BootstrapHostEnvironment {
+    #[clap(subcommand)]
+    command: BootstrapHostEnvironmentCommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum BootstrapHostEnvironmentCommands {
+    Apply(BootstrapHostEnvironmentApply),
+    Status(BootstrapHostEnvironmentStatus),
+}
+
+/// Write configured variables to /etc/environment
+#[derive(Debug, clap::Args)]
+struct BootstrapHostEnvironmentApply {
+    /// Print what would change without changing anything
+    #[clap(long, short = 'n')]
+    dry_run: bool,
+
+    /// Skip the confirmation prompt
+    #[clap(long, short)]
+    yes: bool,
+}
+
+/// Show configured /etc/environment state
+#[derive(Debug, clap::Args)]
+struct BootstrapHostEnvironmentStatus {
+    /// Output in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+
+    /// Exit with code 1 when any variable is not converged
+    #[clap(long)]
+    missing: bool,
+}
+
 /// Manage macOS defaults from `[bootstrap.macos.defaults]`
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment)]
@@ -1144,9 +1479,31 @@ impl Bootstrap {
         if let Some(command) = self.command {
             return command.run().await;
         }
-        let mut config = Config::get().await?;
-        let mut hooks = system::hooks_from_config(&config);
+        let config = Config::get().await?;
         let skip = self.skip_parts();
+        let secrets = system::secrets::resolve(&config, self.prompt_secrets)?;
+        let journal = if self.dry_run {
+            None
+        } else {
+            let include = |resource: &ResourceId| {
+                !bootstrap_resource_is_skipped(resource, &skip)
+                    && !(resource.kind == "compose" && skip.contains(&BootstrapPart::Compose))
+            };
+            let dotfiles = !skip.contains(&BootstrapPart::Dotfiles);
+            record_journal(&config, &secrets, include, dotfiles).await
+        };
+        let result = self.converge(config, secrets, skip).await;
+        finish_journal(journal);
+        result
+    }
+
+    async fn converge(
+        self,
+        mut config: Arc<Config>,
+        secrets: system::secrets::SecretValues,
+        skip: HashSet<BootstrapPart>,
+    ) -> Result<()> {
+        let mut hooks = system::hooks_from_config(&config);
         let accounts_enabled = !skip.contains(&BootstrapPart::Accounts);
         let files_enabled = !skip.contains(&BootstrapPart::Files);
         let configured_accounts =
@@ -1160,7 +1517,6 @@ impl Bootstrap {
                 .as_ref()
                 .expect("enabled accounts were prepared")
         });
-        let secrets = system::secrets::resolve(&config, self.prompt_secrets)?;
         let managed_system_files = if !files_enabled {
             None
         } else {
@@ -1264,6 +1620,30 @@ impl Bootstrap {
             false
         };
 
+        if skip.contains(&BootstrapPart::Certificates) {
+            debug!("bootstrap: CA certificates skipped");
+        } else {
+            let certificates = system::certificates_from_config(&config);
+            if certificates.is_empty() {
+                debug!("bootstrap: no [bootstrap.certificates] configured");
+            } else {
+                info!("bootstrap: CA certificates");
+                system::certificates::apply(&certificates, self.dry_run, self.yes)?;
+            }
+        }
+
+        if skip.contains(&BootstrapPart::KnownHosts) {
+            debug!("bootstrap: SSH known hosts skipped");
+        } else {
+            let known_hosts = system::known_hosts_from_config(&config);
+            if known_hosts.is_empty() {
+                debug!("bootstrap: no [bootstrap.ssh.known_hosts] configured");
+            } else {
+                info!("bootstrap: SSH known hosts");
+                system::known_hosts::apply(&known_hosts, self.dry_run, self.yes)?;
+            }
+        }
+
         if skip.contains(&BootstrapPart::Plugins) {
             debug!("bootstrap: package plugins skipped");
         } else {
@@ -1331,6 +1711,49 @@ impl Bootstrap {
             }
         }
 
+        if skip.contains(&BootstrapPart::KernelModules) {
+            debug!("bootstrap: kernel modules skipped");
+        } else {
+            let modules = system::kernel_modules_from_config(&config);
+            if modules.is_empty() {
+                debug!("bootstrap: no [bootstrap.linux.kernel_modules] configured");
+            } else {
+                info!("bootstrap: kernel modules");
+                system::host::apply_kernel_modules(&modules, self.dry_run, self.yes)?;
+            }
+        }
+
+        if skip.contains(&BootstrapPart::Sysctl) {
+            debug!("bootstrap: sysctl skipped");
+        } else {
+            let parameters = system::sysctl_from_config(&config);
+            if parameters.is_empty() {
+                debug!("bootstrap: no [bootstrap.linux.sysctl] configured");
+            } else {
+                info!("bootstrap: sysctl");
+                // after a real apply the modules are loaded; a dry run still
+                // has to account for them
+                let modules_pending = self.dry_run
+                    && !skip.contains(&BootstrapPart::KernelModules)
+                    && system::host::kernel_modules_pending(&system::kernel_modules_from_config(
+                        &config,
+                    ));
+                system::host::apply_sysctl(&parameters, modules_pending, self.dry_run, self.yes)?;
+            }
+        }
+
+        if skip.contains(&BootstrapPart::Environment) {
+            debug!("bootstrap: /etc/environment skipped");
+        } else {
+            let environment = system::host_environment_from_config(&config);
+            if environment.is_empty() {
+                debug!("bootstrap: no [bootstrap.linux.environment] configured");
+            } else {
+                info!("bootstrap: /etc/environment");
+                system::host::apply_environment(&environment, self.dry_run, self.yes)?;
+            }
+        }
+
         if let Some(services) = &mut managed_services {
             system::services::inspect_requests(services);
             if services.is_empty() {
@@ -1682,6 +2105,7 @@ impl Bootstrap {
             task_cache_explain: false,
             task_cache_explain_json: false,
             task_cache_stats: false,
+            report: vec![],
             timeout: None,
             skip_deps: false,
             // a dry run must not auto-install tools before the (not actually
@@ -1690,6 +2114,9 @@ impl Bootstrap {
             skip_tools: self.dry_run || skip_tools,
             no_deps: false,
             fresh_env: false,
+            limit_cpu: None,
+            limit_memory: None,
+            limit_pids: None,
             deny_all: false,
             deny_read: false,
             deny_write: false,
@@ -1935,6 +2362,7 @@ impl Commands {
             Self::InspectSystemFiles(cmd) => cmd.run(),
             Self::InspectFirewallPlan(cmd) => cmd.run(),
             Self::Accounts(cmd) => cmd.run().await,
+            Self::Certificates(cmd) => cmd.run().await,
             Self::Compose(cmd) => cmd.run().await,
             Self::Dotfiles(cmd) => cmd.run().await,
             Self::Files(cmd) => cmd.run().await,
@@ -1949,11 +2377,14 @@ impl Commands {
             Self::Plugins(cmd) => cmd.run().await,
             Self::Remote(cmd) => cmd.run().await,
             Self::Repos(cmd) => cmd.run().await,
+            Self::Rollback(cmd) => cmd.run().await,
             Self::Secrets(cmd) => cmd.run().await,
             Self::Services(cmd) => cmd.run().await,
+            Self::Ssh(cmd) => cmd.run().await,
             Self::Status(cmd) => cmd.run().await,
             Self::Systemd(cmd) => cmd.run().await,
             Self::User(cmd) => cmd.run().await,
+            Self::Watch(cmd) => cmd.run().await,
         }
     }
 }
@@ -2008,6 +2439,246 @@ impl BootstrapPlan {
     }
 }
 
+/// Journal the pre-image of everything a bootstrap run may change so
+/// `mise bootstrap rollback` can undo it. Failing to journal only warns; it
+/// shouldn't stop a machine from being bootstrapped.
+async fn record_journal(
+    config: &Config,
+    secrets: &system::secrets::SecretValues,
+    include: impl Fn(&ResourceId) -> bool,
+    dotfiles: bool,
+) -> Option<system::journal::Journal> {
+    let journal = match system::journal::Journal::record(config, secrets, include, dotfiles).await {
+        Ok(Some(journal)) => journal,
+        Ok(None) => return None,
+        Err(err) => {
+            warn!("bootstrap: rollback journal not recorded: {err:#}");
+            return None;
+        }
+    };
+    match journal.save() {
+        Ok(()) => {
+            info!("bootstrap: recorded rollback journal {}", journal.id);
+            Some(journal)
+        }
+        Err(err) => {
+            warn!("bootstrap: rollback journal not recorded: {err:#}");
+            None
+        }
+    }
+}
+
+/// Journal what `mise bootstrap <part> apply` may change, the same way a full
+/// bootstrap run does.
+async fn record_part_journal(
+    config: &Config,
+    secrets: Option<&system::secrets::SecretValues>,
+    part: BootstrapPart,
+    dry_run: bool,
+) -> Option<system::journal::Journal> {
+    if dry_run {
+        return None;
+    }
+    let resolved;
+    let secrets = match secrets {
+        Some(secrets) => secrets,
+        None => match system::secrets::resolve(config, false) {
+            Ok(secrets) => {
+                resolved = secrets;
+                &resolved
+            }
+            Err(err) => {
+                warn!("bootstrap: rollback journal not recorded: {err:#}");
+                return None;
+            }
+        },
+    };
+    let include = |resource: &ResourceId| bootstrap_resource_part(resource) == Some(part);
+    record_journal(config, secrets, include, false).await
+}
+
+/// Record what the apply left each journaled file as, so rollback can tell
+/// when a file was edited afterwards.
+fn finish_journal(journal: Option<system::journal::Journal>) {
+    let Some(mut journal) = journal else {
+        return;
+    };
+    journal.record_applied();
+    if let Err(err) = journal.save() {
+        warn!(
+            "bootstrap: rollback journal {} not updated: {err:#}",
+            journal.id
+        );
+    }
+}
+
+impl BootstrapRollback {
+    async fn run(self) -> Result<()> {
+        let journals = system::journal::list()?;
+        if self.list {
+            return self.list(&journals);
+        }
+        let selected = system::journal::select(journals, self.to.as_deref())?;
+        let ids = selected
+            .iter()
+            .map(|journal| journal.id.as_str())
+            .collect::<Vec<_>>();
+        if !self.dry_run
+            && !self.yes
+            && console::user_attended_stderr()
+            && !crate::ui::prompt::confirm(format!("bootstrap rollback: undo {}?", ids.join(", ")))?
+        {
+            info!("bootstrap rollback: skipped");
+            return Ok(());
+        }
+        let report = system::journal::rollback(&selected, self.dry_run, self.force)?;
+        if !self.dry_run {
+            let now = jiff::Timestamp::now().to_string();
+            for mut journal in selected {
+                journal.rolled_back_at = Some(now.clone());
+                journal.save()?;
+            }
+        }
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+        let verb = if self.dry_run {
+            "would restore"
+        } else {
+            "restored"
+        };
+        for resource in &report.restored {
+            info!("bootstrap rollback: {verb} {resource}");
+        }
+        for resource in &report.irreversible {
+            warn!(
+                "bootstrap rollback: cannot restore {} (was {}): {}",
+                resource.resource, resource.previous, resource.reason
+            );
+        }
+        info!(
+            "bootstrap rollback: {} {verb}, {} unchanged, {} irreversible",
+            report.restored.len(),
+            report.unchanged.len(),
+            report.irreversible.len()
+        );
+        Ok(())
+    }
+
+    fn list(&self, journals: &[system::journal::Journal]) -> Result<()> {
+        if self.json {
+            let journals = journals
+                .iter()
+                .map(|journal| {
+                    json!({
+                        "id": journal.id,
+                        "started_at": journal.started_at,
+                        "rolled_back_at": journal.rolled_back_at,
+                        "resources": journal.entries.len(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            miseprintln!("{}", serde_json::to_string_pretty(&journals)?);
+            return Ok(());
+        }
+        if journals.is_empty() {
+            info!("no bootstrap applies recorded");
+            return Ok(());
+        }
+        let mut table = MiseTable::new(false, &["Apply", "Started", "Resources", "Rolled back"]);
+        for journal in journals.iter().rev() {
+            table.add_row(vec![
+                journal.id.clone(),
+                journal.started_at.clone(),
+                journal.entries.len().to_string(),
+                journal.rolled_back_at.clone().unwrap_or_default(),
+            ]);
+        }
+        table.print()
+    }
+}
+
+impl BootstrapWatch {
+    async fn run(self) -> Result<()> {
+        if self.install_timer {
+            return self.install_timer().await;
+        }
+        let report_path = self
+            .report
+            .clone()
+            .unwrap_or_else(system::drift::default_report_path);
+        let mut config = Config::get().await?;
+        loop {
+            match self.check(&config, &report_path).await {
+                Ok(()) => {}
+                Err(err) if self.once => return Err(err),
+                Err(err) => warn!("bootstrap watch: {err:#}"),
+            }
+            if self.once {
+                return Ok(());
+            }
+            tokio::time::sleep(self.interval).await;
+            // pick up config edits made since the last check
+            config = Config::reset().await?;
+        }
+    }
+
+    async fn check(&self, config: &Config, report_path: &std::path::Path) -> Result<()> {
+        let report = system::drift::check(config, report_path).await?;
+        info!(
+            "bootstrap watch: {} drifted, {} new, report written to {}",
+            report.drifted.len(),
+            report.new.len(),
+            report_path.display_user()
+        );
+        if report.new.is_empty() {
+            return Ok(());
+        }
+        for resource in &report.new {
+            warn!("bootstrap watch: {resource} drifted");
+        }
+        if self.notify {
+            system::drift::notify(&report);
+        }
+        if let Some(hook) = &self.hook {
+            system::drift::run_hook(hook, report_path, &report).await?;
+        }
+        Ok(())
+    }
+
+    async fn install_timer(&self) -> Result<()> {
+        if !system::systemd::is_available() {
+            bail!(
+                "--install-timer requires systemd: {}",
+                system::systemd::unavailable_reason()
+            );
+        }
+        let mut args = vec![];
+        if let Some(report) = &self.report {
+            args.push("--report".to_string());
+            args.push(crate::file::replace_path(report).display().to_string());
+        }
+        if self.notify {
+            args.push("--notify".to_string());
+        }
+        if let Some(hook) = &self.hook {
+            args.push("--hook".to_string());
+            args.push(hook.clone());
+        }
+        let units = system::drift::timer_units(&args, self.interval)?;
+        system::systemd::apply(&units, self.dry_run).await?;
+        if !self.dry_run {
+            info!(
+                "bootstrap watch: installed {} for {}",
+                units[1].unit,
+                dirs::CWD.clone().unwrap_or_default().display_user()
+            );
+        }
+        Ok(())
+    }
+}
+
 impl BootstrapApplySystemPlan {
     fn run(self) -> Result<()> {
         system::managed_files::apply_privileged_plan_from_stdin()
@@ -2123,6 +2794,8 @@ impl BootstrapFilesApply {
         } else {
             None
         };
+        let journal =
+            record_part_journal(&config, Some(&secrets), BootstrapPart::Files, self.dry_run).await;
         let report = system::managed_files::apply_with_accounts(
             &files,
             &directories,
@@ -2130,7 +2803,9 @@ impl BootstrapFilesApply {
             false,
             self.dry_run,
             self.yes,
-        )?;
+        );
+        finish_journal(journal);
+        let report = report?;
         if let Some(services) = &mut services {
             system::services::inspect_requests(services);
             system::services::apply_with_notifications(
@@ -2213,8 +2888,12 @@ impl BootstrapServices {
 impl BootstrapServicesApply {
     async fn run(self) -> Result<()> {
         let config = Config::get().await?;
+        let journal =
+            record_part_journal(&config, None, BootstrapPart::Services, self.dry_run).await;
         let requests = system::services::requests_from_config(&config)?;
-        system::services::apply(&requests, self.dry_run, self.yes)
+        let result = system::services::apply(&requests, self.dry_run, self.yes);
+        finish_journal(journal);
+        result
     }
 }
 
@@ -2268,7 +2947,11 @@ impl BootstrapFirewallApply {
             info!("no bootstrap firewall configured");
             return Ok(());
         };
-        system::firewall::apply(&request, self.dry_run, self.yes)
+        let journal =
+            record_part_journal(&config, None, BootstrapPart::Firewall, self.dry_run).await;
+        let result = system::firewall::apply(&request, self.dry_run, self.yes);
+        finish_journal(journal);
+        result
     }
 }
 
@@ -2590,7 +3273,9 @@ impl BootstrapStatus {
         self.collect_secrets(&secrets.used_statuses()?, &mut report);
         self.collect_packages(config, &mut report).await?;
         self.collect_accounts(&accounts, &mut report);
+        self.collect_trust(config, &mut report);
         self.collect_files(files, directories, unavailable_files, &mut report)?;
+        self.collect_host(config, &mut report);
         self.collect_services(&service_requests, &notified_services, &mut report);
         self.collect_firewall(firewall_request.as_ref(), &mut report);
         self.collect_compose(&compose_requests, &mut report);
@@ -2672,6 +3357,60 @@ impl BootstrapStatus {
         Ok(())
     }
 
+    fn collect_trust(&self, config: &Arc<Config>, report: &mut BootstrapStatusReport) {
+        for (key, resources) in [
+            (
+                "certificates",
+                system::certificates::plans(&system::certificates_from_config(config)),
+            ),
+            (
+                "known_hosts",
+                system::known_hosts::plans(&system::known_hosts_from_config(config)),
+            ),
+        ] {
+            for resource in &resources {
+                report.row(
+                    resource.id.kind.clone(),
+                    resource.id.name.clone(),
+                    resource.current.clone(),
+                    resource.action.to_string(),
+                    resource.action != system::resources::ResourceAction::Noop,
+                );
+            }
+            report.json.insert(key.to_string(), json!(resources));
+        }
+    }
+
+    fn collect_host(&self, config: &Arc<Config>, report: &mut BootstrapStatusReport) {
+        let modules = system::kernel_modules_from_config(config);
+        let modules_pending = system::host::kernel_modules_pending(&modules);
+        for (key, resources) in [
+            (
+                "kernel_modules",
+                system::host::kernel_module_plans(&modules),
+            ),
+            (
+                "sysctl",
+                system::host::sysctl_plans(&system::sysctl_from_config(config), modules_pending),
+            ),
+            (
+                "environment",
+                system::host::environment_plans(&system::host_environment_from_config(config)),
+            ),
+        ] {
+            for resource in &resources {
+                report.row(
+                    resource.id.kind.clone(),
+                    resource.id.name.clone(),
+                    resource.current.clone(),
+                    resource.action.to_string(),
+                    resource.action != system::resources::ResourceAction::Noop,
+                );
+            }
+            report.json.insert(key.to_string(), json!(resources));
+        }
+    }
+
     fn collect_services(
         &self,
         requests: &[system::services::ServiceRequest],
@@ -2960,13 +3699,17 @@ impl BootstrapStatus {
                 state_str,
                 missing,
             );
-            json_files.push(json!({
+            let mut entry = json!({
                 "target": req.target_raw,
                 "source": (req.mode != system::files::FileMode::Content)
                     .then(|| req.source.display_user()),
                 "mode": req.mode.name(),
                 "state": state_json,
-            }));
+            });
+            if let system::files::FileState::Differs(reason) = &state {
+                entry["reason"] = json!(reason);
+            }
+            json_files.push(entry);
         }
 
         let mut json_edits = vec![];
@@ -3628,14 +4371,252 @@ impl BootstrapMacos {
     }
 }
 
+impl BootstrapCertificates {
+    async fn run(self) -> Result<()> {
+        match self.command {
+            BootstrapCertificatesCommands::Apply(cmd) => cmd.run().await,
+            BootstrapCertificatesCommands::Status(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl BootstrapCertificatesApply {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let requests = system::certificates_from_config(&config);
+        if requests.is_empty() {
+            info!("nothing configured in [bootstrap.certificates]");
+            return Ok(());
+        }
+        let journal =
+            record_part_journal(&config, None, BootstrapPart::Certificates, self.dry_run).await;
+        let result = system::certificates::apply(&requests, self.dry_run, self.yes);
+        finish_journal(journal);
+        result
+    }
+}
+
+impl BootstrapCertificatesStatus {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let resources = system::certificates::plans(&system::certificates_from_config(&config));
+        print_host_resources(
+            resources,
+            "[bootstrap.certificates]",
+            self.json,
+            self.missing,
+        )
+    }
+}
+
+impl BootstrapSsh {
+    async fn run(self) -> Result<()> {
+        match self.command {
+            BootstrapSshCommands::KnownHosts(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl BootstrapKnownHosts {
+    async fn run(self) -> Result<()> {
+        match self.command {
+            BootstrapKnownHostsCommands::Apply(cmd) => cmd.run().await,
+            BootstrapKnownHostsCommands::Status(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl BootstrapKnownHostsApply {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let requests = system::known_hosts_from_config(&config);
+        if requests.is_empty() {
+            info!("nothing configured in [bootstrap.ssh.known_hosts]");
+            return Ok(());
+        }
+        let journal =
+            record_part_journal(&config, None, BootstrapPart::KnownHosts, self.dry_run).await;
+        let result = system::known_hosts::apply(&requests, self.dry_run, self.yes);
+        finish_journal(journal);
+        result
+    }
+}
+
+impl BootstrapKnownHostsStatus {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let resources = system::known_hosts::plans(&system::known_hosts_from_config(&config));
+        print_host_resources(
+            resources,
+            "[bootstrap.ssh.known_hosts]",
+            self.json,
+            self.missing,
+        )
+    }
+}
+
 impl BootstrapLinux {
     async fn run(self) -> Result<()> {
         match self.command {
+            BootstrapLinuxCommands::Environment(cmd) => cmd.run().await,
+            BootstrapLinuxCommands::KernelModules(cmd) => cmd.run().await,
+            BootstrapLinuxCommands::Sysctl(cmd) => cmd.run().await,
             BootstrapLinuxCommands::SystemdUnits(cmd) => cmd.run().await,
         }
     }
 }
 
+impl BootstrapSysctl {
+    async fn run(self) -> Result<()> {
+        match self.command {
+            BootstrapSysctlCommands::Apply(cmd) => cmd.run().await,
+            BootstrapSysctlCommands::Status(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl BootstrapSysctlApply {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let requests = system::sysctl_from_config(&config);
+        if requests.is_empty() {
+            info!("nothing configured in [bootstrap.linux.sysctl]");
+            return Ok(());
+        }
+        let modules_pending =
+            system::host::kernel_modules_pending(&system::kernel_modules_from_config(&config));
+        let journal = record_part_journal(&config, None, BootstrapPart::Sysctl, self.dry_run).await;
+        let result = system::host::apply_sysctl(&requests, modules_pending, self.dry_run, self.yes);
+        finish_journal(journal);
+        result
+    }
+}
+
+impl BootstrapSysctlStatus {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let modules_pending =
+            system::host::kernel_modules_pending(&system::kernel_modules_from_config(&config));
+        let resources =
+            system::host::sysctl_plans(&system::sysctl_from_config(&config), modules_pending);
+        print_host_resources(
+            resources,
+            "[bootstrap.linux.sysctl]",
+            self.json,
+            self.missing,
+        )
+    }
+}
+
+impl BootstrapKernelModules {
+    async fn run(self) -> Result<()> {
+        match self.command {
+            BootstrapKernelModulesCommands::Apply(cmd) => cmd.run().await,
+            BootstrapKernelModulesCommands::Status(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl BootstrapKernelModulesApply {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let requests = system::kernel_modules_from_config(&config);
+        if requests.is_empty() {
+            info!("nothing configured in [bootstrap.linux.kernel_modules]");
+            return Ok(());
+        }
+        let journal =
+            record_part_journal(&config, None, BootstrapPart::KernelModules, self.dry_run).await;
+        let result = system::host::apply_kernel_modules(&requests, self.dry_run, self.yes);
+        finish_journal(journal);
+        result
+    }
+}
+
+impl BootstrapKernelModulesStatus {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let resources =
+            system::host::kernel_module_plans(&system::kernel_modules_from_config(&config));
+        print_host_resources(
+            resources,
+            "[bootstrap.linux.kernel_modules]",
+            self.json,
+            self.missing,
+        )
+    }
+}
+
+impl BootstrapHostEnvironment {
+    async fn run(self) -> Result<()> {
+        match self.command {
+            BootstrapHostEnvironmentCommands::Apply(cmd) => cmd.run().await,
+            BootstrapHostEnvironmentCommands::Status(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl BootstrapHostEnvironmentApply {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let requests = system::host_environment_from_config(&config);
+        if requests.is_empty() {
+            info!("nothing configured in [bootstrap.linux.environment]");
+            return Ok(());
+        }
+        let journal =
+            record_part_journal(&config, None, BootstrapPart::Environment, self.dry_run).await;
+        let result = system::host::apply_environment(&requests, self.dry_run, self.yes);
+        finish_journal(journal);
+        result
+    }
+}
+
+impl BootstrapHostEnvironmentStatus {
+    async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let resources =
+            system::host::environment_plans(&system::host_environment_from_config(&config));
+        print_host_resources(
+            resources,
+            "[bootstrap.linux.environment]",
+            self.json,
+            self.missing,
+        )
+    }
+}
+
+fn print_host_resources(
+    resources: Vec<system::resources::ResourcePlan>,
+    section: &str,
+    json: bool,
+    missing: bool,
+) -> Result<()> {
+    let any_missing = resources
+        .iter()
+        .any(|resource| resource.action != ResourceAction::Noop);
+    if json {
+        miseprintln!("{}", serde_json::to_string_pretty(&resources)?);
+    } else if resources.is_empty() {
+        info!("nothing configured in {section}");
+    } else {
+        let mut table = MiseTable::new(false, &["Action", "Resource", "Current", "Desired"]);
+        for resource in resources {
+            table.add_row(vec![
+                resource.action.to_string(),
+                resource.id.to_string(),
+                resource.current,
+                resource.desired,
+            ]);
+        }
+        table.print()?;
+    }
+    if missing && any_missing {
+        return Err(crate::request_exit(1));
+    }
+    Ok(())
+}
+
 impl BootstrapMacosDefaults {
     async fn run(self) -> Result<()> {
         match self.command {
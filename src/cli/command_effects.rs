@@ -40,6 +40,9 @@ use usage::SpecCommandEffect::{self, Destructive, Read, Write};
 /// Commands whose effect is fixed, keyed by their full path under `mise`.
 pub(super) const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("activate", Read),
+    ("audit", Read),
+    ("audit scan", Read),
+    ("audit update", Write),
     ("backends", Read),
     ("backends ls", Read),
     ("bin-paths", Read),
@@ -53,6 +56,9 @@ pub(super) const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("bootstrap accounts", Read),
     ("bootstrap accounts apply", Destructive),
     ("bootstrap accounts status", Read),
+    ("bootstrap certificates", Read),
+    ("bootstrap certificates apply", Destructive),
+    ("bootstrap certificates status", Read),
     ("bootstrap compose", Read),
     ("bootstrap compose apply", Destructive),
     ("bootstrap compose status", Read),
@@ -82,6 +88,15 @@ pub(super) const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("bootstrap dotfiles status", Read),
     ("bootstrap dotfiles unapply", Destructive),
     ("bootstrap linux", Read),
+    ("bootstrap linux environment", Read),
+    ("bootstrap linux environment apply", Destructive),
+    ("bootstrap linux environment status", Read),
+    ("bootstrap linux kernel-modules", Read),
+    ("bootstrap linux kernel-modules apply", Destructive),
+    ("bootstrap linux kernel-modules status", Read),
+    ("bootstrap linux sysctl", Read),
+    ("bootstrap linux sysctl apply", Destructive),
+    ("bootstrap linux sysctl status", Read),
     ("bootstrap linux systemd-units", Read),
     ("bootstrap linux systemd-units apply", Write),
     ("bootstrap linux systemd-units status", Read),
@@ -114,8 +129,15 @@ pub(super) const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("bootstrap repos apply", Write),
     ("bootstrap repos status", Read),
     ("bootstrap repos update", Write),
+    // Restores journaled pre-images, removing files and directories an apply
+    // created.
+    ("bootstrap rollback", Destructive),
     ("bootstrap secrets", Read),
     ("bootstrap secrets status", Read),
+    ("bootstrap ssh", Read),
+    ("bootstrap ssh known-hosts", Read),
+    ("bootstrap ssh known-hosts apply", Write),
+    ("bootstrap ssh known-hosts status", Read),
     ("bootstrap status", Read),
     // Changes the current user's login shell.
     ("bootstrap user", Read),
@@ -160,6 +182,7 @@ pub(super) const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("generate", Read),
     // Deprecated spelling of `generate install-script`.
     ("generate bootstrap", Write),
+    ("generate ci", Write),
     ("generate config", Write),
     ("generate devcontainer", Write),
     ("generate git-pre-commit", Write),
@@ -201,6 +224,8 @@ pub(super) const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("plugins ls-remote", Read),
     ("plugins uninstall", Destructive),
     ("plugins update", Write),
+    ("policy", Read),
+    ("policy check", Read),
     ("prune", Destructive),
     ("registry", Read),
     ("reshim", Write),
@@ -240,8 +265,11 @@ pub(super) const EFFECTS: &[(&str, SpecCommandEffect)] = &[
     ("tasks deps", Read),
     ("tasks edit", Write),
     ("tasks graph", Read),
+    ("tasks history", Read),
     ("tasks info", Read),
+    ("tasks logs", Read),
     ("tasks ls", Read),
+    ("tasks update-includes", Write),
     ("tasks validate", Read),
     ("token", Read),
     ("token forgejo", Read),
@@ -296,6 +324,7 @@ pub(crate) const UNCLASSIFIED: &[(&str, &str)] = &[
         "bootstrap repos exec",
         "runs an arbitrary command in each repo",
     ),
+    ("bootstrap watch", "runs the --hook command on drift"),
     ("direnv exec", "runs an arbitrary command"),
     ("en", "starts an interactive shell"),
     ("exec", "runs an arbitrary command"),
@@ -17,9 +17,9 @@ use crate::plugins::PluginType;
 use crate::plugins::core::CORE_PLUGINS;
 use crate::registry::REGISTRY;
 use crate::toolset::install_state;
-use crate::toolset::{ToolVersion, Toolset, ToolsetBuilder};
+use crate::toolset::{ResolveOptions, ToolVersion, Toolset, ToolsetBuilder};
 use crate::ui::{info, style};
-use crate::{backend, dirs, duration, env, file, shims};
+use crate::{backend, dirs, duration, env, file, policy, shims};
 use console::{Alignment, pad_str, style};
 use heck::ToSnakeCase;
 use indexmap::IndexMap;
@@ -159,7 +159,7 @@ impl Doctor {
         );
 
         let config = Config::get().await?;
-        let ts = config.get_toolset().await?;
+        let ts = &policy_report_toolset(&config).await?;
         let desired_shims = self.analyze_shims(&config, ts).await;
         self.analyze_plugins();
         self.analyze_backend_mismatches();
@@ -227,6 +227,8 @@ impl Doctor {
             (f.ba().to_string(), versions)
         });
         data.insert("toolset".into(), tools.collect());
+        let policy = self.check_policy(ts);
+        data.insert("policy".into(), policy.into());
 
         if let Some(system_packages) = self.system_packages_json(&config).await {
             data.insert("system_packages".into(), system_packages);
@@ -485,10 +487,13 @@ impl Doctor {
             }
         }
 
-        match ToolsetBuilder::new().build(config).await {
+        match policy_report_toolset(config).await {
             Ok(ts) => {
                 let desired_shims = self.analyze_shims(config, &ts).await;
                 self.analyze_toolset(&ts).await?;
+                let policy = self.check_policy(&ts);
+                println!();
+                info::inline_section("policy", policy.as_deref().unwrap_or("(none)"))?;
                 self.analyze_paths(&ts).await?;
                 self.check_path_ordering(&ts, config).await;
                 self.check_shim_shadowing(&desired_shims).await;
@@ -813,6 +818,25 @@ impl Doctor {
         Ok(())
     }
 
+    /// Report each tool policy violation as an error. Returns where the policy
+    /// was loaded from, if there is one.
+    fn check_policy(&mut self, ts: &Toolset) -> Option<String> {
+        match policy::get() {
+            Ok(Some(policy)) => {
+                for (tool, reason) in policy.toolset_violations(ts) {
+                    self.errors
+                        .push(format!("{tool} is denied by the tool policy: {reason}"));
+                }
+                Some(policy.location.clone())
+            }
+            Ok(None) => None,
+            Err(err) => {
+                self.errors.push(format!("{err:#}"));
+                None
+            }
+        }
+    }
+
     async fn analyze_shims(&mut self, config: &Arc<Config>, toolset: &Toolset) -> HashSet<String> {
         let mise_bin = shims::mise_bin_for_shims();
 
@@ -1044,6 +1068,18 @@ impl Doctor {
     }
 }
 
+/// The toolset with versions the tool policy denies still resolved, so doctor
+/// can report them instead of failing to load the toolset.
+async fn policy_report_toolset(config: &Arc<Config>) -> eyre::Result<Toolset> {
+    ToolsetBuilder::new()
+        .with_resolve_options(ResolveOptions {
+            skip_policy: true,
+            ..Default::default()
+        })
+        .build(config)
+        .await
+}
+
 fn shim_command_name(shim: &str) -> String {
     if cfg!(windows) {
         let lower = shim.to_ascii_lowercase();
@@ -3,9 +3,10 @@ use std::path::PathBuf;
 use eyre::{Result, bail};
 use toml_edit::{DocumentMut, InlineTable, Item, Table, Value};
 
+use crate::agecrypt;
 use crate::config::config_file::ConfigFile;
 use crate::config::config_file::mise_toml::MiseToml;
-use crate::config::{Config, ConfigPathOptions, resolve_target_config_path};
+use crate::config::{Config, ConfigPathOptions, Settings, resolve_target_config_path};
 use crate::dirs;
 use crate::file;
 use crate::path::PathExt;
@@ -18,6 +19,10 @@ use crate::ui::prompt;
 /// If the target is already managed, this updates its source from the live
 /// target. Otherwise it creates a `[dotfiles]` entry and seeds the source
 /// under `dotfiles.root` unless `--source` is provided.
+///
+/// With `--encrypt` the source is written age-encrypted as `<source>.age` and
+/// decrypted again on apply. Sources already ending in `.age` are always
+/// re-encrypted when they are updated from the target.
 #[derive(Debug, clap::Args)]
 #[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
 pub(crate) struct DotfilesAdd {
@@ -41,6 +46,18 @@ pub(crate) struct DotfilesAdd {
     #[clap(long, short)]
     pub(super) mode: Option<String>,
 
+    /// [experimental] Encrypt the source with age
+    ///
+    /// Uses the recipients of ~/.config/mise/age.txt unless --age-recipient is given.
+    #[clap(long)]
+    pub(super) encrypt: bool,
+
+    /// [experimental] Age recipient (x25519 or SSH public key) to encrypt the source to
+    ///
+    /// Can be used multiple times.
+    #[clap(long, value_name = "RECIPIENT")]
+    pub(super) age_recipient: Vec<String>,
+
     /// Print the config/source updates without writing anything
     #[clap(long, short = 'n')]
     pub(super) dry_run: bool,
@@ -75,8 +92,15 @@ impl DotfilesAdd {
             Some(mode) => {
                 FileMode::parse(mode).ok_or_else(|| eyre::eyre!("unknown dotfile mode: {mode}"))?
             }
+            None if self.encrypt => FileMode::Copy,
             None => system::files::default_mode(),
         };
+        if self.encrypt && !matches!(mode, FileMode::Copy | FileMode::Template) {
+            bail!(
+                "--encrypt requires mode copy or template, a {} would expose the encrypted source",
+                mode.name()
+            );
+        }
         let config = Config::get().await?;
         let managed = system::files::files_from_config(&config)?;
         let config_path = resolve_target_config_path(ConfigPathOptions {
@@ -115,13 +139,29 @@ impl DotfilesAdd {
                     std::slice::from_ref(target_raw),
                 )
             });
-            let source = if let Some(req) = existing {
+            let mut source = if let Some(req) = existing {
                 req.source.clone()
             } else if let Some(source) = &self.source {
                 file::replace_path(source)
             } else {
                 system::files::implied_source(&target)?
             };
+            let encrypted = match existing {
+                Some(req) if self.encrypt && !req.encrypted() => bail!(
+                    "{target_raw}: source {} is not encrypted; remove the entry and add it again with --encrypt",
+                    req.source.display_user()
+                ),
+                Some(req) => req.encrypted(),
+                None => self.encrypt || system::files::is_encrypted_source(&source),
+            };
+            if encrypted && target.is_dir() {
+                bail!("{target_raw}: only single files can be encrypted");
+            }
+            if existing.is_none() && self.encrypt && !system::files::is_encrypted_source(&source) {
+                let mut encrypted_source = source.into_os_string();
+                encrypted_source.push(".age");
+                source = encrypted_source.into();
+            }
             let write_mode = existing.map(|req| req.mode).unwrap_or(mode);
             if let Some(req) = existing
                 && self.mode.is_some()
@@ -139,8 +179,9 @@ impl DotfilesAdd {
                 target,
                 source,
                 mode: write_mode,
-                implied_source: self.source.is_none(),
+                implied_source: self.source.is_none() && !encrypted,
                 explicit_mode: self.mode.is_some(),
+                encrypted,
                 already_managed: existing.cloned(),
             };
             // Wildcard expansion and equivalent spellings can resolve more
@@ -170,6 +211,13 @@ impl DotfilesAdd {
         }
         system::files::validate_composed_file_footprints(&prospective)?;
 
+        let recipients = if planned.iter().any(|item| item.encrypted) && !self.dry_run {
+            Settings::get().ensure_experimental("age encryption")?;
+            self.collect_age_recipients().await?
+        } else {
+            vec![]
+        };
+
         if self.dry_run {
             for item in &planned {
                 if item.already_managed.is_none() {
@@ -180,7 +228,13 @@ impl DotfilesAdd {
                         inline_entry(item)
                     );
                 }
-                if item.target.exists() {
+                if item.target.exists() && item.encrypted {
+                    miseprintln!(
+                        "encrypt {} -> {}",
+                        item.target.display_user(),
+                        item.source.display_user()
+                    );
+                } else if item.target.exists() {
                     miseprintln!(
                         "cp {} {}",
                         item.target.display_user(),
@@ -289,12 +343,21 @@ impl DotfilesAdd {
                                 &backup_dir.path().join("targets").join(index.to_string()),
                             )?,
                         ));
-                        system::files::copy_path(&item.target, &item.source)?;
-                        info!(
-                            "dotfiles: copied {} to {}",
-                            item.target.display_user(),
-                            item.source.display_user()
-                        );
+                        if item.encrypted {
+                            write_encrypted(&item.source, &file::read(&item.target)?, &recipients)?;
+                            info!(
+                                "dotfiles: encrypted {} to {}",
+                                item.target.display_user(),
+                                item.source.display_user()
+                            );
+                        } else {
+                            system::files::copy_path(&item.target, &item.source)?;
+                            info!(
+                                "dotfiles: copied {} to {}",
+                                item.target.display_user(),
+                                item.source.display_user()
+                            );
+                        }
                     }
                 } else if !item.source.exists() {
                     target_backups.push((
@@ -305,10 +368,14 @@ impl DotfilesAdd {
                         )?,
                     ));
                     source_backups.push((item.source.clone(), PathBackup::Missing));
-                    if let Some(parent) = item.source.parent() {
-                        file::create_dir_all(parent)?;
+                    if item.encrypted {
+                        write_encrypted(&item.source, b"", &recipients)?;
+                    } else {
+                        if let Some(parent) = item.source.parent() {
+                            file::create_dir_all(parent)?;
+                        }
+                        file::write(&item.source, "")?;
                     }
-                    file::write(&item.source, "")?;
                     info!("dotfiles: created {}", item.source.display_user());
                 } else if !self.no_apply {
                     target_backups.push((
@@ -392,6 +459,41 @@ impl DotfilesAdd {
         }
         Ok(())
     }
+
+    async fn collect_age_recipients(&self) -> Result<Vec<Box<dyn age::Recipient + Send>>> {
+        if self.age_recipient.is_empty() {
+            let recipients = agecrypt::load_recipients_from_defaults().await?;
+            if recipients.is_empty() {
+                bail!(
+                    "[experimental] No age recipients found. Use --age-recipient or create ~/.config/mise/age.txt"
+                );
+            }
+            return Ok(recipients);
+        }
+        let mut recipients = vec![];
+        for recipient in &self.age_recipient {
+            match agecrypt::parse_recipient(recipient)? {
+                Some(recipient) => recipients.push(recipient),
+                None => bail!("[experimental] Invalid age recipient: {recipient}"),
+            }
+        }
+        Ok(recipients)
+    }
+}
+
+/// Encrypt `plaintext` into `source`; the plaintext itself is never written.
+fn write_encrypted(
+    source: &std::path::Path,
+    plaintext: &[u8],
+    recipients: &[Box<dyn age::Recipient + Send>],
+) -> Result<()> {
+    let ciphertext = agecrypt::encrypt_bytes(plaintext, recipients)?;
+    remove_path(source)?;
+    if let Some(parent) = source.parent() {
+        file::create_dir_all(parent)?;
+    }
+    file::write(source, ciphertext)?;
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -402,6 +504,7 @@ struct PlannedAdd {
     mode: FileMode,
     implied_source: bool,
     explicit_mode: bool,
+    encrypted: bool,
     already_managed: Option<FileRequest>,
 }
 
@@ -512,6 +615,7 @@ fn describe_apply(item: &PlannedAdd) -> String {
         FileMode::Symlink => format!("ln -sf {source} {target}"),
         FileMode::SymlinkEach => format!("ln -sf {source}/* into {target}/"),
         FileMode::Copy if item.source.is_dir() => format!("cp -r {source} {target}"),
+        FileMode::Copy if item.encrypted => format!("decrypt {source} -> {target}"),
         FileMode::Copy => format!("cp {source} {target}"),
         FileMode::Template => format!("render {source} -> {target}"),
         FileMode::Content => unreachable!("dotfiles add always captures a source file"),
@@ -655,6 +759,7 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
     $ <bold>mise bootstrap dotfiles add ~/.zshrc</bold>
     $ <bold>mise bootstrap dotfiles add --mode copy ~/.config/starship.toml</bold>
     $ <bold>mise bootstrap dotfiles add --source dotfiles/gitconfig ~/.gitconfig</bold>
+    $ <bold>mise bootstrap dotfiles add --encrypt ~/.netrc</bold>
 "#
 );
 
@@ -5,6 +5,7 @@ use eyre::{Result, bail};
 use super::add::DotfilesAdd;
 use crate::config::Config;
 use crate::file;
+use crate::path::PathExt;
 use crate::system;
 use crate::system::edits::{BlockSource, EditOp};
 use crate::ui::prompt;
@@ -67,6 +68,8 @@ impl DotfilesEdit {
             targets: vec![self.target.clone()],
             mode: self.mode.clone(),
             source: self.source.clone(),
+            encrypt: false,
+            age_recipient: vec![],
             global: true,
             local: false,
             path: None,
@@ -98,6 +101,14 @@ fn source_for_target(
 ) -> Result<Option<PathBuf>> {
     for req in system::files::files_from_config(config)? {
         if system::files::matches_target(&req.target, &req.target_raw, &[raw.to_string()]) {
+            if req.encrypted() {
+                bail!(
+                    "{} is encrypted; edit {} and re-encrypt it with `mise dotfiles add --encrypt {}`",
+                    req.source.display_user(),
+                    req.target.display_user(),
+                    req.target_raw
+                );
+            }
             return Ok(Some(req.source));
         }
     }
@@ -117,7 +128,8 @@ fn source_for_target(
                     source: BlockSource::Inline(_),
                     ..
                 }
-                | EditOp::Line { .. } => req.config_path.clone(),
+                | EditOp::Line { .. }
+                | EditOp::Set { .. } => req.config_path.clone(),
             }));
         }
         edits => {
@@ -54,7 +54,7 @@ impl DotfilesStatus {
             };
             any_missing |= state != FileState::Applied;
             if self.json {
-                json_files.push(json!({
+                let mut entry = json!({
                     "target": req.target_raw,
                     "source": (req.mode != system::files::FileMode::Content)
                         .then(|| req.source.display_user()),
@@ -66,7 +66,13 @@ impl DotfilesStatus {
                         FileState::SourceMissing => "source_missing",
                         FileState::Differs(_) => "differs",
                     },
-                }));
+                });
+                // copied and templated files name the side that changed:
+                // "source changed", "target edited" or both
+                if let FileState::Differs(reason) = &state {
+                    entry["reason"] = json!(reason);
+                }
+                json_files.push(entry);
             } else {
                 file_rows.push(vec![
                     req.target_raw.clone(),
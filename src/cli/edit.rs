@@ -72,15 +72,22 @@ impl BackendProvider for MiseBackendProvider {
             let (name, description) = match backend_type {
                 BackendType::Aqua => ("aqua", Some("Install tools from aquaproj registry")),
                 BackendType::Asdf => ("asdf", Some("Install tools via asdf plugins")),
+                BackendType::Brew => ("brew", Some("Install Homebrew formula bottles")),
                 BackendType::Cargo => ("cargo", Some("Install Rust packages from crates.io")),
                 BackendType::Conda => ("conda", Some("Install packages from conda-forge")),
                 BackendType::Dotnet => ("dotnet", Some("Install .NET tools")),
                 BackendType::Forgejo => ("forgejo", Some("Install from Forgejo releases")),
                 BackendType::Gem => ("gem", Some("Install Ruby gems")),
+                BackendType::Git => ("git", Some("Build from git repositories")),
                 BackendType::Github => ("github", Some("Install from GitHub releases")),
                 BackendType::Gitlab => ("gitlab", Some("Install from GitLab releases")),
                 BackendType::Go => ("go", Some("Install Go modules")),
+                BackendType::Maven => ("maven", Some("Install JVM tools from Maven repositories")),
                 BackendType::Npm => ("npm", Some("Install npm packages globally")),
+                BackendType::Oci => (
+                    "oci",
+                    Some("Install OCI artifacts from container registries"),
+                ),
                 BackendType::Pipx => ("pipx", Some("Install Python CLI tools")),
                 BackendType::Pkgx => ("pkgx", Some("Install pkgx pantry packages")),
                 BackendType::Spm => ("spm", Some("Install Swift packages")),
@@ -36,6 +36,9 @@ impl En {
             command: Some(command),
             no_deps: false,
             fresh_env: false,
+            limit_cpu: None,
+            limit_memory: None,
+            limit_pids: None,
             deny_all: false,
             deny_read: false,
             deny_write: false,
@@ -16,7 +16,7 @@ use crate::config::{Config, Settings};
 use crate::deps::{DepsEngine, DepsOptions};
 use crate::env;
 use crate::env_diff::EnvDiff;
-use crate::sandbox::SandboxConfig;
+use crate::sandbox::{ResourceLimits, SandboxConfig};
 use crate::toolset::env_cache::CachedEnv;
 use crate::toolset::{InstallOptions, ResolveOptions, Toolset, ToolsetBuilder};
 
@@ -57,7 +57,7 @@ pub(crate) struct Exec {
     pub allow_env: Vec<String>,
 
     /// Allow network to specific host (implies --deny-net for everything else)
-    /// macOS only in v1; on Linux falls back to allowing all network
+    /// Use host:port to limit the port and *.domain for subdomains
     #[clap(long, value_name = "HOST", verbatim_doc_comment)]
     pub allow_net: Vec<String>,
 
@@ -93,6 +93,18 @@ pub(crate) struct Exec {
     #[clap(long)]
     pub fresh_env: bool,
 
+    /// Limit CPU usage to this many CPUs, e.g. --limit-cpu=1.5 (Linux only)
+    #[clap(long, value_name = "CPUS", value_parser = crate::sandbox::parse_cpu, verbatim_doc_comment)]
+    pub limit_cpu: Option<f64>,
+
+    /// Limit memory usage, e.g. --limit-memory=2GiB (Linux only)
+    #[clap(long, value_name = "SIZE", value_parser = crate::sandbox::parse_memory, verbatim_doc_comment)]
+    pub limit_memory: Option<u64>,
+
+    /// Limit the number of processes and threads (Linux only)
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..), verbatim_doc_comment)]
+    pub limit_pids: Option<u64>,
+
     /// Skip automatic dependency preparation
     #[clap(long)]
     pub no_deps: bool,
@@ -271,8 +283,15 @@ impl Exec {
                 allow_write: self.allow_write,
                 allow_net: self.allow_net,
                 allow_env: self.allow_env,
+                deny_read_paths: vec![],
+                deny_syscalls: false,
                 pass_through_env: vec![],
                 cache_env: vec![],
+                limits: ResourceLimits {
+                    memory: self.limit_memory,
+                    cpu: self.limit_cpu,
+                    pids: self.limit_pids,
+                },
             },
         );
         sandbox.resolve_paths();
@@ -398,6 +417,19 @@ where
         ));
     }
     env::remove_var(env::MISE_SHIM_PATH_ENV);
+    // Per-host network filtering and resource limits on Linux need mise to stay alive as
+    // the proxy and to watch the cgroup, so the program runs as a child instead of replacing
+    // this process.
+    #[cfg(target_os = "linux")]
+    if !sandbox.allow_net.is_empty() || sandbox.limits.is_set() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let status = sandbox.run_supervised(&program, &args)?;
+        let code = status
+            .code()
+            .unwrap_or_else(|| 128 + status.signal().unwrap_or(1));
+        return Err(crate::request_exit(code));
+    }
     // Apply sandbox (Landlock/seccomp on Linux, sandbox-exec on macOS)
     let args_str: Vec<String> = args
         .iter()
@@ -0,0 +1,633 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
+
+use eyre::{Result, bail};
+use itertools::Itertools;
+use petgraph::Direction;
+use petgraph::graph::{DiGraph, NodeIndex};
+use serde_yaml::{Mapping, Value};
+use xx::file;
+
+use crate::cli::run::AffectedSelection;
+use crate::config::{Config, Settings};
+use crate::file::display_path;
+use crate::git::Git;
+use crate::task::task_list::get_task_lists;
+use crate::task::{Deps, Task, TaskRunPhase};
+
+/// Generate a CI pipeline from the task graph
+///
+/// Walks the dependencies of a task and emits one CI job per task (or per
+/// workspace project with `--group-by project`). Each job runs `mise run` for
+/// its tasks and `needs` the jobs of the tasks they depend on, so independent
+/// tasks run in parallel across machines.
+///
+/// A job runs `mise run` without `--skip-deps`, so configure a remote task cache
+/// (`task.cache.remote_url`) to restore the results of upstream jobs instead of
+/// running them again. The remote cache settings are passed to every job.
+///
+/// Post-dependencies and service tasks do not get jobs of their own; they run
+/// inside the jobs of the tasks that use them.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub(super) struct Ci {
+    /// The CI provider to generate a pipeline for
+    #[clap(
+        long,
+        short,
+        value_enum,
+        default_value = "github",
+        verbatim_doc_comment
+    )]
+    provider: CiProvider,
+    /// The task to generate jobs for, along with its dependencies
+    #[clap(long, short, default_value = "ci", verbatim_doc_comment)]
+    task: String,
+    /// How tasks are grouped into jobs
+    ///
+    /// - `task` - one job per task
+    /// - `project` - one job per workspace project, running all of its tasks
+    #[clap(long, value_enum, default_value_t = CiGroupBy::Task, verbatim_doc_comment)]
+    group_by: CiGroupBy,
+    /// Only generate jobs for workspace projects affected by Git changes
+    ///
+    /// Selects tasks the same way as `mise run --affected`.
+    /// Prerequisites from unaffected projects get no job and run (or are
+    /// restored from the remote cache) inside the jobs that need them.
+    #[clap(long, verbatim_doc_comment)]
+    affected: bool,
+    /// Git base revision for --affected
+    #[clap(long, requires = "affected", value_name = "REV", verbatim_doc_comment)]
+    affected_base: Option<String>,
+    /// Git head revision for --affected
+    #[clap(long, requires = "affected", value_name = "REV", verbatim_doc_comment)]
+    affected_head: Option<String>,
+    /// Write to the provider's pipeline file instead of stdout
+    ///
+    /// - github: .github/workflows/$name.yml
+    /// - gitlab: .gitlab-ci.yml
+    /// - buildkite: .buildkite/pipeline.yml
+    #[clap(long, short, verbatim_doc_comment)]
+    write: bool,
+    /// The name of the GitHub workflow to generate
+    #[clap(long, default_value = "ci", verbatim_doc_comment)]
+    name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum CiProvider {
+    Github,
+    Gitlab,
+    Buildkite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+enum CiGroupBy {
+    Task,
+    Project,
+}
+
+/// One job of the generated pipeline
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CiJob {
+    id: String,
+    name: String,
+    /// `mise run` arguments of each task the job runs
+    tasks: Vec<Vec<String>>,
+    /// Ids of the jobs that must finish first
+    needs: Vec<String>,
+}
+
+impl CiJob {
+    fn command(&self) -> String {
+        if self.tasks.is_empty() {
+            return "echo 'no tasks to run'".to_string();
+        }
+        let tasks = self.tasks.iter().map(shell_words::join).join(" ::: ");
+        format!("mise run {tasks}")
+    }
+}
+
+impl Ci {
+    pub(super) async fn run(self) -> Result<()> {
+        Settings::get().ensure_experimental("generate ci")?;
+        let config = Config::get().await?;
+        let args = vec![self.task.clone()];
+        let (tasks, affected) = if self.affected {
+            let selection = AffectedSelection::resolve(
+                &config,
+                self.affected_base.as_deref(),
+                self.affected_head.as_deref(),
+            )
+            .await?;
+            let tasks = selection.tasks(&config, &args, false).await?;
+            (tasks, Some(selection))
+        } else {
+            (
+                get_task_lists(&config, &args, false, false, false).await?,
+                None,
+            )
+        };
+        let jobs = if tasks.is_empty() {
+            info!("no affected tasks, generating an empty pipeline");
+            vec![]
+        } else {
+            let deps = Deps::new(&config, tasks).await?;
+            plan_jobs(&deps.graph, self.group_by, |task| {
+                affected.as_ref().is_none_or(|a| a.contains(task))
+            })?
+        };
+        if Settings::get().task.cache.remote_url.is_none()
+            && jobs.iter().any(|j| !j.needs.is_empty())
+        {
+            warn!(
+                "task.cache.remote_url is not set, jobs will re-run the dependencies of their tasks"
+            );
+        }
+        let output = self.generate(jobs)?;
+        if self.write {
+            let path = Git::get_root()?.join(self.path());
+            file::write(&path, &output)?;
+            miseprintln!("Wrote to {}", display_path(&path));
+        } else {
+            miseprint!("{output}")?;
+        }
+        Ok(())
+    }
+
+    fn path(&self) -> PathBuf {
+        match self.provider {
+            CiProvider::Github => {
+                PathBuf::from(".github/workflows").join(format!("{}.yml", self.name))
+            }
+            CiProvider::Gitlab => PathBuf::from(".gitlab-ci.yml"),
+            CiProvider::Buildkite => PathBuf::from(".buildkite/pipeline.yml"),
+        }
+    }
+
+    fn generate(&self, mut jobs: Vec<CiJob>) -> Result<String> {
+        // GitHub and GitLab reject pipelines without jobs
+        if jobs.is_empty() {
+            jobs.push(CiJob {
+                id: "noop".into(),
+                name: "no tasks to run".into(),
+                tasks: vec![],
+                needs: vec![],
+            });
+        }
+        let cache_env = remote_cache_env();
+        let pipeline = match self.provider {
+            CiProvider::Github => {
+                let branch = Git::new(Git::get_root()?).current_branch()?;
+                github(&self.name, &branch, &jobs, &cache_env)
+            }
+            CiProvider::Gitlab => gitlab(&jobs, &cache_env),
+            CiProvider::Buildkite => buildkite(&jobs, &cache_env),
+        };
+        Ok(serde_yaml::to_string(&pipeline)?)
+    }
+}
+
+/// Groups the tasks of `graph` into jobs, in dependency order.
+///
+/// Post-phase tasks, services and tasks rejected by `include` get no job of their own;
+/// `mise run` starts them from the jobs of the tasks that need them, so those jobs
+/// inherit their dependencies instead.
+fn plan_jobs(
+    graph: &DiGraph<Task, ()>,
+    group_by: CiGroupBy,
+    include: impl Fn(&Task) -> bool,
+) -> Result<Vec<CiJob>> {
+    let has_job = |idx: NodeIndex| {
+        let task = &graph[idx];
+        task.run_phase == TaskRunPhase::Normal && !task.service && include(task)
+    };
+    let group_of = |task: &Task| match group_by {
+        CiGroupBy::Task => task_label(task),
+        CiGroupBy::Project => project_of(task),
+    };
+    let mut groups = BTreeMap::<String, BTreeSet<Vec<String>>>::new();
+    let mut needs = BTreeMap::<String, BTreeSet<String>>::new();
+    for idx in graph.node_indices().filter(|idx| has_job(*idx)) {
+        let task = &graph[idx];
+        let group = group_of(task);
+        groups
+            .entry(group.clone())
+            .or_default()
+            .insert(run_args(task));
+        let group_needs = needs.entry(group.clone()).or_default();
+        for dep in nearest_jobs(graph, idx, &has_job) {
+            let dep_group = group_of(&graph[dep]);
+            if dep_group != group {
+                group_needs.insert(dep_group);
+            }
+        }
+    }
+
+    // topological order, alphabetical among jobs that become ready together
+    let mut pending = needs.clone();
+    let mut order = vec![];
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .filter(|(_, needs)| needs.is_empty())
+            .map(|(group, _)| group.clone())
+            .collect_vec();
+        if ready.is_empty() {
+            bail!(
+                "grouping jobs by {group_by} creates a dependency cycle between {}",
+                pending.keys().join(", ")
+            );
+        }
+        for group in &ready {
+            pending.remove(group);
+        }
+        for needs in pending.values_mut() {
+            for group in &ready {
+                needs.remove(group);
+            }
+        }
+        order.extend(ready);
+    }
+
+    let mut ids = BTreeMap::new();
+    let mut used = HashSet::new();
+    for group in &order {
+        let base = job_id(group);
+        let mut id = base.clone();
+        let mut n = 1;
+        while !used.insert(id.clone()) {
+            n += 1;
+            id = format!("{base}-{n}");
+        }
+        ids.insert(group.clone(), id);
+    }
+
+    let jobs = order
+        .into_iter()
+        .map(|group| CiJob {
+            id: ids[&group].clone(),
+            tasks: groups
+                .remove(&group)
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            needs: needs[&group].iter().map(|n| ids[n].clone()).collect(),
+            name: group,
+        })
+        .collect();
+    Ok(jobs)
+}
+
+/// Closest dependencies of `idx` that have a job, looking through those that do not
+fn nearest_jobs(
+    graph: &DiGraph<Task, ()>,
+    idx: NodeIndex,
+    has_job: &impl Fn(NodeIndex) -> bool,
+) -> BTreeSet<NodeIndex> {
+    let mut found = BTreeSet::new();
+    let mut seen = HashSet::new();
+    let mut stack = graph
+        .neighbors_directed(idx, Direction::Outgoing)
+        .collect_vec();
+    while let Some(dep) = stack.pop() {
+        if !seen.insert(dep) {
+            continue;
+        }
+        if has_job(dep) {
+            found.insert(dep);
+        } else {
+            stack.extend(graph.neighbors_directed(dep, Direction::Outgoing));
+        }
+    }
+    found
+}
+
+fn run_args(task: &Task) -> Vec<String> {
+    std::iter::once(task.name.clone())
+        .chain(task.args.iter().cloned())
+        .collect()
+}
+
+fn task_label(task: &Task) -> String {
+    run_args(task).join(" ")
+}
+
+/// The workspace project of a `//path:task` task, or `//` for tasks outside of a project
+fn project_of(task: &Task) -> String {
+    task.name
+        .strip_prefix("//")
+        .and_then(|name| name.split_once(':'))
+        .map(|(project, _)| format!("//{project}"))
+        .unwrap_or_else(|| "//".to_string())
+}
+
+/// A job id accepted by every provider: lowercase alphanumerics separated by dashes
+fn job_id(name: &str) -> String {
+    let id = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let id = id.split('-').filter(|s| !s.is_empty()).join("-");
+    if id.is_empty() {
+        "root".to_string()
+    } else if id.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("job-{id}")
+    } else {
+        id
+    }
+}
+
+/// Environment that points each job at the configured remote task cache
+fn remote_cache_env() -> Vec<(&'static str, String)> {
+    let settings = Settings::get();
+    let cache = &settings.task.cache;
+    let Some(url) = &cache.remote_url else {
+        return vec![];
+    };
+    let mut env = vec![("MISE_TASK_CACHE_REMOTE_URL", url.clone())];
+    if let Some(namespace) = &cache.remote_namespace {
+        env.push(("MISE_TASK_CACHE_REMOTE_NAMESPACE", namespace.clone()));
+    }
+    env.push(("MISE_TASK_CACHE_REMOTE_MODE", cache.remote_mode.to_string()));
+    if let Some(audience) = &cache.remote_oidc_audience {
+        env.push(("MISE_TASK_CACHE_REMOTE_OIDC_AUDIENCE", audience.clone()));
+    }
+    env
+}
+
+fn map<const N: usize>(entries: [(&str, Value); N]) -> Value {
+    Value::Mapping(
+        entries
+            .into_iter()
+            .map(|(k, v)| (Value::from(k), v))
+            .collect(),
+    )
+}
+
+fn list<T: Into<Value>>(items: impl IntoIterator<Item = T>) -> Value {
+    Value::Sequence(items.into_iter().map(Into::into).collect())
+}
+
+fn env_map(env: &[(&str, String)]) -> Value {
+    Value::Mapping(
+        env.iter()
+            .map(|(k, v)| (Value::from(*k), Value::from(v.as_str())))
+            .collect(),
+    )
+}
+
+/// Commands that install mise and the project's tools on providers without a mise action
+const INSTALL_MISE: [&str; 3] = [
+    "curl -fsSL https://mise.run | sh",
+    r#"export PATH="$HOME/.local/bin:$PATH""#,
+    "mise install",
+];
+
+fn github(name: &str, branch: &str, jobs: &[CiJob], cache_env: &[(&str, String)]) -> Value {
+    let oidc = cache_env
+        .iter()
+        .any(|(k, _)| *k == "MISE_TASK_CACHE_REMOTE_OIDC_AUDIENCE");
+    let mut env = cache_env.to_vec();
+    if !cache_env.is_empty() && !oidc {
+        env.push((
+            "MISE_TASK_CACHE_REMOTE_TOKEN",
+            "${{ secrets.MISE_TASK_CACHE_REMOTE_TOKEN }}".to_string(),
+        ));
+    }
+    let mut gh_jobs = Mapping::new();
+    for job in jobs {
+        let mut j = Mapping::new();
+        j.insert("name".into(), job.name.as_str().into());
+        if !job.needs.is_empty() {
+            j.insert("needs".into(), list(job.needs.iter().map(String::as_str)));
+        }
+        j.insert("runs-on".into(), "ubuntu-latest".into());
+        if oidc {
+            j.insert(
+                "permissions".into(),
+                map([("contents", "read".into()), ("id-token", "write".into())]),
+            );
+        }
+        if !env.is_empty() {
+            j.insert("env".into(), env_map(&env));
+        }
+        j.insert(
+            "steps".into(),
+            list([
+                map([("uses", "actions/checkout@v6".into())]),
+                map([("uses", "jdx/mise-action@v3".into())]),
+                map([("run", job.command().into())]),
+            ]),
+        );
+        gh_jobs.insert(job.id.as_str().into(), Value::Mapping(j));
+    }
+    map([
+        ("name", name.into()),
+        (
+            "on",
+            map([
+                ("workflow_dispatch", Value::Mapping(Mapping::new())),
+                ("pull_request", Value::Mapping(Mapping::new())),
+                (
+                    "push",
+                    map([("tags", list(["*"])), ("branches", list([branch]))]),
+                ),
+            ]),
+        ),
+        (
+            "concurrency",
+            map([
+                ("group", "${{ github.workflow }}-${{ github.ref }}".into()),
+                ("cancel-in-progress", true.into()),
+            ]),
+        ),
+        ("env", map([("MISE_EXPERIMENTAL", true.into())])),
+        ("jobs", Value::Mapping(gh_jobs)),
+    ])
+}
+
+fn gitlab(jobs: &[CiJob], cache_env: &[(&str, String)]) -> Value {
+    let mut pipeline = Mapping::new();
+    pipeline.insert(
+        "variables".into(),
+        map([("MISE_EXPERIMENTAL", "true".into())]),
+    );
+    pipeline.insert(
+        "default".into(),
+        map([("before_script", list(INSTALL_MISE))]),
+    );
+    for job in jobs {
+        let mut j = Mapping::new();
+        // an explicit empty list lets the job start before earlier stages finish
+        j.insert("needs".into(), list(job.needs.iter().map(String::as_str)));
+        if !cache_env.is_empty() {
+            j.insert("variables".into(), env_map(cache_env));
+        }
+        j.insert("script".into(), list([job.command()]));
+        pipeline.insert(job.id.as_str().into(), Value::Mapping(j));
+    }
+    Value::Mapping(pipeline)
+}
+
+fn buildkite(jobs: &[CiJob], cache_env: &[(&str, String)]) -> Value {
+    let steps = jobs.iter().map(|job| {
+        let mut step = Mapping::new();
+        step.insert("label".into(), job.name.as_str().into());
+        step.insert("key".into(), job.id.as_str().into());
+        if !job.needs.is_empty() {
+            step.insert(
+                "depends_on".into(),
+                list(job.needs.iter().map(String::as_str)),
+            );
+        }
+        if !cache_env.is_empty() {
+            step.insert("env".into(), env_map(cache_env));
+        }
+        step.insert(
+            "commands".into(),
+            list(
+                INSTALL_MISE
+                    .iter()
+                    .map(|c| c.to_string())
+                    .chain([job.command()]),
+            ),
+        );
+        Value::Mapping(step)
+    });
+    map([
+        ("env", map([("MISE_EXPERIMENTAL", "true".into())])),
+        ("steps", list(steps)),
+    ])
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise generate ci --provider github --task ci --write</bold>
+    $ <bold>mise generate ci --provider gitlab --group-by project > .gitlab-ci.yml</bold>
+
+    # generate jobs only for projects changed since main, e.g. for a dynamic pipeline
+    $ <bold>mise generate ci --provider buildkite --affected --affected-base origin/main | buildkite-agent pipeline upload</bold>
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn task(name: &str) -> Task {
+        Task {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Builds a graph from `(task, depends)` pairs; edges point from a task to its dependency
+    fn graph(tasks: &[Task], edges: &[(&str, &str)]) -> DiGraph<Task, ()> {
+        let mut graph = DiGraph::new();
+        let idx = tasks
+            .iter()
+            .map(|t| (t.name.clone(), graph.add_node(t.clone())))
+            .collect::<BTreeMap<_, _>>();
+        for (a, b) in edges {
+            graph.add_edge(idx[*a], idx[*b], ());
+        }
+        graph
+    }
+
+    fn summary(jobs: &[CiJob]) -> Vec<(String, Vec<String>)> {
+        jobs.iter()
+            .map(|j| (j.id.clone(), j.needs.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_jobs_mirrors_depends() {
+        let g = graph(
+            &[task("ci"), task("lint"), task("build"), task("test")],
+            &[("ci", "lint"), ("ci", "test"), ("test", "build")],
+        );
+        let jobs = plan_jobs(&g, CiGroupBy::Task, |_| true).unwrap();
+        assert_eq!(
+            summary(&jobs),
+            vec![
+                ("build".into(), vec![]),
+                ("lint".into(), vec![]),
+                ("test".into(), vec!["build".into()]),
+                ("ci".into(), vec!["lint".into(), "test".into()]),
+            ]
+        );
+        assert_eq!(jobs[3].command(), "mise run ci");
+    }
+
+    #[test]
+    fn test_plan_jobs_skips_services_and_excluded() {
+        let mut db = task("db");
+        db.service = true;
+        let g = graph(
+            &[task("setup"), db, task("gen"), task("test")],
+            &[("test", "db"), ("db", "setup"), ("test", "gen")],
+        );
+        let jobs = plan_jobs(&g, CiGroupBy::Task, |t| t.name != "gen").unwrap();
+        assert_eq!(
+            summary(&jobs),
+            vec![
+                ("setup".into(), vec![]),
+                ("test".into(), vec!["setup".into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_jobs_group_by_project() {
+        let g = graph(
+            &[
+                task("//app:build"),
+                task("//app:test"),
+                task("//core:build"),
+                task("lint"),
+            ],
+            &[
+                ("//app:test", "//app:build"),
+                ("//app:build", "//core:build"),
+            ],
+        );
+        let jobs = plan_jobs(&g, CiGroupBy::Project, |_| true).unwrap();
+        assert_eq!(
+            summary(&jobs),
+            vec![
+                ("root".into(), vec![]),
+                ("core".into(), vec![]),
+                ("app".into(), vec!["core".into()]),
+            ]
+        );
+        assert_eq!(jobs[2].command(), "mise run //app:build ::: //app:test");
+    }
+
+    #[test]
+    fn test_plan_jobs_group_cycle() {
+        let g = graph(
+            &[task("//a:one"), task("//b:two"), task("//a:three")],
+            &[("//a:three", "//b:two"), ("//b:two", "//a:one")],
+        );
+        let err = plan_jobs(&g, CiGroupBy::Project, |_| true).unwrap_err();
+        assert!(err.to_string().contains("dependency cycle"), "{err}");
+    }
+
+    #[test]
+    fn test_job_id() {
+        assert_eq!(job_id("//crates/app:build"), "crates-app-build");
+        assert_eq!(job_id("test --release"), "test-release");
+        assert_eq!(job_id("//"), "root");
+        assert_eq!(job_id("7zip"), "job-7zip");
+    }
+}
@@ -1,6 +1,7 @@
 use clap::Subcommand;
 use std::path::{Path, PathBuf};
 
+mod ci;
 mod config;
 mod devcontainer;
 mod git_pre_commit;
@@ -25,6 +26,7 @@ enum Commands {
     // command has nothing to do with.
     #[clap(hide = true)]
     Bootstrap(install_script::InstallScript),
+    Ci(ci::Ci),
     Config(config::Config),
     Devcontainer(devcontainer::Devcontainer),
     GitPreCommit(git_pre_commit::GitPreCommit),
@@ -47,6 +49,7 @@ impl Commands {
                 );
                 cmd.run().await
             }
+            Self::Ci(cmd) => cmd.run().await,
             Self::Config(cmd) => cmd.run().await,
             Self::Devcontainer(cmd) => cmd.run().await,
             Self::GitPreCommit(cmd) => cmd.run().await,
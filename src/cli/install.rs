@@ -442,6 +442,7 @@ impl Install {
                 offline: false,
                 refresh_remote_versions: false,
                 inactive: false,
+                skip_policy: false,
             },
             dry_run: self.is_dry_run(),
             locked: Settings::get().locked,
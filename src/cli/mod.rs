@@ -11,6 +11,7 @@ use std::path::PathBuf;
 mod activate;
 pub(crate) mod args;
 mod asdf;
+mod audit;
 pub(crate) mod backends;
 mod bin_paths;
 mod bootstrap;
@@ -52,6 +53,7 @@ mod oci;
 mod outdated;
 mod patrons;
 mod plugins;
+mod policy;
 mod prune;
 mod registry;
 #[cfg(debug_assertions)]
@@ -209,6 +211,7 @@ pub(crate) enum Commands {
     Activate(activate::Activate),
     ToolAlias(Box<tool_alias::ToolAlias>),
     Asdf(asdf::Asdf),
+    Audit(audit::Audit),
     Backends(backends::Backends),
     BinPaths(bin_paths::BinPaths),
     Bootstrap(bootstrap::DeferredBootstrap),
@@ -244,6 +247,7 @@ pub(crate) enum Commands {
     Outdated(outdated::Outdated),
     Patrons(patrons::Patrons),
     Plugins(plugins::Plugins),
+    Policy(policy::Policy),
     Deps(deps::Deps),
     Prune(prune::Prune),
     Registry(registry::Registry),
@@ -322,6 +326,7 @@ impl Commands {
             Self::Activate(cmd) => cmd.run(),
             Self::ToolAlias(cmd) => cmd.run().await,
             Self::Asdf(cmd) => cmd.run().await,
+            Self::Audit(cmd) => cmd.run().await,
             Self::Backends(cmd) => cmd.run().await,
             Self::BinPaths(cmd) => cmd.run().await,
             Self::Bootstrap(cmd) => cmd.run().await,
@@ -357,6 +362,7 @@ impl Commands {
             Self::Outdated(cmd) => cmd.run().await,
             Self::Patrons(cmd) => cmd.run().await,
             Self::Plugins(cmd) => cmd.run().await,
+            Self::Policy(cmd) => cmd.run().await,
             Self::Deps(cmd) => cmd.run().await,
             Self::Prune(cmd) => cmd.run().await,
             Self::Registry(cmd) => cmd.run().await,
@@ -831,6 +837,7 @@ impl Cli {
         measure!("logger", { logger::init() });
         if !print_version {
             measure!("registry::refresh", { crate::registry::refresh().await });
+            measure!("policy::refresh", { crate::policy::refresh().await });
             let _ = measure!("backend::load_tools", { backend::load_tools().await });
         }
         warn_deprecated_backends_alias(deprecated_backends_alias);
@@ -910,11 +917,15 @@ impl Cli {
                         task_cache_explain: false,
                         task_cache_explain_json: false,
                         task_cache_stats: false,
+                        report: vec![],
                         timeout: None,
                         skip_deps: false,
                         skip_tools: false,
                         no_deps: false,
                         fresh_env: false,
+                        limit_cpu: None,
+                        limit_memory: None,
+                        limit_pids: None,
                         deny_all: false,
                         deny_read: false,
                         deny_write: false,
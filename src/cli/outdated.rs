@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use crate::backend::release_notes;
 use crate::cli::args::ToolArg;
 use crate::config::Config;
 use crate::toolset::outdated_info::OutdatedInfo;
@@ -35,6 +36,15 @@ pub(crate) struct Outdated {
     #[clap(short = 'J', long, verbatim_doc_comment)]
     pub json: bool,
 
+    /// Show the release notes between the current and latest version
+    ///
+    /// Supported for tools installed from GitHub, GitLab or Forgejo releases
+    /// (aqua, github, gitlab and forgejo backends). Releases that call out a
+    /// breaking change or start a new major version are marked BREAKING.
+    /// With --json, the notes are added as a `changelog` field.
+    #[clap(long, verbatim_doc_comment)]
+    pub changelog: bool,
+
     /// Deprecated shorthand for --bump
     #[clap(short = 'l', hide = true)]
     pub legacy_bump: bool,
@@ -103,6 +113,11 @@ impl Outdated {
                 },
             )
             .await;
+        let outdated = if self.changelog {
+            OutdatedInfo::load_changelogs(outdated).await?
+        } else {
+            outdated
+        };
         let bump_available = if !self.json && !self.bump && outdated.is_empty() {
             ts.list_outdated_versions(
                 &config,
@@ -140,12 +155,21 @@ impl Outdated {
             }
             return Ok(());
         }
-        let mut table = tabled::Table::new(outdated);
+        let mut table = tabled::Table::new(&outdated);
         if !self.bump {
             table.with(Remove::column(ByColumnName::new("bump")));
         }
         table::default_style(&mut table, self.no_header);
         miseprintln!("{table}");
+        for o in &outdated {
+            if let Some(notes) = &o.changelog {
+                miseprintln!();
+                miseprint!(
+                    "{}",
+                    release_notes::render(&o.name, o.current.as_deref(), &o.latest, notes)
+                )?;
+            }
+        }
         Ok(())
     }
 
@@ -179,6 +203,15 @@ After removal, `-l` will become shorthand for `--local`. Use `-b` or `--bump` in
     $ <bold>mise outdated --json</bold>
     {"python": {"requested": "3.11", "current": "3.11.0", "latest": "3.11.1"}, ...}
 
+    $ <bold>mise outdated --changelog github:cli/cli</bold>
+    Plugin         Requested  Current  Latest
+    github:cli/cli latest     2.61.0   2.62.0
+
+    github:cli/cli 2.61.0 → 2.62.0
+      v2.62.0 GitHub CLI 2.62.0 https://github.com/cli/cli/releases/tag/v2.62.0
+        ## What's Changed
+        ...
+
     $ <bold>mise outdated --local</bold>
     Plugin  Requested  Current  Latest
     node    20         20.0.0   20.1.0
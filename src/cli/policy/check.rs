@@ -0,0 +1,78 @@
+use eyre::Result;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::policy;
+use crate::toolset::{ResolveOptions, ToolsetBuilder};
+use crate::ui::table::MiseTable;
+
+/// Check the current toolset against the tool policy
+///
+/// Resolves the tools of the current config and lists every version the
+/// policy denies. Exits with status 1 if there are any, so it can gate CI.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub(crate) struct PolicyCheck {
+    /// Output violations in JSON format
+    #[clap(long, short = 'J')]
+    json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Violation {
+    tool: String,
+    reason: String,
+}
+
+impl PolicyCheck {
+    pub(crate) async fn run(self) -> Result<()> {
+        let Some(policy) = policy::get()? else {
+            if self.json {
+                miseprintln!("[]");
+            } else {
+                info!("no tool policy configured");
+            }
+            return Ok(());
+        };
+        let config = Config::get().await?;
+        let ts = ToolsetBuilder::new()
+            .with_resolve_options(ResolveOptions {
+                skip_policy: true,
+                ..Default::default()
+            })
+            .build(&config)
+            .await?;
+        let violations = policy
+            .toolset_violations(&ts)
+            .into_iter()
+            .map(|(tool, reason)| Violation { tool, reason })
+            .collect::<Vec<_>>();
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(&violations)?);
+        } else if violations.is_empty() {
+            info!("no policy violations ({})", policy.location);
+        } else {
+            let mut table = MiseTable::new(false, &["Tool", "Reason"]);
+            for violation in &violations {
+                table.add_row(vec![violation.tool.clone(), violation.reason.clone()]);
+            }
+            table.print()?;
+        }
+        if !violations.is_empty() {
+            return Err(crate::request_exit(1));
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise policy check</bold>
+    Tool                Reason
+    python@3.9.18       3.9.18 does not satisfy tools.python.version ">=3.10"
+    npm:prettier@3.3.3  the npm backend is not in backends.allow (core, aqua, github)
+
+    $ <bold>mise policy check --json</bold>
+"#
+);
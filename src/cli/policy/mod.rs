@@ -0,0 +1,25 @@
+mod check;
+
+/// Inspect the organization tool policy
+///
+/// The policy restricts which backends, tools and versions may be resolved
+/// and installed. See https://mise.jdx.dev/tool-policy.html
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment)]
+pub(crate) struct Policy {
+    #[clap(subcommand)]
+    subcommand: Commands,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum Commands {
+    Check(check::PolicyCheck),
+}
+
+impl Policy {
+    pub(crate) async fn run(self) -> eyre::Result<()> {
+        match self.subcommand {
+            Commands::Check(cmd) => cmd.run().await,
+        }
+    }
+}
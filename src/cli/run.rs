@@ -15,9 +15,11 @@ use crate::file::display_path;
 use crate::task::has_any_usage_spec;
 use crate::task::task_executor::TaskRunContext;
 use crate::task::task_helpers::task_needs_permit;
+use crate::task::task_history::TaskHistory;
 use crate::task::task_list::{get_task_lists, resolve_depends};
 use crate::task::task_output::TaskOutput;
 use crate::task::task_output_handler::OutputHandler;
+use crate::task::task_report::{TaskReport, TaskReportTarget};
 use crate::task::{Deps, Task, TaskCacheMode, usage_command_for_args};
 use crate::toolset::{InstallOptions, ResolveOptions, ToolVersion, ToolsetBuilder};
 use crate::ui::{ctrlc, info, style};
@@ -215,6 +217,18 @@ pub(crate) struct Run {
     #[clap(long)]
     pub fresh_env: bool,
 
+    /// Limit CPU usage of each task to this many CPUs, e.g. --limit-cpu=1.5 (Linux only)
+    #[clap(long, value_name = "CPUS", value_parser = crate::sandbox::parse_cpu, verbatim_doc_comment)]
+    pub limit_cpu: Option<f64>,
+
+    /// Limit memory usage of each task, e.g. --limit-memory=2GiB (Linux only)
+    #[clap(long, value_name = "SIZE", value_parser = crate::sandbox::parse_memory, verbatim_doc_comment)]
+    pub limit_memory: Option<u64>,
+
+    /// Limit the number of processes and threads of each task (Linux only)
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..), verbatim_doc_comment)]
+    pub limit_pids: Option<u64>,
+
     /// Do not use cache on remote tasks
     #[clap(long, verbatim_doc_comment, env = "MISE_TASK_REMOTE_NO_CACHE")]
     pub no_cache: bool,
@@ -229,6 +243,15 @@ pub(crate) struct Run {
     #[clap(long, alias = "no-timing", verbatim_doc_comment)]
     pub no_timings: bool,
 
+    /// Write a report of task results when the run finishes
+    ///
+    /// Takes the form `<FORMAT>:<PATH>` where FORMAT is `junit` or `json`.
+    /// Each executed task is reported with its duration, status
+    /// (passed/failed/skipped/cached), exit code, and the tail of its output.
+    /// Can be passed multiple times, e.g.: --report junit:results.xml --report json:results.json
+    #[clap(long, value_name = "FORMAT:PATH", verbatim_doc_comment)]
+    pub report: Vec<TaskReportTarget>,
+
     /// Run only the specified tasks skipping all dependencies
     #[clap(long, verbatim_doc_comment, env = "MISE_TASK_SKIP_DEPENDS")]
     pub skip_deps: bool,
@@ -333,86 +356,138 @@ async fn get_affected_task_list(
     explain: bool,
     json: bool,
 ) -> Result<Vec<Task>> {
-    Settings::get().ensure_experimental("affected tasks")?;
-    let workspace_root = config
-        .monorepo_root()
-        .ok_or_else(|| eyre!("--affected requires a monorepo root configuration"))?;
-    let graph = config.workspace_project_graph()?;
-    let revisions = crate::task::workspace::git::WorkspaceGitRevisions::resolve(base, head);
-    let changed_paths = revisions.changed_paths(&workspace_root)?;
-    let global_inputs = config.monorepo_global_task_inputs().await?;
-    let git = crate::git::Git::new(&workspace_root);
-    let cargo = crate::task::workspace::cargo::CargoWorkspaceProvider;
-    let go = crate::task::workspace::go::GoWorkspaceProvider;
-    let node = crate::task::workspace::node::NodeWorkspaceProvider;
-    let uv = crate::task::workspace::uv::UvWorkspaceProvider;
-    let providers: [&dyn crate::task::workspace::WorkspaceProvider; 4] = [&cargo, &go, &node, &uv];
-    let mut regular_paths = BTreeSet::new();
-    let mut lockfile_projects = BTreeMap::<PathBuf, BTreeSet<_>>::new();
-    let mut comparison_base: Option<String> = None;
-
-    for path in changed_paths {
-        let Some(lockfile_candidates) =
-            graph.affected_projects_for_lockfile(&providers, &path, None, None)?
-        else {
-            regular_paths.insert(path);
-            continue;
-        };
-        if lockfile_candidates.is_empty() {
-            regular_paths.insert(path);
-            continue;
-        }
-        let comparison_base = match &comparison_base {
-            Some(base) => base.clone(),
-            None => {
-                let base = git.merge_base(&revisions.base, &revisions.head)?;
-                comparison_base = Some(base.clone());
-                base
+    let selection = AffectedSelection::resolve(config, base, head).await?;
+    let tasks = selection.tasks(config, args, only).await?;
+    if json {
+        display_affected_json(
+            &selection.revisions,
+            &selection.workspace_root,
+            &selection.graph,
+            &selection.affected,
+            &tasks,
+        )?;
+    } else if explain {
+        display_affected_explanation(
+            &selection.revisions,
+            &selection.workspace_root,
+            &selection.graph,
+            &selection.affected,
+            &tasks,
+        )?;
+    }
+    Ok(tasks)
+}
+
+/// Workspace projects affected by the Git changes between two revisions, as used by `--affected`
+pub(crate) struct AffectedSelection {
+    revisions: crate::task::workspace::git::WorkspaceGitRevisions,
+    workspace_root: PathBuf,
+    graph: Arc<crate::task::workspace::WorkspaceProjectGraph>,
+    affected: crate::task::workspace::AffectedProjects,
+    roots: BTreeSet<PathBuf>,
+}
+
+impl AffectedSelection {
+    pub(crate) async fn resolve(
+        config: &Arc<Config>,
+        base: Option<&str>,
+        head: Option<&str>,
+    ) -> Result<Self> {
+        Settings::get().ensure_experimental("affected tasks")?;
+        let workspace_root = config
+            .monorepo_root()
+            .ok_or_else(|| eyre!("--affected requires a monorepo root configuration"))?;
+        let graph = config.workspace_project_graph()?;
+        let revisions = crate::task::workspace::git::WorkspaceGitRevisions::resolve(base, head);
+        let changed_paths = revisions.changed_paths(&workspace_root)?;
+        let global_inputs = config.monorepo_global_task_inputs().await?;
+        let git = crate::git::Git::new(&workspace_root);
+        let cargo = crate::task::workspace::cargo::CargoWorkspaceProvider;
+        let go = crate::task::workspace::go::GoWorkspaceProvider;
+        let node = crate::task::workspace::node::NodeWorkspaceProvider;
+        let uv = crate::task::workspace::uv::UvWorkspaceProvider;
+        let providers: [&dyn crate::task::workspace::WorkspaceProvider; 4] =
+            [&cargo, &go, &node, &uv];
+        let mut regular_paths = BTreeSet::new();
+        let mut lockfile_projects = BTreeMap::<PathBuf, BTreeSet<_>>::new();
+        let mut comparison_base: Option<String> = None;
+
+        for path in changed_paths {
+            let Some(lockfile_candidates) =
+                graph.affected_projects_for_lockfile(&providers, &path, None, None)?
+            else {
+                regular_paths.insert(path);
+                continue;
+            };
+            if lockfile_candidates.is_empty() {
+                regular_paths.insert(path);
+                continue;
+            }
+            let comparison_base = match &comparison_base {
+                Some(base) => base.clone(),
+                None => {
+                    let base = git.merge_base(&revisions.base, &revisions.head)?;
+                    comparison_base = Some(base.clone());
+                    base
+                }
+            };
+            let before = git.file_at_revision(&comparison_base, &path)?;
+            let after = git.file_at_revision(&revisions.head, &path)?;
+            if let Some(projects) = graph.affected_projects_for_lockfile(
+                &providers,
+                &path,
+                before.as_deref(),
+                after.as_deref(),
+            )? {
+                lockfile_projects.entry(path).or_default().extend(projects);
             }
-        };
-        let before = git.file_at_revision(&comparison_base, &path)?;
-        let after = git.file_at_revision(&revisions.head, &path)?;
-        if let Some(projects) = graph.affected_projects_for_lockfile(
-            &providers,
-            &path,
-            before.as_deref(),
-            after.as_deref(),
-        )? {
-            lockfile_projects.entry(path).or_default().extend(projects);
         }
+
+        let affected = graph.affected_projects_for_changes(
+            &workspace_root,
+            regular_paths,
+            &global_inputs,
+            &lockfile_projects,
+        )?;
+        let roots = affected
+            .projects()
+            .map(|(id, _)| id)
+            .filter_map(|id| graph.get(id))
+            .map(|project| crate::file::desymlink_path(&workspace_root.join(&project.root)))
+            .collect::<BTreeSet<_>>();
+        Ok(Self {
+            revisions,
+            workspace_root,
+            graph,
+            affected,
+            roots,
+        })
     }
 
-    let affected = graph.affected_projects_for_changes(
-        &workspace_root,
-        regular_paths,
-        &global_inputs,
-        &lockfile_projects,
-    )?;
-    let affected_roots = affected
-        .projects()
-        .map(|(id, _)| id)
-        .filter_map(|id| graph.get(id))
-        .map(|project| crate::file::desymlink_path(&workspace_root.join(&project.root)))
-        .collect::<BTreeSet<_>>();
-
-    let args = affected_task_args(args);
-    let mut tasks = get_task_lists(config, &args, true, only, false).await?;
-    // Restrict only the task-pattern matches. `Run::run` calls `resolve_depends`
-    // after this returns, so prerequisites from unaffected projects remain intact.
-    tasks.retain(|task| {
+    /// Whether `task` is defined by one of the affected projects
+    pub(crate) fn contains(&self, task: &Task) -> bool {
         !task.global
             && task
                 .config_root
                 .as_deref()
                 .map(crate::file::desymlink_path)
-                .is_some_and(|root| affected_roots.contains(&root))
-    });
-    if json {
-        display_affected_json(&revisions, &workspace_root, &graph, &affected, &tasks)?;
-    } else if explain {
-        display_affected_explanation(&revisions, &workspace_root, &graph, &affected, &tasks)?;
+                .is_some_and(|root| self.roots.contains(&root))
+    }
+
+    /// Tasks matching `args` that belong to an affected project
+    pub(crate) async fn tasks(
+        &self,
+        config: &Arc<Config>,
+        args: &[String],
+        only: bool,
+    ) -> Result<Vec<Task>> {
+        let args = affected_task_args(args);
+        let mut tasks = get_task_lists(config, &args, true, only, false).await?;
+        // Restrict only the task-pattern matches. `Run::run` calls `resolve_depends`
+        // after this returns, so prerequisites from unaffected projects remain intact.
+        tasks.retain(|task| self.contains(task));
+        Ok(tasks)
     }
-    Ok(tasks)
 }
 
 #[derive(Serialize)]
@@ -910,7 +985,7 @@ impl Run {
         // Pump deps leaves into scheduler
         let mut main_done_rx = scheduler.pump_deps(main_deps.clone()).await;
         let spawn_context = scheduler.spawn_context(config.clone());
-        scheduler
+        let run_result = scheduler
             .run_loop(
                 &mut main_done_rx,
                 main_deps.clone(),
@@ -932,12 +1007,22 @@ impl Run {
                     }
                 },
             )
-            .await?;
+            .await;
+        // Dependents are done (or the run is stopping), so services can shut down before
+        // waiting on the remaining jobs.
+        this.executor.as_ref().unwrap().services.stop_all();
+        run_result?;
 
         let join_result = scheduler.join_all(this.continue_on_error).await;
         if let Some(session) = &this.cache_session {
             crate::cache::session::display_stats(session.finish().await?);
         }
+        if let Some(history) = &this.executor.as_ref().unwrap().history {
+            history.finish();
+        }
+        if let Some(report) = &this.executor.as_ref().unwrap().report {
+            report.write(&this.report, timer.elapsed())?;
+        }
         join_result?;
 
         // Step 7: Display results and handle failures
@@ -1016,6 +1101,31 @@ impl Run {
         ctx.in_flight
             .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         let in_flight_c = ctx.in_flight.clone();
+        // A service leaves the in-flight count (and the dependency graph) once it is ready,
+        // so whichever of "ready" and "finished" happens first does the decrement. A service
+        // nothing depends on is what the user asked to run, so it stays in flight and keeps
+        // the run going until it exits or is interrupted.
+        let released = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let releases_on_ready = task.service && deps_for_remove.lock().await.has_dependents(&task);
+        let on_ready = task.service.then(|| {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let deps = deps_for_remove.clone();
+            let in_flight = in_flight_c.clone();
+            let released = released.clone();
+            let task = task.clone();
+            tokio::spawn(async move {
+                if rx.await.is_ok() {
+                    if releases_on_ready
+                        && !released.swap(true, std::sync::atomic::Ordering::SeqCst)
+                    {
+                        in_flight.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                    }
+                    deps.lock().await.remove(&task);
+                    trace!("service ready, dependents unblocked: {}", task.name);
+                }
+            });
+            tx
+        });
         trace!("running task: {task}");
         let allow_during_interruption = inherited_allow_during_interruption
             || deps_for_remove.lock().await.is_runnable_post_dep(&task);
@@ -1040,6 +1150,7 @@ impl Run {
                 semaphore,
                 permit: &mut permit,
                 allow_during_interruption,
+                on_ready,
             }))
             .catch_unwind()
             .await
@@ -1088,7 +1199,7 @@ impl Run {
                     };
                 }
                 if !interrupted {
-                    this.add_failed_task(task.clone(), status);
+                    this.add_failed_task(task.clone(), status, Error::failure_reason(err));
                 }
                 // SIGTERM any still-running siblings so we exit promptly on
                 // failure instead of waiting for them to finish naturally.
@@ -1118,7 +1229,9 @@ impl Run {
             deps.remove(&task);
             drop(deps);
             trace!("deps removed: {} {}", task.name, task.args.join(" "));
-            in_flight_c.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+            if !released.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                in_flight_c.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+            }
             if interrupted {
                 Ok(())
             } else {
@@ -1247,10 +1360,25 @@ impl Run {
                     allow_write: self.allow_write.clone(),
                     allow_net: self.allow_net.clone(),
                     allow_env: self.allow_env.clone(),
+                    deny_read_paths: vec![],
+                    deny_syscalls: false,
                     pass_through_env: vec![],
                     cache_env: vec![],
+                    limits: crate::sandbox::ResourceLimits {
+                        memory: self.limit_memory,
+                        cpu: self.limit_cpu,
+                        pids: self.limit_pids,
+                    },
                 },
             ),
+            report: (!self.report.is_empty() && !self.dry_run).then(TaskReport::default),
+            history: (!self.dry_run && Settings::get().task.history)
+                .then(|| {
+                    TaskHistory::start()
+                        .inspect_err(|err| warn!("failed to start task history: {err:#}"))
+                        .ok()
+                })
+                .flatten(),
         };
         self.executor = Some(crate::task::task_executor::TaskExecutor::new(
             self.context_builder.clone(),
@@ -1353,9 +1481,9 @@ impl Run {
             .await
     }
 
-    fn add_failed_task(&self, task: Task, status: Option<i32>) {
+    fn add_failed_task(&self, task: Task, status: Option<i32>, reason: Option<String>) {
         if let Some(executor) = &self.executor {
-            executor.add_failed_task(task, status);
+            executor.add_failed_task(task, status, reason);
         }
     }
 
@@ -1371,6 +1499,27 @@ impl Run {
         if !task.pass_through_env.is_empty() {
             Settings::get().ensure_experimental("task environment pass-through")?;
         }
+        if task.service {
+            Settings::get().ensure_experimental("service tasks")?;
+            Settings::get().task_service_stop_timeout()?;
+            if self
+                .output_handler
+                .as_ref()
+                .is_some_and(|oh| oh.raw(Some(task)))
+            {
+                bail!(
+                    "task {} is a service and cannot run with raw or interactive output",
+                    task.name
+                );
+            }
+            if let Some(ready) = &task.ready {
+                ready
+                    .validate()
+                    .wrap_err_with(|| format!("invalid ready probe for task {}", task.name))?;
+            }
+        } else if task.ready.is_some() {
+            warn!("task {} sets `ready` but is not a service", task.name);
+        }
         if let Some(path) = &task.file
             && path.exists()
             && !file::is_executable(path)
@@ -1501,6 +1650,9 @@ static AFTER_LONG_HELP: &str = color_print::cstr!(
 
     # Execute multiple tasks each with their own arguments.
     $ <bold>mise run cmd1 arg1 arg2 ::: cmd2 arg1 arg2</bold>
+
+    # Run all "ci" tasks and write a JUnit report of every task, even failing ones.
+    $ <bold>mise run --continue-on-error --report junit:results.xml ci</bold>
 "#
 );
 
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use comfy_table::Row;
+use eyre::{Result, eyre};
+use jiff::Timestamp;
+use jiff::tz::TimeZone;
+
+use crate::file::display_path;
+use crate::task::task_history::{self, TaskHistoryRun};
+use crate::ui::style;
+use crate::ui::table::MiseTable;
+use crate::ui::time::format_duration;
+
+/// List previous `mise run` invocations recorded in the task history
+///
+/// Each run is numbered. Pass a run number to list the tasks it executed.
+/// Use `mise tasks logs` to print the output of a task from a run.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub(super) struct TasksHistory {
+    /// Show the tasks of this run
+    #[clap(verbatim_doc_comment)]
+    run: Option<u64>,
+
+    /// Number of runs to show
+    #[clap(short = 'n', long, default_value_t = 20, verbatim_doc_comment)]
+    limit: usize,
+
+    /// Output in JSON format
+    #[clap(short = 'J', long, verbatim_doc_comment)]
+    json: bool,
+}
+
+impl TasksHistory {
+    pub(super) async fn run(self) -> Result<()> {
+        let runs = task_history::runs()?;
+        if let Some(id) = self.run {
+            let run = runs
+                .into_iter()
+                .find(|r| r.id == id)
+                .ok_or_else(|| eyre!("run {id} not found in task history"))?;
+            return self.display_run(&run);
+        }
+        let skip = runs.len().saturating_sub(self.limit);
+        let runs = &runs[skip..];
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(runs)?);
+            return Ok(());
+        }
+        if runs.is_empty() {
+            info!("no task runs recorded yet");
+            return Ok(());
+        }
+        let mut table = MiseTable::new(
+            false,
+            &[
+                "Run", "Started", "Duration", "Tasks", "Failed", "Dir", "Command",
+            ],
+        );
+        for run in runs.iter().rev() {
+            let failed = run.failed();
+            table.add_row(Row::from(vec![
+                run.id.to_string(),
+                format_timestamp(run.started_at),
+                run.duration
+                    .map(|d| format_duration(Duration::from_secs_f64(d)))
+                    .unwrap_or_else(|| "-".to_string()),
+                run.tasks.len().to_string(),
+                if failed > 0 {
+                    style::nred(failed).to_string()
+                } else {
+                    failed.to_string()
+                },
+                display_path(&run.dir),
+                run.command.clone(),
+            ]));
+        }
+        table.print()
+    }
+
+    fn display_run(&self, run: &TaskHistoryRun) -> Result<()> {
+        if self.json {
+            miseprintln!("{}", serde_json::to_string_pretty(run)?);
+            return Ok(());
+        }
+        let mut table = MiseTable::new(
+            false,
+            &["Task", "Status", "Exit", "Duration", "Cache Key", "Env"],
+        );
+        for task in &run.tasks {
+            table.add_row(Row::from(vec![
+                task.name.clone(),
+                task.status.to_string(),
+                task.exit_code.map(|c| c.to_string()).unwrap_or_default(),
+                format_duration(Duration::from_secs_f64(task.duration)),
+                task.cache_key.clone().unwrap_or_default(),
+                task.env_hash.clone().unwrap_or_default(),
+            ]));
+        }
+        table.print()
+    }
+}
+
+fn format_timestamp(secs: u64) -> String {
+    i64::try_from(secs)
+        .ok()
+        .and_then(|secs| Timestamp::from_second(secs).ok())
+        .map(|ts| {
+            ts.to_zoned(TimeZone::system())
+                .strftime("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise tasks history</bold>
+    Run  Started              Duration  Tasks  Failed  Dir          Command
+    12   2026-10-18 09:12:44  41.2s     40     1       ~/src/myproj mise run ci
+    11   2026-10-18 09:03:10  2.1s      1      0       ~/src/myproj mise run lint
+
+    $ <bold>mise tasks history 12</bold>
+    Task   Status  Exit  Duration  Cache Key  Env
+    lint   passed  0     1.8s                 3f9c2a1be04d7c55
+    test   failed  1     38.9s                3f9c2a1be04d7c55
+"#
+);
@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use eyre::{Result, bail};
+use itertools::Itertools;
+
+use crate::task::TaskCacheOutput;
+use crate::task::task_history::{self, TaskHistoryEntry, TaskHistoryRun};
+use crate::task::task_report::TaskReportStatus;
+use crate::ui::style;
+use crate::ui::time::format_duration;
+
+/// Print the recorded output of a task from the task history
+///
+/// By default this shows the most recent run that executed the task.
+/// Without a task, every task of the run is shown.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub(super) struct TasksLogs {
+    /// Task to show the output of
+    #[clap(verbatim_doc_comment)]
+    task: Option<String>,
+
+    /// Show output from this run instead of the latest one (see `mise tasks history`)
+    #[clap(long, verbatim_doc_comment)]
+    run: Option<u64>,
+
+    /// Only show tasks that failed
+    #[clap(long, verbatim_doc_comment)]
+    failed: bool,
+}
+
+impl TasksLogs {
+    pub(super) async fn run(self) -> Result<()> {
+        let runs = task_history::runs()?;
+        let matches = |e: &TaskHistoryEntry| {
+            self.task.as_deref().is_none_or(|t| e.is_task(t))
+                && (!self.failed || e.status == TaskReportStatus::Failed)
+        };
+        let run = match self.run {
+            Some(id) => runs.iter().find(|r| r.id == id),
+            None => runs.iter().rev().find(|r| r.tasks.iter().any(matches)),
+        };
+        let Some(run) = run else {
+            match (&self.task, self.run) {
+                (_, Some(id)) => bail!("run {id} not found in task history"),
+                (Some(task), None) => bail!("no recorded runs of task {task}"),
+                (None, None) => bail!("no recorded task runs"),
+            }
+        };
+        let entries = run.tasks.iter().filter(|e| matches(e)).collect_vec();
+        if entries.is_empty() {
+            bail!("no matching tasks in run {}", run.id);
+        }
+        let show_header = entries.len() > 1 || self.task.is_none();
+        for entry in entries {
+            if show_header {
+                miseprintln!("{}", header(run, entry));
+            }
+            for line in run.read_log(entry)? {
+                let (TaskCacheOutput::Stdout(line) | TaskCacheOutput::Stderr(line)) = line;
+                if console::colors_enabled() {
+                    miseprintln!("{line}\x1b[0m");
+                } else {
+                    miseprintln!("{}", console::strip_ansi_codes(&line));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn header(run: &TaskHistoryRun, entry: &TaskHistoryEntry) -> String {
+    let mut status = entry.status.to_string();
+    if let Some(code) = entry.exit_code.filter(|c| *c != 0) {
+        status = format!("{status}, exit {code}");
+    }
+    let status = format!(
+        "{status}, {}",
+        format_duration(Duration::from_secs_f64(entry.duration))
+    );
+    let status = if entry.status == TaskReportStatus::Failed {
+        style::nred(status).to_string()
+    } else {
+        style::ndim(status).to_string()
+    };
+    format!(
+        "{} {} ({status})",
+        style::nbold(format!("==> [run {}]", run.id)),
+        style::nbold(&entry.name)
+    )
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    # output of the latest run of the "test" task
+    $ <bold>mise tasks logs test</bold>
+
+    # output of "test" from run 12
+    $ <bold>mise tasks logs test --run 12</bold>
+
+    # output of every task that failed in the most recent run with a failure
+    $ <bold>mise tasks logs --failed</bold>
+"#
+);
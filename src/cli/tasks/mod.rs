@@ -7,8 +7,11 @@ mod add;
 mod deps;
 mod edit;
 mod graph;
+mod history;
 mod info;
+mod logs;
 mod ls;
+mod update_includes;
 mod validate;
 
 /// Manage tasks
@@ -31,9 +34,12 @@ enum Commands {
     Deps(deps::TasksDeps),
     Edit(edit::TasksEdit),
     Graph(graph::TasksGraph),
+    History(history::TasksHistory),
     Info(info::TasksInfo),
+    Logs(logs::TasksLogs),
     Ls(ls::TasksLs),
     Run(Box<run::Run>),
+    UpdateIncludes(update_includes::TasksUpdateIncludes),
     Validate(validate::TasksValidate),
 }
 
@@ -44,9 +50,12 @@ impl Commands {
             Self::Deps(cmd) => cmd.run().await,
             Self::Edit(cmd) => cmd.run().await,
             Self::Graph(cmd) => cmd.run().await,
+            Self::History(cmd) => cmd.run().await,
             Self::Info(cmd) => cmd.run().await,
+            Self::Logs(cmd) => cmd.run().await,
             Self::Ls(cmd) => cmd.run().await,
             Self::Run(cmd) => (*cmd).run().await,
+            Self::UpdateIncludes(cmd) => cmd.run().await,
             Self::Validate(cmd) => cmd.run().await,
         }
     }
@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use eyre::{Result, bail};
+
+use crate::config::Config;
+use crate::file::display_path;
+use crate::lockfile::Lockfile;
+use crate::task::task_include_lock::{
+    is_remote_task_include, resolve_task_include, task_include_lockfile, update_lockfile,
+};
+use crate::ui::style;
+
+/// Update the pins of remote task includes in mise.lock
+///
+/// Remote `task_config.includes` entries (`git::` and `https://` URLs) are pinned in the
+/// `[task-includes]` section of mise.lock with the commit they resolved to and a digest of
+/// their content, which is verified every time the include is fetched.
+///
+/// This re-resolves each include: git includes with a semver range `ref` (e.g. `?ref=^1.2`)
+/// move to the newest matching tag, other refs to the commit they point to now, and HTTP
+/// includes are downloaded again. Pins of includes that are no longer configured are removed.
+#[derive(Debug, clap::Args)]
+#[clap(verbatim_doc_comment, after_long_help = AFTER_LONG_HELP)]
+pub(super) struct TasksUpdateIncludes {
+    /// Only update these includes, as written in task_config.includes
+    #[clap(verbatim_doc_comment)]
+    includes: Vec<String>,
+
+    /// Show what would be updated without writing mise.lock
+    #[clap(short = 'n', long, verbatim_doc_comment)]
+    dry_run: bool,
+}
+
+impl TasksUpdateIncludes {
+    pub(super) async fn run(self) -> Result<()> {
+        let config = Config::get().await?;
+        let mut by_lockfile: BTreeMap<PathBuf, BTreeSet<String>> = BTreeMap::new();
+        for cf in config.config_files.values() {
+            let lockfile = task_include_lockfile(&config, cf.get_path());
+            let includes = cf.task_config_includes()?.unwrap_or_default();
+            by_lockfile
+                .entry(lockfile)
+                .or_default()
+                .extend(includes.into_iter().filter(|i| is_remote_task_include(i)));
+        }
+        for include in &self.includes {
+            if !by_lockfile
+                .values()
+                .any(|includes| includes.contains(include))
+            {
+                bail!("{include} is not a remote task include in any config file");
+            }
+        }
+
+        let mut updated = 0;
+        for (path, includes) in &by_lockfile {
+            let mut lockfile = Lockfile::read(path)?;
+            let mut changed = false;
+            for include in includes {
+                if !self.includes.is_empty() && !self.includes.contains(include) {
+                    continue;
+                }
+                let (_, lock) = resolve_task_include(include).await?;
+                match lockfile.get_task_include(include) {
+                    Some(old) if *old == lock => {
+                        debug!("{include} is up to date at {lock}");
+                        continue;
+                    }
+                    Some(old) => miseprintln!(
+                        "{include}: {} -> {}",
+                        style::nyellow(old),
+                        style::ncyan(&lock)
+                    ),
+                    None => miseprintln!("{include}: {}", style::ncyan(&lock)),
+                }
+                lockfile.set_task_include(include, lock);
+                changed = true;
+                updated += 1;
+            }
+            if self.includes.is_empty() {
+                for removed in lockfile.retain_task_includes(includes) {
+                    miseprintln!("{removed}: {}", style::ndim("removed"));
+                    changed = true;
+                }
+            }
+            if changed && !self.dry_run {
+                update_lockfile(path, |current| {
+                    for include in includes {
+                        if let Some(lock) = lockfile.get_task_include(include) {
+                            current.set_task_include(include, lock.clone());
+                        }
+                    }
+                    if self.includes.is_empty() {
+                        current.retain_task_includes(includes);
+                    }
+                })?;
+                debug!("updated task includes in {}", display_path(path));
+            }
+        }
+        if updated == 0 {
+            info!("all task includes are up to date");
+        }
+        Ok(())
+    }
+}
+
+static AFTER_LONG_HELP: &str = color_print::cstr!(
+    r#"<bold><underline>Examples:</underline></bold>
+
+    $ <bold>mise tasks update-includes</bold>
+    git::https://github.com/acme/tasks.git//tasks?ref=^1.2: v1.2.0 (3f9c2a1) -> v1.4.1 (8be04d7)
+
+    $ <bold>mise tasks update-includes --dry-run</bold>
+"#
+);
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::backend::pipx::PIPXBackend;
+use crate::backend::release_notes;
 use crate::cli::args::{BackendArg, ToolArg};
 use crate::config::{Config, Settings, config_file};
 use crate::errors::split_install_result;
@@ -27,6 +28,8 @@ use eyre::{Context, Result, eyre};
 use indexmap::IndexMap;
 use jiff::{Span, Timestamp, civil::date};
 
+mod pr;
+
 /// Upgrades outdated tools
 ///
 /// By default, this keeps the range specified in mise.toml. So if you have node@20 set, it will
@@ -53,7 +56,22 @@ pub(crate) struct Upgrade {
     #[clap(long, short = 'b', verbatim_doc_comment)]
     bump: bool,
 
+    /// Commit the upgrades to new git branches instead of the current one
+    ///
+    /// Each upgraded tool gets a `mise/upgrade-<tool>` branch, created from the current
+    /// branch, with the config and lockfile changes committed and the release notes in the
+    /// commit message. Use --group for a single `mise/upgrade-tools` branch instead.
+    /// The current branch is checked out again afterwards and old versions are kept
+    /// installed, since it still uses them. Only tools set in config files inside the repository
+    /// are upgraded. Requires a clean work tree.
+    #[clap(long, verbatim_doc_comment)]
+    branch: bool,
+
     /// Display multiselect menu to choose which tools to upgrade
+    ///
+    /// Release notes since the current version are summarized for tools installed
+    /// from GitHub, GitLab or Forgejo releases, with breaking changes highlighted.
+    /// Use --verbose to print them in full, or see `mise outdated --changelog`.
     #[clap(long, short, verbatim_doc_comment, conflicts_with = "tool")]
     interactive: bool,
 
@@ -76,6 +94,10 @@ pub(crate) struct Upgrade {
     #[clap(long, short = 'x', value_name = "INSTALLED_TOOL", verbatim_doc_comment)]
     exclude: Vec<ToolArg>,
 
+    /// With --branch, commit all upgrades to one branch instead of one per tool
+    #[clap(long, verbatim_doc_comment)]
+    group: bool,
+
     /// Like --dry-run but exits with code 1 if there are outdated tools
     ///
     /// This is useful for scripts to check if tools need to be upgraded.
@@ -117,6 +139,14 @@ pub(crate) struct Upgrade {
     #[clap(long, verbatim_doc_comment, overrides_with = "prune")]
     no_prune: bool,
 
+    /// Push the upgrade branches and open a pull request for each, implies --push
+    ///
+    /// The forge is detected from the `origin` remote; set `upgrade.pr_forge` and
+    /// `upgrade.pr_api_url` for self-hosted forges. Uses the same tokens as
+    /// `mise token github`, `mise token gitlab` and `mise token forgejo`.
+    #[clap(long, verbatim_doc_comment)]
+    open_pr: bool,
+
     /// Uninstall the versions that were upgraded away from
     ///
     /// This is already the default. Use it to override `upgrade.auto_prune = false`
@@ -124,6 +154,13 @@ pub(crate) struct Upgrade {
     #[clap(long, verbatim_doc_comment, overrides_with = "no_prune")]
     prune: bool,
 
+    /// Push the upgrade branches to `origin`, implies --branch
+    ///
+    /// Branches are force-pushed with a lease: a scheduled run replaces the branch it pushed
+    /// before, but not commits someone else pushed to it since.
+    #[clap(long, verbatim_doc_comment)]
+    push: bool,
+
     /// Connect backend install command stdin/stdout/stderr directly to the terminal
     /// Implies --jobs=1
     #[clap(long, overrides_with = "jobs")]
@@ -138,11 +175,20 @@ impl Upgrade {
     /// Whether the version being upgraded away from should be uninstalled. Either flag wins
     /// over the setting, and `overrides_with` makes the later of the two win over the other.
     fn should_prune(&self) -> bool {
+        // the branch the upgrades were made from still uses the old versions
+        if self.creates_branches() {
+            return false;
+        }
         self.prune || !self.no_prune && Settings::get().upgrade.auto_prune
     }
 
+    fn creates_branches(&self) -> bool {
+        self.branch || self.push || self.open_pr
+    }
+
     fn scope(&self) -> ConfigScope {
-        if self.local {
+        // branches only carry changes to config files in the repository
+        if self.local || self.creates_branches() {
             ConfigScope::LocalOnly
         } else {
             ConfigScope::All
@@ -184,6 +230,7 @@ impl Upgrade {
             offline: false,
             refresh_remote_versions: false,
             inactive: self.inactive,
+            skip_policy: false,
         };
         // Filter tools to check before doing expensive version lookups
         let filter_tools = if !self.interactive && !self.tool.is_empty() {
@@ -208,7 +255,8 @@ impl Upgrade {
         )
         .await;
         if self.interactive && !outdated.is_empty() {
-            outdated = self.get_interactive_tool_set(&outdated)?;
+            let outdated_with_notes = OutdatedInfo::load_changelogs(outdated).await?;
+            outdated = self.get_interactive_tool_set(&outdated_with_notes)?;
         }
         if outdated.is_empty() {
             info!("All tools are up to date");
@@ -228,6 +276,8 @@ impl Upgrade {
                     );
                 }
             }
+        } else if self.creates_branches() {
+            pr::upgrade_on_branches(&self, &mut config, outdated, before_date).await?;
         } else {
             self.upgrade(&mut config, outdated, before_date).await?;
         }
@@ -373,6 +423,7 @@ impl Upgrade {
                 offline: false,
                 refresh_remote_versions: false,
                 inactive: self.inactive,
+                skip_policy: false,
             },
             locked: false,
             ..Default::default()
@@ -643,8 +694,26 @@ impl Upgrade {
             .description("Select tools to upgrade")
             .filterable(true)
             .theme(&theme);
+        let verbose = Settings::get().verbose;
         for out in outdated {
-            ms = ms.option(DemandOption::new(out.clone()));
+            let mut option = DemandOption::new(out.clone());
+            // a summary beside each tool, the full notes only when asked for
+            let notes = out.changelog.as_deref().unwrap_or_default();
+            if !notes.is_empty() {
+                if verbose {
+                    miseprintln!(
+                        "{}",
+                        release_notes::render(
+                            &out.name,
+                            out.current.as_deref(),
+                            &out.latest,
+                            notes
+                        )
+                    );
+                }
+                option = option.description(&release_notes::summary(notes));
+            }
+            ms = ms.option(option);
         }
         match ms.run() {
             Ok(selected) => Ok(selected.into_iter().collect()),
@@ -971,6 +1040,9 @@ After removal, `-l` will become shorthand for `--local`. Use `-b` or `--bump` in
 
     # Only upgrade tools defined in local mise.toml, not global ones
     $ <bold>mise upgrade --local</bold>
+
+    # Commit each upgrade to its own branch and open a pull request for it
+    $ <bold>mise upgrade --bump --open-pr</bold>
 "#
 );
 
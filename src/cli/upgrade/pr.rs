@@ -0,0 +1,419 @@
+//! `mise upgrade --branch`, `--push` and `--open-pr`: upgrades committed to
+//! their own git branches, for scheduled dependency update jobs.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use eyre::{Result, WrapErr, bail, ensure, eyre};
+use itertools::Itertools;
+use jiff::Timestamp;
+
+use super::Upgrade;
+use crate::backend::release_notes;
+use crate::config::{Config, Settings};
+use crate::git::{self, Git};
+use crate::toolset::outdated_info::OutdatedInfo;
+use crate::{forgejo, github, gitlab};
+
+const BRANCH_PREFIX: &str = "mise/upgrade-";
+
+pub(super) async fn upgrade_on_branches(
+    upgrade: &Upgrade,
+    config: &mut Arc<Config>,
+    outdated: Vec<OutdatedInfo>,
+    before_date: Option<Timestamp>,
+) -> Result<()> {
+    let root = Git::get_root().wrap_err("`mise upgrade --branch` needs a git repository")?;
+    let outdated = in_repository(&root, outdated);
+    if outdated.is_empty() {
+        info!("no tools from config files in this repository to upgrade");
+        return Ok(());
+    }
+    let groups = if upgrade.group {
+        vec![outdated]
+    } else {
+        outdated.into_iter().map(|o| vec![o]).collect()
+    };
+    if upgrade.is_dry_run() {
+        for group in &groups {
+            let tools = group
+                .iter()
+                .map(|o| format!("{}@{}", o.name, o.latest))
+                .join(", ");
+            miseprintln!("Would commit {tools} to branch {}", branch_name(group));
+        }
+        let outdated = groups.into_iter().flatten().collect();
+        return upgrade.upgrade(config, outdated, before_date).await;
+    }
+
+    let git = Git::new(root);
+    ensure!(
+        git.is_clean()?,
+        "the git work tree has uncommitted changes, commit or stash them before `mise upgrade --branch`"
+    );
+    let base = git.current_branch()?;
+    ensure!(
+        !git::looks_like_sha(&base),
+        "HEAD is detached, check out the branch to upgrade first"
+    );
+    // read the remote up front so a misconfigured forge fails before anything is committed
+    let remote = if upgrade.open_pr {
+        Some(Remote::from_git(&git)?)
+    } else {
+        None
+    };
+
+    let mut failed = vec![];
+    for group in groups {
+        let branch = branch_name(&group);
+        let group = OutdatedInfo::load_changelogs(group).await?;
+        let (title, body) = describe(&group);
+        git.switch_new_branch(&branch, &base)?;
+        // nothing below may return before the base branch is checked out again
+        let result = async {
+            *config = Config::reset().await?;
+            upgrade.upgrade(config, group, before_date).await?;
+            git.commit_all(&format!("{title}\n\n{body}"))
+        }
+        .await;
+        if result.is_err() {
+            git.discard_changes()?;
+        }
+        git.switch(&base)?;
+        match result {
+            Ok(true) => info!("committed {branch}: {title}"),
+            Ok(false) => {
+                info!("{title} changed no files in the repository, skipping {branch}");
+                git.delete_branch(&branch)?;
+                continue;
+            }
+            Err(err) => {
+                warn!("{title} failed: {err:#}");
+                git.delete_branch(&branch)?;
+                failed.push(branch);
+                continue;
+            }
+        }
+        if upgrade.push || upgrade.open_pr {
+            if let Err(err) = git.push_branch(&branch) {
+                // most likely someone pushed to the branch since, which the lease protects
+                warn!("failed to push {branch}: {err:#}");
+                failed.push(branch);
+                continue;
+            }
+            info!("pushed {branch}");
+        }
+        if let Some(remote) = &remote {
+            match remote
+                .open_pull_request(&branch, &base, &title, &body)
+                .await
+            {
+                Ok(url) => miseprintln!("{url}"),
+                // most likely one is already open for the branch, which the push updated
+                Err(err) => warn!("failed to open a pull request for {branch}: {err:#}"),
+            }
+        }
+    }
+    *config = Config::reset().await?;
+    if !failed.is_empty() {
+        bail!("failed to upgrade on {}", failed.join(", "));
+    }
+    Ok(())
+}
+
+/// The upgrades whose version is set in a config file inside the repository.
+/// Anything else, like the global config, would be edited outside of the
+/// branch and left changed.
+fn in_repository(root: &Path, outdated: Vec<OutdatedInfo>) -> Vec<OutdatedInfo> {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let root = canonical(root);
+    outdated
+        .into_iter()
+        .filter(|o| {
+            let inside = o
+                .source
+                .path()
+                .is_some_and(|path| canonical(path).starts_with(&root));
+            if !inside {
+                info!("skipping {}, it is not set in this repository", o.name);
+            }
+            inside
+        })
+        .collect()
+}
+
+/// `mise/upgrade-<tool>` for one tool, `mise/upgrade-tools` for a group. The
+/// name doesn't include the version so a later run replaces the branch.
+fn branch_name(group: &[OutdatedInfo]) -> String {
+    match group {
+        [o] => {
+            let name = o
+                .name
+                .chars()
+                .map(|c| match c {
+                    'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c,
+                    _ => '-',
+                })
+                .collect::<String>();
+            format!("{BRANCH_PREFIX}{name}")
+        }
+        _ => format!("{BRANCH_PREFIX}tools"),
+    }
+}
+
+/// The commit subject and body, which are also the pull request title and
+/// description.
+fn describe(group: &[OutdatedInfo]) -> (String, String) {
+    let title = match group {
+        [o] => format!("Upgrade {} to {}", o.name, o.latest),
+        _ => format!("Upgrade {} tools", group.len()),
+    };
+    let mut body = group
+        .iter()
+        .map(|o| {
+            let from = o.current.as_deref().unwrap_or("(none)");
+            match &o.bump {
+                Some(bump) => format!(
+                    "- {} {from} → {} ({} → {bump})",
+                    o.name, o.latest, o.requested
+                ),
+                None => format!("- {} {from} → {}", o.name, o.latest),
+            }
+        })
+        .join("\n");
+    body.push('\n');
+    for o in group {
+        if let Some(notes) = o.changelog.as_deref().filter(|n| !n.is_empty()) {
+            body.push('\n');
+            body.push_str(&release_notes::markdown(
+                &o.name,
+                o.current.as_deref(),
+                &o.latest,
+                notes,
+            ));
+        }
+    }
+    (title, body)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+enum Forge {
+    Github,
+    Gitlab,
+    Forgejo,
+}
+
+/// The forge repository behind the `origin` remote.
+#[derive(Debug, PartialEq, Eq)]
+struct Remote {
+    forge: Forge,
+    api_url: String,
+    repo: String,
+}
+
+impl Remote {
+    fn from_git(git: &Git) -> Result<Self> {
+        let url = git
+            .get_remote_url()
+            .ok_or_else(|| eyre!("there is no `origin` remote to open pull requests on"))?;
+        let settings = Settings::get();
+        Self::parse(
+            &url,
+            settings.upgrade.pr_forge.as_deref(),
+            settings.upgrade.pr_api_url.as_deref(),
+        )
+    }
+
+    fn parse(url: &str, forge: Option<&str>, api_url: Option<&str>) -> Result<Self> {
+        let Some((host, repo)) = split_remote_url(url) else {
+            bail!("can't read a repository from the `origin` remote {url}");
+        };
+        let forge = match forge {
+            Some(forge) => forge
+                .parse()
+                .map_err(|_| eyre!("invalid upgrade.pr_forge {forge:?}"))?,
+            None if host == "codeberg.org" => Forge::Forgejo,
+            None if host.contains("github") => Forge::Github,
+            None if host.contains("gitlab") => Forge::Gitlab,
+            None if host.contains("forgejo") || host.contains("gitea") => Forge::Forgejo,
+            None => bail!("can't tell which forge {host} is, set upgrade.pr_forge"),
+        };
+        let api_url = match (api_url, forge) {
+            (Some(api_url), _) => api_url.trim_end_matches('/').to_string(),
+            (None, Forge::Github) if host == "github.com" => github::API_URL.to_string(),
+            (None, Forge::Github) => format!("https://{host}/api/v3"),
+            (None, Forge::Gitlab) => format!("https://{host}/api/v4"),
+            (None, Forge::Forgejo) => format!("https://{host}/api/v1"),
+        };
+        Ok(Self {
+            forge,
+            api_url,
+            repo,
+        })
+    }
+
+    /// Opens a pull request (a merge request on GitLab) and returns its URL.
+    async fn open_pull_request(
+        &self,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<String> {
+        let (api_url, repo) = (&self.api_url, &self.repo);
+        match self.forge {
+            Forge::Github => {
+                github::create_pull_request(api_url, repo, head, base, title, body).await
+            }
+            Forge::Gitlab => {
+                gitlab::create_merge_request(api_url, repo, head, base, title, body).await
+            }
+            Forge::Forgejo => {
+                forgejo::create_pull_request(api_url, repo, head, base, title, body).await
+            }
+        }
+    }
+}
+
+/// The host and `owner/repo` path of an https, ssh or scp-like git remote URL.
+fn split_remote_url(url: &str) -> Option<(String, String)> {
+    let (host, path) = match url::Url::parse(url) {
+        Ok(url) => (url.host_str()?.to_string(), url.path().to_string()),
+        // scp-like: git@github.com:owner/repo.git
+        Err(_) => {
+            let (user_host, path) = url.split_once(':')?;
+            let host = user_host.rsplit('@').next()?;
+            (host.to_string(), path.to_string())
+        }
+    };
+    let path = path.trim_matches('/');
+    let repo = path.strip_suffix(".git").unwrap_or(path).to_string();
+    (!host.is_empty() && repo.contains('/')).then_some((host, repo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_split_remote_url() {
+        let github = Some(("github.com".to_string(), "jdx/mise".to_string()));
+        assert_eq!(split_remote_url("https://github.com/jdx/mise.git"), github);
+        assert_eq!(split_remote_url("https://github.com/jdx/mise"), github);
+        assert_eq!(split_remote_url("git@github.com:jdx/mise.git"), github);
+        assert_eq!(
+            split_remote_url("ssh://git@github.com:22/jdx/mise.git"),
+            github
+        );
+        assert_eq!(
+            split_remote_url("https://gitlab.example.com/group/sub/project.git"),
+            Some(("gitlab.example.com".into(), "group/sub/project".into()))
+        );
+        assert_eq!(split_remote_url("/tmp/remote.git"), None);
+        assert_eq!(split_remote_url("file:///tmp/remote.git"), None);
+    }
+
+    #[test]
+    fn test_remote_parse() {
+        let remote = |url, forge, api_url| Remote::parse(url, forge, api_url).unwrap();
+        assert_eq!(
+            remote("git@github.com:jdx/mise.git", None, None),
+            Remote {
+                forge: Forge::Github,
+                api_url: "https://api.github.com".into(),
+                repo: "jdx/mise".into(),
+            }
+        );
+        assert_eq!(
+            remote("https://github.example.com/o/r", None, None).api_url,
+            "https://github.example.com/api/v3"
+        );
+        assert_eq!(
+            remote("https://gitlab.com/o/r", None, None).api_url,
+            "https://gitlab.com/api/v4"
+        );
+        assert_eq!(
+            remote("https://codeberg.org/o/r", None, None).forge,
+            Forge::Forgejo
+        );
+        assert_eq!(
+            remote(
+                "https://git.example.com/o/r",
+                Some("gitlab"),
+                Some("https://git.example.com/gitlab/api/v4/")
+            ),
+            Remote {
+                forge: Forge::Gitlab,
+                api_url: "https://git.example.com/gitlab/api/v4".into(),
+                repo: "o/r".into(),
+            }
+        );
+        assert!(Remote::parse("https://git.example.com/o/r", None, None).is_err());
+        assert!(Remote::parse("https://github.com/o/r", Some("svn"), None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_open_pull_request() {
+        let _config = Config::get().await.unwrap();
+        let mut server = mockito::Server::new_async().await;
+        let github_mock = server
+            .mock("POST", "/repos/o/r/pulls")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "head": "mise/upgrade-node",
+                "base": "main",
+                "title": "Upgrade node to 22.1.0",
+            })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"number": 7, "html_url": "https://github.com/o/r/pull/7"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let gitlab_mock = server
+            .mock("POST", "/projects/group%2Fr/merge_requests")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "source_branch": "mise/upgrade-node",
+                "target_branch": "main",
+            })))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"iid": 3, "web_url": "https://gitlab.com/group/r/-/merge_requests/3"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let rejected_mock = server
+            .mock("POST", "/repos/o/exists/pulls")
+            .with_status(422)
+            .with_body(r#"{"message": "A pull request already exists"}"#)
+            .create_async()
+            .await;
+
+        let open = |forge, repo: &str| {
+            let remote = Remote {
+                forge,
+                api_url: server.url(),
+                repo: repo.to_string(),
+            };
+            async move {
+                remote
+                    .open_pull_request("mise/upgrade-node", "main", "Upgrade node to 22.1.0", "")
+                    .await
+            }
+        };
+        assert_eq!(
+            open(Forge::Github, "o/r").await.unwrap(),
+            "https://github.com/o/r/pull/7"
+        );
+        assert_eq!(
+            open(Forge::Gitlab, "group/r").await.unwrap(),
+            "https://gitlab.com/group/r/-/merge_requests/3"
+        );
+        let err = open(Forge::Github, "o/exists").await.unwrap_err();
+        assert!(format!("{err:#}").contains("A pull request already exists"));
+        github_mock.assert_async().await;
+        gitlab_mock.assert_async().await;
+        rejected_mock.assert_async().await;
+    }
+}
@@ -160,6 +160,7 @@ impl Use {
             offline: false,
             refresh_remote_versions: false,
             inactive: false,
+            skip_policy: false,
         };
         let versions: Vec<_> = self
             .tool
@@ -102,6 +102,13 @@ where
         .join(" ");
     debug!("$ {display_command}");
 
+    #[cfg(target_os = "linux")]
+    if let Some(sandbox) = crate::sandbox::current_install_sandbox() {
+        return duct::cmd(program, args).before_spawn(move |cmd| {
+            sandbox.pre_exec_linux(cmd, None, None);
+            Ok(())
+        });
+    }
     duct::cmd(program, args)
 }
 
@@ -120,7 +127,12 @@ pub(crate) struct CmdLineRunner<'a> {
     observe_stdout: Option<OutputObserver<'a>>,
     observe_stderr: Option<OutputObserver<'a>>,
     timeout: Option<Duration>,
+    stop_signal: Option<(tokio::sync::watch::Receiver<bool>, Duration)>,
     sandbox: Option<crate::sandbox::SandboxConfig>,
+    #[cfg(target_os = "linux")]
+    net_proxy: Option<crate::sandbox::NetProxy>,
+    #[cfg(target_os = "linux")]
+    cgroup: Option<crate::sandbox::Cgroup>,
 }
 
 const GUARD_RUNNING: u8 = 0;
@@ -144,6 +156,24 @@ fn kill_process_tree(pid: u32) {
         .status();
 }
 
+/// Whether `status` is how a process stopped with [`CmdLineRunner::with_stop_signal`] exits:
+/// killed by the SIGTERM or SIGKILL mise sent, or exiting with 128+SIGTERM as shells do. Any
+/// other failure, like a crash while shutting down, is still an error.
+#[cfg(unix)]
+fn exited_from_stop(status: &ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    matches!(
+        status.signal(),
+        Some(nix::libc::SIGTERM | nix::libc::SIGKILL)
+    ) || status.code() == Some(128 + nix::libc::SIGTERM)
+}
+
+/// `taskkill /F` ends processes with exit code 1.
+#[cfg(windows)]
+fn exited_from_stop(status: &ExitStatus) -> bool {
+    status.code() == Some(1)
+}
+
 fn wait_for_cancel_or_deadline<'a>(
     cvar: &'a Condvar,
     mut guard: MutexGuard<'a, bool>,
@@ -252,6 +282,8 @@ static OUTPUT_LOCK: Mutex<()> = Mutex::new(());
 static RAW_LOCK: Lazy<tokio::sync::RwLock<()>> = Lazy::new(|| tokio::sync::RwLock::new(()));
 
 static RUNNING_PIDS: Lazy<Mutex<HashSet<u32>>> = Lazy::new(Default::default);
+/// Running processes started `with_stop_signal`, which their runner stops on Ctrl-C
+static STOPPABLE_PIDS: Lazy<Mutex<HashSet<u32>>> = Lazy::new(Default::default);
 
 #[cfg(all(panic = "abort", unix))]
 fn kill_pids_immediately(pids: &HashSet<u32>) {
@@ -535,6 +567,11 @@ impl<'a> CmdLineRunner<'a> {
         cmd.stdin(Stdio::null());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
+        // commands run by plugins and installers during `mise install`, see `install_sandbox`
+        #[cfg(target_os = "linux")]
+        if let Some(sandbox) = crate::sandbox::current_install_sandbox() {
+            sandbox.pre_exec_linux(cmd.as_std_mut(), None, None);
+        }
 
         Self {
             cmd,
@@ -549,7 +586,12 @@ impl<'a> CmdLineRunner<'a> {
             observe_stdout: None,
             observe_stderr: None,
             timeout: None,
+            stop_signal: None,
             sandbox: None,
+            #[cfg(target_os = "linux")]
+            net_proxy: None,
+            #[cfg(target_os = "linux")]
+            cgroup: None,
         }
     }
 
@@ -562,9 +604,22 @@ impl<'a> CmdLineRunner<'a> {
 
     #[cfg(unix)]
     pub(crate) fn kill_all(signal: nix::sys::signal::Signal) {
+        Self::kill_running(signal, &HashSet::new());
+    }
+
+    /// Like `kill_all`, but leaves processes started `with_stop_signal` to their runner, which
+    /// stops them with SIGTERM and a grace period once Ctrl-C is pressed.
+    #[cfg(all(unix, not(test)))]
+    pub(crate) fn kill_all_except_stoppable(signal: nix::sys::signal::Signal) {
+        let stoppable = STOPPABLE_PIDS.lock().unwrap().clone();
+        Self::kill_running(signal, &stoppable);
+    }
+
+    #[cfg(unix)]
+    fn kill_running(signal: nix::sys::signal::Signal, except: &HashSet<u32>) {
         let use_pgroup = should_use_pgroup();
         let pids = RUNNING_PIDS.lock().unwrap();
-        for pid in pids.iter() {
+        for pid in pids.iter().filter(|pid| !except.contains(pid)) {
             let pid = *pid as i32;
             let nix_pid = nix::unistd::Pid::from_raw(pid);
             if use_pgroup {
@@ -799,6 +854,18 @@ impl<'a> CmdLineRunner<'a> {
         self
     }
 
+    /// Stop the process when `stop` becomes true: SIGTERM first, then SIGKILL if it is
+    /// still running after `grace`. A process that exits from these signals is not treated as a
+    /// failure, but other errors while stopping are.
+    pub(crate) fn with_stop_signal(
+        mut self,
+        stop: tokio::sync::watch::Receiver<bool>,
+        grace: Duration,
+    ) -> Self {
+        self.stop_signal = Some((stop, grace));
+        self
+    }
+
     pub(crate) fn stdin_string(mut self, input: impl Into<String>) -> Self {
         self.cmd.stdin(Stdio::piped());
         self.stdin = Some(input.into());
@@ -1004,7 +1071,9 @@ impl<'a> CmdLineRunner<'a> {
             .wrap_err_with(|| format!("failed to execute command: {self}"))?;
         let id = cp.id().unwrap_or_default();
         RUNNING_PIDS.lock().unwrap().insert(id);
-        if is_cancelled() {
+        if self.stop_signal.is_some() {
+            STOPPABLE_PIDS.lock().unwrap().insert(id);
+        } else if is_cancelled() {
             #[cfg(unix)]
             signal_process_tree(id, nix::sys::signal::SIGINT);
             #[cfg(windows)]
@@ -1077,6 +1146,24 @@ impl<'a> CmdLineRunner<'a> {
         let mut failure_output = self.failure_output_tail();
         let mut status = None;
         let mut wait = Box::pin(cp.wait());
+        let (stop_rx, stop_grace) = match self.stop_signal.take() {
+            Some((rx, grace)) => (Some(rx), grace),
+            None => (None, Duration::ZERO),
+        };
+        let stop_requested = async move {
+            let Some(mut stop_rx) = stop_rx else {
+                return std::future::pending().await;
+            };
+            let mut interrupted = crate::ui::ctrlc::interrupted();
+            tokio::select! {
+                _ = stop_rx.wait_for(|stop| *stop) => {}
+                _ = interrupted.wait_for(|interrupted| *interrupted) => {}
+            }
+        };
+        tokio::pin!(stop_requested);
+        let mut stopped = false;
+        let kill_at = tokio::time::sleep(Duration::MAX);
+        tokio::pin!(kill_at);
         loop {
             tokio::select! {
                 result = &mut wait, if status.is_none() => {
@@ -1087,6 +1174,21 @@ impl<'a> CmdLineRunner<'a> {
                     status = Some(result?);
                     break;
                 }
+                _ = &mut stop_requested, if !stopped => {
+                    debug!("stopping {id} with SIGTERM");
+                    stopped = true;
+                    #[cfg(unix)]
+                    signal_process_tree(id, nix::sys::signal::SIGTERM);
+                    #[cfg(windows)]
+                    kill_process_tree(id);
+                    kill_at.as_mut().reset(tokio::time::Instant::now() + stop_grace);
+                }
+                _ = &mut kill_at, if stopped => {
+                    debug!("{id} still running {stop_grace:?} after SIGTERM, sending SIGKILL");
+                    #[cfg(unix)]
+                    signal_process_tree(id, nix::sys::signal::SIGKILL);
+                    kill_at.as_mut().reset(tokio::time::Instant::now() + Duration::from_secs(3600));
+                }
                 msg = rx.recv() => {
                     let Some(msg) = msg else {
                         if status.is_none() {
@@ -1166,12 +1268,13 @@ impl<'a> CmdLineRunner<'a> {
             }
         }
         RUNNING_PIDS.lock().unwrap().remove(&id);
+        STOPPABLE_PIDS.lock().unwrap().remove(&id);
         if let Some(g) = &timeout_guard {
             g.cancel();
         }
 
         let status = status.unwrap();
-        if !status.success() {
+        if !status.success() && !(stopped && exited_from_stop(&status)) {
             if let Some(duration) = timeout_guard.as_ref().and_then(|g| g.timed_out()) {
                 bail!("timed out after {duration:?}");
             }
@@ -1503,7 +1606,10 @@ impl<'a> CmdLineRunner<'a> {
         let mut attempt = 0;
         loop {
             match self.cmd.as_std_mut().spawn() {
-                Ok(child) => return Ok(child),
+                Ok(child) => {
+                    self.start_net_proxy(child.id())?;
+                    return Ok(child);
+                }
                 Err(err) if Self::is_etxtbsy(&err) && attempt < 3 => {
                     attempt += 1;
                     trace!("retrying spawn after ETXTBSY (attempt {}/3)", attempt);
@@ -1519,7 +1625,10 @@ impl<'a> CmdLineRunner<'a> {
         let mut attempt = 0;
         loop {
             match self.cmd.spawn() {
-                Ok(child) => return Ok(child),
+                Ok(child) => {
+                    self.start_net_proxy(child.id().unwrap_or_default())?;
+                    return Ok(child);
+                }
                 Err(err) if Self::is_etxtbsy(&err) && attempt < 3 => {
                     attempt += 1;
                     trace!("retrying spawn after ETXTBSY (attempt {}/3)", attempt);
@@ -1531,6 +1640,17 @@ impl<'a> CmdLineRunner<'a> {
         }
     }
 
+    /// Start proxying the network of a sandboxed child with per-host `allow_net`.
+    fn start_net_proxy(&mut self, pid: u32) -> std::io::Result<()> {
+        #[cfg(target_os = "linux")]
+        if let Some(proxy) = &mut self.net_proxy {
+            proxy.start(pid)?;
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = pid;
+        Ok(())
+    }
+
     /// Prepare sandbox restrictions on the command. Must be called before execute()
     /// when sandbox is configured. This is async because macOS DNS resolution is async.
     pub(crate) async fn apply_sandbox(&mut self) -> eyre::Result<()> {
@@ -1541,15 +1661,6 @@ impl<'a> CmdLineRunner<'a> {
             return Ok(());
         }
 
-        // Fail early on Linux if per-host network filtering is requested
-        #[cfg(target_os = "linux")]
-        if !sandbox.allow_net.is_empty() {
-            eyre::bail!(
-                "per-host network filtering (--allow-net=<host>) is not supported on Linux. \
-                 Use --deny-net to block all network, or remove --allow-net."
-            );
-        }
-
         #[cfg(target_os = "linux")]
         {
             // On Linux, clear inherited env before pre_exec so child only sees filtered vars.
@@ -1566,26 +1677,25 @@ impl<'a> CmdLineRunner<'a> {
                     self.cmd.env(k, v);
                 }
             }
-            // Use pre_exec to apply Landlock/seccomp in the child process
-            // before it execs the target program. This avoids restricting the mise process.
-            let sandbox = sandbox.clone();
-            unsafe {
-                self.cmd.as_std_mut().pre_exec(move || {
-                    if sandbox.effective_deny_read() || sandbox.effective_deny_write() {
-                        crate::sandbox::landlock_apply(&sandbox)
-                            .map_err(|e| std::io::Error::other(e.to_string()))?;
-                    }
-                    if sandbox.effective_deny_net() {
-                        crate::sandbox::seccomp_apply()
-                            .map_err(|e| std::io::Error::other(e.to_string()))?;
-                    }
-                    Ok(())
-                });
-            }
+            // Apply Landlock/seccomp (and the network namespace for allow_net, the cgroup for
+            // limits) in the child process before it execs the target program. This avoids
+            // restricting mise itself.
+            self.net_proxy = sandbox.net_proxy()?;
+            self.cgroup = sandbox.cgroup();
+            sandbox.pre_exec_linux(
+                self.cmd.as_std_mut(),
+                self.net_proxy.as_ref(),
+                self.cgroup.as_ref(),
+            );
         }
 
         #[cfg(target_os = "macos")]
-        {
+        if sandbox.limits.is_set() {
+            warn!("sandbox resource limits are only supported on Linux, running without them");
+        }
+
+        #[cfg(target_os = "macos")]
+        if sandbox.restricts_access() {
             // On macOS, rewrite the command to go through sandbox-exec.
             // Build a new Command that wraps the original through sandbox-exec,
             // preserving stdio, cwd, and env from the original.
@@ -1723,6 +1833,11 @@ impl<'a> CmdLineRunner<'a> {
                 // eprintln!("{}", output);
             }
         }
+        #[cfg(target_os = "linux")]
+        if let Some(breach) = self.cgroup.as_ref().and_then(|cgroup| cgroup.breach()) {
+            let program = self.get_program();
+            return Err(crate::errors::Error::ResourceLimitExceeded(program, breach).into());
+        }
         Err(ScriptFailed(self.get_program(), Some(status)))?
     }
 
@@ -2290,6 +2405,20 @@ mod tests {
         let r = super::CmdLineRunner::new("bash").cmd_body_args(&["-c".to_string()], "echo hi");
         assert_eq!(r.get_args(), vec!["-c".to_string(), "echo hi".to_string()]);
     }
+
+    #[test]
+    fn test_exited_from_stop() {
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::ExitStatus;
+        // raw wait statuses: the terminating signal, or the exit code shifted left by 8
+        let signaled = |sig: i32| ExitStatus::from_raw(sig);
+        let exited = |code: i32| ExitStatus::from_raw(code << 8);
+        assert!(super::exited_from_stop(&signaled(nix::libc::SIGTERM)));
+        assert!(super::exited_from_stop(&signaled(nix::libc::SIGKILL)));
+        assert!(super::exited_from_stop(&exited(128 + nix::libc::SIGTERM)));
+        assert!(!super::exited_from_stop(&exited(1)));
+        assert!(!super::exited_from_stop(&signaled(nix::libc::SIGSEGV)));
+    }
 }
 
 #[cfg(test)]
@@ -50,7 +50,7 @@ const LEGACY_ENV_KEYS_DEPRECATED_WARN_AT: &str = "2026.4.17";
 const LEGACY_ENV_KEYS_DEPRECATED_REMOVE_AT: &str = "2027.4.0";
 
 /// Convert a `toml::Value` to a `toml_edit::Value` for serialization.
-fn toml_value_to_edit(v: toml::Value) -> Value {
+pub(crate) fn toml_value_to_edit(v: toml::Value) -> Value {
     match v {
         toml::Value::String(s) => Value::from(s),
         toml::Value::Integer(i) => Value::from(i),
@@ -1472,6 +1472,11 @@ impl ConfigFile for MiseToml {
                     // This preserves {{ version }} in the output for install-time rendering
                     let mut opts_context = context.clone();
                     opts_context.insert("version", "{{ version }}");
+                    // The git backend renders its `build` recipe once the install
+                    // path is known, so keep that placeholder as well.
+                    if ba.backend_type() == crate::backend::backend_type::BackendType::Git {
+                        opts_context.insert("install_path", "{{ install_path }}");
+                    }
                     // The http and s3 backends re-render their url/checksum_url per
                     // target platform (host at install, any target during `mise
                     // lock`), so only those two options defer os()/arch() instead of
@@ -5671,4 +5676,88 @@ run = 'echo "template"'
         assert_eq!(firewall.rules[1].interface.as_deref(), Some("eth0"));
         file::remove_file(&p).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_bootstrap_linux_host_settings() {
+        use crate::system::host::SysctlTomlValue;
+
+        let _config = Config::get().await.unwrap();
+        let p = CWD.as_ref().unwrap().join(".test-linux-host.mise.toml");
+        file::write(
+            &p,
+            r#"
+        [bootstrap.linux.sysctl]
+        "vm.max_map_count" = 262144
+        "net.ipv4.tcp_rmem" = "4096 87380 6291456"
+
+        [bootstrap.linux.kernel_modules]
+        br_netfilter = true
+        overlay = false
+
+        [bootstrap.linux.environment]
+        JAVA_TOOL_OPTIONS = "-Xmx2g"
+        "#,
+        )
+        .unwrap();
+        let cf = MiseToml::from_file(&p).unwrap();
+        let linux = cf.bootstrap_config().unwrap().linux;
+        assert_eq!(
+            linux.sysctl["vm.max_map_count"],
+            SysctlTomlValue::Integer(262144)
+        );
+        assert_eq!(
+            linux.sysctl["net.ipv4.tcp_rmem"],
+            SysctlTomlValue::String("4096 87380 6291456".to_string())
+        );
+        assert!(linux.kernel_modules["br_netfilter"]);
+        assert!(!linux.kernel_modules["overlay"]);
+        assert_eq!(linux.environment["JAVA_TOOL_OPTIONS"], "-Xmx2g");
+        file::remove_file(&p).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_certificates_and_known_hosts() {
+        use crate::system::certificates::{CertificateTomlConfig, CertificateTomlTable};
+        use crate::system::known_hosts::KnownHostTomlValue;
+        use crate::system::managed_files::ManagedState;
+
+        let _config = Config::get().await.unwrap();
+        let p = CWD.as_ref().unwrap().join(".test-trust.mise.toml");
+        file::write(
+            &p,
+            r#"
+        [bootstrap.certificates]
+        corp-root = "certs/corp-root.pem"
+        old-root = { state = "absent" }
+
+        [bootstrap.ssh.known_hosts]
+        "github.com" = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl"
+        "[git.corp]:2222" = []
+        "#,
+        )
+        .unwrap();
+        let cf = MiseToml::from_file(&p).unwrap();
+        let bootstrap = cf.bootstrap_config().unwrap();
+        assert_eq!(
+            bootstrap.certificates["corp-root"],
+            CertificateTomlConfig::Source("certs/corp-root.pem".to_string())
+        );
+        assert_eq!(
+            bootstrap.certificates["old-root"],
+            CertificateTomlConfig::Table(CertificateTomlTable {
+                source: None,
+                content: None,
+                state: ManagedState::Absent,
+            })
+        );
+        assert!(matches!(
+            &bootstrap.ssh.known_hosts["github.com"],
+            KnownHostTomlValue::Key(key) if key.starts_with("ssh-ed25519 ")
+        ));
+        assert_eq!(
+            bootstrap.ssh.known_hosts["[git.corp]:2222"],
+            KnownHostTomlValue::Keys(vec![])
+        );
+        file::remove_file(&p).unwrap();
+    }
 }
@@ -31,6 +31,7 @@ use crate::file::display_path;
 use crate::remote_source::RemoteSource;
 use crate::shorthands::{Shorthands, get_shorthands};
 use crate::task::task_file_providers::{TaskFileArtifact, TaskFileProvidersBuilder};
+use crate::task::task_include_lock::{fetch_task_include, is_remote_task_include};
 use crate::task::task_sources::TaskOutputs;
 use crate::task::{
     RunEntry, Task, TaskCacheConfig, TaskRustCacheConfig, TaskTemplate, monorepo_scope,
@@ -4518,7 +4519,7 @@ fn is_mise_config_file_in_task_include(root: &Path, path: &Path) -> bool {
     })
 }
 
-async fn resolve_git_url_to_path(git_url: &str) -> Result<TaskFileArtifact> {
+pub(crate) async fn resolve_git_url_to_path(git_url: &str) -> Result<TaskFileArtifact> {
     let no_cache = Settings::get().task.remote_no_cache.unwrap_or(false);
     if !no_cache {
         let task_file_providers = TaskFileProvidersBuilder::new().with_cache(true).build();
@@ -4651,8 +4652,8 @@ pub(crate) fn task_includes_for_dir(dir: &Path, config_files: &ConfigMap) -> Res
     Ok(includes
         .into_iter()
         .flat_map(|p| {
-            // Git URLs are handled by load_file_tasks, not here
-            if p.starts_with("git::") {
+            // Remote includes are handled by load_file_tasks, not here
+            if is_remote_task_include(&p) {
                 return vec![];
             }
             expand_task_include(&resolve_dir, &p)
@@ -4678,7 +4679,7 @@ pub(crate) fn task_creation_dir_for_dir(dir: &Path, config_files: &ConfigMap) ->
     };
     if let Some(path) = includes
         .iter()
-        .filter(|include| !include.starts_with("git::"))
+        .filter(|include| !is_remote_task_include(include))
         .flat_map(|include| expand_task_include(&resolve_dir, include))
         .find(|path| path.is_dir())
     {
@@ -4912,11 +4913,16 @@ async fn load_task_sources_from_configs(
     // a config can only vouch for task include files when it was actually
     // trusted — safe configs load without trust and cannot vouch for anything
     let require_task_include_trust = !configs.iter().any(|cf| is_path_trusted(cf.get_path()));
-    let (includes, resolve_dir, include_config_precedence) = configs
+    let (includes, resolve_dir, include_config_precedence, include_config_path) = configs
         .iter()
         .enumerate()
         .find_map(|(precedence, cf)| match cf.task_config_includes() {
-            Ok(Some(includes)) => Some(Ok((includes, cf.config_root(), precedence))),
+            Ok(Some(includes)) => Some(Ok((
+                includes,
+                cf.config_root(),
+                precedence,
+                Some(cf.get_path().to_path_buf()),
+            ))),
             Ok(None) => None,
             Err(err) => Some(Err(err)),
         })
@@ -4926,10 +4932,17 @@ async fn load_task_sources_from_configs(
                 tc.task_config
                     .includes
                     .clone()
-                    .map(|includes| (includes, tc.includes_root.clone(), configs.len()))
+                    .map(|includes| (includes, tc.includes_root.clone(), configs.len(), None))
             })
         })
-        .unwrap_or_else(|| (default_task_includes(), dir.to_path_buf(), configs.len()));
+        .unwrap_or_else(|| {
+            (
+                default_task_includes(),
+                dir.to_path_buf(),
+                configs.len(),
+                None,
+            )
+        });
 
     // Resolve task defaults once for the config root so inline tasks from
     // lower-precedence overlay files use the same defaults as file tasks.
@@ -4983,8 +4996,8 @@ async fn load_task_sources_from_configs(
         None
     };
     for include in &includes {
-        let artifacts = if include.starts_with("git::") {
-            vec![resolve_git_url_to_path(include).await?]
+        let artifacts = if is_remote_task_include(include) {
+            vec![fetch_task_include(config, include, include_config_path.as_deref()).await?]
         } else {
             expand_task_include(&resolve_dir, include)
                 .into_iter()
@@ -6,7 +6,7 @@ use crate::{dirs, env, file};
 #[allow(unused_imports)]
 use confique::env::parse::{list_by_colon, list_by_comma};
 use confique::{Config, Layer};
-use eyre::{Result, bail, eyre};
+use eyre::{Result, WrapErr, bail, eyre};
 use indexmap::{IndexMap, indexmap};
 use itertools::Itertools;
 use path_absolutize::Absolutize;
@@ -592,6 +592,31 @@ fn resolve_aqua_registry_paths(settings: &mut toml::Table, path: &Path) {
     }
 }
 
+/// Rewrite relative `registries` (private mise registry) entries the same way
+/// as `aqua.registries`. `file://./registry` is accepted as a relative path
+/// too, since it reads like one.
+fn resolve_mise_registry_paths(settings: &mut toml::Table, path: &Path) {
+    let Some(registries) = settings
+        .get_mut("registries")
+        .and_then(toml::Value::as_array_mut)
+    else {
+        return;
+    };
+    let config_root = crate::config::config_file::config_root::config_root(path);
+    for entry in registries.iter_mut() {
+        let Some(value) = entry.as_str() else {
+            continue;
+        };
+        let value = value
+            .strip_prefix("file://")
+            .filter(|rest| rest.starts_with("./") || rest.starts_with("../"))
+            .unwrap_or(value);
+        if let Some(resolved) = resolve_registry_source(value, &config_root) {
+            *entry = toml::Value::String(resolved);
+        }
+    }
+}
+
 /// Resolve age identity paths while the settings file that declared them is
 /// still known. Once settings layers are merged, a relative `PathBuf` no
 /// longer carries enough information to distinguish two config roots.
@@ -1030,6 +1055,7 @@ impl Settings {
             // After the strips, so a setting that will not survive them is
             // never rewritten.
             resolve_aqua_registry_paths(settings, path);
+            resolve_mise_registry_paths(settings, path);
             resolve_age_paths(settings, path)?;
         }
         let deprecated = deprecated_settings_in_toml_config(&raw);
@@ -1292,6 +1318,15 @@ impl Settings {
             .and_then(|s| duration::parse_duration(s).ok())
     }
 
+    pub(crate) fn task_service_stop_timeout(&self) -> Result<Duration> {
+        duration::parse_duration(&self.task.service_stop_timeout).wrap_err_with(|| {
+            format!(
+                "invalid task.service_stop_timeout: {:?}",
+                self.task.service_stop_timeout
+            )
+        })
+    }
+
     pub(crate) fn log_level(&self) -> log::LevelFilter {
         self.log_level.parse().unwrap_or(log::LevelFilter::Info)
     }
@@ -2168,6 +2203,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn relative_mise_registries_resolve_against_config_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = toml::from_str::<toml::Table>(
+            r#"registries = ["file://./registry", "registry", "https://git.corp/mise-registry"]"#,
+        )
+        .unwrap();
+        resolve_mise_registry_paths(&mut settings, &dir.path().join(".mise.toml"));
+        let registries = settings["registries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        let expected = Url::from_file_path(dir.path().join("registry"))
+            .unwrap()
+            .to_string();
+        assert_eq!(
+            registries,
+            vec![
+                expected.clone(),
+                expected,
+                "https://git.corp/mise-registry".to_string()
+            ]
+        );
+    }
+
     #[test]
     fn registries_without_aqua_table_are_untouched() {
         let dir = tempfile::tempdir().unwrap();
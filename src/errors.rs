@@ -20,12 +20,21 @@ pub(crate) enum Error {
         backend: Box<BackendArg>,
         version: String,
     },
+    #[error("{tool} is denied by the tool policy ({policy}): {reason}")]
+    PolicyDenied {
+        tool: String,
+        policy: String,
+        reason: String,
+    },
     #[error("[{0}] plugin not installed")]
     PluginNotInstalled(String),
     #[error("{0}@{1} not installed")]
     VersionNotInstalled(Box<BackendArg>, String),
     #[error("{} exited with non-zero status: {}", .0, render_exit_status(.1))]
     ScriptFailed(String, Option<ExitStatus>),
+    #[error("{0} exceeded the sandbox {1}")]
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    ResourceLimitExceeded(String, crate::sandbox::LimitBreach),
     #[error("task interrupted before process start")]
     TaskInterrupted,
     #[error(
@@ -122,6 +131,14 @@ impl Error {
         }
     }
 
+    /// Why a process failed when it is more than its exit status, e.g. a sandbox limit
+    pub(crate) fn failure_reason(err: &Report) -> Option<String> {
+        match err.downcast_ref::<Error>() {
+            Some(Error::ResourceLimitExceeded(_, breach)) => Some(format!("exceeded the {breach}")),
+            _ => None,
+        }
+    }
+
     #[cfg(unix)]
     pub(crate) fn is_sigint(err: &Report) -> bool {
         use std::os::unix::process::ExitStatusExt;
@@ -162,6 +179,15 @@ impl Error {
             .unwrap_or(false)
     }
 
+    pub(crate) fn is_policy_denied_err(err: &Report) -> bool {
+        err.chain().any(|source| {
+            matches!(
+                source.downcast_ref::<Error>(),
+                Some(Error::PolicyDenied { .. })
+            )
+        })
+    }
+
     pub(crate) fn is_required_channel_resolution_err(err: &Report) -> bool {
         err.chain().any(|source| {
             matches!(
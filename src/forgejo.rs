@@ -25,6 +25,20 @@ pub(crate) struct ForgejoRelease {
     pub assets: Vec<ForgejoAsset>,
 }
 
+/// A release with its notes; see [`crate::github::GithubReleaseNotes`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct ForgejoReleaseNotes {
+    pub tag_name: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub html_url: Option<String>,
+    pub draft: bool,
+    pub prerelease: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct ForgejoAsset {
     pub id: u64,
@@ -124,6 +138,44 @@ fn is_published_release(release: &ForgejoRelease) -> bool {
     !release.draft
 }
 
+/// The notes of the most recent 100 published releases, newest first. Not
+/// cached: this is only read to show a changelog.
+pub(crate) async fn list_release_notes_from_url(
+    api_url: &str,
+    repo: &str,
+) -> Result<Vec<ForgejoReleaseNotes>> {
+    let url = format!("{api_url}/repos/{repo}/releases?limit=100");
+    let headers = get_headers(&url, api_url);
+    let mut releases = crate::http::HTTP_FETCH
+        .json_with_headers::<Vec<ForgejoReleaseNotes>, _>(url, &headers)
+        .await?;
+    releases.retain(|r| !r.draft);
+    Ok(releases)
+}
+
+#[derive(Debug, Deserialize)]
+struct ForgejoPullRequest {
+    html_url: String,
+}
+
+/// Opens a pull request from `head` into `base` and returns its URL.
+pub(crate) async fn create_pull_request(
+    api_url: &str,
+    repo: &str,
+    head: &str,
+    base: &str,
+    title: &str,
+    body: &str,
+) -> Result<String> {
+    let url = format!("{api_url}/repos/{repo}/pulls");
+    let headers = get_headers(&url, api_url);
+    let request = serde_json::json!({"title": title, "head": head, "base": base, "body": body});
+    let pr: ForgejoPullRequest = crate::http::HTTP
+        .post_json_response_with_headers(&url, &request, &headers)
+        .await?;
+    Ok(pr.html_url)
+}
+
 pub(crate) async fn get_release_for_url(
     api_url: &str,
     repo: &str,
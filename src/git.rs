@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
@@ -394,6 +394,24 @@ impl Git {
         self.dir.join(".git").is_dir()
     }
 
+    /// Lists `(sha, ref)` pairs advertised by a remote repository, optionally
+    /// limited to the given ref patterns. Annotated tags are listed twice: once
+    /// for the tag object and once peeled (`refs/tags/v1.0.0^{}`) for the commit.
+    pub(crate) fn ls_remote(url: &str, patterns: &[&str]) -> Result<Vec<(String, String)>> {
+        let mut args = vec!["ls-remote", url];
+        args.extend(patterns);
+        let output = sanitize_git_env(crate::cmd::cmd("git", args))
+            .read()
+            .wrap_err_with(|| format!("git ls-remote {url} failed"))?;
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let (sha, name) = line.split_once('\t')?;
+                Some((sha.to_string(), name.to_string()))
+            })
+            .collect())
+    }
+
     pub(crate) fn get_root() -> eyre::Result<PathBuf> {
         Ok(cmd!("git", "rev-parse", "--show-toplevel")
             .read()?
@@ -459,6 +477,76 @@ impl Git {
         )?))
     }
 
+    /// Whether the work tree has no staged, unstaged, or untracked changes.
+    pub(crate) fn is_clean(&self) -> Result<bool> {
+        Ok(git_cmd_read!(&self.dir, "status", "--porcelain")?
+            .trim()
+            .is_empty())
+    }
+
+    /// Creates `branch` at `start` and switches to it, resetting a branch of
+    /// the same name left over from an earlier run.
+    pub(crate) fn switch_new_branch(&self, branch: &str, start: &str) -> Result<()> {
+        validate_revision("branch", branch)?;
+        validate_revision("start", start)?;
+        git_cmd_read!(&self.dir, "switch", "--quiet", "-C", branch, start)?;
+        Ok(())
+    }
+
+    pub(crate) fn switch(&self, branch: &str) -> Result<()> {
+        validate_revision("branch", branch)?;
+        git_cmd_read!(&self.dir, "switch", "--quiet", branch)?;
+        Ok(())
+    }
+
+    pub(crate) fn delete_branch(&self, branch: &str) -> Result<()> {
+        validate_revision("branch", branch)?;
+        git_cmd_read!(&self.dir, "branch", "--quiet", "-D", branch)?;
+        Ok(())
+    }
+
+    /// Stages every change in the work tree and commits it. Returns false when
+    /// there was nothing to commit.
+    pub(crate) fn commit_all(&self, message: &str) -> Result<bool> {
+        git_cmd_read!(&self.dir, "add", "--all")?;
+        let unchanged = git_cmd!(&self.dir, "diff", "--cached", "--quiet")
+            .unchecked()
+            .run()
+            .wrap_err("git diff --cached failed")?
+            .status
+            .success();
+        if unchanged {
+            return Ok(false);
+        }
+        git_cmd_read!(&self.dir, "commit", "--quiet", "--message", message)?;
+        Ok(true)
+    }
+
+    /// Drops every change to the work tree, including untracked files that are
+    /// not ignored.
+    pub(crate) fn discard_changes(&self) -> Result<()> {
+        git_cmd_read!(&self.dir, "reset", "--quiet", "--hard")?;
+        git_cmd_read!(&self.dir, "clean", "--quiet", "--force", "-d")?;
+        Ok(())
+    }
+
+    /// Pushes `branch` to `origin`, replacing what an earlier push left there. The push is
+    /// refused if the branch on `origin` moved since it was last fetched or pushed from here, so
+    /// commits someone else pushed to it are not thrown away.
+    pub(crate) fn push_branch(&self, branch: &str) -> Result<()> {
+        validate_revision("branch", branch)?;
+        let refspec = format!("refs/heads/{branch}:refs/heads/{branch}");
+        git_cmd_read!(
+            &self.dir,
+            "push",
+            "--quiet",
+            "--force-with-lease",
+            "origin",
+            refspec.as_str()
+        )?;
+        Ok(())
+    }
+
     pub(crate) fn get_path<P: AsRef<Path>>(path: P) -> eyre::Result<PathBuf> {
         let root = Self::get_root()?;
         let path = cmd!("git", "-C", &root, "rev-parse", "--git-path", path.as_ref()).read()?;
@@ -566,10 +654,29 @@ fn remote_ref_kind(output: &str, branch_ref: &str, tag_ref: &str) -> Option<Remo
 /// so the heuristic is safe in practice. Abbreviated SHAs are intentionally not
 /// matched — they are ambiguous with short branch names and need server-side
 /// resolution before they can be checked out.
-fn looks_like_sha(s: &str) -> bool {
+pub(crate) fn looks_like_sha(s: &str) -> bool {
     matches!(s.len(), 40 | 64) && s.bytes().all(|b| b.is_ascii_hexdigit())
 }
 
+/// Commit of each tag in `git ls-remote` output, preferring peeled commits of annotated tags
+pub(crate) fn tag_commits(refs: &[(String, String)]) -> BTreeMap<String, String> {
+    let mut tags = BTreeMap::new();
+    for (sha, name) in refs {
+        let Some(tag) = name.strip_prefix("refs/tags/") else {
+            continue;
+        };
+        match tag.strip_suffix("^{}") {
+            Some(tag) => {
+                tags.insert(tag.to_string(), sha.clone());
+            }
+            None => {
+                tags.entry(tag.to_string()).or_insert_with(|| sha.clone());
+            }
+        }
+    }
+    tags
+}
+
 /// If `path` is inside a linked git worktree, returns the equivalent path in
 /// the repository's main checkout, e.g. `/repo-wt/sub/mise.toml` →
 /// `/repo/sub/mise.toml`. Returns None for paths in a main checkout, outside
@@ -683,11 +790,29 @@ impl<'a> CloneOptions<'a> {
 
 #[cfg(test)]
 mod tests {
-    use super::{CloneOptions, Git, looks_like_sha, sanitize_git_cmd_runner, sanitize_git_env};
+    use super::{
+        CloneOptions, Git, looks_like_sha, sanitize_git_cmd_runner, sanitize_git_env, tag_commits,
+    };
     use crate::cmd::CmdLineRunner;
     use crate::config::Settings;
     use std::process::Command;
 
+    #[test]
+    fn test_tag_commits() {
+        let refs = [
+            ("aaa", "refs/tags/v1.0.0"),
+            ("bbb", "refs/tags/v2.0.0"),
+            ("ccc", "refs/tags/v2.0.0^{}"),
+            ("ddd", "refs/heads/main"),
+        ]
+        .map(|(sha, name)| (sha.to_string(), name.to_string()));
+        let tags = tag_commits(&refs);
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags["v1.0.0"], "aaa");
+        // annotated tags resolve to the commit they point at
+        assert_eq!(tags["v2.0.0"], "ccc");
+    }
+
     #[test]
     fn sha_detection() {
         assert!(looks_like_sha("0123456789abcdef0123456789abcdef01234567"));
@@ -66,6 +66,21 @@ pub(crate) struct GithubCommitPerson {
     pub date: String,
 }
 
+/// A release with its notes. Kept apart from [`GithubRelease`] so the notes,
+/// which can be large, stay out of the version listing caches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct GithubReleaseNotes {
+    pub tag_name: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub html_url: Option<String>,
+    pub draft: bool,
+    pub prerelease: bool,
+}
+
 /// Tag with date information
 #[derive(Debug, Clone)]
 pub(crate) struct GithubTagWithDate {
@@ -223,6 +238,44 @@ async fn list_releases_(api_url: &str, repo: &str) -> Result<Vec<GithubRelease>>
     Ok(releases)
 }
 
+/// The notes of the most recent 100 published releases, newest first. Not
+/// cached: this is only read to show a changelog.
+pub(crate) async fn list_release_notes_from_url(
+    api_url: &str,
+    repo: &str,
+) -> Result<Vec<GithubReleaseNotes>> {
+    let url = format!("{api_url}/repos/{repo}/releases?per_page=100");
+    let headers = get_headers(&url)?;
+    let mut releases = crate::http::HTTP_FETCH
+        .json_with_headers::<Vec<GithubReleaseNotes>, _>(&url, &headers)
+        .await?;
+    releases.retain(|r| !r.draft);
+    Ok(releases)
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubPullRequest {
+    html_url: String,
+}
+
+/// Opens a pull request from `head` into `base` and returns its URL.
+pub(crate) async fn create_pull_request(
+    api_url: &str,
+    repo: &str,
+    head: &str,
+    base: &str,
+    title: &str,
+    body: &str,
+) -> Result<String> {
+    let url = format!("{api_url}/repos/{repo}/pulls");
+    let headers = get_headers(&url)?;
+    let request = serde_json::json!({"title": title, "head": head, "base": base, "body": body});
+    let pr: GithubPullRequest = crate::http::HTTP
+        .post_json_response_with_headers(&url, &request, &headers)
+        .await?;
+    Ok(pr.html_url)
+}
+
 pub(crate) async fn list_tags(repo: &str) -> Result<Vec<String>> {
     let key = repo.to_kebab_case();
     let cache = get_tags_cache(&key).await;
@@ -164,6 +164,38 @@ async fn list_releases_(api_url: &str, repo: &str, list_all: bool) -> Result<Vec
     Ok(releases)
 }
 
+#[derive(Debug, Deserialize)]
+struct GitlabMergeRequest {
+    web_url: String,
+}
+
+/// Opens a merge request from `source` into `target` and returns its URL.
+pub(crate) async fn create_merge_request(
+    api_url: &str,
+    repo: &str,
+    source: &str,
+    target: &str,
+    title: &str,
+    description: &str,
+) -> Result<String> {
+    let url = format!(
+        "{}/projects/{}/merge_requests",
+        api_url,
+        urlencoding::encode(repo)
+    );
+    let headers = get_headers(&url, api_url);
+    let request = serde_json::json!({
+        "source_branch": source,
+        "target_branch": target,
+        "title": title,
+        "description": description,
+    });
+    let mr: GitlabMergeRequest = crate::http::HTTP
+        .post_json_response_with_headers(&url, &request, &headers)
+        .await?;
+    Ok(mr.web_url)
+}
+
 #[allow(dead_code)]
 pub(crate) async fn list_tags(repo: &str) -> Result<Vec<String>> {
     let key = repo.to_kebab_case();
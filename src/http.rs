@@ -516,10 +516,30 @@ impl Client {
     fn _new() -> ClientBuilder {
         let v = &*version::VERSION;
         let shell = env::MISE_SHELL.map(|s| s.to_string()).unwrap_or_default();
-        ClientBuilder::new()
+        let builder = ClientBuilder::new()
             .user_agent(format!("mise/{v} {shell}").trim())
             .gzip(true)
-            .zstd(true)
+            .zstd(true);
+        // trust CAs from `[bootstrap.certificates]` directly: the system
+        // bundle may not be rebuilt yet, or SSL_CERT_FILE may point elsewhere
+        #[cfg(any(
+            feature = "native-tls",
+            feature = "rustls",
+            feature = "rustls-native-roots"
+        ))]
+        let builder = builder.tls_certs_merge(
+            crate::system::certificates::installed_pems()
+                .iter()
+                .filter_map(|pem| match reqwest::Certificate::from_pem_bundle(pem) {
+                    Ok(certs) => Some(certs),
+                    Err(err) => {
+                        debug!("ignoring unreadable bootstrap CA certificate: {err}");
+                        None
+                    }
+                })
+                .flatten(),
+        );
+        builder
     }
 
     fn request_timeout(&self) -> Duration {
@@ -748,6 +768,37 @@ impl Client {
         Ok(resp.status().is_success())
     }
 
+    /// POST JSON data and parse the JSON response. A non-success status is an
+    /// error that includes the response body, which is where forge APIs explain
+    /// what was wrong with the request.
+    pub(crate) async fn post_json_response_with_headers<T, U: IntoUrl, B: serde::Serialize>(
+        &self,
+        url: U,
+        body: &B,
+        headers: &HeaderMap,
+    ) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        ensure!(!Settings::get().offline(), "offline mode is enabled");
+        let url = url.into_url()?;
+        debug!("POST {}", &url);
+        let resp = self
+            .reqwest()?
+            .post(url.clone())
+            .header("Content-Type", "application/json")
+            .headers(headers.clone())
+            .json(body)
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            bail!("POST {url} failed: {status} {}", body.trim());
+        }
+        Ok(resp.json().await?)
+    }
+
     pub(crate) async fn download_file<U: IntoUrl>(
         &self,
         url: U,
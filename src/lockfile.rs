@@ -8,6 +8,7 @@ use crate::file;
 use crate::file::display_path;
 use crate::path::PathExt;
 use crate::platform::Platform;
+use crate::task::task_include_lock::TaskIncludeLock;
 use crate::toolset::{ToolSource, ToolVersion, Toolset};
 use eyre::{Report, Result, bail, eyre};
 use indexmap::IndexSet;
@@ -75,6 +76,9 @@ pub(crate) struct Lockfile {
     /// Shared pkgx packages: platform -> package@version -> PkgxPackageInfo
     #[serde(skip)]
     pkgx_packages: BTreeMap<String, BTreeMap<String, PkgxPackageInfo>>,
+    /// Remote `task_config.includes` entries, keyed by the include as written in config
+    #[serde(skip)]
+    task_includes: BTreeMap<String, TaskIncludeLock>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -928,6 +932,13 @@ impl Lockfile {
             }
         }
 
+        if let Some(task_includes) = table.remove("task-includes") {
+            let includes: toml::Table = task_includes.try_into()?;
+            for (include, lock) in includes {
+                lockfile.task_includes.insert(include, lock.try_into()?);
+            }
+        }
+
         Ok(lockfile)
     }
 
@@ -987,6 +998,14 @@ impl Lockfile {
             lockfile.insert("pkgx-packages".to_string(), pkgx_packages.into());
         }
 
+        if !self.task_includes.is_empty() {
+            let mut task_includes = toml::Table::new();
+            for (include, lock) in &self.task_includes {
+                task_includes.insert(include.clone(), toml::Value::try_from(lock)?);
+            }
+            lockfile.insert("task-includes".to_string(), task_includes.into());
+        }
+
         // Write tools section
         let mut tools = toml::Table::new();
         for (short, versions) in &self.tools {
@@ -1096,6 +1115,24 @@ impl Lockfile {
         self.pkgx_packages.get(platform)?.get(id)
     }
 
+    pub(crate) fn get_task_include(&self, include: &str) -> Option<&TaskIncludeLock> {
+        self.task_includes.get(include)
+    }
+
+    pub(crate) fn set_task_include(&mut self, include: &str, lock: TaskIncludeLock) {
+        self.task_includes.insert(include.to_string(), lock);
+    }
+
+    /// Drop locked task includes that are no longer configured
+    pub(crate) fn retain_task_includes(&mut self, keep: &BTreeSet<String>) -> Vec<String> {
+        let (keep, removed): (BTreeMap<_, _>, BTreeMap<_, _>) =
+            std::mem::take(&mut self.task_includes)
+                .into_iter()
+                .partition(|(include, _)| keep.contains(include));
+        self.task_includes = keep;
+        removed.into_keys().collect()
+    }
+
     /// Remove unreferenced conda packages from the shared section.
     /// A package is unreferenced if no tool's conda_deps references it.
     fn cleanup_unreferenced_conda_packages(&mut self) {
@@ -1695,6 +1732,10 @@ fn merge_lockfile_preserving_root(root: &mut Lockfile, other: Lockfile) {
             root_packages.entry(id).or_insert(info);
         }
     }
+
+    for (include, lock) in other.task_includes {
+        root.task_includes.entry(include).or_insert(lock);
+    }
 }
 
 pub(crate) fn update_lockfiles(
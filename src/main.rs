@@ -72,11 +72,13 @@ mod migrate;
 mod minisign;
 mod netrc;
 mod oci;
+mod osv;
 pub(crate) mod parallel;
 mod path;
 mod path_env;
 mod platform;
 mod plugins;
+mod policy;
 mod rand;
 mod redactions;
 mod registry;
@@ -76,6 +76,11 @@ fn netrc_path() -> PathBuf {
     dirs::HOME.join(".netrc")
 }
 
+/// Load the netrc file now rather than on first lookup, for threads that can't read it later
+pub(crate) fn load() {
+    LazyLock::force(&NETRC);
+}
+
 /// Look up credentials for a given host from the netrc file
 ///
 /// Returns `Some((login, password))` if credentials are found, `None` otherwise
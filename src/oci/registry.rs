@@ -3,6 +3,10 @@
 //! Pull side: used by `mise oci build --from <ref>` to stream a base image's
 //! layers into the output layout byte-for-byte so digests match.
 //!
+//! Artifact side: used by the `oci:` backend to list a repository's tags and
+//! download the per-platform layer of an ORAS-style artifact, pinned by
+//! manifest digest.
+//!
 //! Push side: used by `mise oci push` to upload an OCI image layout directly
 //! — no skopeo/crane required. Credentials come from the same sources docker
 //! and podman use (see `crate::oci::auth`); anonymous access is used when no
@@ -439,6 +443,19 @@ async fn fetch_manifest_json(
     url: &str,
     accept: &[&str],
 ) -> Result<(serde_json::Value, String)> {
+    let (bytes, content_type) = fetch_manifest_bytes(session, url, accept).await?;
+    let body: serde_json::Value = serde_json::from_slice(&bytes)
+        .wrap_err_with(|| format!("parsing JSON response from {url}"))?;
+    Ok((body, content_type))
+}
+
+/// Like [`fetch_manifest_json`], but returns the raw body so callers can
+/// compute the manifest digest over the exact bytes the registry served.
+async fn fetch_manifest_bytes(
+    session: &mut AuthSession,
+    url: &str,
+    accept: &[&str],
+) -> Result<(Vec<u8>, String)> {
     let accept_hdr = accept.join(", ");
     let resp = session
         .send(|auth| {
@@ -452,15 +469,7 @@ async fn fetch_manifest_json(
         .wrap_err_with(|| format!("fetching {url}"))?;
     let status = resp.status();
     if !status.is_success() {
-        let hint = if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
-            if session.has_credential() {
-                " — the stored credentials were rejected or lack access to this image"
-            } else {
-                " — the image may be private; run `docker login` (or `podman login`) for this registry"
-            }
-        } else {
-            ""
-        };
+        let hint = pull_auth_hint(status, session.has_credential());
         let body = resp.text().await.unwrap_or_default();
         bail!(
             "fetching {url} failed: {}{hint}\n{}",
@@ -469,11 +478,11 @@ async fn fetch_manifest_json(
         );
     }
     let content_type = header_str(&resp, "content-type");
-    let body: serde_json::Value = resp
-        .json()
+    let bytes = resp
+        .bytes()
         .await
-        .wrap_err_with(|| format!("parsing JSON response from {url}"))?;
-    Ok((body, content_type))
+        .wrap_err_with(|| format!("reading response from {url}"))?;
+    Ok((bytes.to_vec(), content_type))
 }
 
 /// Retry a transient-failure-prone operation with mise's standard backoff
@@ -681,19 +690,8 @@ async fn resolve_manifest(
     desired_platform: Option<(&str, &str)>,
     content_type: &str,
 ) -> Result<ImageManifest> {
-    // The OCI spec marks `mediaType` in the body as SHOULD, not MUST. Some
-    // registries omit it, so we also consult the response Content-Type
-    // header and a structural fallback (presence of a `manifests` array).
-    let body_media_type = body.get("mediaType").and_then(|m| m.as_str()).unwrap_or("");
-    let has_manifests_array = body.get("manifests").map(|m| m.is_array()).unwrap_or(false);
-    let is_index = body_media_type == MEDIA_TYPE_OCI_INDEX
-        || body_media_type == MEDIA_TYPE_DOCKER_MANIFEST_LIST
-        || content_type.contains(MEDIA_TYPE_OCI_INDEX)
-        || content_type.contains(MEDIA_TYPE_DOCKER_MANIFEST_LIST)
-        || (body_media_type.is_empty() && has_manifests_array);
-
     // If this is an index / manifest list, pick the right child manifest.
-    if is_index {
+    if is_index(&body, content_type) {
         let manifests = body
             .get("manifests")
             .and_then(|m| m.as_array())
@@ -734,6 +732,21 @@ async fn resolve_manifest(
     parse_single_manifest(body)
 }
 
+/// Whether a manifest response is an index / manifest list rather than a
+/// single image manifest.
+fn is_index(body: &serde_json::Value, content_type: &str) -> bool {
+    // The OCI spec marks `mediaType` in the body as SHOULD, not MUST. Some
+    // registries omit it, so we also consult the response Content-Type
+    // header and a structural fallback (presence of a `manifests` array).
+    let body_media_type = body.get("mediaType").and_then(|m| m.as_str()).unwrap_or("");
+    let has_manifests_array = body.get("manifests").map(|m| m.is_array()).unwrap_or(false);
+    body_media_type == MEDIA_TYPE_OCI_INDEX
+        || body_media_type == MEDIA_TYPE_DOCKER_MANIFEST_LIST
+        || content_type.contains(MEDIA_TYPE_OCI_INDEX)
+        || content_type.contains(MEDIA_TYPE_DOCKER_MANIFEST_LIST)
+        || (body_media_type.is_empty() && has_manifests_array)
+}
+
 fn parse_single_manifest(body: serde_json::Value) -> Result<ImageManifest> {
     let manifest: ImageManifest = serde_json::from_value(body)
         .wrap_err("parsing OCI/Docker manifest; schema v1 manifests are not supported")?;
@@ -870,6 +883,179 @@ pub(crate) async fn fetch_remote_image(reference: &str) -> Result<Option<RemoteI
     Ok(Some(RemoteImage { manifest, diff_ids }))
 }
 
+// ---------------------------------------------------------------------------
+// Artifacts
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct TagList {
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+/// List every tag of `repository` (a reference whose tag, if any, is
+/// ignored), following the registry's `Link: <…>; rel="next"` pagination.
+/// Used by the `oci:` backend to list tool versions.
+pub(crate) async fn list_tags(repository: &str) -> Result<Vec<String>> {
+    let r = Reference::parse(repository)?;
+    let base_url = r.registry_url();
+    let mut session = AuthSession::new(r.clone(), "pull").await?;
+    let mut url = format!("{base_url}/v2/{}/tags/list?n=1000", r.repository);
+    let mut tags = vec![];
+    loop {
+        let resp = session
+            .send(|auth| {
+                let mut rb = HTTP.reqwest()?.get(&url);
+                if let Some(a) = auth {
+                    rb = rb.header("Authorization", a);
+                }
+                Ok(rb)
+            })
+            .await
+            .wrap_err_with(|| format!("fetching {url}"))?;
+        let status = resp.status();
+        if !status.is_success() {
+            bail!(
+                "listing tags of {repository} failed: {}{}",
+                status.as_u16(),
+                pull_auth_hint(status, session.has_credential())
+            );
+        }
+        let next = next_page_url(&base_url, &header_str(&resp, "link"));
+        let page: TagList = resp
+            .json()
+            .await
+            .wrap_err_with(|| format!("parsing JSON response from {url}"))?;
+        tags.extend(page.tags.unwrap_or_default());
+        match next {
+            Some(next) => url = next,
+            None => break,
+        }
+    }
+    Ok(tags)
+}
+
+/// The `rel="next"` target of a `Link` header, made absolute against
+/// `base_url` (registries usually return a path).
+fn next_page_url(base_url: &str, link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (target, params) = part.split_once(';')?;
+        if !params
+            .split(';')
+            .any(|p| matches!(p.trim(), "rel=\"next\"" | "rel=next"))
+        {
+            return None;
+        }
+        let target = target.trim().strip_prefix('<')?.strip_suffix('>')?;
+        Some(if target.starts_with('/') {
+            format!("{base_url}{target}")
+        } else {
+            target.to_string()
+        })
+    })
+}
+
+/// A single-platform manifest and the digest of the exact bytes it was
+/// served as — the value to pin in a lockfile.
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedManifest {
+    pub digest: String,
+    pub manifest: ImageManifest,
+}
+
+/// Resolve `reference` to its manifest for `platform`, given as OCI
+/// `(os, architecture)` names such as `("darwin", "arm64")`. A tag pointing
+/// at an index descends into the matching entry; a single manifest is
+/// returned as-is whatever platform it was pushed for. Digest references
+/// must hash to the digest they name.
+pub(crate) async fn resolve_platform_manifest(
+    reference: &str,
+    platform: (&str, &str),
+) -> Result<ResolvedManifest> {
+    let r = Reference::parse(reference)?;
+    let base_url = r.registry_url();
+    let mut session = AuthSession::new(r.clone(), "pull").await?;
+    let accept = [
+        MEDIA_TYPE_OCI_MANIFEST,
+        MEDIA_TYPE_DOCKER_MANIFEST,
+        MEDIA_TYPE_OCI_INDEX,
+        MEDIA_TYPE_DOCKER_MANIFEST_LIST,
+    ];
+    let url = format!("{base_url}/v2/{}/manifests/{}", r.repository, r.tag);
+    let (mut bytes, content_type) = fetch_manifest_bytes(&mut session, &url, &accept)
+        .await
+        .wrap_err_with(|| format!("fetching manifest for {reference}"))?;
+    let mut digest = sha256_digest(&bytes);
+    if r.tag.starts_with("sha256:") && digest != r.tag {
+        bail!("manifest digest mismatch for {reference}: got {digest}");
+    }
+    let body: serde_json::Value = serde_json::from_slice(&bytes)
+        .wrap_err_with(|| format!("parsing manifest for {reference}"))?;
+    if is_index(&body, &content_type) {
+        let (os, arch) = platform;
+        let child = index_entry_digest(&body, os, arch).ok_or_else(|| {
+            eyre::eyre!("no {os}/{arch} entry in the manifest index for {reference}")
+        })?;
+        crate::oci::layout::validate_sha256_digest(&child)?;
+        let child_url = format!("{base_url}/v2/{}/manifests/{child}", r.repository);
+        let (child_bytes, _) = fetch_manifest_bytes(&mut session, &child_url, &accept[..2]).await?;
+        let actual = sha256_digest(&child_bytes);
+        if actual != child {
+            bail!("manifest digest mismatch for {child_url}: got {actual}");
+        }
+        bytes = child_bytes;
+        digest = child;
+    }
+    let manifest: ImageManifest = serde_json::from_slice(&bytes)
+        .wrap_err_with(|| format!("parsing manifest for {reference}"))?;
+    for layer in &manifest.layers {
+        crate::oci::layout::validate_sha256_digest(&layer.digest)?;
+    }
+    Ok(ResolvedManifest { digest, manifest })
+}
+
+/// Digest of the index entry built for exactly `os`/`arch`.
+fn index_entry_digest(index: &serde_json::Value, os: &str, arch: &str) -> Option<String> {
+    index
+        .get("manifests")?
+        .as_array()?
+        .iter()
+        .find(|entry| {
+            let platform = entry.get("platform");
+            let get = |k: &str| {
+                platform
+                    .and_then(|p| p.get(k))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+            };
+            get("os") == os && get("architecture") == arch
+        })
+        .and_then(|entry| entry.get("digest"))
+        .and_then(|d| d.as_str())
+        .map(String::from)
+}
+
+/// Download blob `digest` from `reference`'s repository to `dest`, checking
+/// its sha256 before anything is written.
+pub(crate) async fn download_verified_blob(
+    reference: &str,
+    digest: &str,
+    dest: &Path,
+    pr: Option<&dyn SingleReport>,
+) -> Result<()> {
+    crate::oci::layout::validate_sha256_digest(digest)?;
+    let r = Reference::parse(reference)?;
+    let base_url = r.registry_url();
+    let mut session = AuthSession::new(r.clone(), "pull").await?;
+    let url = format!("{base_url}/v2/{}/blobs/{digest}", r.repository);
+    let bytes = download_blob(&mut session, &url, pr).await?;
+    let actual = sha256_digest(&bytes);
+    if actual != digest {
+        bail!("blob digest mismatch for {url}: got {actual}, expected {digest}");
+    }
+    crate::file::write(dest, &bytes)
+}
+
 // ---------------------------------------------------------------------------
 // Push
 // ---------------------------------------------------------------------------
@@ -1678,6 +1864,18 @@ fn check_upload_err(slot: &UploadErrSlot, path: &Path) -> Result<()> {
     Ok(())
 }
 
+fn pull_auth_hint(status: StatusCode, had_credential: bool) -> &'static str {
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN if had_credential => {
+            " — the stored credentials were rejected or lack access to this image"
+        }
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            " — the image may be private; run `docker login` (or `podman login`) for this registry"
+        }
+        _ => "",
+    }
+}
+
 fn push_auth_hint(status: StatusCode, had_credential: bool) -> &'static str {
     match status {
         StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN if !had_credential => {
@@ -1960,4 +2158,46 @@ mod tests {
         assert_eq!(r.repository, "foo/bar");
         assert_eq!(r.tag, digest);
     }
+
+    #[test]
+    fn next_page_url_follows_relative_and_absolute_links() {
+        let base = "https://registry.corp";
+        assert_eq!(
+            next_page_url(
+                base,
+                r#"</v2/tools/deployer/tags/list?n=2&last=1.1>; rel="next""#
+            )
+            .as_deref(),
+            Some("https://registry.corp/v2/tools/deployer/tags/list?n=2&last=1.1")
+        );
+        assert_eq!(
+            next_page_url(base, "<https://cdn.corp/v2/x/tags/list?last=b>; rel=next").as_deref(),
+            Some("https://cdn.corp/v2/x/tags/list?last=b")
+        );
+        assert_eq!(
+            next_page_url(base, r#"</v2/x/tags/list>; rel="prev""#),
+            None
+        );
+        assert_eq!(next_page_url(base, ""), None);
+    }
+
+    #[test]
+    fn index_entry_digest_matches_exact_platform() {
+        let index = serde_json::json!({
+            "manifests": [
+                {"digest": "sha256:linux-amd64", "platform": {"os": "linux", "architecture": "amd64"}},
+                {"digest": "sha256:darwin-arm64", "platform": {"os": "darwin", "architecture": "arm64"}},
+                {"digest": "sha256:no-platform"},
+            ]
+        });
+        assert_eq!(
+            index_entry_digest(&index, "darwin", "arm64").as_deref(),
+            Some("sha256:darwin-arm64")
+        );
+        assert_eq!(
+            index_entry_digest(&index, "linux", "amd64").as_deref(),
+            Some("sha256:linux-amd64")
+        );
+        assert_eq!(index_entry_digest(&index, "linux", "arm64"), None);
+    }
 }
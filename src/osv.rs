@@ -0,0 +1,689 @@
+//! Offline vulnerability lookups against an [OSV](https://osv.dev) snapshot
+//!
+//! The snapshot is OSV's per-ecosystem export (`<ecosystem>/all.zip`) extracted
+//! into `<audit.db_dir>/<ecosystem>/`, one JSON record per vulnerability.
+//! `mise audit update` downloads it; after that `mise audit` needs no network,
+//! and a directory filled by hand (or by another machine) works the same way.
+//!
+//! Tools are mapped to OSV packages by backend: `npm:`, `pipx:`, `cargo:`,
+//! `gem:`, `go:` and `maven:` tools are packages in their own ecosystem, and
+//! `aqua:`, `github:` and `ubi:` tools are looked up by their GitHub repository
+//! as a Go module, which is how OSV tracks most single-binary CLIs.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use versions::Versioning;
+
+use crate::backend::backend_type::BackendType;
+use crate::cli::args::BackendArg;
+use crate::config::Settings;
+use crate::dirs;
+use crate::file::{self, ExtractOptions};
+use crate::http::HTTP;
+use crate::ui::multi_progress_report::MultiProgressReport;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Ecosystem {
+    CratesIo,
+    Go,
+    Maven,
+    Npm,
+    PyPI,
+    RubyGems,
+}
+
+impl Ecosystem {
+    /// The ecosystem's name in OSV records and in the export bucket.
+    pub(crate) fn osv_name(&self) -> &'static str {
+        match self {
+            Self::CratesIo => "crates.io",
+            Self::Go => "Go",
+            Self::Maven => "Maven",
+            Self::Npm => "npm",
+            Self::PyPI => "PyPI",
+            Self::RubyGems => "RubyGems",
+        }
+    }
+
+    fn purl_type(&self) -> &'static str {
+        match self {
+            Self::CratesIo => "cargo",
+            Self::Go => "golang",
+            Self::Maven => "maven",
+            Self::Npm => "npm",
+            Self::PyPI => "pypi",
+            Self::RubyGems => "gem",
+        }
+    }
+}
+
+/// The OSV package a tool is published as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Package {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    /// Set when the package was derived from a GitHub repository rather than
+    /// named by the backend, so the purl points at the repository.
+    github_repo: Option<String>,
+}
+
+impl Package {
+    /// The OSV package for a tool, if its backend has one.
+    pub(crate) fn for_backend(ba: &BackendArg) -> Option<Self> {
+        let name = ba.tool_name();
+        let package = |ecosystem, name: &str| Self {
+            ecosystem,
+            name: name.to_string(),
+            github_repo: None,
+        };
+        match ba.backend_type() {
+            BackendType::Npm => Some(package(Ecosystem::Npm, &name)),
+            // git URLs and GitHub shorthands aren't registry packages
+            BackendType::Pipx if !name.contains(['/', ':']) => {
+                Some(package(Ecosystem::PyPI, &name))
+            }
+            BackendType::Cargo if !name.contains(['/', ':']) => {
+                Some(package(Ecosystem::CratesIo, &name))
+            }
+            BackendType::Gem => Some(package(Ecosystem::RubyGems, &name)),
+            BackendType::Go => Some(package(Ecosystem::Go, &name)),
+            BackendType::Maven => Some(package(Ecosystem::Maven, &name)),
+            BackendType::Aqua | BackendType::Github | BackendType::Ubi => {
+                let mut parts = name.split('/');
+                let (Some(owner), Some(repo)) = (parts.next(), parts.next()) else {
+                    return None;
+                };
+                let repo = format!("{owner}/{repo}");
+                Some(Self {
+                    ecosystem: Ecosystem::Go,
+                    name: format!("github.com/{repo}"),
+                    github_repo: Some(repo),
+                })
+            }
+            // the Go toolchain is tracked as the `stdlib` module
+            BackendType::Core if ba.short == "go" => Some(package(Ecosystem::Go, "stdlib")),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn purl(&self, version: &str) -> String {
+        let version = trim_v(version);
+        if let Some(repo) = &self.github_repo {
+            return format!("pkg:github/{repo}@{version}");
+        }
+        let name = match self.ecosystem {
+            Ecosystem::Npm => self.name.replacen('@', "%40", 1),
+            Ecosystem::Maven => self.name.replace(':', "/"),
+            _ => self.name.clone(),
+        };
+        format!("pkg:{}/{name}@{version}", self.ecosystem.purl_type())
+    }
+
+    /// Whether an OSV `affected[].package.name` refers to this package.
+    fn matches(&self, name: &str) -> bool {
+        match self.ecosystem {
+            Ecosystem::PyPI => normalize_pypi(name) == normalize_pypi(&self.name),
+            // a go tool is a package path inside a module, and major versions
+            // after v1 live in a `/vN` suffix of the module path
+            Ecosystem::Go => {
+                let within = |module: &str| {
+                    self.name == module
+                        || self
+                            .name
+                            .strip_prefix(module)
+                            .is_some_and(|rest| rest.starts_with('/'))
+                };
+                within(name)
+                    || name
+                        .strip_prefix(&self.name)
+                        .and_then(|rest| rest.strip_prefix("/v"))
+                        .is_some_and(|major| major.chars().all(|c| c.is_ascii_digit()))
+            }
+            _ => self.name == name,
+        }
+    }
+
+    /// Lowercase strings that any record mentioning this package contains, to
+    /// skip parsing the other records.
+    fn needles(&self) -> Vec<String> {
+        match self.ecosystem {
+            Ecosystem::PyPI => {
+                let name = normalize_pypi(&self.name);
+                vec![name.replace('-', "_"), name]
+            }
+            Ecosystem::Go => vec![
+                self.name
+                    .splitn(4, '/')
+                    .take(3)
+                    .collect::<Vec<_>>()
+                    .join("/")
+                    .to_lowercase(),
+            ],
+            _ => vec![self.name.to_lowercase()],
+        }
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, strum::Display, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum Severity {
+    #[value(skip)]
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    fn from_label(label: &str) -> Option<Self> {
+        match label.to_ascii_uppercase().as_str() {
+            "LOW" => Some(Self::Low),
+            "MODERATE" | "MEDIUM" => Some(Self::Medium),
+            "HIGH" => Some(Self::High),
+            "CRITICAL" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    fn from_score(score: f64) -> Self {
+        match score {
+            s if s >= 9.0 => Self::Critical,
+            s if s >= 7.0 => Self::High,
+            s if s >= 4.0 => Self::Medium,
+            s if s > 0.0 => Self::Low,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A vulnerability that affects a tool version.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Finding {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: Option<String>,
+    pub severity: Severity,
+    /// The lowest version above the scanned one that fixes it.
+    pub fixed_in: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Record {
+    id: String,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    withdrawn: Option<String>,
+    #[serde(default)]
+    affected: Vec<Affected>,
+    #[serde(default)]
+    severity: Vec<SeverityScore>,
+    #[serde(default)]
+    database_specific: Option<DatabaseSpecific>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Affected {
+    #[serde(default)]
+    package: Option<AffectedPackage>,
+    #[serde(default)]
+    ranges: Vec<AffectedRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AffectedPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AffectedRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Event {
+    introduced: Option<String>,
+    fixed: Option<String>,
+    last_affected: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SeverityScore {
+    #[serde(rename = "type")]
+    kind: String,
+    score: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DatabaseSpecific {
+    #[serde(default)]
+    severity: Option<String>,
+}
+
+impl Record {
+    fn finding(&self, package: &Package, version: &str) -> Option<Finding> {
+        if self.withdrawn.is_some() {
+            return None;
+        }
+        let mut affected = false;
+        let mut fixed_in: Option<&str> = None;
+        for a in &self.affected {
+            let Some(p) = &a.package else {
+                continue;
+            };
+            if p.ecosystem != package.ecosystem.osv_name() || !package.matches(&p.name) {
+                continue;
+            }
+            let in_versions = a.versions.iter().any(|v| trim_v(v) == trim_v(version));
+            let in_ranges = a
+                .ranges
+                .iter()
+                .filter(|r| r.kind == "SEMVER" || r.kind == "ECOSYSTEM")
+                .any(|r| r.affects(version));
+            if !in_versions && !in_ranges {
+                continue;
+            }
+            affected = true;
+            for fixed in a
+                .ranges
+                .iter()
+                .flat_map(|r| &r.events)
+                .filter_map(|e| e.fixed.as_deref())
+            {
+                if compare_versions(fixed, version) == Ordering::Greater
+                    && fixed_in.is_none_or(|f| compare_versions(fixed, f) == Ordering::Less)
+                {
+                    fixed_in = Some(fixed);
+                }
+            }
+        }
+        affected.then(|| Finding {
+            id: self.id.clone(),
+            aliases: self.aliases.clone(),
+            summary: self.summary.clone(),
+            severity: self.severity(),
+            fixed_in: fixed_in.map(|f| trim_v(f).to_string()),
+        })
+    }
+
+    /// The advisory's own rating if it has one (GitHub advisories do),
+    /// otherwise the rating of its highest CVSS v3 base score.
+    fn severity(&self) -> Severity {
+        if let Some(severity) = self
+            .database_specific
+            .as_ref()
+            .and_then(|d| d.severity.as_deref())
+            .and_then(Severity::from_label)
+        {
+            return severity;
+        }
+        self.severity
+            .iter()
+            .filter(|s| s.kind == "CVSS_V3")
+            .filter_map(|s| cvss3_base_score(&s.score))
+            .map(Severity::from_score)
+            .max()
+            .unwrap_or(Severity::Unknown)
+    }
+}
+
+impl AffectedRange {
+    /// Evaluates the range's events in version order, as the OSV schema
+    /// specifies: `introduced` starts an affected span, `fixed` ends it at that
+    /// version and `last_affected` ends it after that version.
+    fn affects(&self, version: &str) -> bool {
+        let mut events = self.events.iter().collect::<Vec<_>>();
+        events.sort_by(|a, b| compare_versions(a.version(), b.version()));
+        let mut affected = false;
+        for event in events {
+            if let Some(introduced) = &event.introduced
+                && compare_versions(version, introduced) != Ordering::Less
+            {
+                affected = true;
+            } else if let Some(fixed) = &event.fixed
+                && compare_versions(version, fixed) != Ordering::Less
+            {
+                affected = false;
+            } else if let Some(last) = &event.last_affected
+                && compare_versions(version, last) == Ordering::Greater
+            {
+                affected = false;
+            }
+        }
+        affected
+    }
+}
+
+impl Event {
+    fn version(&self) -> &str {
+        self.introduced
+            .as_deref()
+            .or(self.fixed.as_deref())
+            .or(self.last_affected.as_deref())
+            .unwrap_or("0")
+    }
+}
+
+/// Where the snapshot lives: the `audit.db_dir` setting or `<data dir>/osv`.
+pub(crate) fn db_dir() -> PathBuf {
+    Settings::get()
+        .audit
+        .db_dir
+        .clone()
+        .unwrap_or_else(|| dirs::DATA.join("osv"))
+}
+
+pub(crate) fn has_snapshot(ecosystem: Ecosystem) -> bool {
+    db_dir().join(ecosystem.osv_name()).is_dir()
+}
+
+/// Downloads the export of each ecosystem and replaces its snapshot.
+pub(crate) async fn update(ecosystems: &BTreeSet<Ecosystem>) -> Result<()> {
+    let db = db_dir();
+    file::create_dir_all(&db)?;
+    let base_url = Settings::get()
+        .audit
+        .db_url
+        .trim_end_matches('/')
+        .to_string();
+    let mpr = MultiProgressReport::get();
+    for ecosystem in ecosystems {
+        let name = ecosystem.osv_name();
+        let url = format!("{base_url}/{name}/all.zip");
+        let pid = std::process::id();
+        let archive = db.join(format!("{name}.zip.download-{pid}"));
+        let staging = db.join(format!("{name}.download-{pid}"));
+        let pr = mpr.add(&format!("osv {name}"));
+        let result = async {
+            HTTP.download_file(&url, &archive, Some(pr.as_ref()))
+                .await?;
+            file::unzip(
+                &archive,
+                &staging,
+                &ExtractOptions {
+                    pr: Some(pr.as_ref()),
+                    ..Default::default()
+                },
+            )
+            .wrap_err_with(|| format!("failed to extract {url}"))?;
+            let dest = db.join(name);
+            if dest.exists() {
+                file::remove_all(&dest)?;
+            }
+            file::rename(&staging, &dest)
+        }
+        .await;
+        let _ = file::remove_file(&archive);
+        if result.is_err() {
+            let _ = file::remove_all(&staging);
+            pr.abandon();
+        } else {
+            pr.finish();
+        }
+        result?;
+    }
+    Ok(())
+}
+
+/// Looks up every `(package, version)` in the snapshot. Each ecosystem's
+/// records are read once, and only records that mention one of the packages
+/// are parsed. Returns the findings in the same order as `targets`.
+pub(crate) fn scan(targets: &[(Package, String)]) -> Result<Vec<Vec<Finding>>> {
+    let mut findings = vec![vec![]; targets.len()];
+    let mut by_ecosystem: BTreeMap<Ecosystem, Vec<usize>> = BTreeMap::new();
+    for (i, (package, _)) in targets.iter().enumerate() {
+        by_ecosystem.entry(package.ecosystem).or_default().push(i);
+    }
+    let db = db_dir();
+    for (ecosystem, indexes) in by_ecosystem {
+        let dir = db.join(ecosystem.osv_name());
+        if !dir.is_dir() {
+            continue;
+        }
+        let needles = indexes
+            .iter()
+            .map(|&i| (i, targets[i].0.needles()))
+            .collect::<Vec<_>>();
+        for path in record_paths(&dir)? {
+            let contents = file::read_to_string(&path)?;
+            let lower = contents.to_lowercase();
+            let candidates = needles
+                .iter()
+                .filter(|(_, needles)| needles.iter().any(|n| lower.contains(n.as_str())))
+                .map(|(i, _)| *i)
+                .collect::<Vec<_>>();
+            if candidates.is_empty() {
+                continue;
+            }
+            let record: Record = match serde_json::from_str(&contents) {
+                Ok(record) => record,
+                Err(err) => {
+                    debug!("skipping invalid OSV record {}: {err}", path.display());
+                    continue;
+                }
+            };
+            for i in candidates {
+                let (package, version) = &targets[i];
+                if let Some(finding) = record.finding(package, version) {
+                    findings[i].push(finding);
+                }
+            }
+        }
+    }
+    Ok(findings)
+}
+
+fn record_paths(dir: &Path) -> Result<Vec<PathBuf>> {
+    Ok(file::ls(dir)?
+        .into_iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect())
+}
+
+fn trim_v(version: &str) -> &str {
+    version.strip_prefix(['v', 'V']).unwrap_or(version)
+}
+
+fn normalize_pypi(name: &str) -> String {
+    name.to_lowercase().replace(['_', '.'], "-")
+}
+
+/// Orders versions as semver where both parse, otherwise with the same
+/// general-purpose ordering mise uses for tool versions. `0` is the start of
+/// every ecosystem's history in OSV ranges.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (trim_v(a), trim_v(b));
+    match (a, b) {
+        ("0", "0") => return Ordering::Equal,
+        ("0", _) => return Ordering::Less,
+        (_, "0") => return Ordering::Greater,
+        _ => {}
+    }
+    if let (Ok(a), Ok(b)) = (semver::Version::parse(a), semver::Version::parse(b)) {
+        return a.cmp(&b);
+    }
+    match (Versioning::new(a), Versioning::new(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// The CVSS v3.x base score of a vector like
+/// `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`.
+fn cvss3_base_score(vector: &str) -> Option<f64> {
+    let mut metrics = BTreeMap::new();
+    let mut parts = vector.split('/');
+    if !parts.next()?.starts_with("CVSS:3") {
+        return None;
+    }
+    for part in parts {
+        let (metric, value) = part.split_once(':')?;
+        metrics.insert(metric, value);
+    }
+    let changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let av = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (*metrics.get("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+    let cia = |metric| match *metrics.get(metric)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+    let iss = 1.0 - (1.0 - cia("C")?) * (1.0 - cia("I")?) * (1.0 - cia("A")?);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02_f64).powi(15)
+    } else {
+        6.42 * iss
+    };
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let exploitability = 8.22 * av * ac * pr * ui;
+    let score = if changed {
+        1.08 * (impact + exploitability)
+    } else {
+        impact + exploitability
+    };
+    Some(roundup(score.min(10.0)))
+}
+
+/// CVSS v3.1 "Roundup": the smallest one-decimal number >= the input, computed
+/// on integers to avoid floating point artifacts.
+fn roundup(value: f64) -> f64 {
+    let int = (value * 100_000.0).round() as i64;
+    if int % 10_000 == 0 {
+        int as f64 / 100_000.0
+    } else {
+        ((int / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn package(ecosystem: Ecosystem, name: &str) -> Package {
+        Package {
+            ecosystem,
+            name: name.to_string(),
+            github_repo: None,
+        }
+    }
+
+    #[test]
+    fn test_cvss3_base_score() {
+        let score = |v| cvss3_base_score(v).unwrap();
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"), 9.8);
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:R/S:C/C:L/I:L/A:N"), 6.1);
+        assert_eq!(score("CVSS:3.0/AV:L/AC:H/PR:H/UI:R/S:U/C:L/I:N/A:N"), 1.8);
+        assert_eq!(score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:N"), 0.0);
+        assert_eq!(cvss3_base_score("CVSS:4.0/AV:N/AC:L/AT:N"), None);
+    }
+
+    #[test]
+    fn test_range_affects() {
+        let record: Record = serde_json::from_str(
+            r#"{
+                "id": "GHSA-xxxx",
+                "affected": [{
+                    "package": {"ecosystem": "npm", "name": "prettier"},
+                    "ranges": [{"type": "SEMVER", "events": [
+                        {"introduced": "0"}, {"fixed": "2.8.3"},
+                        {"introduced": "3.0.0"}, {"last_affected": "3.1.0"}
+                    ]}]
+                }],
+                "database_specific": {"severity": "MODERATE"}
+            }"#,
+        )
+        .unwrap();
+        let prettier = package(Ecosystem::Npm, "prettier");
+        let finding = |v| record.finding(&prettier, v);
+        assert_eq!(finding("2.8.2").unwrap().fixed_in.as_deref(), Some("2.8.3"));
+        assert!(finding("2.8.3").is_none());
+        assert!(finding("3.0.0").is_some());
+        assert!(finding("3.1.0").unwrap().fixed_in.is_none());
+        assert!(finding("3.1.1").is_none());
+        assert_eq!(finding("1.0.0").unwrap().severity, Severity::Medium);
+        assert!(
+            record
+                .finding(&package(Ecosystem::Npm, "prettier-plugin"), "1.0.0")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_package_matches() {
+        let go = package(Ecosystem::Go, "github.com/cli/cli");
+        assert!(go.matches("github.com/cli/cli"));
+        assert!(go.matches("github.com/cli/cli/v2"));
+        assert!(!go.matches("github.com/cli/cli-extra"));
+        let go_tool = package(Ecosystem::Go, "golang.org/x/tools/cmd/goimports");
+        assert!(go_tool.matches("golang.org/x/tools"));
+        assert!(!go_tool.matches("golang.org/x/tool"));
+        let pypi = package(Ecosystem::PyPI, "Jinja2");
+        assert!(pypi.matches("jinja2"));
+        assert!(package(Ecosystem::PyPI, "ruamel.yaml").matches("ruamel-yaml"));
+    }
+
+    #[test]
+    fn test_purl() {
+        assert_eq!(
+            package(Ecosystem::Npm, "@biomejs/biome").purl("1.9.0"),
+            "pkg:npm/%40biomejs/biome@1.9.0"
+        );
+        assert_eq!(
+            package(Ecosystem::Maven, "com.pinterest.ktlint:ktlint-cli").purl("1.3.0"),
+            "pkg:maven/com.pinterest.ktlint/ktlint-cli@1.3.0"
+        );
+        let gh = Package {
+            ecosystem: Ecosystem::Go,
+            name: "github.com/cli/cli".to_string(),
+            github_repo: Some("cli/cli".to_string()),
+        };
+        assert_eq!(gh.purl("v2.40.0"), "pkg:github/cli/cli@2.40.0");
+    }
+}
@@ -17,11 +17,11 @@ where
     for item in input.into_iter().enumerate() {
         let semaphore = semaphore.clone();
         let permit = semaphore.acquire_owned().await?;
-        jset.spawn(async move {
+        jset.spawn(crate::sandbox::inherit_install_sandbox(async move {
             let _permit = permit;
             let res = f(item.1).await?;
             Ok((item.0, res))
-        });
+        }));
     }
     while let Some(result) = jset.join_next().await {
         let err: eyre::Report = match result {
@@ -122,10 +122,10 @@ impl GoPlugin {
         let tarball_path = tv.download_path().join(filename);
 
         let tarball_url_ = tarball_url.clone();
-        let checksum_handle = tokio::spawn(async move {
+        let checksum_handle = tokio::spawn(crate::sandbox::inherit_install_sandbox(async move {
             let checksum_url = format!("{}.sha256", tarball_url_);
             HTTP.get_text(checksum_url).await
-        });
+        }));
         pr.set_message(format!("download {filename}"));
         HTTP.download_file(&*tarball_url, &tarball_path, Some(pr))
             .await?;
@@ -153,17 +153,18 @@ impl RustPlugin {
         if rustup_is_initialized(homes) {
             return Ok(());
         }
-        let _installer_lock = tokio::task::spawn_blocking(|| {
-            LockFile::new(&rustup_path())
-                .with_callback(|path| {
-                    debug!(
-                        "waiting for rustup-init lock on {}",
-                        file::display_path(path)
-                    );
-                })
-                .lock()
-        })
-        .await??;
+        let _installer_lock =
+            tokio::task::spawn_blocking(crate::sandbox::inherit_install_sandbox_blocking(|| {
+                LockFile::new(&rustup_path())
+                    .with_callback(|path| {
+                        debug!(
+                            "waiting for rustup-init lock on {}",
+                            file::display_path(path)
+                        );
+                    })
+                    .lock()
+            }))
+            .await??;
         if rustup_is_initialized(homes) {
             return Ok(());
         }
@@ -737,22 +738,24 @@ fn rust_state_lock_identities(rustup_home: &Path, cargo_home: &Path) -> Vec<Path
 
 async fn lock_rust_state(homes: &RustHomes) -> Result<Vec<fslock::LockFile>> {
     let identities = rust_state_lock_identities(&homes.rustup, &homes.cargo);
-    tokio::task::spawn_blocking(move || {
-        identities
-            .into_iter()
-            .map(|identity| {
-                let display_identity = identity.clone();
-                LockFile::new(&identity)
-                    .with_callback(move |_| {
-                        debug!(
-                            "waiting for Rust state lock on {}",
-                            file::display_path(&display_identity)
-                        );
-                    })
-                    .lock()
-            })
-            .collect()
-    })
+    tokio::task::spawn_blocking(crate::sandbox::inherit_install_sandbox_blocking(
+        move || {
+            identities
+                .into_iter()
+                .map(|identity| {
+                    let display_identity = identity.clone();
+                    LockFile::new(&identity)
+                        .with_callback(move |_| {
+                            debug!(
+                                "waiting for Rust state lock on {}",
+                                file::display_path(&display_identity)
+                            );
+                        })
+                        .lock()
+                })
+                .collect()
+        },
+    ))
     .await?
 }
 
@@ -151,6 +151,14 @@ impl VfoxPlugin {
         }));
         vfox.set_url_rewriter(crate::http::apply_url_replacements);
         vfox.set_http_headers_resolver(crate::http::netrc_headers);
+        #[cfg(target_os = "linux")]
+        if let Some(sandbox) = crate::sandbox::current_install_sandbox() {
+            // install hooks run on a thread that can't read the gh CLI's config, so the token is
+            // resolved up front
+            let token = crate::github::resolve_token("github.com").map(|(token, _)| token);
+            vfox.github_token_resolver = Some(Arc::new(move || token.clone()));
+            vfox.set_cmd_hook(move |cmd| sandbox.pre_exec_linux(cmd, None, None));
+        }
         let rx = vfox.log_subscribe();
         Ok((vfox, rx))
     }
@@ -0,0 +1,414 @@
+//! Organization tool policy
+//!
+//! A TOML file that restricts which backends, tools and versions may be
+//! resolved and installed:
+//!
+//! ```toml
+//! [backends]
+//! allow = ["core", "aqua", "github"]
+//!
+//! [tools.python]
+//! version = ">=3.10"
+//! deny = ["3.11.0"]
+//!
+//! [provenance]
+//! require = ["github"]
+//! ```
+//!
+//! It comes from the `policy` setting or `<system config dir>/policy.toml`.
+//! Resolution and installs fail with [`Error::PolicyDenied`] when a rule is
+//! broken; `mise policy check` and `mise doctor` report every violation instead.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::LazyLock as Lazy;
+use std::time::Duration;
+
+use eyre::{Report, Result, bail, ensure};
+use serde::Deserialize;
+
+use crate::backend::backend_type::BackendType;
+use crate::cli::args::BackendArg;
+use crate::config::Settings;
+use crate::errors::Error;
+use crate::http::HTTP;
+use crate::semver::{is_npm_semver_range_query, npm_semver_range_filter};
+use crate::toolset::{ToolRequest, ToolVersion, Toolset};
+use crate::{dirs, file, hash};
+
+/// How long a downloaded policy is used before it is fetched again.
+const POLICY_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+static POLICY: Lazy<std::result::Result<Option<Policy>, String>> =
+    Lazy::new(|| load().map_err(|err| format!("{err:#}")));
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Policy {
+    /// Where the policy was loaded from, for error messages.
+    #[serde(skip)]
+    pub location: String,
+    #[serde(default)]
+    backends: BackendRules,
+    /// Rules keyed by tool short name (`python`) or full name (`aqua:cli/cli`).
+    #[serde(default)]
+    tools: BTreeMap<String, ToolRules>,
+    #[serde(default)]
+    provenance: ProvenanceRules,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackendRules {
+    allow: Option<Vec<String>>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ToolRules {
+    /// npm-style range every version must satisfy, e.g. `>=3.10`
+    version: Option<String>,
+    /// exact versions or ranges that may not be used
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProvenanceRules {
+    /// backends whose installs must have verified provenance
+    #[serde(default)]
+    require: Vec<String>,
+}
+
+impl Policy {
+    pub(crate) fn parse(location: &str, contents: &str) -> Result<Self> {
+        let mut policy: Self = toml::from_str(contents)?;
+        policy.location = location.to_string();
+        let backends = policy.backends.allow.iter().flatten();
+        for backend in backends
+            .chain(&policy.backends.deny)
+            .chain(&policy.provenance.require)
+        {
+            ensure!(
+                matches!(
+                    backend.parse::<BackendType>(),
+                    Ok(bt) if !matches!(bt, BackendType::Unknown | BackendType::VfoxBackend(_))
+                ),
+                "unknown backend: {backend}"
+            );
+        }
+        for (tool, rules) in &policy.tools {
+            if let Some(range) = &rules.version {
+                ensure!(
+                    is_npm_semver_range_query(range) && is_valid_range(range),
+                    "tools.{tool}.version must be a version range like \">=3.10\", got \"{range}\""
+                );
+            }
+            for spec in rules.deny.iter().filter(|s| is_npm_semver_range_query(s)) {
+                ensure!(
+                    is_valid_range(spec),
+                    "tools.{tool}.deny has an invalid version range: \"{spec}\""
+                );
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Why the policy denies this tool version, one reason per broken rule.
+    fn check(&self, tv: &ToolVersion) -> Vec<String> {
+        match &tv.request {
+            // mise doesn't install or pick a system version
+            ToolRequest::System { .. } => vec![],
+            // a local path has no version to check
+            ToolRequest::Path { .. } => self.check_backend(tv.ba()),
+            _ => {
+                let mut reasons = self.check_backend(tv.ba());
+                reasons.extend(self.check_version(tv.ba(), &tv.version));
+                reasons
+            }
+        }
+    }
+
+    fn check_backend(&self, ba: &BackendArg) -> Vec<String> {
+        let backend = backend_name(ba);
+        let mut reasons = vec![];
+        if let Some(allow) = &self.backends.allow
+            && !allow.contains(&backend)
+        {
+            reasons.push(format!(
+                "the {backend} backend is not in backends.allow ({})",
+                allow.join(", ")
+            ));
+        }
+        if self.backends.deny.contains(&backend) {
+            reasons.push(format!("the {backend} backend is in backends.deny"));
+        }
+        reasons
+    }
+
+    fn check_version(&self, ba: &BackendArg, version: &str) -> Vec<String> {
+        let mut reasons = vec![];
+        for (tool, rules) in self.tool_rules(ba) {
+            if let Some(range) = &rules.version
+                && !version_matches(version, range)
+            {
+                reasons.push(format!(
+                    "{version} does not satisfy tools.{tool}.version \"{range}\""
+                ));
+            }
+            if let Some(spec) = rules
+                .deny
+                .iter()
+                .find(|spec| version_matches(version, spec))
+            {
+                reasons.push(format!("{version} matches tools.{tool}.deny \"{spec}\""));
+            }
+        }
+        reasons
+    }
+
+    fn tool_rules(&self, ba: &BackendArg) -> impl Iterator<Item = (&String, &ToolRules)> {
+        let full = ba.full();
+        self.tools
+            .iter()
+            .filter(move |(tool, _)| **tool == ba.short || **tool == full)
+    }
+
+    /// Why an installed version is missing the provenance the policy requires, if it is.
+    fn check_provenance(&self, tv: &ToolVersion, platform_key: &str) -> Option<String> {
+        let backend = backend_name(tv.ba());
+        if !self.provenance.require.contains(&backend) {
+            return None;
+        }
+        let verified = tv
+            .lock_platforms
+            .get(platform_key)
+            .is_some_and(|pi| pi.provenance.is_some() && pi.provenance_verified);
+        (!verified).then(|| {
+            format!("provenance.require needs verified provenance for the {backend} backend")
+        })
+    }
+
+    /// Every violation in a toolset resolved with `skip_policy`, as
+    /// `(tool@version, reason)`. Provenance is only checked for versions that
+    /// have a lockfile entry for this platform; otherwise the install checks it.
+    pub(crate) fn toolset_violations(&self, ts: &Toolset) -> Vec<(String, String)> {
+        ts.list_current_versions()
+            .into_iter()
+            .flat_map(|(backend, tv)| {
+                let platform_key = backend.get_platform_key();
+                let mut reasons = self.check(&tv);
+                if tv.lock_platforms.contains_key(&platform_key) {
+                    reasons.extend(self.check_provenance(&tv, &platform_key));
+                }
+                let tool = format!("{}@{}", tv.ba().short, tv.version);
+                reasons
+                    .into_iter()
+                    .map(move |reason| (tool.clone(), reason))
+            })
+            .collect()
+    }
+
+    fn denied(&self, tv: &ToolVersion, reason: String) -> Report {
+        Error::PolicyDenied {
+            tool: format!("{}@{}", tv.ba().short, tv.version),
+            policy: self.location.clone(),
+            reason,
+        }
+        .into()
+    }
+}
+
+/// The policy in effect, if any. Fails if a configured policy can't be loaded,
+/// so a broken policy never silently allows everything.
+pub(crate) fn get() -> Result<Option<&'static Policy>> {
+    match &*POLICY {
+        Ok(policy) => Ok(policy.as_ref()),
+        Err(err) => bail!("failed to load the tool policy: {err}"),
+    }
+}
+
+/// Fails with [`Error::PolicyDenied`] if the policy denies this tool version.
+pub(crate) fn enforce(tv: &ToolVersion) -> Result<()> {
+    let Some(policy) = get()? else {
+        return Ok(());
+    };
+    match policy.check(tv).into_iter().next() {
+        Some(reason) => Err(policy.denied(tv, reason)),
+        None => Ok(()),
+    }
+}
+
+/// Fails with [`Error::PolicyDenied`] if a just-installed version lacks
+/// provenance the policy requires.
+pub(crate) fn enforce_provenance(tv: &ToolVersion, platform_key: &str) -> Result<()> {
+    let Some(policy) = get()? else {
+        return Ok(());
+    };
+    match policy.check_provenance(tv, platform_key) {
+        Some(reason) => Err(policy.denied(tv, reason)),
+        None => Ok(()),
+    }
+}
+
+/// Download a policy URL before anything loads the policy. Offline and fast
+/// commands use the cached copy as long as there is one.
+pub(crate) async fn refresh() {
+    let Some(url) = location().filter(|location| is_url(location)) else {
+        return;
+    };
+    let settings = Settings::get();
+    let path = cache_path(&url);
+    if path.exists()
+        && (settings.prefer_offline()
+            || file::modified_duration(&path).is_ok_and(|age| age < POLICY_CACHE_TTL))
+    {
+        return;
+    }
+    if settings.offline() {
+        return;
+    }
+    let result = async {
+        let contents = HTTP.get_text(&url).await?;
+        Policy::parse(&url, &contents)?;
+        file::create_dir_all(dirs::CACHE.join("policy"))?;
+        file::write_atomic(&path, contents)
+    }
+    .await;
+    if let Err(err) = result {
+        warn!("failed to refresh the tool policy from {url}: {err:#}");
+    }
+}
+
+/// The `policy` setting, or the system policy file if it exists.
+fn location() -> Option<String> {
+    Settings::get().policy.clone().or_else(|| {
+        let path = dirs::SYSTEM_CONFIG.join("policy.toml");
+        path.exists().then(|| path.to_string_lossy().to_string())
+    })
+}
+
+fn load() -> Result<Option<Policy>> {
+    let Some(location) = location() else {
+        return Ok(None);
+    };
+    let contents = if is_url(&location) {
+        let path = cache_path(&location);
+        ensure!(
+            path.exists(),
+            "{location} has not been downloaded yet, run `mise policy check` with network access"
+        );
+        file::read_to_string(&path)?
+    } else {
+        file::read_to_string(&location)?
+    };
+    let policy = Policy::parse(&location, &contents)?;
+    Ok(Some(policy))
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("https://") || location.starts_with("http://")
+}
+
+fn cache_path(url: &str) -> PathBuf {
+    dirs::CACHE
+        .join("policy")
+        .join(format!("{}.toml", hash::hash_to_str(&url)))
+}
+
+/// The backend name rules refer to, e.g. `aqua` or `core`.
+fn backend_name(ba: &BackendArg) -> String {
+    match ba.backend_type() {
+        BackendType::VfoxBackend(_) => "vfox".into(),
+        backend_type => backend_type.to_string(),
+    }
+}
+
+fn is_valid_range(range: &str) -> bool {
+    npm_semver_range_filter(&[], range).is_some()
+}
+
+/// Whether `version` is `spec` or, for a range, satisfies it. A version that
+/// isn't semver never satisfies a range.
+fn version_matches(version: &str, spec: &str) -> bool {
+    if is_npm_semver_range_query(spec) {
+        return npm_semver_range_filter(&[version.to_string()], spec)
+            .is_some_and(|matched| !matched.is_empty());
+    }
+    version.trim_start_matches('v') == spec.trim_start_matches('v')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"
+[backends]
+allow = ["core", "aqua", "github"]
+deny = ["github"]
+
+[tools.python]
+version = ">=3.10"
+deny = ["3.11.0", "<3.10.4"]
+
+[provenance]
+require = ["aqua"]
+"#;
+
+    #[test]
+    fn test_version_matches() {
+        assert!(version_matches("3.11.0", "3.11.0"));
+        assert!(version_matches("v3.11.0", "3.11.0"));
+        assert!(!version_matches("3.11.1", "3.11.0"));
+        assert!(version_matches("3.12.1", ">=3.10"));
+        assert!(!version_matches("3.9.18", ">=3.10"));
+        assert!(!version_matches("temurin-21.0.1", ">=3.10"));
+    }
+
+    #[test]
+    fn test_check_version() {
+        let policy = Policy::parse("policy.toml", POLICY).unwrap();
+        let ba = BackendArg::new("python".into(), Some("core:python".into()));
+        assert!(policy.check_version(&ba, "3.12.1").is_empty());
+        assert_eq!(
+            policy.check_version(&ba, "3.9.18"),
+            vec![
+                "3.9.18 does not satisfy tools.python.version \">=3.10\"",
+                "3.9.18 matches tools.python.deny \"<3.10.4\"",
+            ]
+        );
+        assert_eq!(
+            policy.check_version(&ba, "3.11.0"),
+            vec!["3.11.0 matches tools.python.deny \"3.11.0\""]
+        );
+        let node = BackendArg::new("node".into(), Some("core:node".into()));
+        assert!(policy.check_version(&node, "16.0.0").is_empty());
+    }
+
+    #[test]
+    fn test_check_backend() {
+        let policy = Policy::parse("policy.toml", POLICY).unwrap();
+        let ba = BackendArg::new("npm:prettier".into(), None);
+        assert_eq!(
+            policy.check_backend(&ba),
+            vec!["the npm backend is not in backends.allow (core, aqua, github)"]
+        );
+        let ba = BackendArg::new("github:cli/cli".into(), None);
+        assert_eq!(
+            policy.check_backend(&ba),
+            vec!["the github backend is in backends.deny"]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_rules() {
+        let err = Policy::parse("p", "[backends]\nallow = [\"aqau\"]").unwrap_err();
+        assert_eq!(err.to_string(), "unknown backend: aqau");
+        let err = Policy::parse("p", "[tools.python]\nversion = \"3.10\"").unwrap_err();
+        assert!(err.to_string().contains("must be a version range"), "{err}");
+        assert!(Policy::parse("p", "[tools.python]\nversions = \">=3\"").is_err());
+    }
+}
@@ -2,10 +2,12 @@ use crate::backend::backend_type::BackendType;
 use crate::backend::options::VersionOrder;
 use crate::cli::args::BackendArg;
 use crate::config::Settings;
+use crate::file::display_path;
+use crate::git::{CloneOptions, Git};
 use crate::http::HTTP;
 use crate::toolset::{RawBackendOptions, ToolVersionOptions};
 use crate::ui::multi_progress_report::MultiProgressReport;
-use crate::{dirs, file};
+use crate::{dirs, file, hash};
 use eyre::{Context, Result, bail, ensure};
 use heck::ToShoutySnakeCase;
 use indexmap::IndexMap;
@@ -32,6 +34,15 @@ pub(crate) fn baked_registry() -> &'static Registry {
 }
 
 pub(crate) static REGISTRY: Lazy<&'static Registry> = Lazy::new(|| {
+    let registry = base_registry();
+    match Settings::get().registries.as_deref() {
+        Some(sources) if !sources.is_empty() => Box::leak(Box::new(registry.with_sources(sources))),
+        _ => registry,
+    }
+});
+
+/// The baked-in registry, or the cached floating one when `registry_floating` is set.
+fn base_registry() -> &'static Registry {
     if !Settings::get().registry_floating {
         return &BAKED_REGISTRY;
     }
@@ -53,7 +64,7 @@ pub(crate) static REGISTRY: Lazy<&'static Registry> = Lazy::new(|| {
             &BAKED_REGISTRY
         }
     }
-});
+}
 
 const MISE_REGISTRY_ARCHIVE_URL: &str = "https://mise.jdx.dev/registry/latest.tar.zst";
 const MAX_REGISTRY_ARCHIVE_ENTRIES: usize = 4096;
@@ -109,6 +120,92 @@ impl Registry {
             missing_version_order,
         }
     }
+
+    /// This registry with the tools of the private `registries` sources on top.
+    /// Earlier sources win over later ones; a source that can't be read is skipped.
+    fn with_sources(&self, sources: &[String]) -> Self {
+        let mut entries = self
+            .iter()
+            .map(|(name, tool)| (name.to_string(), tool.clone()))
+            .collect::<BTreeMap<_, _>>();
+        for source in sources.iter().rev() {
+            match RegistrySource::parse(source)
+                .read()
+                .and_then(registry_from_sources)
+            {
+                Ok(registry) => entries.extend(
+                    registry
+                        .iter()
+                        .map(|(name, tool)| (name.to_string(), tool.clone())),
+                ),
+                Err(err) => warn!("failed to load mise registry source {source}: {err:#}"),
+            }
+        }
+        Self::dynamic(entries, self.missing_version_order)
+    }
+}
+
+/// An entry of the `registries` setting.
+#[derive(Debug, PartialEq)]
+enum RegistrySource {
+    /// a local directory of `<tool>.toml` files
+    Dir(PathBuf),
+    /// a local `.tar.zst` archive of `registry/<tool>.toml` files
+    Archive(PathBuf),
+    /// an `http(s)://` `.tar.zst` archive, downloaded into the cache
+    RemoteArchive(String),
+    /// a git repository, cloned into the cache
+    Git(String),
+}
+
+impl RegistrySource {
+    fn parse(source: &str) -> Self {
+        // a single-letter scheme is a windows drive letter
+        let Some(url) = Url::parse(source).ok().filter(|url| url.scheme().len() > 1) else {
+            if url_like(source) {
+                // scp-like git remotes, e.g. `git@git.corp:tools/mise-registry`
+                return Self::Git(source.to_string());
+            }
+            return Self::local(PathBuf::from(source));
+        };
+        match url.scheme() {
+            "file" => Self::local(
+                url.to_file_path()
+                    .unwrap_or_else(|_| PathBuf::from(url.path())),
+            ),
+            "http" | "https" if url.path().ends_with(".tar.zst") => {
+                Self::RemoteArchive(source.to_string())
+            }
+            _ => Self::Git(source.to_string()),
+        }
+    }
+
+    fn local(path: PathBuf) -> Self {
+        if path.to_string_lossy().ends_with(".tar.zst") {
+            Self::Archive(path)
+        } else {
+            Self::Dir(path)
+        }
+    }
+
+    /// Tool sources (`<short>` → TOML) of this registry. Remote sources are read
+    /// from the cache that [`refresh`] fills.
+    fn read(&self) -> Result<BTreeMap<String, String>> {
+        match self {
+            Self::Dir(dir) => read_registry_dir(dir),
+            Self::Archive(path) => read_registry_archive(path),
+            Self::RemoteArchive(url) => {
+                let path = source_cache_dir(url).join("registry.tar.zst");
+                ensure!(path.exists(), "it has not been downloaded yet");
+                read_registry_archive(&path)
+            }
+            Self::Git(url) => {
+                let dir = source_cache_dir(url).join("repo");
+                ensure!(dir.exists(), "it has not been cloned yet");
+                read_registry_dir(&dir)
+            }
+        }
+    }
 }
 
 impl RegistryLookup {
@@ -187,11 +284,16 @@ fn cache_is_fresh(path: &Path, ttl: Duration) -> bool {
         .is_ok_and(|age| age < ttl)
 }
 
-/// Refresh the floating mise registry before anything initializes [`REGISTRY`].
+/// Refresh the floating mise registry and the remote `registries` sources before
+/// anything initializes [`REGISTRY`].
 /// Fast and offline commands use the cached archive (or the baked registry) without networking.
 pub(crate) async fn refresh() {
     let settings = Settings::get();
-    if !settings.registry_floating || settings.prefer_offline() {
+    if settings.prefer_offline() {
+        return;
+    }
+    refresh_sources(&settings).await;
+    if !settings.registry_floating {
         return;
     }
 
@@ -206,16 +308,67 @@ pub(crate) async fn refresh() {
         }
     }
 
-    if let Err(err) = download_registry_archive(&cache_path).await {
+    if let Err(err) = download_registry_archive(MISE_REGISTRY_ARCHIVE_URL, &cache_path).await {
         warn!("failed to refresh floating mise registry: {err:#}");
     }
 }
 
-async fn download_registry_archive(cache_path: &Path) -> Result<()> {
+fn source_cache_dir(url: &str) -> PathBuf {
+    dirs::CACHE
+        .join("mise-registry")
+        .join("sources")
+        .join(hash::hash_to_str(&url))
+}
+
+async fn refresh_sources(settings: &Settings) {
+    let Some(sources) = &settings.registries else {
+        return;
+    };
+    let ttl = settings.registry_cache_ttl();
+    for source in sources {
+        let result = match RegistrySource::parse(source) {
+            RegistrySource::RemoteArchive(url) => {
+                let cache_path = source_cache_dir(&url).join("registry.tar.zst");
+                if cache_is_fresh(&cache_path, ttl) {
+                    continue;
+                }
+                download_registry_archive(&url, &cache_path).await
+            }
+            RegistrySource::Git(url) => refresh_source_repo(&url, ttl),
+            RegistrySource::Dir(_) | RegistrySource::Archive(_) => continue,
+        };
+        if let Err(err) = result {
+            warn!("failed to refresh mise registry source {source}: {err:#}");
+        }
+    }
+}
+
+/// Clone or update a git `registries` source, at most once per `ttl`.
+fn refresh_source_repo(url: &str, ttl: Duration) -> Result<()> {
+    let cache_dir = source_cache_dir(url);
+    // the checkout's mtime doesn't change on fetch, so track it separately
+    let fetched = cache_dir.join("fetched");
+    if cache_is_fresh(&fetched, ttl) {
+        return Ok(());
+    }
+    let git = Git::new(cache_dir.join("repo"));
+    if git.is_repo() {
+        git.update(None)?;
+    } else {
+        git.clone(url, CloneOptions::default())?;
+    }
+    read_registry_dir(&git.dir).wrap_err("repository is not a mise registry")?;
+    file::touch_file(&fetched)
+}
+
+async fn download_registry_archive(url: &str, cache_path: &Path) -> Result<()> {
+    if let Some(parent) = cache_path.parent() {
+        file::create_dir_all(parent)?;
+    }
     let download_path = cache_path.with_extension(format!("download-{}", std::process::id()));
     let pr = MultiProgressReport::get().add_pre_backend("mise registry");
     if let Err(err) = HTTP
-        .download_file(MISE_REGISTRY_ARCHIVE_URL, &download_path, Some(pr.as_ref()))
+        .download_file(url, &download_path, Some(pr.as_ref()))
         .await
     {
         let _ = file::remove_file(&download_path);
@@ -272,6 +425,10 @@ fn replace_registry_cache(download_path: &Path, cache_path: &Path) -> Result<()>
 }
 
 fn parse_registry_archive(path: &Path) -> Result<Registry> {
+    registry_from_sources(read_registry_archive(path)?)
+}
+
+fn read_registry_archive(path: &Path) -> Result<BTreeMap<String, String>> {
     let file = File::open(path)?;
     let decoder = zstd::Decoder::new(file)?;
     let mut archive = jdx_tar::Archive::new(decoder);
@@ -313,7 +470,33 @@ fn parse_registry_archive(path: &Path) -> Result<Registry> {
         !sources.is_empty(),
         "archive does not contain registry entries"
     );
-    registry_from_sources(sources)
+    Ok(sources)
+}
+
+/// Reads `<tool>.toml` files from `dir/registry/`, or from `dir` itself if it
+/// has no `registry` subdirectory.
+fn read_registry_dir(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let nested = dir.join("registry");
+    let dir = if nested.is_dir() { &nested } else { dir };
+    ensure!(dir.is_dir(), "{} is not a directory", display_path(dir));
+    let mut sources = BTreeMap::new();
+    for path in file::ls(dir)? {
+        if path.extension().is_none_or(|extension| extension != "toml") {
+            continue;
+        }
+        let short = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| eyre::eyre!("invalid registry filename: {}", path.display()))?
+            .to_string();
+        sources.insert(short, file::read_to_string(&path)?);
+    }
+    ensure!(
+        !sources.is_empty(),
+        "{} does not contain registry entries",
+        display_path(dir)
+    );
+    Ok(sources)
 }
 
 fn track_registry_archive_entry(
@@ -971,6 +1154,91 @@ idiomatic_files = [{ path = ".example-version", parser = "shell" }]
         assert!(parse_registry_archive(archive.path()).is_err());
     }
 
+    #[test]
+    fn test_registry_source_parse() {
+        use super::*;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file_url = Url::from_file_path(dir.path()).unwrap().to_string();
+        assert_eq!(
+            RegistrySource::parse(&file_url),
+            RegistrySource::Dir(dir.path().to_path_buf())
+        );
+        assert_eq!(
+            RegistrySource::parse(&format!("{file_url}/registry.tar.zst")),
+            RegistrySource::Archive(dir.path().join("registry.tar.zst"))
+        );
+        assert_eq!(
+            RegistrySource::parse("https://mise.corp/registry.tar.zst"),
+            RegistrySource::RemoteArchive("https://mise.corp/registry.tar.zst".into())
+        );
+        for repo in [
+            "https://git.corp/mise-registry",
+            "ssh://git@git.corp/mise-registry.git",
+            "git@git.corp:tools/mise-registry.git",
+        ] {
+            assert_eq!(
+                RegistrySource::parse(repo),
+                RegistrySource::Git(repo.into())
+            );
+        }
+    }
+
+    #[test]
+    fn test_registry_sources_override_and_extend() {
+        use super::*;
+
+        let base = registry_from_sources(BTreeMap::from([
+            (
+                "deployer".to_string(),
+                "backends = [\"aqua:upstream/deployer\"]".to_string(),
+            ),
+            (
+                "jq".to_string(),
+                "aliases = [\"jqlang\"]\nbackends = [\"aqua:jqlang/jq\"]".to_string(),
+            ),
+        ]))
+        .unwrap();
+        let first = tempfile::tempdir().unwrap();
+        file::create_dir_all(first.path().join("registry")).unwrap();
+        file::write(
+            first.path().join("registry/deployer.toml"),
+            "backends = [\"github:corp/deployer\"]",
+        )
+        .unwrap();
+        let second = tempfile::tempdir().unwrap();
+        file::write(
+            second.path().join("deployer.toml"),
+            "backends = [\"github:other/deployer\"]",
+        )
+        .unwrap();
+        file::write(
+            second.path().join("corp-cli.toml"),
+            "description = \"Corp CLI\"\nbackends = [\"github:corp/cli\"]",
+        )
+        .unwrap();
+
+        let registry = base.with_sources(&[
+            first.path().to_string_lossy().to_string(),
+            second.path().to_string_lossy().to_string(),
+            first.path().join("missing").to_string_lossy().to_string(),
+        ]);
+
+        assert_eq!(
+            registry.get("deployer").unwrap().backends[0].full,
+            "github:corp/deployer"
+        );
+        assert_eq!(
+            registry.get("corp-cli").unwrap().description,
+            Some("Corp CLI")
+        );
+        assert_eq!(registry.get("jqlang").unwrap().short, "jq");
+        assert_eq!(
+            registry.keys().collect::<Vec<_>>(),
+            vec!["corp-cli", "deployer", "jq", "jqlang"]
+        );
+    }
+
     #[test]
     fn test_registry_archive_limits() {
         use super::*;
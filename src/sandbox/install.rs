@@ -0,0 +1,331 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use eyre::{Result, bail};
+
+use super::SandboxConfig;
+use crate::backend::backend_type::BackendType;
+use crate::config::Settings;
+use crate::dirs;
+use crate::file;
+use crate::toolset::ToolVersion;
+
+/// Credentials under the home directory that install scripts and builds can't read.
+const SENSITIVE_HOME_PATHS: &[&str] = &[
+    ".ssh",
+    ".aws",
+    ".azure",
+    ".config/gcloud",
+    ".config/gh",
+    ".docker",
+    ".gnupg",
+    ".kube",
+    ".cargo/credentials",
+    ".cargo/credentials.toml",
+    ".git-credentials",
+    ".netrc",
+    ".password-store",
+];
+
+tokio::task_local! {
+    static INSTALL_SANDBOX: Option<SandboxConfig>;
+}
+
+/// Sandboxes of the sandboxed installs in progress, for commands spawned on tasks that don't
+/// carry an install's sandbox, see [`current_install_sandbox`].
+static ACTIVE_INSTALL_SANDBOXES: Mutex<Vec<(u64, SandboxConfig)>> = Mutex::new(vec![]);
+static NEXT_INSTALL_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Registers a sandboxed install in [`ACTIVE_INSTALL_SANDBOXES`] until it is dropped.
+struct ActiveInstallSandbox(u64);
+
+impl ActiveInstallSandbox {
+    fn register(sandbox: SandboxConfig) -> Self {
+        let id = NEXT_INSTALL_ID.fetch_add(1, Ordering::Relaxed);
+        ACTIVE_INSTALL_SANDBOXES.lock().unwrap().push((id, sandbox));
+        Self(id)
+    }
+}
+
+impl Drop for ActiveInstallSandbox {
+    fn drop(&mut self) {
+        ACTIVE_INSTALL_SANDBOXES
+            .lock()
+            .unwrap()
+            .retain(|(id, _)| *id != self.0);
+    }
+}
+
+/// Returns whether `tv` installs in a sandbox. The tool's `install_sandbox` option takes
+/// precedence over the `install_sandbox_backends` and `install_sandbox` settings.
+pub(crate) fn install_sandbox_enabled(tv: &ToolVersion) -> Result<bool> {
+    let Some(value) = tv.request.options().opts.get("install_sandbox") else {
+        let settings = Settings::get();
+        let backend_sandboxed = tv.ba().backend_type().disable_key().is_some_and(|backend| {
+            settings
+                .install_sandbox_backends
+                .iter()
+                .any(|b| b == backend)
+        });
+        return Ok(settings.install_sandbox || backend_sandboxed);
+    };
+    match value {
+        toml::Value::Boolean(value) => Ok(*value),
+        toml::Value::String(value) if value.eq_ignore_ascii_case("true") => Ok(true),
+        toml::Value::String(value) if value.eq_ignore_ascii_case("false") => Ok(false),
+        value => bail!("install_sandbox must be a boolean, got {value}"),
+    }
+}
+
+/// Runs `f` with `sandbox` applied to every command spawned through [`crate::cmd`] and to the
+/// commands of vfox plugins.
+pub(crate) async fn with_install_sandbox<F: Future>(
+    sandbox: Option<SandboxConfig>,
+    f: F,
+) -> F::Output {
+    let _active = sandbox.clone().map(ActiveInstallSandbox::register);
+    INSTALL_SANDBOX.scope(sandbox, f).await
+}
+
+/// The sandbox applied to commands spawned on the current task.
+///
+/// That is the sandbox of the install running on the task. Tasks started with `tokio::spawn`, a
+/// `JoinSet` or `spawn_blocking` don't carry one, so commands they spawn while sandboxed installs
+/// are in progress get a sandbox allowing what any of those installs may do.
+pub(crate) fn current_install_sandbox() -> Option<SandboxConfig> {
+    INSTALL_SANDBOX
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| active_install_sandbox())
+}
+
+/// The sandboxes of every sandboxed install in progress, merged.
+fn active_install_sandbox() -> Option<SandboxConfig> {
+    let active = ACTIVE_INSTALL_SANDBOXES.lock().unwrap();
+    let mut sandboxes = active.iter().map(|(_, sandbox)| sandbox);
+    let mut merged = sandboxes.next()?.clone();
+    for sandbox in sandboxes {
+        merged
+            .allow_write
+            .extend(sandbox.allow_write.iter().cloned());
+        merged
+            .deny_read_paths
+            .extend(sandbox.deny_read_paths.iter().cloned());
+    }
+    merged.allow_write.sort();
+    merged.allow_write.dedup();
+    merged.deny_read_paths.sort();
+    merged.deny_read_paths.dedup();
+    Some(merged)
+}
+
+/// Wraps `f` to run with the install sandbox of the current task, so commands spawned from a task
+/// started during an install are held to that install's sandbox rather than the merged one.
+pub(crate) fn inherit_install_sandbox<F: Future>(f: F) -> impl Future<Output = F::Output> {
+    let scope = INSTALL_SANDBOX.try_with(Clone::clone).ok();
+    async move {
+        match scope {
+            Some(sandbox) => INSTALL_SANDBOX.scope(sandbox, f).await,
+            None => f.await,
+        }
+    }
+}
+
+/// Like [`inherit_install_sandbox`], for closures run with `spawn_blocking` or on a thread.
+pub(crate) fn inherit_install_sandbox_blocking<R>(f: impl FnOnce() -> R) -> impl FnOnce() -> R {
+    let scope = INSTALL_SANDBOX.try_with(Clone::clone).ok();
+    move || match scope {
+        Some(sandbox) => INSTALL_SANDBOX.sync_scope(sandbox, f),
+        None => f(),
+    }
+}
+
+/// Runs `f` held to the install sandbox of the current task.
+///
+/// Code mise runs in-process for an install, like the Lua hooks of vfox plugins, can't be
+/// sandboxed between fork and exec like the commands it spawns. It runs on a thread of its own
+/// instead, which Landlock and seccomp restrict: both only apply to the thread that enables them
+/// and to the processes it starts. Without an install sandbox, `f` runs in place.
+pub(crate) async fn confine<F, T>(f: F) -> Result<T>
+where
+    F: Future<Output = Result<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    #[cfg(target_os = "linux")]
+    if let Some(sandbox) = current_install_sandbox() {
+        // the confined thread can't read ~/.netrc, so its credentials are loaded first
+        crate::netrc::load();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        std::thread::Builder::new()
+            .name("install-sandbox".to_string())
+            .spawn(move || {
+                let _ = tx.send(run_confined(sandbox, f));
+            })?;
+        return rx
+            .await
+            .map_err(|_| eyre::eyre!("the install sandbox thread panicked"))?;
+    }
+    f.await
+}
+
+#[cfg(target_os = "linux")]
+fn run_confined<F, T>(sandbox: SandboxConfig, f: F) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    sandbox.confine_current_thread()?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    runtime.block_on(with_install_sandbox(Some(sandbox), f))
+}
+
+impl SandboxConfig {
+    /// Sandbox for the plugin and installer code of `tv`: writes are limited to its install and
+    /// download directories, the mise cache, package manager caches and the
+    /// `install_sandbox_allow_write` setting, and credentials in the home directory can't be
+    /// read. The install and download directories must already exist.
+    pub(crate) fn for_install(tv: &ToolVersion) -> Self {
+        let mut allow_write = vec![
+            tv.install_path(),
+            tv.download_path(),
+            dirs::CACHE.to_path_buf(),
+        ];
+        allow_write.extend(package_manager_caches(&tv.ba().backend_type()));
+        allow_write.extend(Settings::get().install_sandbox_allow_write.iter().cloned());
+        let mut sandbox = Self {
+            deny_write: true,
+            allow_write,
+            deny_read_paths: sensitive_paths(),
+            deny_syscalls: true,
+            ..Default::default()
+        };
+        sandbox.resolve_paths();
+        sandbox
+    }
+}
+
+/// Caches package managers write to while installing. The caches of `backend` are created, since
+/// Landlock can only grant access to existing directories; the others are allowed if they exist.
+fn package_manager_caches(backend: &BackendType) -> Vec<PathBuf> {
+    let home = &*dirs::HOME;
+    let env_dir = |key: &str| {
+        std::env::var_os(key)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    let cargo_home = env_dir("CARGO_HOME").unwrap_or_else(|| home.join(".cargo"));
+    let gopath = env_dir("GOPATH")
+        .and_then(|paths| std::env::split_paths(&paths).next())
+        .unwrap_or_else(|| home.join("go"));
+    // `None` is used by every backend: pip, uv, go and most build tools cache under ~/.cache
+    let caches = [
+        (
+            None,
+            env_dir("XDG_CACHE_HOME").unwrap_or_else(|| home.join(".cache")),
+        ),
+        (
+            Some(BackendType::Npm),
+            env_dir("npm_config_cache").unwrap_or_else(|| home.join(".npm")),
+        ),
+        (Some(BackendType::Cargo), cargo_home.join("registry")),
+        (Some(BackendType::Cargo), cargo_home.join("git")),
+        (
+            Some(BackendType::Go),
+            env_dir("GOMODCACHE").unwrap_or_else(|| gopath.join("pkg").join("mod")),
+        ),
+        (Some(BackendType::Gem), home.join(".gem")),
+        (
+            Some(BackendType::Dotnet),
+            home.join(".nuget").join("packages"),
+        ),
+        (Some(BackendType::Maven), home.join(".m2")),
+    ];
+    caches
+        .into_iter()
+        .filter(|(owner, dir)| match owner {
+            Some(owner) if owner != backend => dir.exists(),
+            _ => file::create_dir_all(dir).is_ok(),
+        })
+        .map(|(_, dir)| dir)
+        .collect()
+}
+
+fn sensitive_paths() -> Vec<PathBuf> {
+    SENSITIVE_HOME_PATHS
+        .iter()
+        .map(|path| dirs::HOME.join(path))
+        .filter(|path| path.exists())
+        .map(|path| path.canonicalize().unwrap_or(path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serializes tests that start sandboxed installs, which are visible to every test through
+    /// the active install registry.
+    static INSTALLS: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    #[tokio::test]
+    async fn test_spawned_tasks_inherit_install_sandbox() {
+        let _installs = INSTALLS.lock().await;
+        let sandbox = SandboxConfig {
+            deny_write: true,
+            ..Default::default()
+        };
+        let (spawned, blocking) = with_install_sandbox(Some(sandbox), async {
+            let spawned =
+                tokio::spawn(inherit_install_sandbox(async { current_install_sandbox() }));
+            let blocking = tokio::task::spawn_blocking(inherit_install_sandbox_blocking(
+                current_install_sandbox,
+            ));
+            (spawned.await.unwrap(), blocking.await.unwrap())
+        })
+        .await;
+        assert!(spawned.is_some_and(|s| s.deny_write));
+        assert!(blocking.is_some_and(|s| s.deny_write));
+        assert!(
+            tokio::spawn(inherit_install_sandbox(async { current_install_sandbox() }))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unscoped_tasks_get_the_active_install_sandboxes() {
+        let _installs = INSTALLS.lock().await;
+        let sandbox = |path: &str| SandboxConfig {
+            deny_write: true,
+            allow_write: vec![PathBuf::from(path)],
+            ..Default::default()
+        };
+        let (spawned, unsandboxed) = with_install_sandbox(Some(sandbox("/a")), async {
+            with_install_sandbox(Some(sandbox("/b")), async {
+                let spawned = tokio::spawn(async { current_install_sandbox() });
+                // an unsandboxed install keeps commands on its own task unsandboxed
+                let unsandboxed =
+                    with_install_sandbox(None, async { current_install_sandbox() }).await;
+                (spawned.await.unwrap(), unsandboxed)
+            })
+            .await
+        })
+        .await;
+        let spawned = spawned.unwrap();
+        assert!(spawned.deny_write);
+        assert_eq!(
+            spawned.allow_write,
+            vec![PathBuf::from("/a"), PathBuf::from("/b")]
+        );
+        assert!(unsandboxed.is_none());
+        assert!(
+            tokio::spawn(async { current_install_sandbox() })
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
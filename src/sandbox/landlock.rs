@@ -1,8 +1,12 @@
 use eyre::{Result, eyre};
 use landlock::{
     ABI, AccessFs, BitFlags, Compatible, PathBeneath, PathFd, Ruleset, RulesetAttr,
-    RulesetCreatedAttr, RulesetStatus,
+    RulesetCreatedAttr,
 };
+use nix::libc;
+
+use std::os::fd::{AsRawFd, OwnedFd, RawFd};
+use std::path::PathBuf;
 
 use super::SandboxConfig;
 
@@ -64,8 +68,47 @@ fn add_path_rule(
     }
 }
 
-/// Apply Landlock filesystem restrictions.
+/// Returns the paths that cover the whole filesystem except `denied`.
+///
+/// Landlock can only grant access, so a denied path is carved out by granting its siblings and
+/// those of each of its ancestors instead of `/`. The ancestors themselves can then be traversed
+/// but not listed, and entries created in them later are not readable.
+///
+/// This lists directories, so it must run before fork, not in `pre_exec`.
+fn readable_roots(denied: &[PathBuf]) -> Vec<PathBuf> {
+    let mut roots = vec![];
+    let mut dirs = vec![PathBuf::from("/")];
+    while let Some(dir) = dirs.pop() {
+        if denied.contains(&dir) {
+            continue;
+        }
+        if !denied.iter().any(|path| path.starts_with(&dir)) {
+            roots.push(dir);
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        dirs.extend(entries.flatten().map(|entry| entry.path()));
+    }
+    roots.sort();
+    roots
+}
+
+/// Apply Landlock filesystem restrictions to the calling thread and the processes it starts.
 pub(super) fn apply_landlock(config: &SandboxConfig) -> Result<()> {
+    if let Some(ruleset) = ruleset(config)? {
+        restrict_self(ruleset.as_raw_fd())
+            .map_err(|e| eyre!("failed to apply landlock restrictions: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Creates the Landlock ruleset for `config`, or `None` if it restricts nothing.
+///
+/// Creating it opens the allowed paths and allocates, so it must happen before fork; the child
+/// only passes the ruleset to [`restrict_self`].
+pub(super) fn ruleset(config: &SandboxConfig) -> Result<Option<OwnedFd>> {
     let abi = ABI::V5;
 
     let read_access = AccessFs::from_read(abi);
@@ -82,7 +125,13 @@ pub(super) fn apply_landlock(config: &SandboxConfig) -> Result<()> {
         (true, true) => full_access,
         (true, false) => read_access,
         (false, true) => full_access, // need full to add read+write rules for allowed paths
-        (false, false) => return Ok(()), // nothing to restrict
+        (false, false) if !config.deny_read_paths.is_empty() => read_access,
+        (false, false) => return Ok(None), // nothing to restrict
+    };
+    let readable_roots = if deny_read {
+        vec![]
+    } else {
+        readable_roots(&config.deny_read_paths)
     };
 
     let mut ruleset = Ruleset::default()
@@ -138,20 +187,77 @@ pub(super) fn apply_landlock(config: &SandboxConfig) -> Result<()> {
         }
     } else if deny_write {
         // Only writes restricted — allow read everywhere, deny write except allowed paths
-        ruleset = add_read_rule(ruleset, "/", read_access)?;
+        for path in &readable_roots {
+            ruleset = add_path_rule(ruleset, path, read_access)?;
+        }
         ruleset = add_read_rule(ruleset, "/tmp", full_access)?;
         ruleset = add_read_rule(ruleset, "/dev", full_access)?;
         for path in &config.allow_write {
             ruleset = add_path_rule(ruleset, path, full_access)?;
         }
+    } else {
+        // Reads allowed everywhere except deny_read_paths
+        for path in &readable_roots {
+            ruleset = add_path_rule(ruleset, path, read_access)?;
+        }
     }
 
-    let status = ruleset
-        .restrict_self()
-        .map_err(|e| eyre!("failed to apply landlock restrictions: {e}"))?;
-    if status.ruleset == RulesetStatus::NotEnforced || !status.no_new_privs {
-        eyre::bail!("failed to apply landlock restrictions: {status:?}");
+    match Option::<OwnedFd>::from(ruleset) {
+        Some(fd) => Ok(Some(fd)),
+        None => eyre::bail!("failed to apply landlock restrictions: not supported by this kernel"),
     }
+}
 
+/// Restricts the calling thread and the processes it starts with a ruleset from [`ruleset`].
+/// Only makes syscalls, so it is safe to call between fork and exec.
+pub(super) fn restrict_self(ruleset: RawFd) -> std::io::Result<()> {
+    // required to restrict an unprivileged process
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    if unsafe { libc::syscall(libc::SYS_landlock_restrict_self, ruleset, 0) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readable_roots_without_denied_paths() {
+        assert_eq!(readable_roots(&[]), vec![PathBuf::from("/")]);
+    }
+
+    #[test]
+    fn test_readable_roots_excludes_denied_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        for dir in [
+            "home/.ssh",
+            "home/.config/gh",
+            "home/.config/other",
+            "other",
+        ] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("home/file"), "").unwrap();
+        let denied = [root.join("home/.ssh"), root.join("home/.config/gh")];
+
+        let roots = readable_roots(&denied);
+        for path in ["home/file", "home/.config/other", "other"] {
+            assert!(
+                roots.contains(&root.join(path)),
+                "{path} should be readable"
+            );
+        }
+        assert!(roots.iter().all(|path| !path.starts_with(&denied[0])));
+        assert!(roots.iter().all(|path| !path.starts_with(&denied[1])));
+        assert!(!roots.contains(&root));
+        assert!(!roots.contains(&root.join("home")));
+        // everything outside the temp dir stays readable through its siblings
+        assert!(!roots.contains(&PathBuf::from("/")));
+        assert!(roots.iter().any(|path| !path.starts_with(&root)));
+    }
+}
@@ -0,0 +1,408 @@
+//! Resource limits for sandboxed processes.
+//!
+//! On Linux each limited process gets its own cgroup v2 below the one mise runs in, so the
+//! kernel enforces memory, CPU and process limits for the whole process tree and records
+//! breaches in `memory.events` and `pids.events`. That requires the cgroup to be delegated to
+//! the user (e.g. `systemd-run --user --scope -p Delegate=yes mise run test`). mise is never
+//! moved out of its own cgroup: if that cgroup can't hand controllers to children because it
+//! holds processes, the limited cgroups are created next to it when its parent delegates the
+//! controllers. Otherwise mise warns and limits memory with `RLIMIT_DATA`, per process and
+//! without reporting breaches. Process and CPU limits need the cgroup and are not enforced without
+//! one: `RLIMIT_NPROC` would count every process of the user, not just the sandboxed tree.
+
+use std::fmt;
+
+use bytesize::ByteSize;
+use eyre::{Result, bail, eyre};
+
+/// Memory, CPU and process limits of a sandbox
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ResourceLimits {
+    /// Maximum memory in bytes
+    pub memory: Option<u64>,
+    /// Maximum CPU time as a number of CPUs, e.g. `1.5`
+    pub cpu: Option<f64>,
+    /// Maximum number of processes and threads
+    pub pids: Option<u64>,
+}
+
+impl ResourceLimits {
+    pub(crate) fn is_set(&self) -> bool {
+        self.memory.is_some() || self.cpu.is_some() || self.pids.is_some()
+    }
+
+    /// Combines two sets of limits, keeping the stricter value of each.
+    pub(crate) fn min(self, other: Self) -> Self {
+        fn stricter<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+                (a, b) => a.or(b),
+            }
+        }
+        Self {
+            memory: stricter(self.memory, other.memory),
+            cpu: stricter(self.cpu, other.cpu),
+            pids: stricter(self.pids, other.pids),
+        }
+    }
+}
+
+/// Parses a memory limit such as `512M`, `2G` or `2GiB` into bytes.
+pub(crate) fn parse_memory(s: &str) -> Result<u64> {
+    let bytes = s
+        .trim()
+        .parse::<ByteSize>()
+        .map_err(|err| eyre!("invalid memory limit {s:?}: {err}"))?
+        .as_u64();
+    if bytes == 0 {
+        bail!("invalid memory limit {s:?}: must be greater than 0");
+    }
+    Ok(bytes)
+}
+
+/// Parses a CPU limit such as `2` or `0.5`.
+pub(crate) fn parse_cpu(s: &str) -> Result<f64> {
+    let cpu = s
+        .trim()
+        .parse::<f64>()
+        .map_err(|err| eyre!("invalid CPU limit {s:?}: {err}"))?;
+    validate_cpu(cpu)
+}
+
+pub(crate) fn validate_cpu(cpu: f64) -> Result<f64> {
+    if !cpu.is_finite() || cpu <= 0.0 {
+        bail!("invalid CPU limit {cpu}: must be a positive number of CPUs");
+    }
+    Ok(cpu)
+}
+
+/// A limit a sandboxed process ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) enum LimitBreach {
+    Memory(u64),
+    Pids(u64),
+}
+
+impl fmt::Display for LimitBreach {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitBreach::Memory(bytes) => {
+                write!(f, "memory limit ({})", ByteSize::b(*bytes).display().iec())
+            }
+            LimitBreach::Pids(pids) => write!(f, "process limit ({pids})"),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) use linux::Cgroup;
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs::{self, File};
+    use std::io;
+    use std::os::fd::{AsRawFd, RawFd};
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use nix::libc;
+
+    use super::{LimitBreach, ResourceLimits};
+
+    const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+    const CPU_PERIOD_US: u64 = 100_000;
+    const CONTROLLERS: &[&str] = &["cpu", "memory", "pids"];
+
+    /// A cgroup v2 holding one sandboxed process tree, removed when dropped
+    #[derive(Debug)]
+    pub(crate) struct Cgroup {
+        path: PathBuf,
+        procs: File,
+        limits: ResourceLimits,
+    }
+
+    impl Cgroup {
+        /// Creates a cgroup enforcing `limits`, or `None` when no delegated cgroup v2 is
+        /// available and the limits have to fall back to rlimits.
+        pub(crate) fn create(limits: &ResourceLimits) -> Option<Self> {
+            let Some(parent) = delegated_parent() else {
+                warn_rlimit_fallback(limits);
+                return None;
+            };
+            match Self::create_in(parent, limits) {
+                Ok(cgroup) => Some(cgroup),
+                Err(err) => {
+                    warn!("failed to create a cgroup for sandbox limits: {err}");
+                    warn_rlimit_fallback(limits);
+                    None
+                }
+            }
+        }
+
+        fn create_in(parent: &Path, limits: &ResourceLimits) -> io::Result<Self> {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let name = format!(
+                "mise-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            );
+            let path = parent.join(name);
+            fs::create_dir(&path)?;
+            let procs = match File::options().write(true).open(path.join("cgroup.procs")) {
+                Ok(procs) => procs,
+                Err(err) => {
+                    let _ = fs::remove_dir(&path);
+                    return Err(err);
+                }
+            };
+            let cgroup = Self {
+                path,
+                procs,
+                limits: *limits,
+            };
+            if let Some(memory) = limits.memory {
+                cgroup.write("memory.max", &memory.to_string())?;
+                // breach the limit instead of swapping; not every kernel has swap accounting
+                let _ = cgroup.write("memory.swap.max", "0");
+            }
+            if let Some(cpu) = limits.cpu {
+                let quota = (cpu * CPU_PERIOD_US as f64).ceil() as u64;
+                cgroup.write("cpu.max", &format!("{quota} {CPU_PERIOD_US}"))?;
+            }
+            if let Some(pids) = limits.pids {
+                cgroup.write("pids.max", &pids.to_string())?;
+            }
+            debug!("created sandbox cgroup {}", cgroup.path.display());
+            Ok(cgroup)
+        }
+
+        /// `cgroup.procs` of this cgroup; writing `0` to it from the child after fork moves the
+        /// child in before it execs.
+        pub(crate) fn procs_fd(&self) -> RawFd {
+            self.procs.as_raw_fd()
+        }
+
+        /// The limit the process tree ran into, if any
+        pub(crate) fn breach(&self) -> Option<LimitBreach> {
+            if let Some(memory) = self.limits.memory
+                && self.event_count("memory.events", "oom_kill") > 0
+            {
+                return Some(LimitBreach::Memory(memory));
+            }
+            if let Some(pids) = self.limits.pids
+                && self.event_count("pids.events", "max") > 0
+            {
+                return Some(LimitBreach::Pids(pids));
+            }
+            None
+        }
+
+        fn write(&self, file: &str, value: &str) -> io::Result<()> {
+            fs::write(self.path.join(file), value)
+                .map_err(|err| io::Error::new(err.kind(), format!("{file}: {err}")))
+        }
+
+        fn event_count(&self, file: &str, event: &str) -> u64 {
+            fs::read_to_string(self.path.join(file))
+                .unwrap_or_default()
+                .lines()
+                .find_map(|line| line.strip_prefix(event)?.trim().parse().ok())
+                .unwrap_or(0)
+        }
+    }
+
+    impl Drop for Cgroup {
+        fn drop(&mut self) {
+            // fails while processes the sandboxed program left behind are still running
+            if let Err(err) = fs::remove_dir(&self.path) {
+                debug!("failed to remove cgroup {}: {err}", self.path.display());
+            }
+        }
+    }
+
+    /// Moves the calling process into the cgroup. Runs between fork and exec, so it only
+    /// makes a raw syscall.
+    pub(crate) fn join(procs_fd: RawFd) -> io::Result<()> {
+        let ret = unsafe { libc::write(procs_fd, b"0".as_ptr().cast(), 1) };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// Where sandbox cgroups are created: the cgroup mise runs in with the cpu, memory and
+    /// pids controllers enabled for its children, or its parent when mise's cgroup holds
+    /// processes and the parent already delegates them. `None` if neither is writable.
+    fn delegated_parent() -> Option<&'static Path> {
+        static PARENT: OnceLock<Option<PathBuf>> = OnceLock::new();
+        PARENT
+            .get_or_init(|| match sandbox_parent() {
+                Ok(parent) => Some(parent),
+                Err(err) => {
+                    debug!("cgroup v2 is not delegated, using rlimits for sandbox limits: {err}");
+                    None
+                }
+            })
+            .as_deref()
+    }
+
+    fn sandbox_parent() -> io::Result<PathBuf> {
+        let own = fs::read_to_string("/proc/self/cgroup")?
+            .lines()
+            .find_map(|line| line.strip_prefix("0::").map(str::to_string))
+            .ok_or_else(|| io::Error::other("not using the cgroup v2 hierarchy"))?;
+        let own = Path::new(CGROUP_ROOT).join(own.trim_start_matches('/'));
+        match enable_controllers(&own) {
+            Ok(()) => Ok(own),
+            // cgroup v2 only lets a cgroup without processes of its own hand controllers to
+            // children. Moving mise out would also move whatever else shares its cgroup's
+            // accounting, so the sandbox cgroups become siblings of mise's cgroup instead.
+            Err(err) if err.raw_os_error() == Some(libc::EBUSY) => {
+                if let Some(parent) = own.parent()
+                    && parent.starts_with(CGROUP_ROOT)
+                    && controllers_enabled(parent)
+                    && nix::unistd::access(parent, nix::unistd::AccessFlags::W_OK).is_ok()
+                {
+                    return Ok(parent.to_path_buf());
+                }
+                warn!(
+                    "cgroup {} also holds processes and its parent does not delegate the cpu, \
+                     memory and pids controllers, so sandbox limits fall back to rlimits",
+                    own.display()
+                );
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn enable_controllers(cgroup: &Path) -> io::Result<()> {
+        let available = fs::read_to_string(cgroup.join("cgroup.controllers"))?;
+        let enabled = fs::read_to_string(cgroup.join("cgroup.subtree_control"))?;
+        let missing = CONTROLLERS
+            .iter()
+            .filter(|c| !enabled.split_whitespace().any(|e| e == **c))
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        if let Some(c) = missing
+            .iter()
+            .find(|c| !available.split_whitespace().any(|a| a == ***c))
+        {
+            return Err(io::Error::other(format!(
+                "the {c} controller is not available"
+            )));
+        }
+        let enable = missing
+            .iter()
+            .map(|c| format!("+{c}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        fs::write(cgroup.join("cgroup.subtree_control"), enable)
+    }
+
+    fn controllers_enabled(cgroup: &Path) -> bool {
+        let Ok(enabled) = fs::read_to_string(cgroup.join("cgroup.subtree_control")) else {
+            return false;
+        };
+        CONTROLLERS
+            .iter()
+            .all(|c| enabled.split_whitespace().any(|e| e == *c))
+    }
+
+    /// Tells the user which of `limits` the rlimit fallback enforces, once per set of limits.
+    fn warn_rlimit_fallback(limits: &ResourceLimits) {
+        let mut unenforced = vec![];
+        if limits.pids.is_some() {
+            unenforced.push("limit_pids");
+        }
+        if limits.cpu.is_some() {
+            unenforced.push("limit_cpu");
+        }
+        let mut msg = "sandbox limits require a delegated cgroup v2".to_string();
+        if limits.memory.is_some() {
+            msg.push_str(", limiting memory per process with RLIMIT_DATA instead");
+        }
+        if !unenforced.is_empty() {
+            msg.push_str(&format!(", {} not enforced", unenforced.join(" and ")));
+        }
+        warn_once!("{msg}");
+    }
+
+    /// Applies the memory limit with setrlimit, for when no cgroup is available. `RLIMIT_DATA`
+    /// rather than `RLIMIT_AS`, since runtimes like node, the JVM and Go reserve far more
+    /// address space than they use. Process limits are left alone: `RLIMIT_NPROC` counts every
+    /// process of the user. Runs between fork and exec.
+    pub(crate) fn set_rlimits(limits: &ResourceLimits) -> io::Result<()> {
+        if let Some(memory) = limits.memory {
+            let limit = libc::rlimit {
+                rlim_cur: memory,
+                rlim_max: memory,
+            };
+            if unsafe { libc::setrlimit(libc::RLIMIT_DATA, &limit) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) use linux::{join as join_cgroup, set_rlimits};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory() {
+        assert_eq!(parse_memory("512").unwrap(), 512);
+        assert_eq!(parse_memory("2GiB").unwrap(), 2 * 1024 * 1024 * 1024);
+        assert_eq!(parse_memory("2G").unwrap(), 2_000_000_000);
+        assert!(parse_memory("0").is_err());
+        assert!(parse_memory("lots").is_err());
+    }
+
+    #[test]
+    fn test_parse_cpu() {
+        assert_eq!(parse_cpu("2").unwrap(), 2.0);
+        assert_eq!(parse_cpu("0.5").unwrap(), 0.5);
+        assert!(parse_cpu("0").is_err());
+        assert!(parse_cpu("-1").is_err());
+        assert!(parse_cpu("NaN").is_err());
+    }
+
+    #[test]
+    fn test_min_keeps_stricter_limits() {
+        let task = ResourceLimits {
+            memory: Some(2048),
+            cpu: Some(2.0),
+            pids: None,
+        };
+        let cli = ResourceLimits {
+            memory: Some(1024),
+            cpu: None,
+            pids: Some(64),
+        };
+        assert_eq!(
+            task.min(cli),
+            ResourceLimits {
+                memory: Some(1024),
+                cpu: Some(2.0),
+                pids: Some(64),
+            }
+        );
+    }
+
+    #[test]
+    fn test_breach_display() {
+        assert_eq!(
+            LimitBreach::Memory(2 * 1024 * 1024 * 1024).to_string(),
+            "memory limit (2.0 GiB)"
+        );
+        assert_eq!(LimitBreach::Pids(512).to_string(), "process limit (512)");
+    }
+}
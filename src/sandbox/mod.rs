@@ -2,13 +2,27 @@ use std::path::PathBuf;
 
 use crate::file::replace_path;
 
+mod install;
 #[cfg(target_os = "linux")]
 mod landlock;
+mod limits;
 #[cfg(target_os = "macos")]
 mod macos;
 #[cfg(target_os = "linux")]
+mod netns;
+#[cfg(target_os = "linux")]
 mod seccomp;
 
+pub(crate) use install::{
+    confine, current_install_sandbox, inherit_install_sandbox, inherit_install_sandbox_blocking,
+    install_sandbox_enabled, with_install_sandbox,
+};
+#[cfg(target_os = "linux")]
+pub(crate) use limits::Cgroup;
+pub(crate) use limits::{LimitBreach, ResourceLimits, parse_cpu, parse_memory, validate_cpu};
+#[cfg(target_os = "linux")]
+pub(crate) use netns::NetProxy;
+
 /// Configuration for process sandboxing.
 ///
 /// Any `deny_*` or `allow_*` field being set implicitly enables sandboxing.
@@ -24,10 +38,16 @@ pub(crate) struct SandboxConfig {
     pub allow_write: Vec<PathBuf>,
     pub allow_net: Vec<String>,
     pub allow_env: Vec<String>,
+    /// Paths that can't be read even when reads are otherwise allowed (Linux only).
+    pub deny_read_paths: Vec<PathBuf>,
+    /// Block syscalls that reach other processes and the kernel keyrings (Linux only).
+    pub deny_syscalls: bool,
     /// Environment patterns that survive an active env sandbox without enabling it themselves.
     pub pass_through_env: Vec<String>,
     /// Exact hashed environment names that survive an active env sandbox.
     pub cache_env: Vec<String>,
+    /// Memory, CPU and process limits; these do not restrict access on their own.
+    pub limits: ResourceLimits,
 }
 
 /// Minimal env vars inherited when deny_env is active.
@@ -68,8 +88,13 @@ impl SandboxConfig {
         cli
     }
 
-    /// Returns true if any sandbox restriction is configured.
+    /// Returns true if any sandbox restriction or resource limit is configured.
     pub(crate) fn is_active(&self) -> bool {
+        self.restricts_access() || self.limits.is_set()
+    }
+
+    /// Returns true if filesystem, network or env access is restricted.
+    pub(crate) fn restricts_access(&self) -> bool {
         self.deny_read
             || self.deny_write
             || self.deny_net
@@ -78,6 +103,8 @@ impl SandboxConfig {
             || !self.allow_write.is_empty()
             || !self.allow_net.is_empty()
             || !self.allow_env.is_empty()
+            || !self.deny_read_paths.is_empty()
+            || self.deny_syscalls
     }
 
     /// Resolve allow_* paths to absolute paths relative to cwd.
@@ -116,6 +143,13 @@ impl SandboxConfig {
         self.deny_net || !self.allow_net.is_empty()
     }
 
+    #[cfg(target_os = "linux")]
+    fn restricts_fs(&self) -> bool {
+        self.effective_deny_read()
+            || self.effective_deny_write()
+            || !self.deny_read_paths.is_empty()
+    }
+
     pub(crate) fn effective_deny_env(&self) -> bool {
         self.deny_env || !self.allow_env.is_empty()
     }
@@ -202,6 +236,12 @@ impl SandboxConfig {
 
         #[cfg(target_os = "macos")]
         {
+            if self.limits.is_set() {
+                warn!("sandbox resource limits are only supported on Linux, running without them");
+            }
+            if !self.restricts_access() {
+                return Ok(None);
+            }
             return self.apply_macos(program, args).await;
         }
 
@@ -214,21 +254,159 @@ impl SandboxConfig {
 
     #[cfg(all(not(test), target_os = "linux"))]
     fn apply_linux(&self) -> eyre::Result<()> {
-        if self.effective_deny_read() || self.effective_deny_write() {
+        if !self.allow_net.is_empty() || self.limits.is_set() {
+            // mise has to stay alive to proxy the network and watch limits, see `run_supervised`
+            eyre::bail!("per-host network filtering and resource limits need a child process");
+        }
+        if self.restricts_fs() {
             landlock::apply_landlock(self)?;
         }
+        if self.deny_syscalls {
+            seccomp::apply_filter(&seccomp::install_filter()?)?;
+        }
         if self.effective_deny_net() {
-            if !self.allow_net.is_empty() {
-                eyre::bail!(
-                    "per-host network filtering (--allow-net=<host>) is not supported on Linux. \
-                     Use --deny-net to block all network, or remove --allow-net."
-                );
-            }
-            seccomp::apply_seccomp_net_filter()?;
+            seccomp::apply_filter(&seccomp::net_filter()?)?;
         }
         Ok(())
     }
 
+    /// Creates the proxy serving `allow_net` hosts, when per-host filtering is requested.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn net_proxy(&self) -> eyre::Result<Option<NetProxy>> {
+        if self.allow_net.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(NetProxy::new(&self.allow_net)?))
+    }
+
+    /// Creates the cgroup enforcing `limits`, when they are set and cgroup v2 is delegated.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn cgroup(&self) -> Option<Cgroup> {
+        if !self.limits.is_set() {
+            return None;
+        }
+        Cgroup::create(&self.limits)
+    }
+
+    /// Applies Landlock, seccomp, the network namespace and resource limits in the child process
+    /// between fork and exec, so the mise process itself stays unrestricted. `net_proxy` must be
+    /// started once the child has been spawned. Without a `cgroup`, limits fall back to rlimits.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn pre_exec_linux(
+        &self,
+        cmd: &mut std::process::Command,
+        net_proxy: Option<&NetProxy>,
+        cgroup: Option<&Cgroup>,
+    ) {
+        use std::os::fd::AsRawFd;
+        use std::os::unix::process::CommandExt;
+
+        let proxy_fd = net_proxy.map(|proxy| proxy.child_fd());
+        if proxy_fd.is_some() {
+            cmd.envs(netns::proxy_env());
+        }
+        let cgroup_fd = cgroup.map(|cgroup| cgroup.procs_fd());
+        let resource_limits = self.limits;
+        // the child can only report an errno, so a preparation error is logged here and the
+        // child fails the spawn rather than run unsandboxed
+        let prepared = self
+            .prepare_child(proxy_fd.is_some())
+            .inspect_err(|e| error!("failed to prepare sandbox: {e:#}"))
+            .ok();
+        unsafe {
+            cmd.pre_exec(move || {
+                let Some((ruleset, filters)) = &prepared else {
+                    return Err(std::io::Error::from_raw_os_error(nix::libc::EPERM));
+                };
+                // joined first, while the process still has the credentials of the cgroup's owner
+                match cgroup_fd {
+                    Some(fd) => limits::join_cgroup(fd)?,
+                    None if resource_limits.is_set() => limits::set_rlimits(&resource_limits)?,
+                    None => {}
+                }
+                // before Landlock and seccomp, which could block setting up the namespace
+                if let Some(fd) = proxy_fd {
+                    netns::enter_net_namespace(fd)?;
+                }
+                if let Some(ruleset) = ruleset {
+                    landlock::restrict_self(ruleset.as_raw_fd())?;
+                }
+                for filter in filters.iter() {
+                    seccomp::apply_filter(filter)?;
+                }
+                Ok(())
+            });
+        }
+    }
+
+    /// Creates the Landlock ruleset and compiles the seccomp filters a child applies in
+    /// [`Self::pre_exec_linux`]. Both allocate and open files, which the child of a multithreaded
+    /// process must not, so they are prepared before fork and the child only makes syscalls.
+    #[cfg(target_os = "linux")]
+    fn prepare_child(
+        &self,
+        net_namespace: bool,
+    ) -> eyre::Result<(Option<std::os::fd::OwnedFd>, Vec<seccompiler::BpfProgram>)> {
+        let ruleset = match self.restricts_fs() {
+            true => landlock::ruleset(self)?,
+            false => None,
+        };
+        let mut filters = vec![];
+        if self.deny_syscalls {
+            filters.push(seccomp::install_filter()?);
+        }
+        // with a proxy the namespace already isolates the network
+        if self.effective_deny_net() && !net_namespace {
+            filters.push(seccomp::net_filter()?);
+        }
+        Ok((ruleset, filters))
+    }
+
+    /// Applies Landlock and seccomp to the calling thread and the processes it starts, for code
+    /// mise runs in-process, see [`confine`]. The rest of the process stays unrestricted.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn confine_current_thread(&self) -> eyre::Result<()> {
+        if self.restricts_fs() {
+            landlock::apply_landlock(self)?;
+        }
+        if self.deny_syscalls {
+            seccomp::apply_filter(&seccomp::install_filter()?)?;
+        }
+        Ok(())
+    }
+
+    /// Runs a program under a sandbox with per-host network access or resource limits and
+    /// waits for it.
+    ///
+    /// `mise x` normally execs into the program, but the proxy and the cgroup are managed by the
+    /// mise process, so on Linux the program runs as a child instead.
+    #[cfg(all(not(test), target_os = "linux"))]
+    pub(crate) fn run_supervised(
+        &self,
+        program: &std::ffi::OsStr,
+        args: &[std::ffi::OsString],
+    ) -> eyre::Result<std::process::ExitStatus> {
+        let mut proxy = self.net_proxy()?;
+        let cgroup = self.cgroup();
+        let mut cmd = std::process::Command::new(program);
+        cmd.args(args);
+        self.pre_exec_linux(&mut cmd, proxy.as_ref(), cgroup.as_ref());
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| eyre::eyre!("{}: {e}", program.to_string_lossy()))?;
+        if let Some(proxy) = &mut proxy {
+            proxy.start(child.id())?;
+        }
+        let status = child.wait()?;
+        if !status.success()
+            && let Some(breach) = cgroup.as_ref().and_then(Cgroup::breach)
+        {
+            let program = program.to_string_lossy().to_string();
+            return Err(crate::errors::Error::ResourceLimitExceeded(program, breach).into());
+        }
+        Ok(status)
+    }
+
     #[cfg(all(not(test), target_os = "macos"))]
     async fn apply_macos(
         &self,
@@ -261,18 +439,6 @@ pub(crate) struct SandboxedCommand {
 
 // Public functions for use by cmd.rs (which can't access private submodules)
 
-/// Apply Landlock filesystem restrictions (Linux only).
-#[cfg(target_os = "linux")]
-pub(crate) fn landlock_apply(config: &SandboxConfig) -> eyre::Result<()> {
-    landlock::apply_landlock(config)
-}
-
-/// Apply seccomp network filter (Linux only).
-#[cfg(target_os = "linux")]
-pub(crate) fn seccomp_apply() -> eyre::Result<()> {
-    seccomp::apply_seccomp_net_filter()
-}
-
 /// Generate a macOS Seatbelt profile string (macOS only).
 #[cfg(target_os = "macos")]
 pub(crate) async fn macos_generate_profile(config: &SandboxConfig) -> String {
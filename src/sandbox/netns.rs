@@ -0,0 +1,564 @@
+//! Per-host network filtering for the Linux sandbox.
+//!
+//! The sandboxed process runs in a new user and network namespace whose only interface is
+//! loopback. Before exec it binds a listener on that loopback and hands the socket to mise over
+//! a unix socketpair. mise accepts connections on it and forwards `CONNECT` tunnels and plain
+//! HTTP requests for `allow_net` hosts from its own network namespace. The process finds the
+//! proxy through the standard `HTTP_PROXY`/`HTTPS_PROXY` variables; any other connection has
+//! nowhere to go.
+//!
+//! Everything the child does between fork and exec is a raw syscall: it unshares, sends mise
+//! its pid and waits while a mise thread writes the namespace's uid and gid maps, then brings up
+//! loopback and binds the listener without allocating.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::{Arc, mpsc};
+
+use nix::libc;
+
+/// Port of the proxy listener on the sandbox's private loopback interface
+pub(crate) const PROXY_PORT: u16 = 3128;
+
+/// Largest request head accepted from the sandboxed process
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+/// Environment pointing HTTP clients in the sandbox at the proxy
+pub(crate) fn proxy_env() -> Vec<(&'static str, String)> {
+    let url = format!("http://127.0.0.1:{PROXY_PORT}");
+    let mut env = [
+        "HTTP_PROXY",
+        "HTTPS_PROXY",
+        "ALL_PROXY",
+        "http_proxy",
+        "https_proxy",
+        "all_proxy",
+    ]
+    .map(|key| (key, url.clone()))
+    .to_vec();
+    env.push(("NO_PROXY", String::new()));
+    env.push(("no_proxy", String::new()));
+    env
+}
+
+/// Hosts the proxy forwards to, parsed from `allow_net` entries such as `registry.npmjs.org`,
+/// `*.github.com`, `example.com:443` or `[::1]:8080`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HostAllowlist(Vec<(String, Option<u16>)>);
+
+impl HostAllowlist {
+    pub(crate) fn new(entries: &[String]) -> Self {
+        Self(entries.iter().map(|e| parse_host_port(e)).collect())
+    }
+
+    pub(crate) fn allows(&self, host: &str, port: u16) -> bool {
+        let host = normalize_host(host);
+        self.0.iter().any(|(pattern, allowed_port)| {
+            allowed_port.is_none_or(|p| p == port)
+                && match pattern.strip_prefix("*.") {
+                    Some(domain) => host
+                        .strip_suffix(domain)
+                        .is_some_and(|sub| sub.ends_with('.')),
+                    None => pattern == "*" || *pattern == host,
+                }
+        })
+    }
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_ascii_lowercase()
+}
+
+/// Splits `host[:port]`, leaving bare IPv6 addresses intact
+fn parse_host_port(s: &str) -> (String, Option<u16>) {
+    if let Some(rest) = s.strip_prefix('[')
+        && let Some((host, rest)) = rest.split_once(']')
+    {
+        let port = rest.strip_prefix(':').and_then(|p| p.parse().ok());
+        return (normalize_host(host), port);
+    }
+    match s.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => match port.parse() {
+            Ok(port) => (normalize_host(host), Some(port)),
+            Err(_) => (normalize_host(s), None),
+        },
+        _ => (normalize_host(s), None),
+    }
+}
+
+/// The mise side of a sandbox network namespace
+#[derive(Debug)]
+pub(crate) struct NetProxy {
+    allow: Arc<HostAllowlist>,
+    child: Option<OwnedFd>,
+    listeners: mpsc::Receiver<(i32, OwnedFd)>,
+    listener: Option<OwnedFd>,
+}
+
+impl NetProxy {
+    pub(crate) fn new(allow_net: &[String]) -> std::io::Result<Self> {
+        let mut fds = [0; 2];
+        // packets, so the pid and the listener the child sends arrive as separate messages
+        let ret = unsafe {
+            libc::socketpair(
+                libc::AF_UNIX,
+                libc::SOCK_SEQPACKET | libc::SOCK_CLOEXEC,
+                0,
+                fds.as_mut_ptr(),
+            )
+        };
+        if ret != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let (parent, child) =
+            unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+        let (tx, listeners) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("sandbox-netns".into())
+            .spawn(move || set_up_namespaces(parent, tx))?;
+        Ok(Self {
+            allow: Arc::new(HostAllowlist::new(allow_net)),
+            child: Some(child),
+            listeners,
+            listener: None,
+        })
+    }
+
+    /// Socket the child sets its namespace up over, for [`enter_net_namespace`]
+    pub(crate) fn child_fd(&self) -> RawFd {
+        self.child.as_ref().map_or(-1, |fd| fd.as_raw_fd())
+    }
+
+    /// Takes the listener the spawned child `pid` bound and starts serving it. The child sends
+    /// it before exec, so it is on its way once spawn returns; listeners from earlier attempts
+    /// whose exec failed are dropped.
+    pub(crate) fn start(&mut self, pid: u32) -> std::io::Result<()> {
+        self.child = None;
+        let fd = loop {
+            match self.listeners.recv() {
+                Ok((from, fd)) if from as u32 == pid => break fd,
+                Ok(_) => continue,
+                Err(_) => {
+                    return Err(std::io::Error::other(
+                        "sandboxed process did not send its network listener",
+                    ));
+                }
+            }
+        };
+        self.listener = Some(fd.try_clone()?);
+        let listener = TcpListener::from(fd);
+        let allow = self.allow.clone();
+        std::thread::Builder::new()
+            .name("sandbox-net-proxy".into())
+            .spawn(move || {
+                // ends once `Drop` shuts the listener down
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else { break };
+                    let allow = allow.clone();
+                    std::thread::spawn(move || {
+                        if let Err(err) = handle(stream, &allow) {
+                            trace!("sandbox proxy connection failed: {err}");
+                        }
+                    });
+                }
+            })?;
+        Ok(())
+    }
+}
+
+impl Drop for NetProxy {
+    fn drop(&mut self) {
+        if let Some(listener) = &self.listener {
+            unsafe { libc::shutdown(listener.as_raw_fd(), libc::SHUT_RDWR) };
+        }
+    }
+}
+
+/// Serves the namespace setup of every child spawned with this proxy: writes the id maps of
+/// each pid a child sends once it has unshared, and passes on the listener it sends back.
+/// Ends when the last copy of the child's end of the socket is closed.
+fn set_up_namespaces(sock: OwnedFd, listeners: mpsc::Sender<(i32, OwnedFd)>) {
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    let mut pid = 0;
+    loop {
+        match recv_packet(sock.as_raw_fd()) {
+            Ok(Packet::Pid(child)) => {
+                pid = child;
+                let ack: u8 = match write_id_maps(pid, uid, gid) {
+                    Ok(()) => 0,
+                    Err(err) => {
+                        warn!("failed to set up the sandbox network namespace: {err}");
+                        1
+                    }
+                };
+                if unsafe { libc::send(sock.as_raw_fd(), [ack].as_ptr().cast(), 1, 0) } < 0 {
+                    return;
+                }
+            }
+            Ok(Packet::Listener(fd)) => {
+                let _ = listeners.send((pid, fd));
+            }
+            Ok(Packet::Closed) => return,
+            Err(err) => {
+                trace!("sandbox network namespace setup ended: {err}");
+                return;
+            }
+        }
+    }
+}
+
+/// Maps the child's namespace to the same ids as mise, so file ownership looks unchanged. The
+/// owner of a user namespace may write its maps from the parent namespace.
+fn write_id_maps(pid: i32, uid: libc::uid_t, gid: libc::gid_t) -> std::io::Result<()> {
+    let proc = std::path::PathBuf::from(format!("/proc/{pid}"));
+    std::fs::write(proc.join("setgroups"), "deny")?;
+    std::fs::write(proc.join("uid_map"), format!("{uid} {uid} 1"))?;
+    std::fs::write(proc.join("gid_map"), format!("{gid} {gid} 1"))
+}
+
+/// Moves the calling process into its own user and network namespace, brings up loopback and
+/// sends a listener bound to [`PROXY_PORT`] back to mise.
+///
+/// Runs in the forked child before exec, so it only makes raw syscalls. A failure reaches the
+/// spawner as the errno alone; `EPERM` from `unshare` usually means unprivileged user
+/// namespaces are disabled.
+pub(crate) fn enter_net_namespace(sock: RawFd) -> std::io::Result<()> {
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let pid = unsafe { libc::getpid() }.to_ne_bytes();
+    if unsafe { libc::send(sock, pid.as_ptr().cast(), pid.len(), 0) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    let mut ack = [1u8];
+    let n = unsafe { libc::recv(sock, ack.as_mut_ptr().cast(), 1, 0) };
+    if n < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    if n == 0 || ack[0] != 0 {
+        return Err(std::io::Error::from_raw_os_error(libc::EPERM));
+    }
+    loopback_up()?;
+    let listener = bind_proxy_listener()?;
+    send_fd(sock, listener.as_raw_fd())
+}
+
+fn bind_proxy_listener() -> std::io::Result<OwnedFd> {
+    unsafe {
+        let fd = libc::socket(libc::AF_INET, libc::SOCK_STREAM | libc::SOCK_CLOEXEC, 0);
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let fd = OwnedFd::from_raw_fd(fd);
+        let mut addr: libc::sockaddr_in = std::mem::zeroed();
+        addr.sin_family = libc::AF_INET as libc::sa_family_t;
+        addr.sin_port = PROXY_PORT.to_be();
+        addr.sin_addr.s_addr = u32::from_be_bytes([127, 0, 0, 1]).to_be();
+        if libc::bind(
+            fd.as_raw_fd(),
+            (&addr as *const libc::sockaddr_in).cast(),
+            size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        ) < 0
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+        if libc::listen(fd.as_raw_fd(), libc::SOMAXCONN) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(fd)
+    }
+}
+
+fn loopback_up() -> std::io::Result<()> {
+    unsafe {
+        let sock = libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0);
+        if sock < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let sock = OwnedFd::from_raw_fd(sock);
+        let mut ifr: libc::ifreq = std::mem::zeroed();
+        for (dst, src) in ifr.ifr_name.iter_mut().zip(b"lo\0") {
+            *dst = *src as libc::c_char;
+        }
+        if libc::ioctl(sock.as_raw_fd(), libc::SIOCGIFFLAGS as _, &mut ifr) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        ifr.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short;
+        if libc::ioctl(sock.as_raw_fd(), libc::SIOCSIFFLAGS as _, &ifr) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Control message buffer, aligned for `cmsghdr`
+#[repr(C, align(8))]
+struct CmsgBuf([u8; 64]);
+
+fn send_fd(sock: RawFd, fd: RawFd) -> std::io::Result<()> {
+    unsafe {
+        let mut byte = [0u8; 1];
+        let mut iov = libc::iovec {
+            iov_base: byte.as_mut_ptr().cast(),
+            iov_len: 1,
+        };
+        let mut buf = CmsgBuf([0; 64]);
+        let mut msg: libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = buf.0.as_mut_ptr().cast();
+        msg.msg_controllen = libc::CMSG_SPACE(size_of::<RawFd>() as u32) as _;
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(size_of::<RawFd>() as u32) as _;
+        std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>(), fd);
+        if libc::sendmsg(sock, &msg, 0) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// A message from a child setting up its namespace
+enum Packet {
+    Pid(i32),
+    Listener(OwnedFd),
+    Closed,
+}
+
+fn recv_packet(sock: RawFd) -> std::io::Result<Packet> {
+    unsafe {
+        let mut data = [0u8; 4];
+        let mut iov = libc::iovec {
+            iov_base: data.as_mut_ptr().cast(),
+            iov_len: data.len(),
+        };
+        let mut buf = CmsgBuf([0; 64]);
+        let mut msg: libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = buf.0.as_mut_ptr().cast();
+        msg.msg_controllen = buf.0.len() as _;
+        let n = libc::recvmsg(sock, &mut msg, libc::MSG_CMSG_CLOEXEC);
+        if n < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        if n == 0 {
+            return Ok(Packet::Closed);
+        }
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        if !cmsg.is_null()
+            && (*cmsg).cmsg_level == libc::SOL_SOCKET
+            && (*cmsg).cmsg_type == libc::SCM_RIGHTS
+        {
+            let fd = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>());
+            return Ok(Packet::Listener(OwnedFd::from_raw_fd(fd)));
+        }
+        if n as usize != data.len() {
+            return Err(std::io::Error::other(
+                "unexpected message from sandboxed process",
+            ));
+        }
+        Ok(Packet::Pid(i32::from_ne_bytes(data)))
+    }
+}
+
+/// Serves one proxy connection from the sandbox
+fn handle(client: TcpStream, allow: &HostAllowlist) -> std::io::Result<()> {
+    let mut reader = BufReader::new(client.try_clone()?);
+    let mut client = client;
+    let mut head = vec![];
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let done = line == "\r\n" || line == "\n";
+        head.push(line);
+        if done {
+            break;
+        }
+        if head.iter().map(String::len).sum::<usize>() > MAX_HEAD_BYTES {
+            return respond(&mut client, "431 Request Header Fields Too Large", "");
+        }
+    }
+    let mut parts = head[0].split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return respond(&mut client, "400 Bad Request", "");
+    };
+    let (host, port, request) = if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = parse_host_port(target);
+        (host, port.unwrap_or(443), None)
+    } else {
+        let Ok(url) = url::Url::parse(target) else {
+            return respond(
+                &mut client,
+                "400 Bad Request",
+                "expected an absolute http:// URL",
+            );
+        };
+        if url.scheme() != "http" {
+            return respond(
+                &mut client,
+                "400 Bad Request",
+                "expected an absolute http:// URL",
+            );
+        }
+        let host = normalize_host(url.host_str().unwrap_or_default());
+        let mut path = url.path().to_string();
+        if let Some(query) = url.query() {
+            path = format!("{path}?{query}");
+        }
+        let mut request = format!("{method} {path} {version}\r\n");
+        for line in &head[1..] {
+            let name = line.split(':').next().unwrap_or_default().trim();
+            if !name.eq_ignore_ascii_case("proxy-connection")
+                && !name.eq_ignore_ascii_case("proxy-authorization")
+            {
+                request.push_str(line);
+            }
+        }
+        (host, url.port().unwrap_or(80), Some(request))
+    };
+    if !allow.allows(&host, port) {
+        warn!("sandbox blocked network access to {host}:{port} (not in allow_net)");
+        let body = format!("mise sandbox: {host}:{port} is not in allow_net\n");
+        return respond(&mut client, "403 Forbidden", &body);
+    }
+    let mut upstream = match TcpStream::connect((host.as_str(), port)) {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            let body = format!("mise sandbox: failed to connect to {host}:{port}: {err}\n");
+            return respond(&mut client, "502 Bad Gateway", &body);
+        }
+    };
+    match request {
+        Some(request) => upstream.write_all(request.as_bytes())?,
+        None => client.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")?,
+    }
+    upstream.write_all(reader.buffer())?;
+    let len = reader.buffer().len();
+    reader.consume(len);
+    tunnel(reader.into_inner(), client, upstream)
+}
+
+fn respond(client: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    write!(
+        client,
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Copies bytes both ways until either side closes
+fn tunnel(
+    mut client_read: TcpStream,
+    mut client_write: TcpStream,
+    upstream: TcpStream,
+) -> std::io::Result<()> {
+    let mut upstream_read = upstream.try_clone()?;
+    let mut upstream_write = upstream;
+    let downstream = std::thread::spawn(move || {
+        let _ = std::io::copy(&mut upstream_read, &mut client_write);
+        let _ = client_write.shutdown(Shutdown::Write);
+    });
+    let _ = std::io::copy(&mut client_read, &mut upstream_write);
+    let _ = upstream_write.shutdown(Shutdown::Write);
+    let _ = downstream.join();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn allowlist(entries: &[&str]) -> HostAllowlist {
+        HostAllowlist::new(&entries.iter().map(|e| e.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_allowlist_hosts() {
+        let allow = allowlist(&["registry.npmjs.org", "*.github.com", "Example.com."]);
+        assert!(allow.allows("registry.npmjs.org", 443));
+        assert!(allow.allows("REGISTRY.npmjs.org", 80));
+        assert!(allow.allows("api.github.com", 443));
+        assert!(!allow.allows("github.com", 443));
+        assert!(!allow.allows("evilgithub.com", 443));
+        assert!(allow.allows("example.com", 443));
+        assert!(!allow.allows("npmjs.org", 443));
+    }
+
+    #[test]
+    fn test_allowlist_ports() {
+        let allow = allowlist(&["127.0.0.1:8080", "[::1]:9090", "::1"]);
+        assert!(allow.allows("127.0.0.1", 8080));
+        assert!(!allow.allows("127.0.0.1", 8081));
+        assert!(allow.allows("[::1]", 9090));
+        assert!(allow.allows("::1", 22));
+    }
+
+    #[test]
+    fn test_parse_host_port() {
+        assert_eq!(parse_host_port("example.com"), ("example.com".into(), None));
+        assert_eq!(
+            parse_host_port("example.com:443"),
+            ("example.com".into(), Some(443))
+        );
+        assert_eq!(parse_host_port("[::1]:80"), ("::1".into(), Some(80)));
+        assert_eq!(parse_host_port("fe80::1"), ("fe80::1".into(), None));
+    }
+
+    #[test]
+    fn test_proxy_forwards_only_allowed_hosts() {
+        let upstream = TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream_port = upstream.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in upstream.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let body = request.lines().next().unwrap_or_default().to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        let proxy = TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy_addr = proxy.local_addr().unwrap();
+        let allow = Arc::new(allowlist(&[&format!("127.0.0.1:{upstream_port}")]));
+        std::thread::spawn(move || {
+            for stream in proxy.incoming() {
+                let allow = allow.clone();
+                std::thread::spawn(move || handle(stream.unwrap(), &allow));
+            }
+        });
+
+        let request = |target: &str| {
+            let mut stream = TcpStream::connect(proxy_addr).unwrap();
+            write!(
+                stream,
+                "GET {target} HTTP/1.1\r\nHost: x\r\nProxy-Connection: keep-alive\r\n\r\n"
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let allowed = request(&format!("http://127.0.0.1:{upstream_port}/a?b=1"));
+        assert!(allowed.starts_with("HTTP/1.1 200 OK"), "{allowed}");
+        assert!(allowed.ends_with("GET /a?b=1 HTTP/1.1"), "{allowed}");
+        let denied = request("http://127.0.0.1:1/");
+        assert!(denied.starts_with("HTTP/1.1 403 Forbidden"), "{denied}");
+    }
+}
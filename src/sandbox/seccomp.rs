@@ -10,26 +10,13 @@ fn syscall_number<T: Into<i64>>(number: T) -> i64 {
     number.into()
 }
 
-/// Apply a seccomp-bpf filter that blocks network syscalls.
+/// Compiles a seccomp-bpf filter that blocks network syscalls.
 ///
 /// Blocks AF_INET and AF_INET6 sockets while allowing AF_UNIX (needed by many tools).
-/// Based on the syscall list from OpenAI's codex-linux-sandbox.
-pub(super) fn apply_seccomp_net_filter() -> Result<()> {
-    // Must set PR_SET_NO_NEW_PRIVS before installing seccomp filter
-    let ret = unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
-    if ret != 0 {
-        return Err(eyre!(
-            "failed to set PR_SET_NO_NEW_PRIVS: {}",
-            std::io::Error::last_os_error()
-        ));
-    }
-
-    let arch = std::env::consts::ARCH;
-    let target_arch = match arch {
-        "x86_64" => TargetArch::x86_64,
-        "aarch64" => TargetArch::aarch64,
-        _ => return Err(eyre!("unsupported architecture for seccomp: {arch}")),
-    };
+/// Based on the syscall list from OpenAI's codex-linux-sandbox. Like [`install_filter`], it is
+/// compiled before fork and installed with [`apply_filter`].
+pub(super) fn net_filter() -> Result<BpfProgram> {
+    let target_arch = target_arch()?;
 
     // Block socket() and socketpair() for AF_INET (2) and AF_INET6 (10)
     // Allow AF_UNIX (1) — needed by many tools for IPC
@@ -69,7 +56,69 @@ pub(super) fn apply_seccomp_net_filter() -> Result<()> {
     )?
     .try_into()?;
 
-    seccompiler::apply_filter(&filter).map_err(|e| eyre!("failed to apply seccomp filter: {e}"))?;
+    Ok(filter)
+}
 
-    Ok(())
+/// Syscalls blocked for install scripts and builds: they reach outside the sandboxed process
+/// tree into other processes' memory, the kernel keyrings holding credentials, or the kernel.
+const INSTALL_DENIED_SYSCALLS: &[libc::c_long] = &[
+    libc::SYS_ptrace,
+    libc::SYS_process_vm_readv,
+    libc::SYS_process_vm_writev,
+    libc::SYS_keyctl,
+    libc::SYS_add_key,
+    libc::SYS_request_key,
+    libc::SYS_bpf,
+    libc::SYS_perf_event_open,
+];
+
+/// Compiles the seccomp-bpf filter of the install sandbox.
+///
+/// It is compiled before fork so the child only has to install it, see [`apply_filter`].
+pub(super) fn install_filter() -> Result<BpfProgram> {
+    let rules = INSTALL_DENIED_SYSCALLS
+        .iter()
+        .map(|syscall| (syscall_number(*syscall), vec![]))
+        .collect();
+    let filter = SeccompFilter::new(
+        rules,
+        SeccompAction::Allow,
+        SeccompAction::Errno(libc::EPERM as u32),
+        target_arch()?,
+    )?;
+    Ok(filter.try_into()?)
+}
+
+/// Installs a compiled filter, setting PR_SET_NO_NEW_PRIVS first. Only makes syscalls, so it is
+/// safe to call between fork and exec.
+pub(super) fn apply_filter(filter: &BpfProgram) -> std::io::Result<()> {
+    seccompiler::apply_filter(filter).map_err(|e| match e {
+        seccompiler::Error::Prctl(e) | seccompiler::Error::Seccomp(e) => e,
+        _ => std::io::Error::from_raw_os_error(libc::EINVAL),
+    })
+}
+
+fn target_arch() -> Result<TargetArch> {
+    match std::env::consts::ARCH {
+        "x86_64" => Ok(TargetArch::x86_64),
+        "aarch64" => Ok(TargetArch::aarch64),
+        arch => Err(eyre!("unsupported architecture for seccomp: {arch}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_filter_compiles() {
+        let filter = install_filter().unwrap();
+        assert!(!filter.is_empty());
+    }
+
+    #[test]
+    fn test_net_filter_compiles() {
+        let filter = net_filter().unwrap();
+        assert!(!filter.is_empty());
+    }
 }
@@ -64,6 +64,9 @@ pub(crate) async fn handle_shim() -> Result<()> {
         raw: false,
         no_deps: true, // Skip deps for shims to avoid performance impact
         fresh_env: false,
+        limit_cpu: None,
+        limit_memory: None,
+        limit_pids: None,
         deny_all: false,
         deny_read: false,
         deny_write: false,
@@ -0,0 +1,259 @@
+//! Drift detection for `mise bootstrap watch`.
+//!
+//! Each check computes the same plan as `mise bootstrap plan` and writes a
+//! JSON report of the resources that are no longer in their desired state. A
+//! resource that needs a create, update or remove but didn't at the previous
+//! check is *new* drift; notifications and hooks only fire for new drift, so a
+//! machine that stays drifted isn't reported again on every check.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use eyre::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, Settings};
+use crate::dirs;
+use crate::file;
+use crate::path::PathExt;
+use crate::system::resources::{PlanSummary, ResourceAction, ResourcePlan};
+use crate::system::systemd::{SystemdRequest, SystemdTomlConfig};
+
+/// systemd unit names for `mise bootstrap watch --install-timer`. The timer
+/// and the service it starts need different names because a
+/// `[bootstrap.linux.systemd.units]` name owns both of its unit kinds.
+const SERVICE_NAME: &str = "bootstrap-drift";
+const TIMER_NAME: &str = "bootstrap-watch";
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DriftReport {
+    pub checked_at: String,
+    pub summary: PlanSummary,
+    /// resources that need a change, in plan order
+    pub drifted: Vec<DriftedResource>,
+    /// the subset of `drifted` that was unchanged at the previous check
+    pub new: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DriftedResource {
+    pub resource: String,
+    pub action: ResourceAction,
+    pub current: String,
+    pub desired: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<PathBuf>,
+}
+
+/// the part of an earlier report a check compares against
+#[derive(Debug, Default, Deserialize)]
+struct PreviousReport {
+    #[serde(default)]
+    drifted: Vec<PreviousResource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PreviousResource {
+    resource: String,
+}
+
+pub(crate) fn default_report_path() -> PathBuf {
+    dirs::STATE.join("bootstrap").join("drift.json")
+}
+
+/// Plan every bootstrap resource and write the drift report to `report_path`.
+pub(crate) async fn check(config: &Config, report_path: &Path) -> Result<DriftReport> {
+    let secrets = super::secrets::resolve(config, false)?;
+    let plan = super::resources::plan(config, &secrets).await?;
+    let output = plan.output()?;
+    let previous = read_previous(report_path);
+    let report = build_report(&output.resources, output.summary, previous.as_ref());
+    file::create_dir_all(report_path.parent().unwrap_or(Path::new(".")))?;
+    file::write(report_path, serde_json::to_string_pretty(&report)?)?;
+    Ok(report)
+}
+
+fn read_previous(report_path: &Path) -> Option<HashSet<String>> {
+    let raw = file::read_to_string(report_path).ok()?;
+    match serde_json::from_str::<PreviousReport>(&raw) {
+        Ok(previous) => Some(
+            previous
+                .drifted
+                .into_iter()
+                .map(|resource| resource.resource)
+                .collect(),
+        ),
+        Err(err) => {
+            debug!(
+                "bootstrap watch: ignoring unreadable report {}: {err}",
+                report_path.display_user()
+            );
+            None
+        }
+    }
+}
+
+/// Without a previous report every drifted resource counts as new.
+fn build_report(
+    resources: &[&ResourcePlan],
+    summary: PlanSummary,
+    previous: Option<&HashSet<String>>,
+) -> DriftReport {
+    let drifted = resources
+        .iter()
+        .filter(|resource| {
+            matches!(
+                resource.action,
+                ResourceAction::Create | ResourceAction::Update | ResourceAction::Remove
+            )
+        })
+        .map(|resource| DriftedResource {
+            resource: resource.id.to_string(),
+            action: resource.action,
+            current: resource.current.clone(),
+            desired: resource.desired.clone(),
+            config: resource.origin.as_ref().map(|origin| origin.config.clone()),
+        })
+        .collect::<Vec<_>>();
+    let new = drifted
+        .iter()
+        .filter(|resource| previous.is_none_or(|previous| !previous.contains(&resource.resource)))
+        .map(|resource| resource.resource.clone())
+        .collect();
+    DriftReport {
+        checked_at: jiff::Timestamp::now().to_string(),
+        summary,
+        drifted,
+        new,
+    }
+}
+
+fn describe_new(report: &DriftReport) -> String {
+    const SHOWN: usize = 5;
+    let mut names = report.new.iter().take(SHOWN).cloned().collect::<Vec<_>>();
+    if report.new.len() > SHOWN {
+        names.push(format!("and {} more", report.new.len() - SHOWN));
+    }
+    let noun = if report.new.len() == 1 {
+        "resource"
+    } else {
+        "resources"
+    };
+    format!("{} {noun} drifted: {}", report.new.len(), names.join(", "))
+}
+
+/// Show a desktop notification for new drift. Failures are only warned about
+/// so a missing notifier doesn't stop the watch.
+pub(crate) fn notify(report: &DriftReport) {
+    let body = describe_new(report);
+    let status = if cfg!(target_os = "macos") {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        std::process::Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                "display notification {} with title \"mise bootstrap\"",
+                quote(&body)
+            ))
+            .status()
+    } else if cfg!(windows) {
+        warn!("bootstrap watch: desktop notifications are not supported on Windows");
+        return;
+    } else {
+        std::process::Command::new("notify-send")
+            .args(["--app-name=mise", "mise bootstrap", &body])
+            .status()
+    };
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => warn!("bootstrap watch: desktop notification failed: {status}"),
+        Err(err) => warn!("bootstrap watch: desktop notification failed: {err}"),
+    }
+}
+
+/// Run `hook` with the report path in `MISE_DRIFT_REPORT` and the newly
+/// drifted resources, one per line, in `MISE_DRIFT_RESOURCES`.
+pub(crate) async fn run_hook(hook: &str, report_path: &Path, report: &DriftReport) -> Result<()> {
+    let shell = Settings::get().default_inline_shell()?;
+    let Some((program, shell_args)) = shell.split_first() else {
+        bail!("default inline shell args must not be empty");
+    };
+    info!("$ {hook}");
+    crate::cmd::CmdLineRunner::new(program)
+        .cmd_body_args(shell_args, hook)
+        .env("MISE_DRIFT_REPORT", report_path)
+        .env("MISE_DRIFT_RESOURCES", report.new.join("\n"))
+        .raw(true)
+        .execute_async()
+        .await?;
+    Ok(())
+}
+
+/// The systemd user service and timer that run `mise bootstrap watch --once
+/// <args>` every `interval` from the current directory.
+pub(crate) fn timer_units(args: &[String], interval: Duration) -> Result<Vec<SystemdRequest>> {
+    let exe = std::env::current_exe()?;
+    let exec_start = std::iter::once(exe.to_string_lossy().to_string())
+        .chain(["bootstrap", "watch", "--once"].map(String::from))
+        .chain(args.iter().cloned())
+        .collect::<Vec<_>>();
+    let service = SystemdRequest::from_toml(
+        SERVICE_NAME.to_string(),
+        SystemdTomlConfig {
+            description: Some("mise bootstrap drift check".to_string()),
+            exec_start: Some(shell_words::join(exec_start)),
+            service_type: Some("oneshot".to_string()),
+            working_directory: Some(dirs::CWD.clone().unwrap_or_default().display().to_string()),
+            start: false,
+            wanted_by: Some(vec![]),
+            ..Default::default()
+        },
+    )?;
+    let timer = SystemdRequest::from_toml(
+        TIMER_NAME.to_string(),
+        SystemdTomlConfig {
+            description: Some("Check bootstrap resources for drift".to_string()),
+            on_boot_sec: Some("5min".to_string()),
+            on_unit_active_sec: Some(format!("{}s", interval.as_secs().max(1))),
+            unit: Some(SERVICE_NAME.to_string()),
+            start: true,
+            ..Default::default()
+        },
+    )?;
+    Ok(vec![service, timer])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::resources::ResourceId;
+
+    fn resource(name: &str, action: ResourceAction) -> ResourcePlan {
+        ResourcePlan::new(ResourceId::new("file", name), "current", "desired", action)
+    }
+
+    #[test]
+    fn test_new_drift_is_relative_to_previous_report() {
+        let resources = [
+            resource("/a", ResourceAction::Update),
+            resource("/b", ResourceAction::Noop),
+            resource("/c", ResourceAction::Create),
+            resource("/d", ResourceAction::Unknown),
+        ];
+        let resources = resources.iter().collect::<Vec<_>>();
+
+        let report = build_report(&resources, PlanSummary::default(), None);
+        let drifted = report
+            .drifted
+            .iter()
+            .map(|resource| resource.resource.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(drifted, ["file:/a", "file:/c"]);
+        assert_eq!(report.new, ["file:/a", "file:/c"]);
+
+        let previous = HashSet::from(["file:/a".to_string()]);
+        let report = build_report(&resources, PlanSummary::default(), Some(&previous));
+        assert_eq!(report.new, ["file:/c"]);
+        assert_eq!(describe_new(&report), "1 resource drifted: file:/c");
+    }
+}
@@ -12,13 +12,18 @@
 //! "~/.zshrc/activate" = { block = 'eval "$(mise activate zsh)"' }
 //! "~/.zshrc/aliases" = { source = "snippets/aliases.sh", template = "tera" }
 //! "/etc/hosts/dev" = { line = "127.0.0.1 dev.local" }
+//! "~/.docker/config.json/creds" = { set = { credsStore = "pass" } }
 //! ```
 //!
 //! A `block` is delimited by marker comments in the target file —
 //! `# >>> mise:activate >>>` / `# <<< mise:activate <<<` — which double as
 //! the ownership record: apply replaces only what's between them, so the
 //! design stays stateless like the rest of `[dotfiles]`. A `line` ensures an
-//! exact line exists, appending it if absent.
+//! exact line exists, appending it if absent. A `set` merges keys into a
+//! JSON, TOML or YAML file without reformatting it (see
+//! [`crate::system::structured`]); formats without comments can't hold
+//! markers, so the keys' prior values are recorded in the state directory
+//! instead, and that record is what unapply restores from.
 //!
 //! Entries merge across the config hierarchy as a union keyed by
 //! `(path, id)` — a more local config overrides an edit with the same id,
@@ -28,13 +33,16 @@ use std::path::{Path, PathBuf};
 
 use eyre::{Result, bail};
 use indexmap::IndexMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::config::{Config, ConfigMap};
+use crate::dirs;
 use crate::file;
+use crate::hash::hash_to_str;
 use crate::path::PathExt;
 use crate::system::files::FileState;
 use crate::system::resources::ResourceOrigin;
+use crate::system::structured::{self, Format, display_key};
 use crate::ui::prompt;
 
 /// one `[dotfiles]` edit entry as written in mise.toml. Operations stay loosely typed so configs using operations
@@ -46,7 +54,7 @@ pub(crate) enum EditTomlEntry {
     /// `activate = 'eval "$(mise activate zsh)"'` — inline block content
     Block(String),
     /// `aliases = { source = "...", template = "tera" }` /
-    /// `dev = { line = "..." }`
+    /// `dev = { line = "..." }` / `creds = { set = { ... } }`
     Table(EditTomlTable),
 }
 
@@ -70,6 +78,13 @@ pub(crate) struct EditTomlTable {
     /// when omitted
     #[serde(default)]
     pub comment: Option<String>,
+    /// keys to merge into a JSON, TOML or YAML file
+    #[serde(default)]
+    pub set: Option<toml::Table>,
+    /// format of a `set` target (`json`, `toml` or `yaml`); inferred from
+    /// the file extension when omitted
+    #[serde(default)]
+    pub format: Option<String>,
 }
 
 /// where a block's content comes from
@@ -90,6 +105,11 @@ pub(crate) enum EditOp {
     Line {
         line: String,
     },
+    /// leaf keys and the values they're set to, in config order
+    Set {
+        keys: Vec<(Vec<String>, serde_json::Value)>,
+        format: Format,
+    },
 }
 
 /// one edit, resolved against the config file that declared it
@@ -117,6 +137,7 @@ impl EditRequest {
         match &self.op {
             EditOp::Block { .. } => format!("block:{}", self.id),
             EditOp::Line { .. } => format!("line:{}", self.id),
+            EditOp::Set { .. } => format!("set:{}", self.id),
         }
     }
 
@@ -217,6 +238,7 @@ fn edit_entry_from_toml(path_and_id: &str, value: toml::Value) -> Option<EditTom
                 || (table.contains_key("source") || table.contains_key("content"))
                     && !table.contains_key("block")
                     && !table.contains_key("line")
+                    && !table.contains_key("set")
                     && !table.contains_key("template")
                     && !table.contains_key("comment");
             if is_whole_file_table {
@@ -273,69 +295,97 @@ fn resolve_entry(
             template: None,
             line: None,
             comment: None,
+            set: None,
+            format: None,
         },
         EditTomlEntry::Table(table) => table,
     };
     let is_block = entry.block.is_some() || entry.source.is_some();
-    let op = match (&is_block, &entry.line) {
-        (true, Some(_)) => {
+    let op = if let Some(set) = entry.set {
+        if is_block || entry.line.is_some() {
             bail!(
-                "\"{path_raw}\".{id}: block/source and line are mutually exclusive, ignoring entry"
+                "\"{path_raw}\".{id}: set is mutually exclusive with block/source and line, ignoring entry"
             )
         }
-        (false, None) => {
-            bail!(
-                "\"{path_raw}\".{id}: no recognized operation (block, source, or line), ignoring entry"
-            )
+        let format = match entry.format.as_deref() {
+            Some(name) => Format::from_name(name).ok_or_else(|| {
+                eyre::eyre!(
+                    "\"{path_raw}\".{id}: unknown format '{name}' (expected \"json\", \"toml\", or \"yaml\"), ignoring entry"
+                )
+            })?,
+            None => Format::from_path(&path).ok_or_else(|| {
+                eyre::eyre!(
+                    "\"{path_raw}\".{id}: can't infer the file format from the extension; set format = \"json\", \"toml\", or \"yaml\", ignoring entry"
+                )
+            })?,
+        };
+        let mut keys = vec![];
+        flatten_set(&[], set, &mut keys);
+        if keys.is_empty() {
+            bail!("\"{path_raw}\".{id}: set has no keys, ignoring entry")
         }
-        (true, None) => {
-            let source = match (entry.block, entry.source) {
-                (Some(_), Some(_)) => {
-                    bail!(
-                        "\"{path_raw}\".{id}: block and source are mutually exclusive, ignoring entry"
-                    )
-                }
-                (Some(inline), None) => BlockSource::Inline(inline),
-                (None, Some(src)) => {
-                    let src = file::replace_path(&src);
-                    let src = if src.is_relative() {
-                        base.join(src)
-                    } else {
-                        src
-                    };
-                    origin.source = Some(src.clone());
-                    BlockSource::File(src)
+        EditOp::Set { keys, format }
+    } else {
+        match (&is_block, &entry.line) {
+            (true, Some(_)) => {
+                bail!(
+                    "\"{path_raw}\".{id}: block/source and line are mutually exclusive, ignoring entry"
+                )
+            }
+            (false, None) => {
+                bail!(
+                    "\"{path_raw}\".{id}: no recognized operation (block, source, line, or set), ignoring entry"
+                )
+            }
+            (true, None) => {
+                let source = match (entry.block, entry.source) {
+                    (Some(_), Some(_)) => {
+                        bail!(
+                            "\"{path_raw}\".{id}: block and source are mutually exclusive, ignoring entry"
+                        )
+                    }
+                    (Some(inline), None) => BlockSource::Inline(inline),
+                    (None, Some(src)) => {
+                        let src = file::replace_path(&src);
+                        let src = if src.is_relative() {
+                            base.join(src)
+                        } else {
+                            src
+                        };
+                        origin.source = Some(src.clone());
+                        BlockSource::File(src)
+                    }
+                    (None, None) => unreachable!("is_block"),
+                };
+                let template = match entry.template.as_deref() {
+                    None => false,
+                    Some("tera") => true,
+                    Some(other) => {
+                        bail!(
+                            "\"{path_raw}\".{id}: unknown template engine '{other}' (expected \"tera\"), ignoring entry"
+                        )
+                    }
+                };
+                let comment = entry
+                    .comment
+                    .unwrap_or_else(|| infer_comment(&path).to_string());
+                EditOp::Block {
+                    source,
+                    template,
+                    comment,
                 }
-                (None, None) => unreachable!("is_block"),
-            };
-            let template = match entry.template.as_deref() {
-                None => false,
-                Some("tera") => true,
-                Some(other) => {
+            }
+            (false, Some(line)) => {
+                // a "line" is matched against the file's individual lines, so an
+                // embedded newline could never converge — use a block for
+                // multi-line content
+                if line.contains('\n') {
                     bail!(
-                        "\"{path_raw}\".{id}: unknown template engine '{other}' (expected \"tera\"), ignoring entry"
+                        "\"{path_raw}\".{id}: line may not contain a newline; use a block for multi-line content, ignoring entry"
                     )
                 }
-            };
-            let comment = entry
-                .comment
-                .unwrap_or_else(|| infer_comment(&path).to_string());
-            EditOp::Block {
-                source,
-                template,
-                comment,
-            }
-        }
-        (false, Some(line)) => {
-            // a "line" is matched against the file's individual lines, so an
-            // embedded newline could never converge — use a block for
-            // multi-line content
-            if line.contains('\n') {
-                bail!(
-                    "\"{path_raw}\".{id}: line may not contain a newline; use a block for multi-line content, ignoring entry"
-                )
+                EditOp::Line { line: line.clone() }
             }
-            EditOp::Line { line: line.clone() }
         }
     };
     Ok(EditRequest {
@@ -349,6 +399,23 @@ fn resolve_entry(
     })
 }
 
+/// leaf keys of a `set` table: nested tables name parent keys, anything
+/// else (an empty table included) is a value to set
+fn flatten_set(
+    prefix: &[String],
+    table: toml::Table,
+    out: &mut Vec<(Vec<String>, serde_json::Value)>,
+) {
+    for (key, value) in table {
+        let mut path = prefix.to_vec();
+        path.push(key);
+        match value {
+            toml::Value::Table(table) if !table.is_empty() => flatten_set(&path, table, out),
+            value => out.push((path, structured::toml_to_json(value))),
+        }
+    }
+}
+
 /// comment prefix for marker lines, by file extension; `#` covers most
 /// config and shell files (and extensionless files like `.zshrc`, `hosts`)
 fn infer_comment(path: &Path) -> &'static str {
@@ -530,7 +597,118 @@ fn precheck(req: &EditRequest) -> Result<Option<EditCheck>> {
         } else {
             FileState::Missing
         }))),
+        EditOp::Set { keys, format } => Ok(Some(set_check(&text, keys, *format))),
+    }
+}
+
+/// compare each key of a `set` edit with the file. A file that doesn't
+/// parse is blocked: rewriting it could lose whatever the parser missed
+fn set_check(text: &str, keys: &[(Vec<String>, serde_json::Value)], format: Format) -> EditCheck {
+    let mut present = false;
+    let mut differ = vec![];
+    for (path, value) in keys {
+        match structured::get(format, text, path) {
+            Ok(Some(current)) => {
+                present = true;
+                if &current != value {
+                    differ.push(display_key(path));
+                }
+            }
+            Ok(None) => differ.push(display_key(path)),
+            Err(err) => {
+                return EditCheck::Blocked(format!("failed to parse as {}: {err}", format.name()));
+            }
+        }
     }
+    EditCheck::State(if differ.is_empty() {
+        FileState::Applied
+    } else if present {
+        FileState::Differs(format!("keys differ: {}", differ.join(", ")))
+    } else {
+        FileState::Missing
+    })
+}
+
+/// what a `set` edit changed, recorded when it is applied: with no comment
+/// markers to find in the file, this is its ownership record
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SetRecord {
+    keys: Vec<SetRecordKey>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SetRecordKey {
+    path: Vec<String>,
+    /// the value apply last set
+    applied: serde_json::Value,
+    /// whether the key existed before the first apply, and its value then
+    existed: bool,
+    #[serde(default)]
+    prior: serde_json::Value,
+    /// the outermost parent table the first apply created for this key
+    #[serde(default)]
+    created: Option<Vec<String>>,
+}
+
+fn set_record_path(req: &EditRequest) -> PathBuf {
+    dirs::STATE.join("edits").join(format!(
+        "{}.json",
+        hash_to_str(&(req.path.as_path(), req.id.as_str()))
+    ))
+}
+
+fn load_set_record(req: &EditRequest) -> Result<Option<SetRecord>> {
+    let path = set_record_path(req);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let record = serde_json::from_str(&file::read_to_string(&path)?).map_err(|err| {
+        eyre::eyre!(
+            "edits: failed to read edit state {}: {err}",
+            path.display_user()
+        )
+    })?;
+    Ok(Some(record))
+}
+
+fn save_set_record(req: &EditRequest, record: &SetRecord) -> Result<()> {
+    let path = set_record_path(req);
+    file::create_dir_all(path.parent().expect("edit state parent"))?;
+    file::write(&path, serde_json::to_string_pretty(record)?)
+}
+
+/// Record the values a `set` edit is about to replace. Keys recorded by an
+/// earlier apply keep their original prior value, so unapply restores the
+/// file as it was before mise first touched it.
+fn record_set(req: &EditRequest, text: &str) -> Result<()> {
+    let EditOp::Set { keys, format } = &req.op else {
+        return Ok(());
+    };
+    let mut record = load_set_record(req)?.unwrap_or_default();
+    for (path, value) in keys {
+        if let Some(key) = record.keys.iter_mut().find(|key| &key.path == path) {
+            key.applied = value.clone();
+            continue;
+        }
+        let prior = structured::get(*format, text, path)?;
+        let mut created = None;
+        if prior.is_none() {
+            for len in 1..path.len() {
+                if structured::get(*format, text, &path[..len])?.is_none() {
+                    created = Some(path[..len].to_vec());
+                    break;
+                }
+            }
+        }
+        record.keys.push(SetRecordKey {
+            path: path.clone(),
+            applied: value.clone(),
+            existed: prior.is_some(),
+            prior: prior.unwrap_or_default(),
+            created,
+        });
+    }
+    save_set_record(req, &record)
 }
 
 /// content comparison for a block whose markers exist ([`precheck`]
@@ -771,6 +949,28 @@ pub(crate) fn plan_unapply<'a>(
                 }
             }
             EditOp::Line { .. } => {}
+            EditOp::Set { keys, format } => match load_set_record(req) {
+                Ok(Some(_)) => todo.push(UnapplyPlan { req, text }),
+                Ok(None) => {
+                    let present = keys.iter().any(|(path, _)| {
+                        structured::get(*format, &text, path).is_ok_and(|v| v.is_some())
+                    });
+                    if present && !opts.force {
+                        problems.push(format!(
+                            "  \"{}\" ({}): no record of the keys' prior values; use --force to remove the keys",
+                            req.path_raw,
+                            req.describe_op()
+                        ));
+                    } else if present {
+                        todo.push(UnapplyPlan { req, text });
+                    }
+                }
+                Err(err) => problems.push(format!(
+                    "  \"{}\" ({}): {err}",
+                    req.path_raw,
+                    req.describe_op()
+                )),
+            },
         }
     }
     if !problems.is_empty() {
@@ -863,6 +1063,15 @@ pub(crate) fn execute_unapply(todo: &[UnapplyPlan<'_>], opts: &UnapplyOpts) -> R
 
 fn unapply_one(req: &EditRequest) -> Result<()> {
     let text = file::read_to_string(&req.path)?;
+    if let EditOp::Set { keys, format } = &req.op {
+        let out = unapply_set(req, keys, *format, text)?;
+        file::write(&req.path, out)?;
+        let record = set_record_path(req);
+        if record.exists() {
+            file::remove_file(record)?;
+        }
+        return Ok(());
+    }
     let lines = text_lines(&text);
     let remove = match &req.op {
         EditOp::Block { comment, .. } => {
@@ -885,6 +1094,7 @@ fn unapply_one(req: &EditRequest) -> Result<()> {
                 return Ok(());
             }
         }
+        EditOp::Set { .. } => unreachable!("set edits are restored above"),
     };
     let mut out = text;
     out.replace_range(remove, "");
@@ -892,6 +1102,44 @@ fn unapply_one(req: &EditRequest) -> Result<()> {
     Ok(())
 }
 
+/// Restore the recorded prior value of every key a `set` edit changed.
+/// Keys edited since apply are left alone. Without a record (`--force`),
+/// the configured keys are removed.
+fn unapply_set(
+    req: &EditRequest,
+    keys: &[(Vec<String>, serde_json::Value)],
+    format: Format,
+    mut text: String,
+) -> Result<String> {
+    let Some(record) = load_set_record(req)? else {
+        for (path, _) in keys {
+            text = structured::remove(format, &text, path)?;
+        }
+        return Ok(text);
+    };
+    for key in record.keys.iter().rev() {
+        if structured::get(format, &text, &key.path)?.as_ref() != Some(&key.applied) {
+            debug!(
+                "edits: {} changed since apply, leaving it",
+                display_key(&key.path)
+            );
+            continue;
+        }
+        text = if key.existed {
+            structured::set(format, &text, &key.path, &key.prior)?
+        } else {
+            structured::remove(format, &text, &key.path)?
+        };
+        if let Some(created) = &key.created
+            && structured::get(format, &text, created)?
+                .is_some_and(|v| v.as_object().is_some_and(|t| t.is_empty()))
+        {
+            text = structured::remove(format, &text, created)?;
+        }
+    }
+    Ok(text)
+}
+
 struct TextLine<'a> {
     content: &'a str,
     start: usize,
@@ -943,12 +1191,28 @@ fn apply_one(req: &EditRequest, desired: Option<&str>) -> Result<()> {
         String::new()
     };
     let out = apply_to_string(req, desired, &text)?;
+    // recorded before the write, so a failed write never loses prior values
+    record_set(req, &text)?;
     // file::write truncates in place, preserving the file's permissions
     file::write(&req.path, &out)?;
     Ok(())
 }
 
 fn apply_to_string(req: &EditRequest, desired: Option<&str>, text: &str) -> Result<String> {
+    if let EditOp::Set { keys, format } = &req.op {
+        let mut out = text.to_string();
+        for (path, value) in keys {
+            out = structured::set(*format, &out, path, value).map_err(|err| {
+                eyre::eyre!(
+                    "edits: \"{}\" ({}): failed to set {}: {err}",
+                    req.path_raw,
+                    req.describe_op(),
+                    display_key(path)
+                )
+            })?;
+        }
+        return Ok(out);
+    }
     let mut lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
     match &req.op {
         EditOp::Block { comment, .. } => {
@@ -985,6 +1249,7 @@ fn apply_to_string(req: &EditRequest, desired: Option<&str>, text: &str) -> Resu
                 lines.push(line.clone());
             }
         }
+        EditOp::Set { .. } => unreachable!("set edits are applied above"),
     }
     let mut out = lines.join("\n");
     out.push('\n');
@@ -1004,6 +1269,33 @@ mod tests {
         assert_eq!(infer_comment(Path::new("/a/foo.ini")), ";");
     }
 
+    #[test]
+    fn test_flatten_set() {
+        let set: toml::Table = toml::from_str(
+            r#"
+            "editor.fontSize" = 14
+            net = { git-fetch-with-cli = true, retry = {} }
+            "#,
+        )
+        .unwrap();
+        let mut keys = vec![];
+        flatten_set(&[], set, &mut keys);
+        assert_eq!(
+            keys,
+            vec![
+                (vec!["editor.fontSize".to_string()], serde_json::json!(14)),
+                (
+                    vec!["net".to_string(), "git-fetch-with-cli".to_string()],
+                    serde_json::json!(true)
+                ),
+                (
+                    vec!["net".to_string(), "retry".to_string()],
+                    serde_json::json!({})
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_find_block() {
         let lines = vec![
@@ -13,8 +13,13 @@
 //! "~/.ssh/config" = { source = "ssh.tmpl", mode = "template" }
 //! "~/.config/nvim" = "dotfiles/nvim"                     # symlink the dir itself
 //! "~/.local/bin" = { source = "bin", mode = "symlink-each" }
+//! "~/.netrc" = { source = "secrets/netrc.age" }          # decrypted on apply
 //! ```
 //!
+//! A source ending in `.age` is age-encrypted: it is decrypted in memory with
+//! the configured `age` identities and written only to its target, so it is
+//! copied or rendered, never symlinked.
+//!
 //! Like `[bootstrap.packages]`, entries merge across the config hierarchy
 //! (global -> local, local overrides by target key) and are only ever
 //! applied by an explicit command, never implicitly.
@@ -149,6 +154,42 @@ pub(crate) enum FileState {
     SourceMissing,
 }
 
+/// how a copied or templated target moved away from the content mise last
+/// wrote to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Drift {
+    /// only the source (or the template output) changed — a plain update
+    Source,
+    /// only the target was edited in place
+    Target,
+    /// both changed — applying merges the edits into the new content
+    Both,
+}
+
+impl Drift {
+    fn reason(self) -> &'static str {
+        match self {
+            Self::Source => "source changed",
+            Self::Target => "target edited",
+            Self::Both => "source changed and target edited",
+        }
+    }
+}
+
+/// three-way merge of a target edited in place with its changed source
+enum Merge {
+    Clean(Vec<u8>),
+    /// the content with diff3 conflict markers
+    Conflict(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConflictResolution {
+    Markers,
+    KeepLocal,
+    Overwrite,
+}
+
 /// Aggregate whole-file `[dotfiles]` entries across all loaded config files.
 /// Keys union global -> local; a more local config overrides an entry for the
 /// same target. Malformed entries and unknown modes warn and are skipped.
@@ -374,7 +415,8 @@ fn merge_file_entry(
             }
         })
         .collect::<Vec<_>>();
-    let mode = match mode.as_deref() {
+    let explicit_mode = mode.is_some();
+    let mut mode = match mode.as_deref() {
         None => default_mode(),
         Some(m) => match FileMode::parse(m) {
             Some(m) => m,
@@ -424,6 +466,17 @@ fn merge_file_entry(
             }
         },
     };
+    // a symlink would expose the ciphertext, so encrypted sources default to
+    // a copy of their plaintext
+    if is_encrypted_source(&source) && matches!(mode, FileMode::Symlink | FileMode::SymlinkEach) {
+        if explicit_mode {
+            warn!(
+                "[dotfiles].\"{target_raw}\": encrypted sources can't be symlinked, use mode copy or template, ignoring entry"
+            );
+            return;
+        }
+        mode = FileMode::Copy;
+    }
     let mut origin = origin.clone();
     origin.source = Some(source.clone());
     for req in expand_request(
@@ -451,6 +504,28 @@ pub(crate) fn default_mode() -> FileMode {
     }
 }
 
+/// whether `source` is age-encrypted (see the module docs)
+pub(crate) fn is_encrypted_source(source: &Path) -> bool {
+    source.extension().is_some_and(|ext| ext == "age")
+}
+
+impl FileRequest {
+    pub(crate) fn encrypted(&self) -> bool {
+        self.mode != FileMode::Content && is_encrypted_source(&self.source)
+    }
+}
+
+/// the plaintext of a single-file source; encrypted sources are decrypted in
+/// memory and never written anywhere but the target
+fn read_source(req: &FileRequest) -> Result<Vec<u8>> {
+    if req.encrypted() {
+        crate::agecrypt::decrypt_file(&req.source)
+            .map_err(|err| eyre::eyre!("[dotfiles].\"{}\": {err}", req.target_raw))
+    } else {
+        file::read(&req.source)
+    }
+}
+
 pub(crate) fn dotfiles_root() -> PathBuf {
     file::replace_path(&Settings::get().dotfiles.root)
 }
@@ -780,16 +855,19 @@ fn check_rendered(req: &FileRequest, rendered: Option<&str>) -> Result<FileState
         FileMode::Symlink => check_symlink(&req.source, &req.target),
         FileMode::SymlinkEach => check_symlink_each(req),
         FileMode::Copy if req.source.is_dir() => check_copy_dir(req),
-        FileMode::Copy => check_copy(&req.source, &req.target),
+        FileMode::Copy if req.encrypted() => check_content(&req.target, &read_source(req)?),
+        FileMode::Copy => check_tracked_content(req, &file::read(&req.source)?),
         FileMode::Content => check_content(
             &req.target,
             req.content.as_deref().expect("inline content").as_bytes(),
         ),
         FileMode::Template => {
-            let state = check_content(
-                &req.target,
-                rendered.expect("rendered template content").as_bytes(),
-            )?;
+            let desired = desired_content(req, rendered)?;
+            let state = if req.encrypted() {
+                check_content(&req.target, &desired)?
+            } else {
+                check_tracked_content(req, &desired)?
+            };
             // templates promise the source file's permissions — repair
             // drift (e.g. a later chmod), not just content
             #[cfg(unix)]
@@ -797,7 +875,7 @@ fn check_rendered(req: &FileRequest, rendered: Option<&str>) -> Result<FileState
                 use std::os::unix::fs::PermissionsExt;
                 let mode_of =
                     |p: &Path| -> Result<u32> { Ok(p.metadata()?.permissions().mode() & 0o7777) };
-                if mode_of(&req.source)? != mode_of(&req.target)? {
+                if target_mode(req)? != mode_of(&req.target)? {
                     return Ok(FileState::Differs("permissions differ".into()));
                 }
             }
@@ -936,8 +1014,89 @@ fn check_content(target: &Path, expected: &[u8]) -> Result<FileState> {
     }
 }
 
+/// [`check_content`] for entries that record what they wrote: a content
+/// mismatch names the side that changed
+fn check_tracked_content(req: &FileRequest, desired: &[u8]) -> Result<FileState> {
+    let state = check_content(&req.target, desired)?;
+    if matches!(state, FileState::Differs(_))
+        && let Some(drift) = drift(req, desired)?
+    {
+        return Ok(FileState::Differs(drift.reason().into()));
+    }
+    Ok(state)
+}
+
+/// how a copy or template target moved away from the content mise last wrote
+/// to it. `None` when it matches `desired`, isn't a regular file, or nothing was
+/// recorded yet (targets applied by older versions, or never applied)
+fn drift(req: &FileRequest, desired: &[u8]) -> Result<Option<Drift>> {
+    if req.target.is_symlink() || !req.target.is_file() {
+        return Ok(None);
+    }
+    let Some(base) = load_applied_content(req) else {
+        return Ok(None);
+    };
+    let live = file::read(&req.target)?;
+    Ok(match (live == desired, live == base, desired == base) {
+        (true, _, _) => None,
+        (false, true, _) => Some(Drift::Source),
+        (false, false, true) => Some(Drift::Target),
+        (false, false, false) => Some(Drift::Both),
+    })
+}
+
+/// [`drift`] of an entry that records its content, with the content it
+/// should have
+fn content_drift(req: &FileRequest, rendered: Option<&str>) -> Result<Option<(Drift, Vec<u8>)>> {
+    if !tracks_content(req) {
+        return Ok(None);
+    }
+    let desired = desired_content(req, rendered)?;
+    Ok(drift(req, &desired)?.map(|drift| (drift, desired)))
+}
+
+/// three-way merge of the recorded content (base), the edited target and the
+/// new content
+fn merge_content(req: &FileRequest, desired: &[u8]) -> Result<Merge> {
+    let base = load_applied_content(req).unwrap_or_default();
+    let live = file::read(&req.target)?;
+    Ok(match diffy::merge_bytes(&base, &live, desired) {
+        Ok(merged) => Merge::Clean(merged),
+        Err(conflicted) => Merge::Conflict(conflicted),
+    })
+}
+
+/// the content a copy or template entry writes
+fn desired_content(req: &FileRequest, rendered: Option<&str>) -> Result<Vec<u8>> {
+    Ok(match req.mode {
+        FileMode::Template => rendered
+            .expect("rendered template content")
+            .as_bytes()
+            .to_vec(),
+        _ => read_source(req)?,
+    })
+}
+
+/// permissions of a rendered or decrypted target: the source's, except that
+/// plaintext from an encrypted source is readable only by its owner
+#[cfg(unix)]
+fn target_mode(req: &FileRequest) -> Result<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    if req.encrypted() {
+        Ok(0o600)
+    } else {
+        Ok(req.source.metadata()?.permissions().mode() & 0o7777)
+    }
+}
+
 pub(crate) fn render_template(config: &Config, req: &FileRequest) -> Result<String> {
-    let raw = file::read_to_string(&req.source)?;
+    let raw = String::from_utf8(read_source(req)?).map_err(|_| {
+        eyre::eyre!(
+            "[dotfiles].\"{}\": template {} is not valid UTF-8",
+            req.target_raw,
+            req.source.display_user()
+        )
+    })?;
     let mut tera = crate::tera::get_tera(Some(&req.base));
     let rendered = crate::tera::render_str(
         &mut tera,
@@ -1054,6 +1213,54 @@ fn remove_symlink_each_state(req: &FileRequest) -> Result<()> {
     Ok(())
 }
 
+/// copied and templated files record the content they write, so later edits
+/// to the target can be told apart from source changes and merged with them.
+/// Inline content has no source file to edit instead, so it is overwritten,
+/// and plaintext from encrypted sources must not be recorded.
+fn tracks_content(req: &FileRequest) -> bool {
+    if req.encrypted() {
+        return false;
+    }
+    match req.mode {
+        FileMode::Copy => !req.source.is_dir(),
+        FileMode::Template => true,
+        FileMode::Symlink | FileMode::SymlinkEach | FileMode::Content => false,
+    }
+}
+
+fn applied_content_path(req: &FileRequest) -> PathBuf {
+    dirs::STATE
+        .join("dotfiles")
+        .join("applied")
+        .join(hash_to_str(&req.target.as_path()))
+}
+
+fn load_applied_content(req: &FileRequest) -> Option<Vec<u8>> {
+    file::read(applied_content_path(req)).ok()
+}
+
+fn save_applied_content(req: &FileRequest, content: &[u8]) {
+    let path = applied_content_path(req);
+    let result = (|| -> Result<()> {
+        file::create_dir_all(path.parent().expect("dotfiles state parent"))?;
+        file::write(&path, content)
+    })();
+    if let Err(err) = result {
+        warn!(
+            "files: failed to write dotfiles state {}: {err}",
+            path.display_user()
+        );
+    }
+}
+
+fn remove_applied_content(req: &FileRequest) -> Result<()> {
+    let path = applied_content_path(req);
+    if path.exists() {
+        file::remove_file(path)?;
+    }
+    Ok(())
+}
+
 fn link_points_to(source: &Path, target: &Path) -> bool {
     if !target.is_symlink() {
         return false;
@@ -1202,15 +1409,20 @@ pub(crate) struct ApplyOpts {
 }
 
 pub(crate) struct ApplyPlan<'a> {
-    todo: Vec<(&'a FileRequest, Option<String>)>,
+    todo: Vec<(&'a FileRequest, Option<String>, Option<Merge>)>,
     record_symlink_each: Vec<&'a FileRequest>,
+    /// applied single-file entries whose recorded content is missing or stale
+    record_content: Vec<(&'a FileRequest, Vec<u8>)>,
 }
 
 /// Apply all entries that aren't already in the desired state. Conflicting
 /// targets (a real file where a symlink should go, a directory where a file
 /// should go) are an error unless `force` is set — content updates for
-/// copy/template entries are not conflicts, overwriting is their job. Returns
-/// `false` when the user declines the confirmation prompt.
+/// copy/template entries are not conflicts, overwriting is their job. Local
+/// edits to copy/template targets are kept: they are merged into a changed
+/// source, with conflicts resolved interactively, and left alone otherwise
+/// (`force` overwrites them). Returns `false` when the user declines the
+/// confirmation prompt.
 pub(crate) fn apply(config: &Config, requests: &[FileRequest], opts: &ApplyOpts) -> Result<bool> {
     execute_apply(plan_apply(config, requests, opts)?, opts)
 }
@@ -1221,16 +1433,24 @@ pub(crate) fn execute_apply(plan: ApplyPlan<'_>, opts: &ApplyOpts) -> Result<boo
             for req in plan.record_symlink_each {
                 save_symlink_each_state(req);
             }
+            for (req, content) in &plan.record_content {
+                save_applied_content(req, content);
+            }
         }
         info!("files: all files are applied");
         return Ok(true);
     }
     if opts.dry_run {
-        for (req, rendered) in &plan.todo {
+        for (req, rendered, merge) in &plan.todo {
             // template state wasn't computed (no rendering on dry runs), so
             // the entry may already be converged
             let conditional = req.mode == FileMode::Template && rendered.is_none();
-            let suffix = if conditional { " (if changed)" } else { "" };
+            let suffix = match merge {
+                Some(Merge::Clean(_)) => " (merging local edits)",
+                Some(Merge::Conflict(_)) => " (conflicts with local edits)",
+                None if conditional => " (if changed)",
+                None => "",
+            };
             miseprintln!("{}{suffix}", describe(req)?);
             if opts.verbose && !conditional {
                 print_diff(req, rendered.as_deref())?;
@@ -1242,7 +1462,7 @@ pub(crate) fn execute_apply(plan: ApplyPlan<'_>, opts: &ApplyOpts) -> Result<boo
         let list = plan
             .todo
             .iter()
-            .map(|(r, _)| r.target_raw.clone())
+            .map(|(r, _, _)| r.target_raw.clone())
             .collect::<Vec<_>>()
             .join(", ");
         if !prompt::confirm(format!("files: apply {list}?"))? {
@@ -1250,23 +1470,64 @@ pub(crate) fn execute_apply(plan: ApplyPlan<'_>, opts: &ApplyOpts) -> Result<boo
             return Ok(false);
         }
     }
-    for (req, rendered) in &plan.todo {
-        apply_one(req, rendered.as_deref())?;
+    for (req, rendered, merge) in &plan.todo {
+        let rendered = rendered.as_deref();
+        match merge {
+            None => {
+                apply_one(req, rendered)?;
+                info!("files: {}", describe_applied(req)?);
+            }
+            Some(Merge::Clean(merged)) => {
+                file::write(&req.target, merged)?;
+                info!(
+                    "files: merged changes from {} into {}",
+                    req.source.display_user(),
+                    req.target.display_user()
+                );
+            }
+            Some(Merge::Conflict(conflicted)) => match resolve_conflict(req)? {
+                ConflictResolution::Markers => {
+                    file::write(&req.target, conflicted)?;
+                    warn!(
+                        "files: wrote conflict markers to {}, resolve them by hand",
+                        req.target.display_user()
+                    );
+                }
+                ConflictResolution::KeepLocal => {
+                    info!("files: kept local edits to {}", req.target.display_user());
+                }
+                ConflictResolution::Overwrite => {
+                    apply_one(req, rendered)?;
+                    info!("files: {}", describe_applied(req)?);
+                }
+            },
+        }
         if req.mode == FileMode::SymlinkEach {
             save_symlink_each_state(req);
         }
-        info!("files: {}", describe_applied(req)?);
+        // the new content becomes the base of the next merge, whichever
+        // side of a conflict was kept
+        if tracks_content(req) {
+            save_applied_content(req, &desired_content(req, rendered)?);
+        }
     }
     for req in plan.record_symlink_each {
-        if !plan.todo.iter().any(|(todo, _)| std::ptr::eq(*todo, req)) {
+        if !plan
+            .todo
+            .iter()
+            .any(|(todo, _, _)| std::ptr::eq(*todo, req))
+        {
             save_symlink_each_state(req);
         }
     }
+    for (req, content) in &plan.record_content {
+        save_applied_content(req, content);
+    }
     info!(
         "files: applied {}",
         plan.todo
             .iter()
-            .map(|(r, _)| r.target_raw.clone())
+            .map(|(r, _, _)| r.target_raw.clone())
             .collect::<Vec<_>>()
             .join(", ")
     );
@@ -1283,11 +1544,13 @@ pub(crate) fn plan_apply<'a>(
     validate_composed_file_footprints(requests)?;
     // pre-rendered template output rides along so it's written as compared,
     // and exec() in templates runs once per apply
-    let mut todo: Vec<(&FileRequest, Option<String>)> = vec![];
+    let mut todo: Vec<(&FileRequest, Option<String>, Option<Merge>)> = vec![];
     let mut missing_sources = vec![];
     let mut broken = vec![];
     let mut conflicts = vec![];
+    let mut merge_conflicts = vec![];
     let mut record_symlink_each = vec![];
+    let mut record_content = vec![];
     for req in requests {
         // report every problem in one pass instead of fix-and-retry — a
         // render or check failure on one entry must not hide the rest
@@ -1303,7 +1566,7 @@ pub(crate) fn plan_apply<'a>(
         // so list template entries without computing their current state
         if opts.dry_run && req.mode == FileMode::Template {
             conflicts.extend(find_conflicts(req)?);
-            todo.push((req, None));
+            todo.push((req, None, None));
             continue;
         }
         let rendered = match req.mode {
@@ -1322,6 +1585,12 @@ pub(crate) fn plan_apply<'a>(
                 if req.mode == FileMode::SymlinkEach && symlink_each_state_needs_update(req)? {
                     record_symlink_each.push(req);
                 }
+                if tracks_content(req) {
+                    let content = desired_content(req, rendered.as_deref())?;
+                    if load_applied_content(req).as_ref() != Some(&content) {
+                        record_content.push((req, content));
+                    }
+                }
                 continue;
             }
             Ok(_) => {}
@@ -1330,8 +1599,41 @@ pub(crate) fn plan_apply<'a>(
                 continue;
             }
         }
+        let drifted = if opts.force {
+            Ok(None)
+        } else {
+            content_drift(req, rendered.as_deref())
+        };
+        let merge = match drifted {
+            // only the target changed: there is nothing to bring in
+            Ok(Some((Drift::Target, _))) => {
+                info!(
+                    "files: keeping local edits to {} ({} to overwrite them)",
+                    req.target_raw, opts.force_hint
+                );
+                continue;
+            }
+            Ok(Some((Drift::Both, desired))) => match merge_content(req, &desired) {
+                Ok(merge) => Some(merge),
+                Err(err) => {
+                    broken.push(format!("  [dotfiles].\"{}\": {err}", req.target_raw));
+                    continue;
+                }
+            },
+            Ok(_) => None,
+            Err(err) => {
+                broken.push(format!("  [dotfiles].\"{}\": {err}", req.target_raw));
+                continue;
+            }
+        };
+        if matches!(merge, Some(Merge::Conflict(_)))
+            && !opts.dry_run
+            && !console::user_attended_stderr()
+        {
+            merge_conflicts.push(req.target.clone());
+        }
         conflicts.extend(find_conflicts(req)?);
-        todo.push((req, rendered));
+        todo.push((req, rendered, merge));
     }
     let mut problems = vec![];
     if !missing_sources.is_empty() {
@@ -1354,12 +1656,24 @@ pub(crate) fn plan_apply<'a>(
                 .join("\n")
         ));
     }
+    if !merge_conflicts.is_empty() {
+        problems.push(format!(
+            "local edits conflict with source changes (apply interactively to merge, or {} to overwrite them):\n{}",
+            opts.force_hint,
+            merge_conflicts
+                .iter()
+                .map(|p| format!("  {}", p.display_user()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
     if !problems.is_empty() {
         bail!("files: {}", problems.join("\nfiles: "));
     }
     Ok(ApplyPlan {
         todo,
         record_symlink_each,
+        record_content,
     })
 }
 
@@ -1525,6 +1839,9 @@ pub(crate) fn execute_unapply(plans: &[UnapplyPlan<'_>], opts: &UnapplyOpts) ->
         if plan.clear_symlink_each_state {
             remove_symlink_each_state(plan.req)?;
         }
+        if tracks_content(plan.req) {
+            remove_applied_content(plan.req)?;
+        }
     }
     info!(
         "files: unapplied {}",
@@ -1677,6 +1994,10 @@ fn plan_single_file(
     }
     if opts.force && (req.target.exists() || req.target.is_symlink()) {
         paths.insert(req.target.clone(), ());
+    } else if req.encrypted() && req.source.is_file() {
+        if req.target.exists() || req.target.is_symlink() {
+            plan_expected_content(&read_source(req)?, &req.target, false, paths)?;
+        }
     } else if req.source.exists() {
         plan_regular_file(&req.source, &req.target, false, paths)?;
     }
@@ -1873,6 +2194,31 @@ fn find_conflicts(req: &FileRequest) -> Result<Vec<PathBuf>> {
     Ok(out)
 }
 
+fn resolve_conflict(req: &FileRequest) -> Result<ConflictResolution> {
+    let message = format!(
+        "files: {} was edited and conflicts with changes to {}",
+        req.target.display_user(),
+        req.source.display_user()
+    );
+    let choice = prompt::select(
+        message,
+        vec![
+            (
+                ConflictResolution::Markers,
+                "merge, marking conflicts to resolve by hand",
+            ),
+            (ConflictResolution::KeepLocal, "keep my edits"),
+            (ConflictResolution::Overwrite, "overwrite my edits"),
+        ],
+    )?;
+    choice.ok_or_else(|| {
+        eyre::eyre!(
+            "files: {} has conflicting local edits",
+            req.target.display_user()
+        )
+    })
+}
+
 fn describe(req: &FileRequest) -> Result<String> {
     let src = req.source.display_user();
     let tgt = req.target.display_user();
@@ -1890,6 +2236,7 @@ fn describe(req: &FileRequest) -> Result<String> {
             )
         }
         FileMode::Copy if req.source.is_dir() => format!("cp -r {src} {tgt}"),
+        FileMode::Copy if req.encrypted() => format!("decrypt {src} -> {tgt}"),
         FileMode::Copy => format!("cp {src} {tgt}"),
         FileMode::Template => format!("render {src} -> {tgt}"),
         FileMode::Content => format!("write inline content to {tgt}"),
@@ -1905,6 +2252,7 @@ fn describe_applied(req: &FileRequest) -> Result<String> {
             "created {} symlink(s) from {src} in {tgt}",
             walk_source_files(req)?.len()
         ),
+        FileMode::Copy if req.encrypted() => format!("decrypted {src} to {tgt}"),
         FileMode::Copy => format!("copied {src} to {tgt}"),
         FileMode::Template => format!("rendered {src} to {tgt}"),
         FileMode::Content => format!("wrote inline content to {tgt}"),
@@ -1945,7 +2293,7 @@ fn print_diff(req: &FileRequest, rendered: Option<&str>) -> Result<()> {
         FileMode::Copy | FileMode::Template if req.source.is_file() => {
             let desired = match req.mode {
                 FileMode::Template => rendered.unwrap_or_default().as_bytes().to_vec(),
-                _ => file::read(&req.source)?,
+                _ => read_source(req)?,
             };
             let current = if req.target.exists() && req.target.is_file() {
                 file::read(&req.target)?
@@ -2037,6 +2385,10 @@ fn apply_one(req: &FileRequest, rendered: Option<&str>) -> Result<()> {
                     }
                     file::copy(&source, &target)?;
                 }
+            } else if req.encrypted() {
+                let plaintext = read_source(req)?;
+                remove_existing(&req.target)?;
+                write_private(&req.target, &plaintext)?;
             } else {
                 remove_existing(&req.target)?;
                 file::copy(&req.source, &req.target)?;
@@ -2045,9 +2397,13 @@ fn apply_one(req: &FileRequest, rendered: Option<&str>) -> Result<()> {
         FileMode::Template => {
             let rendered = rendered.expect("rendered template content");
             remove_existing(&req.target)?;
-            file::write(&req.target, rendered)?;
-            #[cfg(unix)]
-            std::fs::set_permissions(&req.target, req.source.metadata()?.permissions())?;
+            if req.encrypted() {
+                write_private(&req.target, rendered.as_bytes())?;
+            } else {
+                file::write(&req.target, rendered)?;
+                #[cfg(unix)]
+                std::fs::set_permissions(&req.target, req.source.metadata()?.permissions())?;
+            }
         }
         FileMode::Content => {
             remove_existing(&req.target)?;
@@ -2062,6 +2418,24 @@ fn apply_one(req: &FileRequest, rendered: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// create `path` readable only by its owner before any content is written, so
+/// decrypted plaintext is never briefly world-readable
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    use std::io::Write;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut f = options
+        .open(path)
+        .map_err(|err| eyre::eyre!("failed to create {}: {err}", path.display_user()))?;
+    f.write_all(content)?;
+    Ok(())
+}
+
 /// delete this entry's leftover links (see [`stale_links`]) and any directory
 /// they emptied out. A directory only goes when the links we just removed were
 /// all that was in it and the entry has no source file left that needs it, so
@@ -2626,4 +3000,65 @@ mod tests {
         ));
         Ok(())
     }
+
+    #[test]
+    fn copy_drift_names_the_side_that_changed() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let source = dir.path().join("gitconfig");
+        let target = dir.path().join(".gitconfig");
+        let req = link_req(&source, &target, FileMode::Copy);
+        file::write(&source, "a\nb\n")?;
+        file::write(&target, "a\nb\n")?;
+        save_applied_content(&req, b"a\nb\n");
+        assert_eq!(check_rendered(&req, None)?, FileState::Applied);
+
+        file::write(&source, "a\nb\nc\n")?;
+        assert_eq!(
+            check_rendered(&req, None)?,
+            FileState::Differs("source changed".into())
+        );
+        file::write(&source, "a\nb\n")?;
+        file::write(&target, "A\nb\n")?;
+        assert_eq!(
+            check_rendered(&req, None)?,
+            FileState::Differs("target edited".into())
+        );
+        file::write(&source, "a\nb\nc\n")?;
+        assert_eq!(
+            check_rendered(&req, None)?,
+            FileState::Differs("source changed and target edited".into())
+        );
+
+        // nothing recorded: a plain content mismatch
+        remove_applied_content(&req)?;
+        assert_eq!(
+            check_rendered(&req, None)?,
+            FileState::Differs("content differs".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn edits_merge_with_source_changes() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let source = dir.path().join("gitconfig");
+        let target = dir.path().join(".gitconfig");
+        let req = link_req(&source, &target, FileMode::Copy);
+        save_applied_content(&req, b"one\ntwo\nthree\n");
+        file::write(&target, "ONE\ntwo\nthree\n")?;
+
+        let Merge::Clean(merged) = merge_content(&req, b"one\ntwo\nthree\nfour\n")? else {
+            panic!("expected a clean merge");
+        };
+        assert_eq!(merged, b"ONE\ntwo\nthree\nfour\n");
+
+        let Merge::Conflict(conflicted) = merge_content(&req, b"uno\ntwo\nthree\n")? else {
+            panic!("expected a conflict");
+        };
+        let conflicted = String::from_utf8(conflicted)?;
+        assert!(conflicted.contains("<<<<<<<"), "{conflicted}");
+        assert!(conflicted.contains("ONE"), "{conflicted}");
+        assert!(conflicted.contains("uno"), "{conflicted}");
+        Ok(())
+    }
 }
@@ -285,6 +285,15 @@ struct FirewallInspection {
     active: bool,
     reason: Option<String>,
     current_rules: Option<Vec<FirewallRule>>,
+    /// the saved mise-managed firewall, when its live rules still match
+    #[serde(default)]
+    previous: Option<Box<FirewallRequest>>,
+}
+
+/// The firewall a bootstrap apply replaces, kept in the rollback journal.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct FirewallPreImage {
+    request: FirewallRequest,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -691,6 +700,38 @@ impl FirewallRule {
     }
 }
 
+impl FirewallRequest {
+    /// The firewall in place before this request is applied: the saved
+    /// mise-managed firewall, or this request marked absent when mise didn't
+    /// manage one. `None` when the current firewall couldn't be verified.
+    pub(crate) fn pre_image(&self) -> Option<FirewallPreImage> {
+        let inspection = self.inspection.as_ref()?;
+        let request = if let Some(previous) = &inspection.previous {
+            FirewallRequest {
+                ssh_connection: self.ssh_connection.clone(),
+                inspection: None,
+                ..(**previous).clone()
+            }
+        } else if !inspection.managed && inspection.reason.is_none() {
+            FirewallRequest {
+                state: FirewallState::Absent,
+                inspection: None,
+                ..self.clone()
+            }
+        } else {
+            return None;
+        };
+        Some(FirewallPreImage { request })
+    }
+}
+
+/// Put the firewall back into a journaled pre-image.
+pub(crate) fn restore(pre_image: &FirewallPreImage, dry_run: bool, yes: bool) -> Result<()> {
+    let mut request = pre_image.request.clone();
+    inspect_request(&mut request)?;
+    apply(&request, dry_run, yes)
+}
+
 pub(crate) fn apply(request: &FirewallRequest, dry_run: bool, yes: bool) -> Result<()> {
     let plan = request.plans();
     let changes = plan
@@ -795,6 +836,7 @@ fn inspect_privileged(request: &FirewallRequest) -> FirewallInspection {
                 active: false,
                 reason: Some(error.to_string()),
                 current_rules: None,
+                previous: None,
             };
         }
     };
@@ -806,6 +848,7 @@ fn inspect_privileged(request: &FirewallRequest) -> FirewallInspection {
             active: false,
             reason: Some(error.to_string()),
             current_rules: None,
+            previous: None,
         };
     }
     let effective = effective_request(request, state.as_ref());
@@ -817,6 +860,7 @@ fn inspect_privileged(request: &FirewallRequest) -> FirewallInspection {
             active: false,
             reason: Some(error.to_string()),
             current_rules: None,
+            previous: None,
         };
     }
     let expected_digest = request_digest(&effective, backend).unwrap_or_default();
@@ -866,6 +910,9 @@ fn inspect_privileged(request: &FirewallRequest) -> FirewallInspection {
         } else {
             None
         },
+        previous: state
+            .filter(|_| saved_rules_verified)
+            .map(|state| Box::new(state.request)),
     }
 }
 
@@ -2388,6 +2435,7 @@ mod tests {
             active: false,
             reason: None,
             current_rules: Some(vec![]),
+            previous: None,
         });
         let plans = request.plans();
         assert!(plans.iter().all(|plan| plan.action == ResourceAction::Noop));
@@ -2405,6 +2453,7 @@ mod tests {
             active: true,
             reason: None,
             current_rules: None,
+            previous: None,
         });
 
         let plans = request.plans();
@@ -2424,6 +2473,7 @@ mod tests {
             active: false,
             reason: Some("firewall backend unavailable".to_string()),
             current_rules: None,
+            previous: None,
         });
 
         let plans = request.plans();
@@ -2446,6 +2496,7 @@ mod tests {
             active: true,
             reason: None,
             current_rules: Some(current_rules),
+            previous: None,
         });
 
         let plans = request.plans();
@@ -1,5 +1,5 @@
 use eyre::{Result, bail};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::config::Config;
 use crate::system::resources::{ResourceAction, ResourceId, ResourcePlan};
@@ -13,6 +13,9 @@ pub(crate) struct FirewallTomlConfig {
 #[derive(Clone, Debug)]
 pub(crate) struct FirewallRequest;
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct FirewallPreImage;
+
 pub(crate) fn prepare_request_from_config(config: &Config) -> Result<Option<FirewallRequest>> {
     reject_configured(config)
 }
@@ -34,6 +37,10 @@ pub(crate) fn inspect_request(_request: &mut FirewallRequest) -> Result<()> {
 }
 
 impl FirewallRequest {
+    pub(crate) fn pre_image(&self) -> Option<FirewallPreImage> {
+        None
+    }
+
     pub(crate) fn plans(&self) -> Vec<ResourcePlan> {
         vec![ResourcePlan::new(
             ResourceId::new("firewall", "linux"),
@@ -44,6 +51,10 @@ impl FirewallRequest {
     }
 }
 
+pub(crate) fn restore(_pre_image: &FirewallPreImage, _dry_run: bool, _yes: bool) -> Result<()> {
+    bail!("bootstrap firewall management is only supported on Linux")
+}
+
 pub(crate) fn apply(_request: &FirewallRequest, _dry_run: bool, _yes: bool) -> Result<()> {
     bail!("bootstrap firewall management is only supported on Linux")
 }
@@ -0,0 +1,646 @@
+//! Linux host settings for `[bootstrap.linux.sysctl]`,
+//! `[bootstrap.linux.kernel_modules]` and `[bootstrap.linux.environment]`.
+//!
+//! Kernel parameters and modules are persisted in mise-owned drop-ins under
+//! `/etc/sysctl.d/` and `/etc/modules-load.d/` and applied live with
+//! `sysctl -p` and `modprobe`. Environment entries are merged key by key into
+//! `/etc/environment` and take effect on the next login. File writes go
+//! through the same privileged plan as `[bootstrap.files]`.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use eyre::{Result, bail};
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::system::managed_files::{PrivilegedAction, PrivilegedPlan};
+use crate::system::resources::{ResourceAction, ResourceId, ResourcePlan};
+
+const SYSCTL_DROP_IN: &str = "/etc/sysctl.d/60-mise.conf";
+const MODULES_DROP_IN: &str = "/etc/modules-load.d/mise.conf";
+const ENVIRONMENT_FILE: &str = "/etc/environment";
+const DROP_IN_HEADER: &str = "# Managed by mise from";
+
+/// A `[bootstrap.linux.sysctl]` value. Integers cover most keys; strings
+/// carry multi-value parameters such as `net.ipv4.tcp_rmem`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum SysctlTomlValue {
+    Integer(i64),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SysctlRequest {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KernelModuleRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EnvironmentRequest {
+    pub key: String,
+    pub value: String,
+}
+
+/// The live value of one kernel parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LiveValue {
+    Value(String),
+    Missing,
+    Unreadable,
+}
+
+impl SysctlRequest {
+    pub(crate) fn from_toml(key: String, value: SysctlTomlValue) -> Result<Self> {
+        if !valid_sysctl_key(&key) {
+            bail!(
+                "sysctl key '{key}' must be a dotted or slash-separated parameter name, \
+                 e.g. vm.max_map_count"
+            );
+        }
+        let value = match value {
+            SysctlTomlValue::Integer(value) => value.to_string(),
+            SysctlTomlValue::String(value) => normalize_sysctl_value(&value),
+        };
+        if value.is_empty() {
+            bail!("sysctl key '{key}' must have a non-empty value");
+        }
+        Ok(Self { key, value })
+    }
+
+    fn proc_path(&self) -> PathBuf {
+        let separator = if self.key.contains('/') { '/' } else { '.' };
+        let mut path = host_path("/proc/sys");
+        for segment in self.key.split(separator) {
+            path.push(segment);
+        }
+        path
+    }
+
+    fn live_value(&self) -> LiveValue {
+        match std::fs::read_to_string(self.proc_path()) {
+            Ok(value) => LiveValue::Value(normalize_sysctl_value(&value)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => LiveValue::Missing,
+            Err(_) => LiveValue::Unreadable,
+        }
+    }
+
+    fn plan(&self, persisted: &IndexMap<String, String>, modules_pending: bool) -> ResourcePlan {
+        let id = ResourceId::new("sysctl", &self.key);
+        if !cfg!(target_os = "linux") {
+            return unsupported(id, &self.value);
+        }
+        let persisted = persisted.get(&self.key);
+        let is_persisted = persisted == Some(&self.value);
+        let live = self.live_value();
+        let mut current = match &live {
+            LiveValue::Value(value) => value.clone(),
+            LiveValue::Missing => "unavailable".to_string(),
+            LiveValue::Unreadable => "unreadable".to_string(),
+        };
+        if !is_persisted {
+            current.push_str(" (not persisted)");
+        }
+        let action = match live {
+            LiveValue::Value(value) if value == self.value && is_persisted => ResourceAction::Noop,
+            // bridge and netfilter keys only appear once their module loads
+            LiveValue::Missing if !modules_pending => ResourceAction::Unknown,
+            LiveValue::Unreadable if is_persisted => ResourceAction::Unknown,
+            _ if persisted.is_none() => ResourceAction::Create,
+            _ => ResourceAction::Update,
+        };
+        ResourcePlan::new(id, current, self.value.clone(), action)
+    }
+}
+
+impl KernelModuleRequest {
+    pub(crate) fn from_toml(name: String) -> Result<Self> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            bail!("kernel module '{name}' must contain only letters, numbers, '_' or '-'");
+        }
+        Ok(Self { name })
+    }
+
+    /// modprobe treats `-` and `_` as equivalent; /proc/modules uses `_`.
+    fn canonical_name(&self) -> String {
+        self.name.replace('-', "_")
+    }
+
+    fn is_loaded(&self, loaded: &HashSet<String>) -> bool {
+        let name = self.canonical_name();
+        // built-in modules don't appear in /proc/modules
+        loaded.contains(&name) || host_path("/sys/module").join(&name).exists()
+    }
+
+    fn plan(&self, persisted: &HashSet<String>, loaded: &HashSet<String>) -> ResourcePlan {
+        let id = ResourceId::new("kernel-module", &self.name);
+        if !cfg!(target_os = "linux") {
+            return unsupported(id, "loaded");
+        }
+        let is_persisted = persisted.contains(&self.canonical_name());
+        let is_loaded = self.is_loaded(loaded);
+        let mut current = if is_loaded { "loaded" } else { "not loaded" }.to_string();
+        if !is_persisted {
+            current.push_str(" (not persisted)");
+        }
+        let action = match (is_loaded, is_persisted) {
+            (true, true) => ResourceAction::Noop,
+            (_, false) => ResourceAction::Create,
+            (false, true) => ResourceAction::Update,
+        };
+        ResourcePlan::new(id, current, "loaded", action)
+    }
+}
+
+impl EnvironmentRequest {
+    pub(crate) fn from_toml(key: String, value: String) -> Result<Self> {
+        let mut chars = key.chars();
+        if !chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            bail!("environment variable '{key}' must be a valid variable name");
+        }
+        if value.contains(['\n', '\r', '"']) {
+            bail!("environment variable '{key}' must not contain newlines or double quotes");
+        }
+        Ok(Self { key, value })
+    }
+
+    fn render(&self) -> String {
+        if self
+            .value
+            .contains(|c: char| c.is_whitespace() || c == '#' || c == '\'')
+        {
+            format!("{}=\"{}\"", self.key, self.value)
+        } else {
+            format!("{}={}", self.key, self.value)
+        }
+    }
+
+    fn plan(&self, current: &IndexMap<String, String>) -> ResourcePlan {
+        let id = ResourceId::new("environment", &self.key);
+        if !cfg!(target_os = "linux") {
+            return unsupported(id, &self.value);
+        }
+        match current.get(&self.key) {
+            None => ResourcePlan::new(id, "unset", self.value.clone(), ResourceAction::Create),
+            Some(value) if value == &self.value => {
+                ResourcePlan::new(id, value.clone(), self.value.clone(), ResourceAction::Noop)
+            }
+            Some(value) => ResourcePlan::new(
+                id,
+                value.clone(),
+                self.value.clone(),
+                ResourceAction::Update,
+            ),
+        }
+    }
+}
+
+pub(super) fn unsupported(id: ResourceId, desired: &str) -> ResourcePlan {
+    ResourcePlan::new(
+        id,
+        "unsupported platform",
+        desired.to_string(),
+        ResourceAction::Unknown,
+    )
+}
+
+/// Whether any configured module still has to be loaded. Parameters provided
+/// by those modules can't be read until then.
+pub(crate) fn kernel_modules_pending(requests: &[KernelModuleRequest]) -> bool {
+    let loaded = loaded_modules();
+    requests.iter().any(|request| !request.is_loaded(&loaded))
+}
+
+pub(crate) fn sysctl_plans(requests: &[SysctlRequest], modules_pending: bool) -> Vec<ResourcePlan> {
+    let persisted = parse_sysctl_conf(&read_or_empty(&host_path(SYSCTL_DROP_IN)));
+    requests
+        .iter()
+        .map(|request| request.plan(&persisted, modules_pending))
+        .collect()
+}
+
+pub(crate) fn kernel_module_plans(requests: &[KernelModuleRequest]) -> Vec<ResourcePlan> {
+    let persisted = parse_modules_conf(&read_or_empty(&host_path(MODULES_DROP_IN)));
+    let loaded = loaded_modules();
+    requests
+        .iter()
+        .map(|request| request.plan(&persisted, &loaded))
+        .collect()
+}
+
+pub(crate) fn environment_plans(requests: &[EnvironmentRequest]) -> Vec<ResourcePlan> {
+    let current = parse_environment(&read_or_empty(&host_path(ENVIRONMENT_FILE)));
+    requests
+        .iter()
+        .map(|request| request.plan(&current))
+        .collect()
+}
+
+/// Write the sysctl drop-in when it differs and load it with `sysctl -p`
+/// when any live value differs.
+pub(crate) fn apply_sysctl(
+    requests: &[SysctlRequest],
+    modules_pending: bool,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    if requests.is_empty() {
+        return Ok(());
+    }
+    ensure_linux("sysctl")?;
+    let path = host_path(SYSCTL_DROP_IN);
+    let plans = sysctl_plans(requests, modules_pending);
+    let mut actions = vec![];
+    write_if_changed(&mut actions, &path, render_sysctl_conf(requests));
+    let mut commands = vec![];
+    if plans.iter().any(|plan| plan.action != ResourceAction::Noop) {
+        commands.push((
+            "sysctl".to_string(),
+            vec!["-p".to_string(), path.to_string_lossy().to_string()],
+        ));
+    }
+    converge("sysctl", &plans, actions, commands, dry_run, yes)
+}
+
+/// Write the modules-load.d drop-in when it differs and `modprobe` every
+/// module that isn't loaded yet.
+pub(crate) fn apply_kernel_modules(
+    requests: &[KernelModuleRequest],
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    if requests.is_empty() {
+        return Ok(());
+    }
+    ensure_linux("kernel modules")?;
+    let plans = kernel_module_plans(requests);
+    let mut actions = vec![];
+    write_if_changed(
+        &mut actions,
+        &host_path(MODULES_DROP_IN),
+        render_modules_conf(requests),
+    );
+    let loaded = loaded_modules();
+    let commands = requests
+        .iter()
+        .filter(|request| !request.is_loaded(&loaded))
+        .map(|request| ("modprobe".to_string(), vec![request.name.clone()]))
+        .collect();
+    converge("kernel modules", &plans, actions, commands, dry_run, yes)
+}
+
+/// Rewrite `/etc/environment` with the configured keys, leaving every other
+/// line in place.
+pub(crate) fn apply_environment(
+    requests: &[EnvironmentRequest],
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    if requests.is_empty() {
+        return Ok(());
+    }
+    ensure_linux("/etc/environment")?;
+    let path = host_path(ENVIRONMENT_FILE);
+    let plans = environment_plans(requests);
+    let mut actions = vec![];
+    let content = render_environment(&read_or_empty(&path), requests);
+    write_if_changed(&mut actions, &path, content);
+    converge("/etc/environment", &plans, actions, vec![], dry_run, yes)?;
+    if !dry_run && plans.iter().any(|plan| plan.action != ResourceAction::Noop) {
+        info!("/etc/environment: changes take effect on the next login");
+    }
+    Ok(())
+}
+
+pub(super) fn converge(
+    label: &str,
+    plans: &[ResourcePlan],
+    actions: Vec<PrivilegedAction>,
+    commands: Vec<(String, Vec<String>)>,
+    dry_run: bool,
+    yes: bool,
+) -> Result<()> {
+    let change_count = actions.len() + commands.len();
+    if dry_run {
+        for action in &actions {
+            miseprintln!("would {}", action.description());
+        }
+        for (program, args) in &commands {
+            miseprintln!("would run {}", super::sudo::argv(program, args).join(" "));
+        }
+        for plan in plans
+            .iter()
+            .filter(|plan| plan.action == ResourceAction::Unknown)
+        {
+            warn!(
+                "would not change {}: current {}, desired {} (manual action required)",
+                plan.id, plan.current, plan.desired
+            );
+        }
+        if change_count == 0 {
+            info!("{label}: already converged");
+        }
+        return Ok(());
+    }
+    if change_count == 0 {
+        info!("{label}: already converged");
+        return Ok(());
+    }
+    if !yes
+        && console::user_attended_stderr()
+        && !crate::ui::prompt::confirm(format!("{label}: apply {change_count} change(s)?"))?
+    {
+        info!("{label}: skipped");
+        return Ok(());
+    }
+    super::managed_files::apply_plan(PrivilegedPlan { actions })?;
+    for (program, args) in commands {
+        super::sudo::run(&program, &args, &[])?;
+    }
+    info!("{label}: applied {change_count} change(s)");
+    Ok(())
+}
+
+pub(super) fn write_if_changed(actions: &mut Vec<PrivilegedAction>, path: &Path, content: String) {
+    if std::fs::read_to_string(path).ok().as_deref() == Some(content.as_str()) {
+        return;
+    }
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        actions.push(PrivilegedAction::CreateDirectory {
+            path: parent.to_path_buf(),
+            owner: None,
+            group: None,
+            mode: 0o755,
+            replace: false,
+        });
+    }
+    actions.push(PrivilegedAction::WriteFile {
+        path: path.to_path_buf(),
+        content,
+        owner: None,
+        group: None,
+        mode: 0o644,
+        replace: false,
+    });
+}
+
+pub(super) fn ensure_linux(what: &str) -> Result<()> {
+    if !cfg!(target_os = "linux") {
+        bail!("bootstrap {what} management is only supported on Linux");
+    }
+    Ok(())
+}
+
+/// The backing file rollback journals restore for a host resource kind.
+pub(crate) fn backing_file(kind: &str) -> Option<PathBuf> {
+    match kind {
+        "sysctl" => Some(host_path(SYSCTL_DROP_IN)),
+        "kernel-module" => Some(host_path(MODULES_DROP_IN)),
+        "environment" => Some(host_path(ENVIRONMENT_FILE)),
+        _ => None,
+    }
+}
+
+/// `MISE_TEST_HOST_ROOT` relocates `/etc`, `/proc` and `/sys` for tests.
+pub(super) fn host_path(path: &str) -> PathBuf {
+    match crate::env::var("MISE_TEST_HOST_ROOT") {
+        Ok(root) => Path::new(&root).join(path.trim_start_matches('/')),
+        Err(_) => PathBuf::from(path),
+    }
+}
+
+pub(super) fn read_or_empty(path: &Path) -> String {
+    std::fs::read_to_string(path).unwrap_or_default()
+}
+
+fn loaded_modules() -> HashSet<String> {
+    read_or_empty(&host_path("/proc/modules"))
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+fn valid_sysctl_key(key: &str) -> bool {
+    let separator = if key.contains('/') { '/' } else { '.' };
+    !key.is_empty()
+        && key
+            .split(separator)
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..")
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'))
+}
+
+/// /proc/sys separates multi-value parameters with tabs; config uses spaces.
+fn normalize_sysctl_value(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn render_sysctl_conf(requests: &[SysctlRequest]) -> String {
+    let mut out = format!("{DROP_IN_HEADER} [bootstrap.linux.sysctl]\n");
+    for request in requests {
+        out.push_str(&format!("{} = {}\n", request.key, request.value));
+    }
+    out
+}
+
+fn parse_sysctl_conf(content: &str) -> IndexMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', ';']))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            (
+                key.trim().trim_start_matches('-').to_string(),
+                normalize_sysctl_value(value),
+            )
+        })
+        .collect()
+}
+
+fn render_modules_conf(requests: &[KernelModuleRequest]) -> String {
+    let mut out = format!("{DROP_IN_HEADER} [bootstrap.linux.kernel_modules]\n");
+    for request in requests {
+        out.push_str(&request.name);
+        out.push('\n');
+    }
+    out
+}
+
+fn parse_modules_conf(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', ';']))
+        .map(|line| line.replace('-', "_"))
+        .collect()
+}
+
+/// Parse `KEY=value` assignments the way pam_env reads `/etc/environment`.
+/// Later assignments win.
+fn parse_environment(content: &str) -> IndexMap<String, String> {
+    content
+        .lines()
+        .filter_map(environment_assignment)
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn environment_assignment(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let (key, value) = line.split_once('=')?;
+    let value = value.trim();
+    let value = ['"', '\'']
+        .into_iter()
+        .find_map(|quote| {
+            value
+                .strip_prefix(quote)
+                .and_then(|value| value.strip_suffix(quote))
+        })
+        .unwrap_or(value);
+    Some((key.trim(), value))
+}
+
+/// Replace the first assignment of each configured key in place, drop later
+/// duplicates so the configured value wins, and append keys that are unset.
+fn render_environment(current: &str, requests: &[EnvironmentRequest]) -> String {
+    let mut rendered = HashSet::new();
+    let mut out = String::new();
+    for line in current.lines() {
+        let request = environment_assignment(line)
+            .and_then(|(key, _)| requests.iter().find(|request| request.key == key));
+        match request {
+            Some(request) if rendered.insert(request.key.as_str()) => {
+                out.push_str(&request.render());
+                out.push('\n');
+            }
+            Some(_) => {}
+            None => {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    for request in requests {
+        if !rendered.contains(request.key.as_str()) {
+            out.push_str(&request.render());
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sysctl(key: &str, value: &str) -> SysctlRequest {
+        SysctlRequest::from_toml(key.to_string(), SysctlTomlValue::String(value.to_string()))
+            .unwrap()
+    }
+
+    fn environment(key: &str, value: &str) -> EnvironmentRequest {
+        EnvironmentRequest::from_toml(key.to_string(), value.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_sysctl_keys_and_values_are_validated() {
+        assert!(valid_sysctl_key("vm.max_map_count"));
+        assert!(valid_sysctl_key("net/ipv4/conf/eth0.100/rp_filter"));
+        assert!(!valid_sysctl_key("vm..max_map_count"));
+        assert!(!valid_sysctl_key("net/ipv4/../../etc"));
+        assert!(!valid_sysctl_key("vm.max_map_count = 1"));
+        assert_eq!(
+            sysctl("net.ipv4.tcp_rmem", "4096\t87380  6291456").value,
+            "4096 87380 6291456"
+        );
+        assert!(
+            SysctlRequest::from_toml("vm.swappiness".into(), SysctlTomlValue::String(" ".into()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_sysctl_drop_in_round_trips() {
+        let requests = vec![
+            SysctlRequest::from_toml("vm.max_map_count".into(), SysctlTomlValue::Integer(262144))
+                .unwrap(),
+            sysctl("net.ipv4.tcp_rmem", "4096 87380 6291456"),
+        ];
+        let conf = render_sysctl_conf(&requests);
+        assert_eq!(
+            conf,
+            "# Managed by mise from [bootstrap.linux.sysctl]\n\
+             vm.max_map_count = 262144\n\
+             net.ipv4.tcp_rmem = 4096 87380 6291456\n"
+        );
+        let parsed = parse_sysctl_conf(&conf);
+        assert_eq!(parsed["vm.max_map_count"], "262144");
+        assert_eq!(parsed["net.ipv4.tcp_rmem"], "4096 87380 6291456");
+        assert_eq!(
+            parse_sysctl_conf("; comment\n-net.core.somaxconn=1024\n")["net.core.somaxconn"],
+            "1024"
+        );
+    }
+
+    #[test]
+    fn test_kernel_modules_treat_dash_and_underscore_alike() {
+        assert!(KernelModuleRequest::from_toml("br_netfilter".into()).is_ok());
+        assert!(KernelModuleRequest::from_toml("../evil".into()).is_err());
+        let persisted = parse_modules_conf("# header\nbr-netfilter\n");
+        let request = KernelModuleRequest::from_toml("br_netfilter".into()).unwrap();
+        assert!(persisted.contains(&request.canonical_name()));
+    }
+
+    #[test]
+    fn test_environment_keeps_unmanaged_lines() {
+        let current = "# system defaults\n\
+                       PATH=\"/usr/local/sbin:/usr/local/bin:/usr/bin\"\n\
+                       EDITOR=nano\n\
+                       export EDITOR=vi\n";
+        let requests = vec![
+            environment("EDITOR", "vim"),
+            environment("JAVA_OPTS", "-Xmx2g -Xms1g"),
+        ];
+        assert_eq!(parse_environment(current)["EDITOR"], "vi");
+        assert_eq!(
+            parse_environment(current)["PATH"],
+            "/usr/local/sbin:/usr/local/bin:/usr/bin"
+        );
+        let rendered = render_environment(current, &requests);
+        assert_eq!(
+            rendered,
+            "# system defaults\n\
+             PATH=\"/usr/local/sbin:/usr/local/bin:/usr/bin\"\n\
+             EDITOR=vim\n\
+             JAVA_OPTS=\"-Xmx2g -Xms1g\"\n"
+        );
+        let parsed = parse_environment(&rendered);
+        assert_eq!(parsed["EDITOR"], "vim");
+        assert_eq!(parsed["JAVA_OPTS"], "-Xmx2g -Xms1g");
+        assert!(EnvironmentRequest::from_toml("1BAD".into(), "x".into()).is_err());
+        assert!(EnvironmentRequest::from_toml("QUOTED".into(), "a\"b".into()).is_err());
+    }
+}
@@ -0,0 +1,950 @@
+//! Rollback journal for `mise bootstrap`.
+//!
+//! Before a bootstrap run changes anything, it writes the pre-image of every
+//! resource it is about to converge to
+//! `$MISE_STATE_DIR/bootstrap/journal/<apply-id>.json`, keyed by
+//! [`ResourceId`]. `mise bootstrap rollback` puts back the resources whose
+//! pre-image can be restored — managed files and directories, dotfiles,
+//! CA certificates, services and the firewall — and reports the rest
+//! (accounts, packages and Compose projects) as irreversible, along with the
+//! state they were in. Once the apply finishes, the journal also records what
+//! each file was left as, so rollback can refuse to overwrite later edits.
+//! Targets of encrypted dotfile sources only get a fingerprint, so decrypted
+//! content never lands in a journal. Journals older than `cache_prune_age` are
+//! removed when the next one is recorded.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use eyre::{Result, bail};
+use indexmap::{IndexMap, IndexSet};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, Settings};
+use crate::dirs;
+use crate::file;
+use crate::hash;
+use crate::path::PathExt;
+use crate::system::edits::{EditOp, EditRequest};
+use crate::system::files::{FileMode, FileRequest, FileState};
+use crate::system::firewall::FirewallPreImage;
+use crate::system::managed_files::{PrivilegedAction, PrivilegedPlan};
+use crate::system::resources::{ResourceAction, ResourceId, ResourcePlan};
+use crate::system::secrets::SecretValues;
+use crate::system::services::{ServicePreImage, ServiceRequest};
+
+/// The pre-images recorded for one bootstrap apply.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Journal {
+    pub id: String,
+    pub started_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rolled_back_at: Option<String>,
+    pub entries: Vec<JournalEntry>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct JournalEntry {
+    pub resource: ResourceId,
+    pub action: ResourceAction,
+    pub pre_image: PreImage,
+    /// what a file or dotfile looked like once the apply finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applied: Option<FileFingerprint>,
+}
+
+/// What a resource looked like before the apply.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum PreImage {
+    /// `previous` is `None` when the file didn't exist
+    File {
+        path: PathBuf,
+        previous: Option<FileImage>,
+    },
+    Directory {
+        path: PathBuf,
+        previous: Option<DirectoryImage>,
+    },
+    /// a `[dotfiles]` target, restored as the current user
+    Dotfile {
+        path: PathBuf,
+        previous: Option<DotfileImage>,
+    },
+    Service(ServicePreImage),
+    Firewall(FirewallPreImage),
+    /// kept so rollback can report what the resource was
+    Irreversible {
+        previous: String,
+        reason: String,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct FileImage {
+    pub content: String,
+    pub mode: u32,
+    pub owner: Option<String>,
+    pub group: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct DirectoryImage {
+    pub mode: u32,
+    pub owner: Option<String>,
+    pub group: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum DotfileImage {
+    /// `content` is base64 so binary dotfiles survive the JSON round trip
+    File {
+        content: String,
+        mode: u32,
+    },
+    Symlink {
+        target: PathBuf,
+    },
+}
+
+/// A file's content or symlink target, compared to spot edits made since an
+/// apply without keeping a second copy of the content.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum FileFingerprint {
+    Absent,
+    File { sha256: String },
+    Symlink { target: PathBuf },
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct RollbackReport {
+    pub restored: Vec<ResourceId>,
+    pub unchanged: Vec<ResourceId>,
+    pub irreversible: Vec<IrreversibleResource>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct IrreversibleResource {
+    pub resource: ResourceId,
+    pub previous: String,
+    pub reason: String,
+}
+
+pub(crate) fn journal_dir() -> PathBuf {
+    dirs::STATE.join("bootstrap").join("journal")
+}
+
+impl Journal {
+    /// Capture the pre-image of every planned change `include` accepts, and of
+    /// every dotfile target the apply will change when `dotfiles` is set.
+    /// Returns `None` when there is nothing to journal.
+    pub(crate) async fn record(
+        config: &Config,
+        secrets: &SecretValues,
+        include: impl Fn(&ResourceId) -> bool,
+        dotfiles: bool,
+    ) -> Result<Option<Self>> {
+        prune();
+        let plan = super::resources::plan(config, secrets).await?;
+        let output = plan.output()?;
+        let changes = output
+            .resources
+            .into_iter()
+            .filter(|resource| {
+                matches!(
+                    resource.action,
+                    ResourceAction::Create | ResourceAction::Update | ResourceAction::Remove
+                ) && include(&resource.id)
+            })
+            .collect::<Vec<_>>();
+
+        let services = if changes.iter().any(|resource| resource.id.kind == "service") {
+            let mut requests = super::services::status_requests_from_config(config)?;
+            super::services::inspect_requests(&mut requests);
+            requests
+                .into_iter()
+                .map(|request| (request.name.clone(), request))
+                .collect::<IndexMap<_, _>>()
+        } else {
+            IndexMap::new()
+        };
+
+        let mut entries = vec![];
+        let mut firewall_recorded = false;
+        let mut host_files = IndexSet::new();
+        for resource in changes {
+            let pre_image = match resource.id.kind.as_str() {
+                "file" => capture_file(Path::new(&resource.id.name), resource),
+                "directory" => capture_directory(Path::new(&resource.id.name), resource),
+                "service" => services
+                    .get(&resource.id.name)
+                    .and_then(ServiceRequest::pre_image)
+                    .map(PreImage::Service)
+                    .unwrap_or_else(|| {
+                        irreversible(resource, "service state could not be inspected")
+                    }),
+                // the policy and its rules are restored together
+                "firewall" | "firewall-rule" => {
+                    if firewall_recorded {
+                        continue;
+                    }
+                    firewall_recorded = true;
+                    let pre_image = match super::firewall::request_from_config(config) {
+                        Ok(request) => request.and_then(|request| request.pre_image()),
+                        Err(err) => {
+                            debug!("bootstrap journal: cannot inspect firewall: {err:#}");
+                            None
+                        }
+                    };
+                    entries.push(JournalEntry {
+                        resource: ResourceId::new("firewall", "linux"),
+                        action: ResourceAction::Update,
+                        pre_image: pre_image.map(PreImage::Firewall).unwrap_or_else(|| {
+                            PreImage::Irreversible {
+                                previous: "unverified firewall rules".to_string(),
+                                reason: "the current firewall could not be verified".to_string(),
+                            }
+                        }),
+                        applied: None,
+                    });
+                    continue;
+                }
+                // the drop-in or /etc/environment is restored as a whole;
+                // live kernel state follows it on the next boot
+                "sysctl" | "kernel-module" | "environment" => {
+                    let path = super::host::backing_file(&resource.id.kind)
+                        .expect("host resource kinds have a backing file");
+                    if !host_files.insert(path.clone()) {
+                        continue;
+                    }
+                    entries.push(JournalEntry {
+                        resource: ResourceId::new("file", path.to_string_lossy()),
+                        action: ResourceAction::Update,
+                        pre_image: capture_file(&path, resource),
+                        applied: None,
+                    });
+                    continue;
+                }
+                "certificate" => match super::certificates::anchor_path(&resource.id.name) {
+                    Some(path) => capture_file(&path, resource),
+                    None => irreversible(resource, "no CA trust store found"),
+                },
+                // ~/.ssh/known_hosts is the user's file and is restored as a
+                // whole, like a dotfile
+                "known-host" => {
+                    let path = super::known_hosts::backing_file();
+                    if !host_files.insert(path.clone()) {
+                        continue;
+                    }
+                    entries.push(JournalEntry {
+                        resource: ResourceId::new("dotfile", path.to_string_lossy()),
+                        action: ResourceAction::Update,
+                        pre_image: match inspect_dotfile(&path) {
+                            Ok(previous) => PreImage::Dotfile { path, previous },
+                            Err(err) => irreversible(resource, err.to_string()),
+                        },
+                        applied: None,
+                    });
+                    continue;
+                }
+                "user" | "group" => irreversible(resource, "accounts are not rolled back"),
+                "package" => irreversible(resource, "packages are not uninstalled or downgraded"),
+                "compose" => irreversible(resource, "Compose projects are not rolled back"),
+                _ => irreversible(resource, "this resource kind is not journaled"),
+            };
+            entries.push(JournalEntry {
+                resource: resource.id.clone(),
+                action: resource.action,
+                pre_image,
+                applied: None,
+            });
+        }
+
+        if dotfiles {
+            // target -> whether an encrypted source writes to it
+            let mut targets: IndexMap<PathBuf, bool> = IndexMap::new();
+            for request in super::files::files_from_config(config)? {
+                if dotfile_will_change(config, &request) {
+                    let encrypted = request.encrypted();
+                    *targets.entry(request.target).or_default() |= encrypted;
+                }
+            }
+            for request in super::edits::edits_from_config(config)? {
+                if edit_will_change(config, &request) {
+                    targets.entry(request.path).or_default();
+                }
+            }
+            for (target, encrypted) in targets {
+                let pre_image = if encrypted {
+                    encrypted_target(&target)
+                } else {
+                    match inspect_dotfile(&target) {
+                        Ok(previous) => PreImage::Dotfile {
+                            path: target.clone(),
+                            previous,
+                        },
+                        Err(err) => PreImage::Irreversible {
+                            previous: "unreadable".to_string(),
+                            reason: err.to_string(),
+                        },
+                    }
+                };
+                entries.push(JournalEntry {
+                    resource: ResourceId::new("dotfile", target.to_string_lossy()),
+                    // dotfiles aren't part of the resource plan
+                    action: ResourceAction::Unknown,
+                    pre_image,
+                    applied: None,
+                });
+            }
+        }
+
+        if entries.is_empty() {
+            return Ok(None);
+        }
+        let now = jiff::Timestamp::now();
+        let base = now.strftime("%Y%m%dT%H%M%SZ").to_string();
+        let mut id = base.clone();
+        let mut attempt = 1;
+        while journal_dir().join(format!("{id}.json")).exists() {
+            attempt += 1;
+            id = format!("{base}-{attempt}");
+        }
+        Ok(Some(Self {
+            id,
+            started_at: now.to_string(),
+            rolled_back_at: None,
+            entries,
+        }))
+    }
+
+    /// Record what every journaled file and dotfile looks like now that the
+    /// apply has finished.
+    pub(crate) fn record_applied(&mut self) {
+        for entry in &mut self.entries {
+            entry.applied = match &entry.pre_image {
+                PreImage::File { path, .. } | PreImage::Dotfile { path, .. } => {
+                    fingerprint(path).ok()
+                }
+                _ => None,
+            };
+        }
+    }
+
+    pub(crate) fn path(&self) -> PathBuf {
+        journal_dir().join(format!("{}.json", self.id))
+    }
+
+    /// Write the journal readable only by the current user, since pre-images
+    /// can hold the previous contents of secret-bearing files.
+    pub(crate) fn save(&self) -> Result<()> {
+        let path = self.path();
+        file::create_dir_all(journal_dir())?;
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut journal = options.open(&path)?;
+        journal.write_all(serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Remove journals older than `cache_prune_age`
+fn prune() {
+    let Some(max_age) = Settings::get().cache_prune_age_duration() else {
+        return;
+    };
+    let Ok(paths) = file::ls(&journal_dir()) else {
+        return;
+    };
+    for path in paths {
+        if path.extension().is_some_and(|ext| ext == "json")
+            && file::modified_duration(&path).is_ok_and(|age| age > max_age)
+        {
+            trace!("pruning bootstrap journal {}", path.display_user());
+            if let Err(err) = file::remove_file(&path) {
+                debug!(
+                    "failed to prune bootstrap journal {}: {err}",
+                    path.display_user()
+                );
+            }
+        }
+    }
+}
+
+/// Whether applying `request` would change its target. Templates are assumed
+/// to, since checking them renders them and runs their `exec()` calls a second
+/// time.
+fn dotfile_will_change(config: &Config, request: &FileRequest) -> bool {
+    request.mode == FileMode::Template
+        || !matches!(
+            super::files::check(config, request),
+            Ok(FileState::Applied | FileState::SourceMissing)
+        )
+}
+
+/// [`dotfile_will_change`] for a `[dotfiles]` edit
+fn edit_will_change(config: &Config, request: &EditRequest) -> bool {
+    matches!(request.op, EditOp::Block { template: true, .. })
+        || !matches!(
+            super::edits::check(config, request),
+            Ok(FileState::Applied | FileState::SourceMissing)
+        )
+}
+
+/// Every journal on disk, oldest first.
+pub(crate) fn list() -> Result<Vec<Journal>> {
+    let mut journals = vec![];
+    for path in file::ls(&journal_dir())? {
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        match serde_json::from_str::<Journal>(&file::read_to_string(&path)?) {
+            Ok(journal) => journals.push(journal),
+            Err(err) => warn!(
+                "bootstrap rollback: ignoring unreadable journal {}: {err}",
+                path.display_user()
+            ),
+        }
+    }
+    journals.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(journals)
+}
+
+/// The journals to undo, newest first: the latest apply that hasn't been
+/// rolled back, or every such apply back to and including `to`.
+pub(crate) fn select(journals: Vec<Journal>, to: Option<&str>) -> Result<Vec<Journal>> {
+    if let Some(to) = to
+        && let Some(journal) = journals.iter().find(|journal| journal.id == to)
+        && journal.rolled_back_at.is_some()
+    {
+        bail!("bootstrap apply {to} was already rolled back");
+    }
+    let mut pending = journals
+        .into_iter()
+        .filter(|journal| journal.rolled_back_at.is_none())
+        .collect::<Vec<_>>();
+    let start = match to {
+        Some(to) => match pending.iter().position(|journal| journal.id == to) {
+            Some(start) => start,
+            None => {
+                bail!("no bootstrap apply journal named {to}; see `mise bootstrap rollback --list`")
+            }
+        },
+        None if pending.is_empty() => bail!("no bootstrap apply to roll back"),
+        None => pending.len() - 1,
+    };
+    let mut selected = pending.split_off(start);
+    selected.reverse();
+    Ok(selected)
+}
+
+/// Restore the pre-images recorded in `journals` (newest first). When a
+/// resource appears in several journals, the oldest pre-image wins. Files
+/// changed since the newest apply that touched them are only overwritten with
+/// `force`.
+pub(crate) fn rollback(journals: &[Journal], dry_run: bool, force: bool) -> Result<RollbackReport> {
+    let edited = edited_since_apply(journals);
+    if !edited.is_empty() {
+        let paths = edited
+            .iter()
+            .map(|path| path.display_user())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !force {
+            bail!(
+                "changed since the apply: {paths}\nrerun with --force to overwrite these changes"
+            );
+        }
+        warn!("bootstrap rollback: overwriting changes made since the apply: {paths}");
+    }
+    let mut report = RollbackReport::default();
+    let mut system_files = PrivilegedPlan::default();
+    let mut services = vec![];
+    let mut firewall = None;
+    let mut certificates_restored = false;
+    for entry in merge(journals) {
+        let id = entry.resource.clone();
+        match &entry.pre_image {
+            PreImage::File { path, previous } => match file_action(path, previous.as_ref()) {
+                Some(action) => {
+                    system_files.actions.push(action);
+                    certificates_restored |= id.kind == "certificate";
+                    report.restored.push(id);
+                }
+                None => report.unchanged.push(id),
+            },
+            PreImage::Directory { path, previous } => {
+                match directory_action(path, previous.as_ref()) {
+                    Some(action) => {
+                        system_files.actions.push(action);
+                        report.restored.push(id);
+                    }
+                    None => report.unchanged.push(id),
+                }
+            }
+            PreImage::Dotfile { path, previous } => {
+                if inspect_dotfile(path).is_ok_and(|current| current == *previous) {
+                    report.unchanged.push(id);
+                } else if dry_run {
+                    miseprintln!("would restore dotfile {}", path.display_user());
+                    report.restored.push(id);
+                } else {
+                    restore_dotfile(path, previous.as_ref())?;
+                    report.restored.push(id);
+                }
+            }
+            PreImage::Service(pre_image) => {
+                services.push((
+                    id.clone(),
+                    ServiceRequest::from_pre_image(id.name.clone(), pre_image),
+                ));
+            }
+            PreImage::Firewall(pre_image) => {
+                firewall = Some(pre_image.clone());
+                report.restored.push(id);
+            }
+            PreImage::Irreversible { previous, reason } => {
+                report.irreversible.push(IrreversibleResource {
+                    resource: id,
+                    previous: previous.clone(),
+                    reason: reason.clone(),
+                });
+            }
+        }
+    }
+
+    // Undo in the reverse of apply order: firewall, then services (while
+    // their unit files still exist), then system files.
+    if let Some(firewall) = &firewall {
+        super::firewall::restore(firewall, dry_run, true)?;
+    }
+    if !services.is_empty() {
+        let (ids, mut requests): (Vec<_>, Vec<_>) = services.into_iter().unzip();
+        super::services::inspect_requests(&mut requests);
+        for (id, request) in ids.into_iter().zip(&requests) {
+            if request.plan().action == ResourceAction::Noop {
+                report.unchanged.push(id);
+            } else {
+                report.restored.push(id);
+            }
+        }
+        super::services::apply(&requests, dry_run, true)?;
+    }
+    if dry_run {
+        for action in &system_files.actions {
+            miseprintln!("would {}", action.description());
+        }
+    } else if !system_files.actions.is_empty() {
+        super::managed_files::apply_plan(system_files)?;
+    }
+    if certificates_restored {
+        super::certificates::refresh_trust_store(dry_run)?;
+    }
+    Ok(report)
+}
+
+/// Oldest pre-image per resource, in the reverse of the order the resources
+/// were first changed.
+fn merge(journals: &[Journal]) -> Vec<&JournalEntry> {
+    let mut merged: IndexMap<&ResourceId, &JournalEntry> = IndexMap::new();
+    for journal in journals.iter().rev() {
+        for entry in &journal.entries {
+            merged.entry(&entry.resource).or_insert(entry);
+        }
+    }
+    merged.into_values().rev().collect()
+}
+
+/// Journaled files that no longer look the way the newest apply that touched
+/// them left them.
+fn edited_since_apply(journals: &[Journal]) -> Vec<&Path> {
+    let mut applied: IndexMap<&ResourceId, (&Path, Option<&FileFingerprint>)> = IndexMap::new();
+    for entry in journals.iter().flat_map(|journal| &journal.entries) {
+        let (PreImage::File { path, .. } | PreImage::Dotfile { path, .. }) = &entry.pre_image
+        else {
+            continue;
+        };
+        applied
+            .entry(&entry.resource)
+            .or_insert((path.as_path(), entry.applied.as_ref()));
+    }
+    applied
+        .into_values()
+        .filter_map(|(path, state)| {
+            let state = state?;
+            (fingerprint(path).ok().as_ref() != Some(state)).then_some(path)
+        })
+        .collect()
+}
+
+fn irreversible(resource: &ResourcePlan, reason: impl Into<String>) -> PreImage {
+    PreImage::Irreversible {
+        previous: resource.current.clone(),
+        reason: reason.into(),
+    }
+}
+
+/// The target of an encrypted dotfile source may hold plaintext from an
+/// earlier decrypt, so only its fingerprint is kept and it isn't restored.
+fn encrypted_target(path: &Path) -> PreImage {
+    let previous = match fingerprint(path) {
+        Ok(FileFingerprint::File { sha256 }) => format!("sha256:{sha256}"),
+        Ok(FileFingerprint::Symlink { target }) => format!("symlink to {}", target.display_user()),
+        Ok(FileFingerprint::Absent) => "absent".to_string(),
+        Err(err) => err.to_string(),
+    };
+    PreImage::Irreversible {
+        previous,
+        reason: "targets of encrypted sources are not journaled".to_string(),
+    }
+}
+
+fn capture_file(path: &Path, resource: &ResourcePlan) -> PreImage {
+    match inspect_file(path) {
+        Ok(previous) => PreImage::File {
+            path: path.to_path_buf(),
+            previous,
+        },
+        Err(err) => irreversible(resource, err.to_string()),
+    }
+}
+
+fn capture_directory(path: &Path, resource: &ResourcePlan) -> PreImage {
+    let previous = match inspect_directory(path) {
+        Ok(previous) => previous,
+        Err(err) => return irreversible(resource, err.to_string()),
+    };
+    if previous.is_some()
+        && resource.action == ResourceAction::Remove
+        && !fs::read_dir(path).is_ok_and(|mut entries| entries.next().is_none())
+    {
+        return irreversible(resource, "the directory's contents are not journaled");
+    }
+    PreImage::Directory {
+        path: path.to_path_buf(),
+        previous,
+    }
+}
+
+fn inspect_file(path: &Path) -> Result<Option<FileImage>> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => bail!("cannot read pre-image: {err}"),
+    };
+    if !metadata.is_file() {
+        bail!("only regular files are journaled");
+    }
+    let Ok(content) = String::from_utf8(fs::read(path)?) else {
+        bail!("previous content is not UTF-8");
+    };
+    let (owner, group) = ownership(&metadata);
+    Ok(Some(FileImage {
+        content,
+        mode: mode(&metadata),
+        owner,
+        group,
+    }))
+}
+
+fn inspect_directory(path: &Path) -> Result<Option<DirectoryImage>> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => bail!("cannot read pre-image: {err}"),
+    };
+    if !metadata.is_dir() {
+        bail!("the path was not a directory");
+    }
+    let (owner, group) = ownership(&metadata);
+    Ok(Some(DirectoryImage {
+        mode: mode(&metadata),
+        owner,
+        group,
+    }))
+}
+
+fn inspect_dotfile(path: &Path) -> Result<Option<DotfileImage>> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => bail!("cannot read pre-image: {err}"),
+    };
+    if metadata.is_symlink() {
+        return Ok(Some(DotfileImage::Symlink {
+            target: fs::read_link(path)?,
+        }));
+    }
+    if !metadata.is_file() {
+        bail!("directory targets are not journaled");
+    }
+    Ok(Some(DotfileImage::File {
+        content: BASE64_STANDARD.encode(file::read(path)?),
+        mode: mode(&metadata),
+    }))
+}
+
+fn fingerprint(path: &Path) -> Result<FileFingerprint> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(FileFingerprint::Absent);
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if metadata.is_symlink() {
+        return Ok(FileFingerprint::Symlink {
+            target: fs::read_link(path)?,
+        });
+    }
+    if !metadata.is_file() {
+        bail!("{} is not a file", path.display_user());
+    }
+    Ok(FileFingerprint::File {
+        sha256: hash::file_hash_sha256(path, None)?,
+    })
+}
+
+fn file_action(path: &Path, previous: Option<&FileImage>) -> Option<PrivilegedAction> {
+    match previous {
+        None => fs::symlink_metadata(path)
+            .is_ok()
+            .then(|| PrivilegedAction::RemoveFile {
+                path: path.to_path_buf(),
+            }),
+        Some(previous) => {
+            if inspect_file(path).is_ok_and(|current| current.as_ref() == Some(previous)) {
+                return None;
+            }
+            Some(PrivilegedAction::WriteFile {
+                path: path.to_path_buf(),
+                content: previous.content.clone(),
+                owner: previous.owner.clone(),
+                group: previous.group.clone(),
+                mode: previous.mode,
+                replace: true,
+            })
+        }
+    }
+}
+
+fn directory_action(path: &Path, previous: Option<&DirectoryImage>) -> Option<PrivilegedAction> {
+    match previous {
+        None => fs::symlink_metadata(path)
+            .is_ok()
+            .then(|| PrivilegedAction::RemoveDirectory {
+                path: path.to_path_buf(),
+                recursive: false,
+            }),
+        Some(previous) => {
+            if inspect_directory(path).is_ok_and(|current| current.as_ref() == Some(previous)) {
+                return None;
+            }
+            Some(PrivilegedAction::CreateDirectory {
+                path: path.to_path_buf(),
+                owner: previous.owner.clone(),
+                group: previous.group.clone(),
+                mode: previous.mode,
+                replace: false,
+            })
+        }
+    }
+}
+
+fn restore_dotfile(path: &Path, previous: Option<&DotfileImage>) -> Result<()> {
+    // never write through a symlink into a dotfile source
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if metadata.is_dir() {
+            bail!(
+                "cannot restore {}: it is now a directory",
+                path.display_user()
+            );
+        }
+        file::remove_file(path)?;
+    }
+    match previous {
+        None => {}
+        Some(DotfileImage::Symlink { target }) => {
+            file::make_symlink(target, path)?;
+        }
+        Some(DotfileImage::File { content, mode }) => {
+            if let Some(parent) = path.parent() {
+                file::create_dir_all(parent)?;
+            }
+            file::write(path, BASE64_STANDARD.decode(content)?)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(path, fs::Permissions::from_mode(*mode))?;
+            }
+            #[cfg(not(unix))]
+            let _ = mode;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn mode(metadata: &fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn mode(metadata: &fs::Metadata) -> u32 {
+    if metadata.permissions().readonly() {
+        0o444
+    } else {
+        0o644
+    }
+}
+
+#[cfg(unix)]
+fn ownership(metadata: &fs::Metadata) -> (Option<String>, Option<String>) {
+    use std::os::unix::fs::MetadataExt;
+    let owner = nix::unistd::User::from_uid(nix::unistd::Uid::from_raw(metadata.uid()))
+        .ok()
+        .flatten()
+        .map(|user| user.name);
+    let group = nix::unistd::Group::from_gid(nix::unistd::Gid::from_raw(metadata.gid()))
+        .ok()
+        .flatten()
+        .map(|group| group.name);
+    (owner, group)
+}
+
+#[cfg(not(unix))]
+fn ownership(_metadata: &fs::Metadata) -> (Option<String>, Option<String>) {
+    (None, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal(id: &str, entries: &[(&str, &str)]) -> Journal {
+        Journal {
+            id: id.to_string(),
+            started_at: String::new(),
+            rolled_back_at: None,
+            entries: entries
+                .iter()
+                .map(|(name, previous)| JournalEntry {
+                    resource: ResourceId::new("package", *name),
+                    action: ResourceAction::Create,
+                    pre_image: PreImage::Irreversible {
+                        previous: previous.to_string(),
+                        reason: String::new(),
+                    },
+                    applied: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn previous(entry: &JournalEntry) -> (&str, &str) {
+        let PreImage::Irreversible { previous, .. } = &entry.pre_image else {
+            unreachable!()
+        };
+        (entry.resource.name.as_str(), previous.as_str())
+    }
+
+    #[test]
+    fn test_merge_keeps_oldest_pre_image_in_reverse_order() {
+        let journals = [
+            journal("2", &[("b", "b after 1"), ("c", "c before 2")]),
+            journal("1", &[("a", "a before 1"), ("b", "b before 1")]),
+        ];
+        let merged = merge(&journals)
+            .into_iter()
+            .map(previous)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            merged,
+            [
+                ("c", "c before 2"),
+                ("b", "b before 1"),
+                ("a", "a before 1")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_defaults_to_latest_pending_apply() {
+        let journals = || {
+            let mut rolled_back = journal("3", &[]);
+            rolled_back.rolled_back_at = Some("now".to_string());
+            vec![journal("1", &[]), journal("2", &[]), rolled_back]
+        };
+        let ids = |selected: Vec<Journal>| {
+            selected
+                .into_iter()
+                .map(|journal| journal.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(select(journals(), None).unwrap()), ["2"]);
+        assert_eq!(ids(select(journals(), Some("1")).unwrap()), ["2", "1"]);
+        let err = select(journals(), Some("3")).unwrap_err();
+        assert!(err.to_string().contains("already rolled back"));
+        let err = select(journals(), Some("9")).unwrap_err();
+        assert!(err.to_string().contains("no bootstrap apply journal"));
+    }
+
+    #[test]
+    fn test_edited_since_apply_compares_newest_applied_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("managed");
+        fs::write(&path, "applied").unwrap();
+        let applied = fingerprint(&path).unwrap();
+        let entry = |applied: Option<FileFingerprint>| JournalEntry {
+            resource: ResourceId::new("file", path.to_string_lossy()),
+            action: ResourceAction::Update,
+            pre_image: PreImage::File {
+                path: path.clone(),
+                previous: None,
+            },
+            applied,
+        };
+        let journal = |id: &str, entries| Journal {
+            id: id.to_string(),
+            started_at: String::new(),
+            rolled_back_at: None,
+            entries,
+        };
+        let journals = [
+            journal("2", vec![entry(Some(applied))]),
+            journal("1", vec![entry(Some(FileFingerprint::Absent))]),
+        ];
+        assert!(edited_since_apply(&journals).is_empty());
+
+        fs::write(&path, "edited").unwrap();
+        assert_eq!(edited_since_apply(&journals), [path.as_path()]);
+
+        // journals written before the apply finished have nothing to compare
+        let journals = [journal("1", vec![entry(None)])];
+        assert!(edited_since_apply(&journals).is_empty());
+    }
+
+    #[test]
+    fn test_encrypted_target_keeps_only_a_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".netrc");
+        fs::write(&path, "password hunter2").unwrap();
+        let PreImage::Irreversible { previous, .. } = encrypted_target(&path) else {
+            panic!("encrypted targets are not restorable");
+        };
+        assert!(previous.starts_with("sha256:"), "{previous}");
+        assert!(!previous.contains("hunter2"));
+    }
+}
@@ -0,0 +1,426 @@
+//! SSH host keys for `[bootstrap.ssh.known_hosts]`.
+//!
+//! Entries are managed by host in the current user's `~/.ssh/known_hosts`:
+//! lines for a configured host — plain or hashed with `HashKnownHosts` — are
+//! replaced by the configured keys, and every other line is left alone. The
+//! file belongs to the user, so no privilege is needed.
+
+use std::path::PathBuf;
+
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use eyre::{Result, bail};
+use hmac::{Hmac, KeyInit, Mac};
+use serde::Deserialize;
+use sha1::Sha1;
+
+use crate::dirs;
+use crate::file;
+use crate::path::PathExt;
+use crate::system::resources::{ResourceAction, ResourceId, ResourcePlan};
+
+/// A `[bootstrap.ssh.known_hosts]` value: one `<type> <base64>` key or a list
+/// of them. An empty list removes every key for the host.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum KnownHostTomlValue {
+    Key(String),
+    Keys(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KnownHostRequest {
+    /// `host` for port 22, otherwise `[host]:port`, as ssh writes it.
+    pub host: String,
+    pub keys: Vec<HostKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HostKey {
+    pub key_type: String,
+    pub key: String,
+}
+
+impl HostKey {
+    fn parse(host: &str, line: &str) -> Result<Self> {
+        let mut fields = line.split_whitespace();
+        let (Some(key_type), Some(key)) = (fields.next(), fields.next()) else {
+            bail!("known host '{host}': expected '<type> <base64 key>', got '{line}'");
+        };
+        if !["ssh-", "ecdsa-sha2-", "sk-ssh-", "sk-ecdsa-sha2-"]
+            .iter()
+            .any(|prefix| key_type.starts_with(prefix))
+        {
+            bail!("known host '{host}': '{key_type}' is not an SSH key type");
+        }
+        if BASE64_STANDARD.decode(key).is_err() {
+            bail!("known host '{host}': the {key_type} key is not valid base64");
+        }
+        Ok(Self {
+            key_type: key_type.to_string(),
+            key: key.to_string(),
+        })
+    }
+}
+
+impl KnownHostRequest {
+    pub(crate) fn from_toml(host: String, value: KnownHostTomlValue) -> Result<Self> {
+        let host = normalize_host(&host)?;
+        let lines = match value {
+            KnownHostTomlValue::Key(line) => vec![line],
+            KnownHostTomlValue::Keys(lines) => lines,
+        };
+        let mut keys: Vec<HostKey> = vec![];
+        for line in lines {
+            let key = HostKey::parse(&host, &line)?;
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        Ok(Self { host, keys })
+    }
+
+    fn desired(&self) -> String {
+        if self.keys.is_empty() {
+            "absent".to_string()
+        } else {
+            describe(&self.keys)
+        }
+    }
+
+    fn plan(&self, entries: &[Entry]) -> ResourcePlan {
+        let id = ResourceId::new("known-host", &self.host);
+        let current = entries
+            .iter()
+            .filter(|entry| entry.matches(&self.host))
+            .filter_map(|entry| entry.key.clone())
+            .fold(vec![], |mut keys, key| {
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+                keys
+            });
+        let converged =
+            current.len() == self.keys.len() && current.iter().all(|key| self.keys.contains(key));
+        let action = if converged {
+            ResourceAction::Noop
+        } else if current.is_empty() {
+            ResourceAction::Create
+        } else if self.keys.is_empty() {
+            ResourceAction::Remove
+        } else {
+            ResourceAction::Update
+        };
+        let current = if current.is_empty() {
+            "absent".to_string()
+        } else {
+            describe(&current)
+        };
+        ResourcePlan::new(id, current, self.desired(), action)
+    }
+}
+
+fn describe(keys: &[HostKey]) -> String {
+    keys.iter()
+        .map(|key| key.key_type.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Validate a host and put it in the form ssh matches against: a bare name
+/// for port 22 and `[name]:port` otherwise.
+fn normalize_host(host: &str) -> Result<String> {
+    let (name, port) = match host
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("]:"))
+    {
+        Some((name, port)) => (name, Some(port)),
+        None => (host, None),
+    };
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ':'))
+    {
+        bail!("known host '{host}' must be a host name or address, optionally as [host]:port");
+    }
+    let name = name.to_ascii_lowercase();
+    match port {
+        None | Some("22") => Ok(name),
+        Some(port) if port.parse::<u16>().is_ok_and(|port| port > 0) => {
+            Ok(format!("[{name}]:{port}"))
+        }
+        Some(port) => bail!("known host '{host}': '{port}' is not a valid port"),
+    }
+}
+
+/// One line of a known_hosts file. Comments, blank lines and marker lines
+/// (`@cert-authority`, `@revoked`) have no `key` and are never matched.
+#[derive(Debug, Clone)]
+struct Entry {
+    line: String,
+    hosts: Option<String>,
+    key: Option<HostKey>,
+}
+
+impl Entry {
+    fn parse(line: &str) -> Self {
+        let trimmed = line.trim();
+        let mut fields = trimmed.split_whitespace();
+        let parsed = if trimmed.starts_with(['#', '@']) {
+            None
+        } else {
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(hosts), Some(key_type), Some(key)) => Some((
+                    hosts.to_string(),
+                    HostKey {
+                        key_type: key_type.to_string(),
+                        key: key.to_string(),
+                    },
+                )),
+                _ => None,
+            }
+        };
+        let (hosts, key) = parsed.unzip();
+        Self {
+            line: line.to_string(),
+            hosts,
+            key,
+        }
+    }
+
+    fn matches(&self, host: &str) -> bool {
+        match &self.hosts {
+            Some(hosts) if hosts.starts_with("|1|") => hashed_host_matches(hosts, host),
+            Some(hosts) => hosts
+                .split(',')
+                .any(|pattern| pattern.eq_ignore_ascii_case(host)),
+            None => false,
+        }
+    }
+
+    /// The line with `host` taken out, or `None` when nothing is left.
+    fn without(&self, host: &str) -> Option<String> {
+        let hosts = match &self.hosts {
+            Some(hosts) if self.matches(host) => hosts,
+            _ => return Some(self.line.clone()),
+        };
+        if hosts.starts_with("|1|") {
+            return None;
+        }
+        let remaining = hosts
+            .split(',')
+            .filter(|pattern| !pattern.eq_ignore_ascii_case(host))
+            .collect::<Vec<_>>();
+        if remaining.is_empty() {
+            return None;
+        }
+        let rest = self.line.trim_start().strip_prefix(hosts.as_str())?;
+        Some(format!("{}{rest}", remaining.join(",")))
+    }
+}
+
+/// `|1|<salt>|<hash>` is HMAC-SHA1 of the host name keyed by the salt.
+fn hashed_host_matches(hashed: &str, host: &str) -> bool {
+    let mut parts = hashed.trim_start_matches("|1|").split('|');
+    let (Some(salt), Some(hash)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+    let (Ok(salt), Ok(hash)) = (BASE64_STANDARD.decode(salt), BASE64_STANDARD.decode(hash)) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(&salt) else {
+        return false;
+    };
+    mac.update(host.as_bytes());
+    mac.verify_slice(&hash).is_ok()
+}
+
+fn known_hosts_path() -> PathBuf {
+    dirs::HOME.join(".ssh").join("known_hosts")
+}
+
+fn read_entries() -> Vec<Entry> {
+    std::fs::read_to_string(known_hosts_path())
+        .unwrap_or_default()
+        .lines()
+        .map(Entry::parse)
+        .collect()
+}
+
+pub(crate) fn plans(requests: &[KnownHostRequest]) -> Vec<ResourcePlan> {
+    let entries = read_entries();
+    requests
+        .iter()
+        .map(|request| request.plan(&entries))
+        .collect()
+}
+
+/// Drop every line for the hosts that need changes and append their
+/// configured keys. Lines for other hosts keep their order and formatting.
+fn render(entries: &[Entry], requests: &[&KnownHostRequest]) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        let line = requests
+            .iter()
+            .try_fold(entry.line.clone(), |line, request| {
+                Entry::parse(&line).without(&request.host)
+            });
+        if let Some(line) = line {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    for request in requests {
+        for key in &request.keys {
+            out.push_str(&format!("{} {} {}\n", request.host, key.key_type, key.key));
+        }
+    }
+    out
+}
+
+/// Bring `~/.ssh/known_hosts` in line with the configured hosts.
+pub(crate) fn apply(requests: &[KnownHostRequest], dry_run: bool, yes: bool) -> Result<()> {
+    if requests.is_empty() {
+        return Ok(());
+    }
+    let path = known_hosts_path();
+    let entries = read_entries();
+    let changed = requests
+        .iter()
+        .filter(|request| request.plan(&entries).action != ResourceAction::Noop)
+        .collect::<Vec<_>>();
+    if changed.is_empty() {
+        info!("known_hosts: already converged");
+        return Ok(());
+    }
+    if dry_run {
+        for request in &changed {
+            miseprintln!(
+                "would set {} in {} to {}",
+                request.host,
+                path.display_user(),
+                request.desired()
+            );
+        }
+        return Ok(());
+    }
+    if !yes
+        && console::user_attended_stderr()
+        && !crate::ui::prompt::confirm(format!("known_hosts: apply {} change(s)?", changed.len()))?
+    {
+        info!("known_hosts: skipped");
+        return Ok(());
+    }
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        file::create_dir_all(parent)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(parent, std::fs::Permissions::from_mode(0o700))?;
+        }
+    }
+    file::write_atomic(&path, render(&entries, &changed))?;
+    info!("known_hosts: applied {} change(s)", changed.len());
+    Ok(())
+}
+
+/// The file rollback journals restore for `known-host` resources.
+pub(crate) fn backing_file() -> PathBuf {
+    known_hosts_path()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ED25519: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIOMqqnkVzrm0SdG6UOoqKLsabgH5C9okWi0dh2l9GKJl";
+    const ECDSA: &str = "ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTY=";
+
+    fn request(host: &str, keys: &[&str]) -> KnownHostRequest {
+        KnownHostRequest::from_toml(
+            host.to_string(),
+            KnownHostTomlValue::Keys(keys.iter().map(|key| key.to_string()).collect()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_known_host_names_are_normalized() {
+        assert_eq!(normalize_host("GitHub.com").unwrap(), "github.com");
+        assert_eq!(normalize_host("[git.corp]:22").unwrap(), "git.corp");
+        assert_eq!(
+            normalize_host("[git.corp]:2222").unwrap(),
+            "[git.corp]:2222"
+        );
+        assert_eq!(normalize_host("::1").unwrap(), "::1");
+        assert!(normalize_host("*.corp").is_err());
+        assert!(normalize_host("a,b").is_err());
+        assert!(normalize_host("[git.corp]:99999").is_err());
+        assert!(
+            KnownHostRequest::from_toml(
+                "github.com".into(),
+                KnownHostTomlValue::Key("github.com ssh-ed25519 AAAA".into())
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_hashed_hosts_match() {
+        // hash "example.com" the way `ssh-keygen -H` does, with a fixed salt
+        let salt = [0u8; 20];
+        let mut mac = Hmac::<Sha1>::new_from_slice(&salt).unwrap();
+        mac.update(b"example.com");
+        let hashed = format!(
+            "|1|{}|{}",
+            BASE64_STANDARD.encode(salt),
+            BASE64_STANDARD.encode(mac.finalize().into_bytes())
+        );
+        assert!(hashed_host_matches(&hashed, "example.com"));
+        assert!(!hashed_host_matches(&hashed, "example.org"));
+        let entry = Entry::parse(&format!("{hashed} {ED25519}"));
+        assert!(entry.matches("example.com"));
+        assert_eq!(entry.without("example.com"), None);
+    }
+
+    #[test]
+    fn test_known_hosts_render_replaces_only_configured_hosts() {
+        let current = format!(
+            "# pinned\n\
+             other.example {ED25519}\n\
+             git.corp,10.0.0.5 {ECDSA} old key\n\
+             @cert-authority *.corp {ED25519}\n"
+        );
+        let entries = current.lines().map(Entry::parse).collect::<Vec<_>>();
+        let git = request("git.corp", &[ED25519]);
+        assert_eq!(git.plan(&entries).action, ResourceAction::Update);
+        assert_eq!(git.plan(&entries).current, "ecdsa-sha2-nistp256");
+        let other = request("other.example", &[ED25519]);
+        assert_eq!(other.plan(&entries).action, ResourceAction::Noop);
+        let gone = request("10.0.0.5", &[]);
+        assert_eq!(gone.plan(&entries).action, ResourceAction::Remove);
+        assert_eq!(
+            render(&entries, &[&git, &gone]),
+            format!(
+                "# pinned\n\
+                 other.example {ED25519}\n\
+                 @cert-authority *.corp {ED25519}\n\
+                 git.corp {ED25519}\n"
+            )
+        );
+        assert_eq!(
+            render(&entries, &[&git]),
+            format!(
+                "# pinned\n\
+                 other.example {ED25519}\n\
+                 10.0.0.5 {ECDSA} old key\n\
+                 @cert-authority *.corp {ED25519}\n\
+                 git.corp {ED25519}\n"
+            )
+        );
+    }
+}
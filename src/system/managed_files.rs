@@ -619,7 +619,7 @@ impl ManagedFileRequest {
     }
 }
 
-fn resolve_source_path(base: &Path, source: &str) -> PathBuf {
+pub(crate) fn resolve_source_path(base: &Path, source: &str) -> PathBuf {
     let source = Path::new(source);
     if source.is_absolute() {
         source.to_path_buf()
@@ -809,26 +809,33 @@ pub(crate) fn apply_with_accounts(
         return Ok(ApplyReport::default());
     }
     let change_count = plan.actions.len();
-    let privileged_plan = plan.apply_until_elevation_required()?;
-    if !privileged_plan.actions.is_empty() {
-        let input = serde_json::to_vec(&privileged_plan)?;
-        let executable = std::env::current_exe()?.to_string_lossy().to_string();
-        crate::system::sudo::run_with_input(
-            &executable,
-            &[
-                "--no-config".to_string(),
-                "--no-env".to_string(),
-                "--no-hooks".to_string(),
-                "bootstrap".to_string(),
-                "__apply-system-plan".to_string(),
-            ],
-            &input,
-        )?;
-    }
+    apply_plan(plan)?;
     info!("system files: applied {change_count} change(s)");
     Ok(report)
 }
 
+/// Apply `plan` as the current user, handing the actions that need elevation
+/// to the privileged helper.
+pub(crate) fn apply_plan(plan: PrivilegedPlan) -> Result<()> {
+    let privileged_plan = plan.apply_until_elevation_required()?;
+    if privileged_plan.actions.is_empty() {
+        return Ok(());
+    }
+    let input = serde_json::to_vec(&privileged_plan)?;
+    let executable = std::env::current_exe()?.to_string_lossy().to_string();
+    crate::system::sudo::run_with_input(
+        &executable,
+        &[
+            "--no-config".to_string(),
+            "--no-env".to_string(),
+            "--no-hooks".to_string(),
+            "bootstrap".to_string(),
+            "__apply-system-plan".to_string(),
+        ],
+        &input,
+    )
+}
+
 #[cfg(unix)]
 pub(crate) fn validate_principals(
     files: &[ManagedFileRequest],
@@ -938,7 +945,7 @@ impl PrivilegedAction {
         }
     }
 
-    fn description(&self) -> String {
+    pub(crate) fn description(&self) -> String {
         match self {
             Self::WriteFile { path, .. } => format!("write file {}", path.display()),
             Self::RemoveFile { path } => format!("remove file {}", path.display()),
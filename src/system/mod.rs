@@ -13,7 +13,11 @@
 //! shell activation setup — `[bootstrap.macos.defaults]` — declarative macOS
 //! user defaults — `[bootstrap.macos.launchd.agents]` — declarative macOS
 //! LaunchAgents — `[bootstrap.linux.systemd.units]` — declarative Linux
-//! systemd user services — `[bootstrap.user].login_shell` — and
+//! systemd user services — `[bootstrap.linux.sysctl]`,
+//! `[bootstrap.linux.kernel_modules]` and `[bootstrap.linux.environment]` —
+//! Linux host settings — `[bootstrap.certificates]` — trusted CA
+//! certificates — `[bootstrap.ssh.known_hosts]` — SSH host keys —
+//! `[bootstrap.user].login_shell` — and
 //! `[bootstrap.hooks]` bootstrap phase hooks.
 //! These are intentionally not part of `[tools]`: they're unversioned,
 //! machine-global settings and resources, not mise's per-project toolset.
@@ -44,9 +48,11 @@ pub(crate) mod accounts;
 #[cfg(not(target_os = "linux"))]
 #[path = "accounts_non_linux.rs"]
 pub(crate) mod accounts;
+pub(crate) mod certificates;
 pub(crate) mod compose;
 pub(crate) mod defaults;
 pub(crate) mod deps;
+pub(crate) mod drift;
 pub(crate) mod edits;
 pub(crate) mod files;
 #[cfg(target_os = "linux")]
@@ -55,6 +61,9 @@ pub(crate) mod firewall;
 #[path = "firewall_non_linux.rs"]
 pub(crate) mod firewall;
 pub(crate) mod hooks;
+pub(crate) mod host;
+pub(crate) mod journal;
+pub(crate) mod known_hosts;
 pub(crate) mod launchd;
 pub(crate) mod login_shell;
 pub(crate) mod managed_files;
@@ -69,6 +78,7 @@ pub(crate) mod services;
 #[path = "services_non_linux.rs"]
 pub(crate) mod services;
 pub(crate) mod shell_activation;
+pub(crate) mod structured;
 pub(crate) mod sudo;
 pub(crate) mod systemd;
 
@@ -113,6 +123,12 @@ pub(crate) struct BootstrapTomlConfig {
     /// `"~/path"` -> git repo checkout.
     #[serde(default)]
     pub repos: IndexMap<String, RepoTomlConfig>,
+    /// Certificate name -> CA certificate added to the system trust store.
+    #[serde(default)]
+    pub certificates: IndexMap<String, certificates::CertificateTomlConfig>,
+    /// SSH client bootstrap config.
+    #[serde(default)]
+    pub ssh: BootstrapSshTomlConfig,
     /// macOS-specific bootstrap config.
     #[serde(default)]
     pub macos: BootstrapMacosTomlConfig,
@@ -244,6 +260,14 @@ pub(crate) fn plugins_from_config(config: &Config) -> IndexMap<String, String> {
     plugins
 }
 
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct BootstrapSshTomlConfig {
+    /// `[bootstrap.ssh.known_hosts]`: host -> pinned host key(s) in the
+    /// current user's ~/.ssh/known_hosts.
+    #[serde(default)]
+    pub known_hosts: IndexMap<String, known_hosts::KnownHostTomlValue>,
+}
+
 #[derive(Debug, Default, Clone, Deserialize)]
 pub(crate) struct BootstrapUserTomlConfig {
     /// desired login shell for the current user, applied with `chsh -s`
@@ -294,6 +318,18 @@ pub(crate) struct BootstrapLinuxTomlConfig {
     /// services and timers rendered to ~/.config/systemd/user.
     #[serde(default)]
     pub systemd: BootstrapLinuxSystemdTomlConfig,
+    /// `[bootstrap.linux.sysctl]`: kernel parameter -> value, persisted in
+    /// /etc/sysctl.d and applied live.
+    #[serde(default)]
+    pub sysctl: IndexMap<String, host::SysctlTomlValue>,
+    /// `[bootstrap.linux.kernel_modules]`: module name -> whether it is loaded
+    /// now and at boot. `false` drops a module declared by a broader config.
+    #[serde(default)]
+    pub kernel_modules: IndexMap<String, bool>,
+    /// `[bootstrap.linux.environment]`: system-wide variables merged into
+    /// /etc/environment.
+    #[serde(default)]
+    pub environment: IndexMap<String, String>,
 }
 
 #[derive(Debug, Default, Clone, Deserialize)]
@@ -1182,6 +1218,135 @@ pub(crate) fn systemd_from_config(config: &Config) -> Vec<SystemdRequest> {
     out
 }
 
+/// Aggregate `[bootstrap.linux.sysctl]` across all loaded config files.
+///
+/// Keys union global -> local; a more local config replaces the value.
+/// Invalid entries warn and are skipped.
+pub(crate) fn sysctl_from_config(config: &Config) -> Vec<host::SysctlRequest> {
+    let mut merged: IndexMap<String, host::SysctlTomlValue> = IndexMap::new();
+    // config_files is ordered local -> global; reverse for global -> local
+    for cf in config.config_files.values().rev() {
+        if let Some(sys) = cf.bootstrap_config() {
+            merged.extend(sys.linux.sysctl);
+        }
+    }
+    let mut out = vec![];
+    for (key, value) in merged {
+        match host::SysctlRequest::from_toml(key, value) {
+            Ok(request) => out.push(request),
+            Err(err) => warn!("[bootstrap.linux.sysctl]: {err}, ignoring entry"),
+        }
+    }
+    out
+}
+
+/// Aggregate `[bootstrap.linux.kernel_modules]` across all loaded config files.
+///
+/// Module names union global -> local; `false` in a more local config removes
+/// a module declared by a broader one. Invalid entries warn and are skipped.
+pub(crate) fn kernel_modules_from_config(config: &Config) -> Vec<host::KernelModuleRequest> {
+    let mut merged: IndexMap<String, bool> = IndexMap::new();
+    // config_files is ordered local -> global; reverse for global -> local
+    for cf in config.config_files.values().rev() {
+        if let Some(sys) = cf.bootstrap_config() {
+            merged.extend(sys.linux.kernel_modules);
+        }
+    }
+    let mut out = vec![];
+    for (name, enabled) in merged {
+        if !enabled {
+            continue;
+        }
+        match host::KernelModuleRequest::from_toml(name) {
+            Ok(request) => out.push(request),
+            Err(err) => warn!("[bootstrap.linux.kernel_modules]: {err}, ignoring entry"),
+        }
+    }
+    out
+}
+
+/// Aggregate `[bootstrap.linux.environment]` across all loaded config files.
+///
+/// Keys union global -> local; a more local config replaces the value.
+/// Invalid entries warn and are skipped.
+pub(crate) fn host_environment_from_config(config: &Config) -> Vec<host::EnvironmentRequest> {
+    let mut merged: IndexMap<String, String> = IndexMap::new();
+    // config_files is ordered local -> global; reverse for global -> local
+    for cf in config.config_files.values().rev() {
+        if let Some(sys) = cf.bootstrap_config() {
+            merged.extend(sys.linux.environment);
+        }
+    }
+    let mut out = vec![];
+    for (key, value) in merged {
+        match host::EnvironmentRequest::from_toml(key, value) {
+            Ok(request) => out.push(request),
+            Err(err) => warn!("[bootstrap.linux.environment]: {err}, ignoring entry"),
+        }
+    }
+    out
+}
+
+/// Aggregate `[bootstrap.certificates]` across all loaded config files.
+///
+/// Names union global -> local; a more local config replaces the entry, and
+/// `source` paths resolve relative to the declaring config file. Invalid
+/// entries warn and are skipped.
+pub(crate) fn certificates_from_config(config: &Config) -> Vec<certificates::CertificateRequest> {
+    let mut merged: IndexMap<String, (certificates::CertificateTomlConfig, PathBuf)> =
+        IndexMap::new();
+    // config_files is ordered local -> global; reverse for global -> local
+    for cf in config.config_files.values().rev() {
+        if let Some(sys) = cf.bootstrap_config() {
+            let base = cf
+                .get_path()
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .to_path_buf();
+            for (name, certificate) in sys.certificates {
+                merged.insert(name, (certificate, base.clone()));
+            }
+        }
+    }
+    let mut out = vec![];
+    for (name, (certificate, base)) in merged {
+        match certificates::CertificateRequest::from_toml(name, certificate, &base) {
+            Ok(request) => out.push(request),
+            Err(err) => warn!("[bootstrap.certificates]: {err:#}, ignoring entry"),
+        }
+    }
+    out
+}
+
+/// Aggregate `[bootstrap.ssh.known_hosts]` across all loaded config files.
+///
+/// Hosts union global -> local; a more local config replaces the keys for
+/// the same host. Invalid entries warn and are skipped.
+pub(crate) fn known_hosts_from_config(config: &Config) -> Vec<known_hosts::KnownHostRequest> {
+    let mut merged: IndexMap<String, known_hosts::KnownHostTomlValue> = IndexMap::new();
+    // config_files is ordered local -> global; reverse for global -> local
+    for cf in config.config_files.values().rev() {
+        if let Some(sys) = cf.bootstrap_config() {
+            merged.extend(sys.ssh.known_hosts);
+        }
+    }
+    let mut out: Vec<known_hosts::KnownHostRequest> = vec![];
+    for (host, keys) in merged {
+        match known_hosts::KnownHostRequest::from_toml(host, keys) {
+            // `[host]:22` and `host` name the same entry
+            Ok(request) => match out
+                .iter_mut()
+                .find(|existing| existing.host == request.host)
+            {
+                Some(existing) => *existing = request,
+                None => out.push(request),
+            },
+            Err(err) => warn!("[bootstrap.ssh.known_hosts]: {err}, ignoring entry"),
+        }
+    }
+    out
+}
+
 /// Desired login shell from the most local config that declares it.
 pub(crate) fn login_shell_from_config(config: &Config) -> Option<login_shell::LoginShellRequest> {
     let mut shell = None;
@@ -17,6 +17,9 @@ pub(super) struct Formula {
     pub tap: Option<String>,
     #[serde(default)]
     pub aliases: Vec<String>,
+    /// other major-version formulae of the same software (`postgresql@16`)
+    #[serde(default)]
+    pub versioned_formulae: Vec<String>,
     pub versions: Versions,
     #[serde(default)]
     pub revision: u32,
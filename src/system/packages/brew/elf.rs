@@ -55,10 +55,16 @@ pub(super) struct LinkageOpts {
 
 impl LinkageOpts {
     pub(super) fn for_formula(name: &str) -> Self {
+        Self::for_formula_in(name, &super::prefix::prefix())
+    }
+
+    /// Linkage for a keg poured under `prefix`, with its Cellar at
+    /// `<prefix>/Cellar`.
+    pub(super) fn for_formula_in(name: &str, prefix: &Path) -> Self {
         let is_gcc = name == "gcc" || name.starts_with("gcc@");
         LinkageOpts {
-            prefix: super::prefix::prefix().to_string_lossy().to_string(),
-            cellar: super::prefix::cellar().to_string_lossy().to_string(),
+            prefix: prefix.to_string_lossy().to_string(),
+            cellar: prefix.join("Cellar").to_string_lossy().to_string(),
             gcc_current: !is_gcc,
         }
     }
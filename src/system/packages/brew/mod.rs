@@ -37,10 +37,12 @@ mod relocate;
 mod resolve;
 mod source;
 mod tag;
+mod tool;
 
 pub(crate) struct BrewManager {}
 pub(crate) use cask::{BrewCaskManager, apply_cask_prune_plan, cask_prune_plan};
 pub(crate) use maintenance::{apply_prune_plan, default_tap_url, linked_formulae, prune_plan};
+pub(crate) use tool::{ToolBottle, install_tool, tool_bottle, tool_versions};
 
 impl BrewManager {
     pub(crate) fn new() -> Self {
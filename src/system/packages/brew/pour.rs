@@ -199,43 +199,8 @@ pub(super) async fn pour(
     let name = &rf.formula.name;
     let pkg_version = rf.formula.pkg_version()?;
     let keg = keg_path(name, &pkg_version);
-    let rack = keg.parent().unwrap().to_path_buf();
-    let tmp = rack.join(format!(".mise-tmp-{pkg_version}"));
-    let scratch = rack.join(format!(".mise-extract-{pkg_version}"));
-    for dir in [&tmp, &scratch] {
-        if dir.exists() {
-            crate::file::remove_all(dir)?;
-        }
-    }
-    crate::file::create_dir_all(&scratch)?;
-
-    // bottle tarballs contain <name>/<pkg_version>/...
-    pr.set_message("extract".to_string());
-    crate::file::untar(
-        tarball,
-        &scratch,
-        ExtractionFormat::TarGz,
-        &ExtractOptions {
-            strip_components: 0,
-            pr: Some(pr),
-            preserve_mtime: true,
-        },
-    )
-    .wrap_err_with(|| format!("failed to extract bottle for {name}"))?;
-    let inner = scratch.join(name).join(&pkg_version);
-    if !inner.exists() {
-        bail!("unexpected bottle layout for {name}: missing {name}/{pkg_version} in archive");
-    }
-    crate::file::rename(&inner, &tmp)?;
-    crate::file::remove_all(&scratch)?;
-
-    // ":any_skip_relocation" skips binary linkage relocation, but Homebrew
-    // still replaces placeholders in text files. On Linux, bottles built by
-    // Homebrew < 5.1.15 are incorrectly tagged and still need ELF linkage
-    // relocation (brew applies the same version check in
-    // extend/os/linux/bottle_specification.rb).
-    let skip_linkage = bottle.cellar == ":any_skip_relocation"
-        && (cfg!(target_os = "macos") || bottled_by_homebrew_at_least(&tmp, (5, 1, 15)));
+    let tmp = unpack_bottle(name, &pkg_version, tarball, keg.parent().unwrap(), pr)?;
+    let skip_linkage = skip_linkage_relocation(bottle, &tmp);
     pr.set_message("relocate".to_string());
     let report = relocate::relocate_keg(&tmp, name, skip_linkage)?;
     // arm64 macOS kills binaries whose signature doesn't match; Linux ELF
@@ -270,6 +235,57 @@ pub(super) async fn pour(
     Ok(())
 }
 
+/// Extract a bottle into a scratch keg next to its final location in `rack`
+/// (`<cellar>/<name>`) and return the scratch keg's path. The caller
+/// relocates it and renames it into place.
+pub(super) fn unpack_bottle(
+    name: &str,
+    pkg_version: &str,
+    tarball: &Path,
+    rack: &Path,
+    pr: &dyn SingleReport,
+) -> Result<PathBuf> {
+    let tmp = rack.join(format!(".mise-tmp-{pkg_version}"));
+    let scratch = rack.join(format!(".mise-extract-{pkg_version}"));
+    for dir in [&tmp, &scratch] {
+        if dir.exists() {
+            crate::file::remove_all(dir)?;
+        }
+    }
+    crate::file::create_dir_all(&scratch)?;
+
+    // bottle tarballs contain <name>/<pkg_version>/...
+    pr.set_message("extract".to_string());
+    crate::file::untar(
+        tarball,
+        &scratch,
+        ExtractionFormat::TarGz,
+        &ExtractOptions {
+            strip_components: 0,
+            pr: Some(pr),
+            preserve_mtime: true,
+        },
+    )
+    .wrap_err_with(|| format!("failed to extract bottle for {name}"))?;
+    let inner = scratch.join(name).join(pkg_version);
+    if !inner.exists() {
+        bail!("unexpected bottle layout for {name}: missing {name}/{pkg_version} in archive");
+    }
+    crate::file::rename(&inner, &tmp)?;
+    crate::file::remove_all(&scratch)?;
+    Ok(tmp)
+}
+
+/// ":any_skip_relocation" skips binary linkage relocation, but Homebrew
+/// still replaces placeholders in text files. On Linux, bottles built by
+/// Homebrew < 5.1.15 are incorrectly tagged and still need ELF linkage
+/// relocation (brew applies the same version check in
+/// extend/os/linux/bottle_specification.rb).
+pub(super) fn skip_linkage_relocation(bottle: &BottleFile, keg: &Path) -> bool {
+    bottle.cellar == ":any_skip_relocation"
+        && (cfg!(target_os = "macos") || bottled_by_homebrew_at_least(keg, (5, 1, 15)))
+}
+
 /// Was this bottle built by Homebrew >= `min`? Read from the receipt the
 /// bottle ships with (brew calls it the tab), before we overwrite it with our
 /// own. This mirrors brew's own `parsed_homebrew_version >= "5.1.15"` check —
@@ -376,7 +392,7 @@ pub(super) fn write_receipt(
 }
 
 /// relative symlink target from `link` to `dest`
-fn relative_target(dest: &Path, link: &Path) -> PathBuf {
+pub(super) fn relative_target(dest: &Path, link: &Path) -> PathBuf {
     let link_dir = link.parent().unwrap();
     let mut common = 0;
     let dest_parts: Vec<_> = dest.components().collect();
@@ -67,19 +67,29 @@ pub(super) fn repository() -> PathBuf {
 /// Called before and after pours so a glibc poured in the current run
 /// repoints the symlink.
 pub(super) fn setup_linux_runtime() -> Result<()> {
+    let glibc_kegs = super::pour::installed_versions("glibc")
+        .into_iter()
+        .map(|version| cellar().join("glibc").join(version))
+        .collect::<Vec<_>>();
+    setup_linux_runtime_in(&prefix(), &glibc_kegs)
+}
+
+/// [`setup_linux_runtime`] for an arbitrary prefix. `glibc_kegs` are brewed
+/// glibc kegs to prefer over the host loader, best first.
+pub(super) fn setup_linux_runtime_in(prefix: &Path, glibc_kegs: &[PathBuf]) -> Result<()> {
     if !cfg!(target_os = "linux") {
         return Ok(());
     }
-    let lib = prefix().join("lib");
+    let lib = prefix.join("lib");
     crate::file::create_dir_all(&lib)?;
     let ld = lib.join("ld.so");
     // a brewed glibc keg takes precedence (hosts older than the bottles'
     // build glibc); otherwise the host loader
     let loader_names = ["ld-linux-x86-64.so.2", "ld-linux-aarch64.so.1"];
-    let brewed_glibc = super::pour::installed_versions("glibc")
-        .into_iter()
-        .filter_map(|version| {
-            let keg_lib = cellar().join("glibc").join(version).join("lib");
+    let brewed_glibc = glibc_kegs
+        .iter()
+        .filter_map(|keg| {
+            let keg_lib = keg.join("lib");
             loader_names
                 .iter()
                 .map(|name| keg_lib.join(name))
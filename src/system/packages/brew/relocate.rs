@@ -27,10 +27,14 @@ pub(super) struct Replacement {
 }
 
 pub(super) fn standard_replacements() -> Vec<Replacement> {
-    let prefix_buf = super::prefix::prefix();
-    let prefix = prefix_buf.to_string_lossy();
-    let repository_buf = super::prefix::repository();
-    let repository = repository_buf.to_string_lossy();
+    prefix_replacements(&super::prefix::prefix(), &super::prefix::repository())
+}
+
+/// Replacements for a keg poured under `prefix` (with its Cellar at
+/// `<prefix>/Cellar`) rather than the canonical Homebrew prefix.
+pub(super) fn prefix_replacements(prefix: &Path, repository: &Path) -> Vec<Replacement> {
+    let prefix = prefix.to_string_lossy();
+    let repository = repository.to_string_lossy();
     let macos = cfg!(target_os = "macos");
     vec![
         Replacement {
@@ -200,7 +204,30 @@ pub(super) fn relocate_keg(
     formula_name: &str,
     skip_linkage: bool,
 ) -> Result<RelocationReport> {
-    relocate_keg_with_replacements(keg, formula_name, skip_linkage, &standard_replacements())
+    relocate_keg_with_replacements(
+        keg,
+        formula_name,
+        skip_linkage,
+        &standard_replacements(),
+        &super::elf::LinkageOpts::for_formula(formula_name),
+    )
+}
+
+/// [`relocate_keg`] for a keg poured under a private `prefix` instead of
+/// the canonical one — the `brew:` tool backend's per-version installs.
+pub(super) fn relocate_keg_in(
+    keg: &Path,
+    formula_name: &str,
+    skip_linkage: bool,
+    prefix: &Path,
+) -> Result<RelocationReport> {
+    relocate_keg_with_replacements(
+        keg,
+        formula_name,
+        skip_linkage,
+        &prefix_replacements(prefix, prefix),
+        &super::elf::LinkageOpts::for_formula_in(formula_name, prefix),
+    )
 }
 
 fn relocate_keg_with_replacements(
@@ -208,8 +235,8 @@ fn relocate_keg_with_replacements(
     formula_name: &str,
     skip_linkage: bool,
     replacements: &[Replacement],
+    elf_opts: &super::elf::LinkageOpts,
 ) -> Result<RelocationReport> {
-    let elf_opts = super::elf::LinkageOpts::for_formula(formula_name);
     // brew never patches glibc's own files — rewriting the dynamic linker
     // breaks it (extend/os/linux/keg_relocate.rb)
     let patch_elf = formula_name != "glibc" && !formula_name.starts_with("glibc@");
@@ -262,7 +289,7 @@ fn relocate_keg_with_replacements(
             // placeholder copies in abandoned string tables are unreferenced.
             if patch_elf {
                 let mut content = content;
-                if super::elf::patch(&mut content, &elf_opts, path)? {
+                if super::elf::patch(&mut content, elf_opts, path)? {
                     crate::file::write(path, &content)?;
                     report.changed_files.push(path.to_path_buf());
                 }
@@ -356,8 +383,13 @@ pub(super) mod tests {
         crate::file::write(&binary, &binary_content)?;
         std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o444))?;
 
-        let report =
-            relocate_keg_with_replacements(tmp.path(), "formula", true, &test_replacements())?;
+        let report = relocate_keg_with_replacements(
+            tmp.path(),
+            "formula",
+            true,
+            &test_replacements(),
+            &super::super::elf::LinkageOpts::for_formula("formula"),
+        )?;
 
         assert_eq!(
             crate::file::read_to_string(&text)?,
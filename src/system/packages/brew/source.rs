@@ -461,6 +461,7 @@ mod tests {
             name: "test".to_string(),
             tap: None,
             aliases: vec![],
+            versioned_formulae: vec![],
             versions: Versions {
                 stable: Some("1.0.0".to_string()),
             },
@@ -55,6 +55,30 @@ pub(super) fn candidates() -> Vec<String> {
     tags
 }
 
+/// Bottle tags for a mise platform other than this machine, in preference
+/// order — used for lockfile entries. A remote Mac's OS version is unknown,
+/// so the oldest macOS tag is preferred: it runs on every newer macOS, so
+/// whichever Mac installs from the lockfile can pour the locked bottle.
+pub(super) fn candidates_for(os: &str, arch: &str) -> Vec<String> {
+    let mut tags: Vec<String> = match (os, arch) {
+        ("macos", "arm64") => MACOS_VERSIONS
+            .iter()
+            .rev()
+            .map(|(_, name)| format!("arm64_{name}"))
+            .collect(),
+        ("macos", _) => MACOS_VERSIONS
+            .iter()
+            .rev()
+            .map(|(_, name)| name.to_string())
+            .collect(),
+        ("linux", "arm64") => vec!["arm64_linux".to_string()],
+        ("linux", _) => vec!["x86_64_linux".to_string()],
+        _ => vec![],
+    };
+    tags.push("all".to_string());
+    tags
+}
+
 /// Pick the best bottle for this machine from a formula's `files` map.
 /// Returns the tag and the bottle entry.
 pub(super) fn select(files: &HashMap<String, BottleFile>) -> Option<(String, &BottleFile)> {
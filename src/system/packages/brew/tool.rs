@@ -0,0 +1,403 @@
+//! Bottles poured into a private prefix for the `brew:` tool backend.
+//!
+//! Each tool version gets its own miniature Homebrew prefix — mise's install
+//! directory for it. Kegs for the formula and its runtime dependencies live
+//! in `<prefix>/Cellar/<name>/<version>`, `<prefix>/opt/<name>` points at
+//! each, and the requested formula is linked into `<prefix>/bin`, `lib`,
+//! `include`, …. Placeholders are relocated to that prefix with the same
+//! code bootstrap pours use, so nothing touches the global Homebrew prefix.
+//!
+//! Only relocatable bottles are poured here; formulae that would need a
+//! source build, or whose bottles hardcode the global Homebrew cellar, are
+//! rejected.
+
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, eyre};
+
+use super::api::{self, BottleFile, Formula};
+use super::resolve::{self, ResolvedFormula};
+use super::{fetch, pour, prefix, relocate, tag};
+use crate::file;
+use crate::result::Result;
+use crate::system::packages::PackageRequest;
+use crate::ui::progress_report::SingleReport;
+
+/// The bottle a tool version resolves to for one platform.
+#[derive(Debug, Clone)]
+pub(crate) struct ToolBottle {
+    pub url: String,
+    pub sha256: String,
+}
+
+/// The formula and its versioned siblings (`postgresql`, `postgresql@16`, …).
+async fn formula_family(name: &str) -> Result<Vec<Formula>> {
+    let formula = api::formula(name).await?;
+    let mut family = vec![];
+    for versioned in &formula.versioned_formulae {
+        match api::formula(versioned).await {
+            Ok(f) => family.push(f),
+            // a versioned formula can be deleted before the main formula's
+            // JSON is regenerated
+            Err(err) => debug!("brew: skipping {versioned}: {err}"),
+        }
+    }
+    family.push(formula);
+    Ok(family)
+}
+
+/// Stable versions across a formula family.
+fn family_versions(family: &[Formula]) -> Vec<String> {
+    let mut versions: Vec<String> = family
+        .iter()
+        .filter_map(|f| f.versions.stable.clone())
+        .collect();
+    versions.sort();
+    versions.dedup();
+    versions
+}
+
+/// The family member whose current version is `version`. Bottles are only
+/// published for a formula's current version, so older releases of the same
+/// major can't be installed.
+fn family_member(family: Vec<Formula>, name: &str, version: &str) -> Result<Formula> {
+    let available = family_versions(&family);
+    family
+        .into_iter()
+        .find(|f| f.versions.stable.as_deref() == Some(version))
+        .ok_or_else(|| {
+            eyre!(
+                "brew: {name}@{version} is not available — Homebrew only publishes bottles for \
+                 each formula's current version: {}",
+                available.join(", ")
+            )
+        })
+}
+
+/// Versions installable for `name`: the formula's current version plus the
+/// current version of each of its versioned formulae.
+pub(crate) async fn tool_versions(name: &str) -> Result<Vec<String>> {
+    Ok(family_versions(&formula_family(name).await?))
+}
+
+/// The bottle `name@version` resolves to on `os`/`arch` (mise platform
+/// names), for lockfile entries. `host` is set when that platform is this
+/// machine, which then locks the same bottle [`install_tool`] pours.
+pub(crate) async fn tool_bottle(
+    name: &str,
+    version: &str,
+    os: &str,
+    arch: &str,
+    host: bool,
+) -> Result<ToolBottle> {
+    let formula = family_member(formula_family(name).await?, name, version)?;
+    let files = formula
+        .bottle_files()
+        .ok_or_else(|| eyre!("brew: {} has no bottles", formula.name))?;
+    let tags = if host {
+        tag::candidates()
+    } else {
+        tag::candidates_for(os, arch)
+    };
+    let Some(bottle) = tags.iter().find_map(|tag| files.get(tag)) else {
+        bail!("brew: {} has no bottle for {os}-{arch}", formula.name);
+    };
+    ensure_relocatable(&formula.name, bottle)?;
+    Ok(ToolBottle {
+        url: bottle.url.clone(),
+        sha256: bottle.sha256.clone(),
+    })
+}
+
+/// Bottles built with a pinned cellar hardcode the global Homebrew prefix
+/// and break when poured anywhere else.
+fn ensure_relocatable(name: &str, bottle: &BottleFile) -> Result<()> {
+    if !matches!(bottle.cellar.as_str(), ":any" | ":any_skip_relocation") {
+        bail!(
+            "brew: the {name} bottle is built for {} and can't be poured into a private prefix; \
+             install it with `[bootstrap.packages]` instead",
+            bottle.cellar
+        );
+    }
+    Ok(())
+}
+
+/// The bottle of the requested formula: the one with the `locked` sha256 if
+/// the lockfile has one for this platform, else the best one for this
+/// machine. Picking it by checksum means the download is verified against
+/// the lockfile before anything is poured.
+fn requested_bottle<'a>(
+    formula: &'a Formula,
+    locked: Option<&str>,
+) -> Result<Option<&'a BottleFile>> {
+    let Some(files) = formula.bottle_files() else {
+        return Ok(None);
+    };
+    let Some(locked) = locked else {
+        return Ok(tag::select(files).map(|(_, bottle)| bottle));
+    };
+    if let Some(bottle) = tag::candidates()
+        .iter()
+        .filter_map(|tag| files.get(tag))
+        .find(|bottle| bottle.sha256 == locked)
+    {
+        return Ok(Some(bottle));
+    }
+    match tag::select(files) {
+        Some((tag, bottle)) => bail!(
+            "brew: {} bottle checksum sha256:{} ({tag}) does not match the lockfile checksum \
+             sha256:{locked}; the formula was rebuilt or the locked bottle doesn't run on this \
+             machine, run `mise lock` to update it",
+            formula.name,
+            bottle.sha256
+        ),
+        None => Ok(None),
+    }
+}
+
+/// Pour `name@version` and its runtime dependencies into `prefix`. `locked`
+/// is the requested formula's sha256 from the lockfile. Returns the
+/// requested formula's bottle.
+pub(crate) async fn install_tool(
+    name: &str,
+    version: &str,
+    prefix: &Path,
+    locked: Option<&str>,
+    pr: &dyn SingleReport,
+) -> Result<ToolBottle> {
+    let formula = family_member(formula_family(name).await?, name, version)?;
+    let closure = resolve::resolve_closure_with_taps(&[PackageRequest {
+        name: formula.name.clone(),
+        version: None,
+        tap_url: None,
+    }])
+    .await?;
+    // check every bottle exists before downloading anything
+    let mut bottles = vec![];
+    for rf in &closure {
+        let bottle = if rf.on_request {
+            requested_bottle(&rf.formula, locked)?
+        } else {
+            rf.formula
+                .bottle_files()
+                .and_then(tag::select)
+                .map(|(_, bottle)| bottle)
+        };
+        let Some(bottle) = bottle else {
+            bail!(
+                "brew: {} has no bottle for this platform; the brew backend does not build \
+                 from source",
+                rf.formula.name
+            );
+        };
+        ensure_relocatable(&rf.formula.name, bottle)?;
+        bottles.push((rf, bottle.clone()));
+    }
+
+    let cellar = prefix.join("Cellar");
+    let mut installed = None;
+    // closure is in install order: dependencies first
+    for (rf, bottle) in bottles {
+        let name = &rf.formula.name;
+        let pkg_version = rf.formula.pkg_version()?;
+        let tarball = fetch::fetch_bottle(name, &pkg_version, &bottle, Some(pr)).await?;
+        let rack = cellar.join(name);
+        file::create_dir_all(&rack)?;
+        let tmp = pour::unpack_bottle(name, &pkg_version, &tarball, &rack, pr)?;
+        let skip_linkage = pour::skip_linkage_relocation(&bottle, &tmp);
+        pr.set_message(format!("relocate {name}"));
+        let report = relocate::relocate_keg_in(&tmp, name, skip_linkage, prefix)?;
+        if cfg!(target_os = "macos") && !report.changed_machos.is_empty() {
+            pr.set_message("codesign".to_string());
+            relocate::codesign(&report.changed_machos)?;
+        }
+        let keg = rack.join(&pkg_version);
+        if keg.exists() {
+            file::remove_all(&keg)?;
+        }
+        file::rename(&tmp, &keg)?;
+        link_keg_in(prefix, rf, &keg)?;
+        if rf.on_request {
+            installed = Some(ToolBottle {
+                url: bottle.url.clone(),
+                sha256: bottle.sha256.clone(),
+            });
+        }
+    }
+    prefix::setup_linux_runtime_in(prefix, &glibc_kegs(&cellar)?)?;
+    installed.ok_or_else(|| eyre!("brew: {name} was not part of its own dependency closure"))
+}
+
+/// glibc kegs poured into this prefix, newest first.
+fn glibc_kegs(cellar: &Path) -> Result<Vec<PathBuf>> {
+    let rack = cellar.join("glibc");
+    if !rack.exists() {
+        return Ok(vec![]);
+    }
+    Ok(file::dir_subdirs(&rack)?
+        .into_iter()
+        .rev()
+        .map(|version| rack.join(version))
+        .collect())
+}
+
+/// Link a keg into a private prefix: `opt/<name>` always, and the keg's
+/// contents for the requested formula and non-keg-only dependencies. Only
+/// the requested formula's `bin`/`sbin` are linked, so a dependency's
+/// executables don't end up on PATH. The first formula to claim a path keeps
+/// it.
+fn link_keg_in(prefix: &Path, rf: &ResolvedFormula, keg: &Path) -> Result<()> {
+    let name = &rf.formula.name;
+    let opt = prefix.join("opt").join(name);
+    file::create_dir_all(opt.parent().unwrap())?;
+    if opt.symlink_metadata().is_ok() {
+        file::remove_file(&opt)?;
+    }
+    file::make_symlink(&pour::relative_target(keg, &opt), &opt)?;
+
+    if rf.formula.keg_only && !rf.on_request {
+        return Ok(());
+    }
+    for dir in pour::LINK_DIRS {
+        if !rf.on_request && matches!(*dir, "bin" | "sbin") {
+            continue;
+        }
+        let src_root = keg.join(dir);
+        if !src_root.exists() {
+            continue;
+        }
+        for entry in walkdir::WalkDir::new(&src_root).follow_links(false) {
+            let entry = entry?;
+            if entry.file_type().is_dir() {
+                continue;
+            }
+            let dest = prefix.join(entry.path().strip_prefix(keg)?);
+            if dest.symlink_metadata().is_ok() {
+                debug!("brew: {} already linked, skipping", dest.display());
+                continue;
+            }
+            file::create_dir_all(dest.parent().unwrap())?;
+            file::make_symlink(&pour::relative_target(entry.path(), &dest), &dest)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formula(name: &str, version: &str, keg_only: bool) -> Formula {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "versions": { "stable": version },
+            "keg_only": keg_only,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_family_member() {
+        let family = || {
+            vec![
+                formula("postgresql@15", "15.8", false),
+                formula("postgresql@16", "16.4", false),
+                formula("postgresql@17", "17.0", false),
+            ]
+        };
+        assert_eq!(family_versions(&family()), vec!["15.8", "16.4", "17.0"]);
+        assert_eq!(
+            family_member(family(), "postgresql@17", "16.4")
+                .unwrap()
+                .name,
+            "postgresql@16"
+        );
+        let err = family_member(family(), "postgresql@17", "16.3")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("15.8, 16.4, 17.0"), "{err}");
+    }
+
+    fn bottled(files: serde_json::Value) -> Formula {
+        serde_json::from_value(serde_json::json!({
+            "name": "libpq",
+            "versions": { "stable": "17.0" },
+            "bottle": { "stable": { "files": files } },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_requested_bottle() {
+        let f = bottled(serde_json::json!({
+            "all": { "cellar": ":any", "url": "https://ghcr.io/all", "sha256": "aaa" },
+        }));
+        assert_eq!(requested_bottle(&f, None).unwrap().unwrap().sha256, "aaa");
+        assert_eq!(
+            requested_bottle(&f, Some("aaa")).unwrap().unwrap().sha256,
+            "aaa"
+        );
+        let err = requested_bottle(&f, Some("bbb")).unwrap_err().to_string();
+        assert!(
+            err.contains("does not match the lockfile checksum sha256:bbb"),
+            "{err}"
+        );
+        let f = bottled(serde_json::json!({}));
+        assert!(requested_bottle(&f, Some("aaa")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_ensure_relocatable() {
+        let bottle = |cellar: &str| BottleFile {
+            cellar: cellar.to_string(),
+            url: String::new(),
+            sha256: String::new(),
+        };
+        assert!(ensure_relocatable("libpq", &bottle(":any")).is_ok());
+        assert!(ensure_relocatable("libpq", &bottle(":any_skip_relocation")).is_ok());
+        let err = ensure_relocatable("libpq", &bottle("/opt/homebrew/Cellar"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("/opt/homebrew/Cellar"), "{err}");
+    }
+
+    #[test]
+    fn test_link_keg_in() {
+        let tmp = tempfile::tempdir().unwrap();
+        let prefix = tmp.path();
+        let keg = |name: &str, files: &[&str]| {
+            let keg = prefix.join("Cellar").join(name).join("1.0");
+            for f in files {
+                file::create_dir_all(keg.join(f).parent().unwrap()).unwrap();
+                file::write(keg.join(f), "").unwrap();
+            }
+            keg
+        };
+        let rf = |name: &str, keg_only: bool, on_request: bool| ResolvedFormula {
+            formula: formula(name, "1.0", keg_only),
+            tap_raw_base: None,
+            on_request,
+        };
+
+        let ssl = keg("openssl@3", &["bin/openssl", "lib/libssl.so.3"]);
+        link_keg_in(prefix, &rf("openssl@3", false, false), &ssl).unwrap();
+        let readline = keg("readline", &["lib/libreadline.so"]);
+        link_keg_in(prefix, &rf("readline", true, false), &readline).unwrap();
+        let libpq = keg("libpq", &["bin/psql", "lib/libpq.so.5"]);
+        link_keg_in(prefix, &rf("libpq", true, true), &libpq).unwrap();
+
+        for name in ["openssl@3", "readline", "libpq"] {
+            assert!(prefix.join("opt").join(name).join("lib").exists(), "{name}");
+        }
+        assert_eq!(
+            std::fs::read_link(prefix.join("opt/libpq")).unwrap(),
+            Path::new("../Cellar/libpq/1.0")
+        );
+        assert!(prefix.join("bin/psql").exists());
+        assert!(prefix.join("lib/libpq.so.5").exists());
+        assert!(prefix.join("lib/libssl.so.3").exists());
+        // dependency executables stay off PATH; keg-only deps stay unlinked
+        assert!(!prefix.join("bin/openssl").exists());
+        assert!(!prefix.join("lib/libreadline.so").exists());
+    }
+}
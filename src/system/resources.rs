@@ -7,13 +7,13 @@ use base64::Engine;
 use base64::prelude::BASE64_URL_SAFE_NO_PAD;
 use eyre::{Result, bail};
 use indexmap::{IndexMap, IndexSet};
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 
 use crate::config::Config;
 use crate::system::packages::{PackageRequest, PackageState};
 
 /// Stable identity for one declarative bootstrap resource.
-#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub(crate) struct ResourceId {
     pub kind: String,
     pub name: String,
@@ -103,7 +103,7 @@ impl fmt::Display for ResourceId {
 }
 
 /// The operation needed to converge a resource.
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum ResourceAction {
     Create,
@@ -351,6 +351,14 @@ pub(crate) async fn plan(
             )?;
         }
     }
+    let mut certificate_ids = vec![];
+    for resource in super::certificates::plans(&super::certificates_from_config(config)) {
+        certificate_ids.push(resource.id.clone());
+        plan.insert(resource)?;
+    }
+    for resource in super::known_hosts::plans(&super::known_hosts_from_config(config)) {
+        plan.insert(resource)?;
+    }
     for manager_packages in super::packages_from_config(config) {
         let manager = manager_packages.manager;
         let manager_name = manager.name().to_string();
@@ -398,6 +406,18 @@ pub(crate) async fn plan(
             ))?;
         }
     }
+    // package mirrors may be signed by a configured CA
+    let package_ids = plan
+        .resources
+        .keys()
+        .filter(|id| id.kind == "package")
+        .cloned()
+        .collect::<Vec<_>>();
+    for id in &package_ids {
+        for dependency in &certificate_ids {
+            plan.add_dependency(id, dependency.clone())?;
+        }
+    }
     let (files, directories, unavailable_files) =
         super::managed_files::status_requests_from_config(config, secrets)?;
     super::managed_files::validate_principals(
@@ -519,10 +539,43 @@ pub(crate) async fn plan(
     for resource in unavailable_files {
         plan.insert(resource)?;
     }
+    let kernel_modules = super::kernel_modules_from_config(config);
+    let mut module_ids = vec![];
+    for resource in super::host::kernel_module_plans(&kernel_modules) {
+        let id = resource.id.clone();
+        plan.insert(resource)?;
+        // packages may ship the module, e.g. DKMS builds
+        for dependency in &package_ids {
+            plan.add_dependency(&id, dependency.clone())?;
+        }
+        module_ids.push(id);
+    }
+    let modules_pending = super::host::kernel_modules_pending(&kernel_modules);
+    for resource in super::host::sysctl_plans(&super::sysctl_from_config(config), modules_pending) {
+        let id = resource.id.clone();
+        plan.insert(resource)?;
+        for dependency in &module_ids {
+            plan.add_dependency(&id, dependency.clone())?;
+        }
+    }
+    for resource in super::host::environment_plans(&super::host_environment_from_config(config)) {
+        plan.insert(resource)?;
+    }
     let service_dependencies = plan
         .resources
         .keys()
-        .filter(|id| matches!(id.kind.as_str(), "package" | "file" | "directory"))
+        .filter(|id| {
+            matches!(
+                id.kind.as_str(),
+                "package"
+                    | "file"
+                    | "directory"
+                    | "certificate"
+                    | "kernel-module"
+                    | "sysctl"
+                    | "environment"
+            )
+        })
         .cloned()
         .collect::<Vec<_>>();
     for resource in super::services::plans_with_notifications(&services, &notified_services) {
@@ -61,6 +61,15 @@ pub(crate) struct ServiceRequest {
     inspection: Option<ServiceInspection>,
 }
 
+/// A service's state before a bootstrap apply, kept in the rollback journal.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct ServicePreImage {
+    pub unit: String,
+    pub state: ServiceState,
+    pub enabled: bool,
+    pub masked: bool,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub(crate) struct ServiceNotifications {
     sources: IndexMap<String, IndexSet<ResourceId>>,
@@ -396,6 +405,43 @@ impl ServiceRequest {
         }
     }
 
+    /// The inspected state as a pre-image, or `None` when the unit is missing
+    /// or could not be inspected.
+    pub(crate) fn pre_image(&self) -> Option<ServicePreImage> {
+        let Some(ServiceInspection::Present {
+            active_state,
+            unit_file_state,
+            ..
+        }) = &self.inspection
+        else {
+            return None;
+        };
+        Some(ServicePreImage {
+            unit: self.unit.clone(),
+            state: if active_state_matches(ServiceState::Running, active_state) {
+                ServiceState::Running
+            } else {
+                ServiceState::Stopped
+            },
+            enabled: unit_file_state_is_enabled(unit_file_state),
+            masked: unit_file_state_is_masked(unit_file_state),
+        })
+    }
+
+    /// A request that puts a service back into its journaled state.
+    pub(crate) fn from_pre_image(name: String, pre_image: &ServicePreImage) -> Self {
+        Self {
+            name,
+            unit: pre_image.unit.clone(),
+            state: pre_image.state,
+            enabled: pre_image.enabled,
+            masked: pre_image.masked,
+            on_change: ServiceChangeAction::None,
+            origin: None,
+            inspection: None,
+        }
+    }
+
     fn is_active(&self) -> bool {
         matches!(
             &self.inspection,
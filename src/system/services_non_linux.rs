@@ -5,7 +5,7 @@ use indexmap::{IndexMap, IndexSet};
 use serde::{Deserialize, Serialize};
 
 use crate::config::{Config, ConfigMap};
-use crate::system::resources::{ResourceId, ResourceOrigin, ResourcePlan};
+use crate::system::resources::{ResourceAction, ResourceId, ResourceOrigin, ResourcePlan};
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -39,7 +39,34 @@ pub(crate) struct ServiceTomlConfig {
 
 #[derive(Clone, Debug)]
 pub(crate) struct ServiceRequest {
-    name: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct ServicePreImage {
+    pub unit: String,
+    pub state: ServiceState,
+    pub enabled: bool,
+    pub masked: bool,
+}
+
+impl ServiceRequest {
+    pub(crate) fn pre_image(&self) -> Option<ServicePreImage> {
+        None
+    }
+
+    pub(crate) fn plan(&self) -> ResourcePlan {
+        ResourcePlan::new(
+            ResourceId::new("service", &self.name),
+            "unsupported platform",
+            "configured Linux service",
+            ResourceAction::Unknown,
+        )
+    }
+
+    pub(crate) fn from_pre_image(name: String, _pre_image: &ServicePreImage) -> Self {
+        Self { name }
+    }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
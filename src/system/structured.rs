@@ -0,0 +1,860 @@
+//! Format-preserving key edits to JSON, TOML and YAML files, used by `set`
+//! edit entries in [`super::edits`].
+//!
+//! Values are read by parsing the whole file, but written by splicing the
+//! edited keys into the original text, so comments, key order and
+//! indentation everywhere else survive. JSON files may contain comments and
+//! trailing commas (JSONC, like VS Code's settings.json). YAML edits are
+//! limited to block mappings: a flow mapping (`{a: 1}`) or a sequence along
+//! the edited key path is refused rather than rewritten.
+
+use std::ops::Range;
+use std::path::Path;
+
+use eyre::{Result, bail, eyre};
+use serde::Serialize;
+use serde_json::Value;
+use toml_edit::{Item, TableLike};
+
+/// file format of a `set` edit's target
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum Format {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" | "jsonc" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        Self::from_name(path.extension()?.to_str()?)
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Toml => "TOML",
+            Self::Yaml => "YAML",
+        }
+    }
+}
+
+/// dotted display of a key path for messages; keys containing dots are quoted
+pub(crate) fn display_key(path: &[String]) -> String {
+    path.iter()
+        .map(|key| {
+            if key.contains('.') || key.is_empty() {
+                format!("{key:?}")
+            } else {
+                key.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The value at `path`, or None when a key along it is absent.
+pub(crate) fn get(format: Format, text: &str, path: &[String]) -> Result<Option<Value>> {
+    let mut value = &parse(format, text)?;
+    for key in path {
+        match value.get(key) {
+            Some(child) => value = child,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(value.clone()))
+}
+
+/// `text` with `path` set to `value`, creating missing parent tables.
+pub(crate) fn set(format: Format, text: &str, path: &[String], value: &Value) -> Result<String> {
+    match format {
+        Format::Json => json_set(text, path, value),
+        Format::Toml => toml_set(text, path, value),
+        Format::Yaml => yaml_set(text, path, value),
+    }
+}
+
+/// `text` without `path`; unchanged when the key is already absent.
+pub(crate) fn remove(format: Format, text: &str, path: &[String]) -> Result<String> {
+    match format {
+        Format::Json => json_remove(text, path),
+        Format::Toml => toml_remove(text, path),
+        Format::Yaml => yaml_remove(text, path),
+    }
+}
+
+fn parse(format: Format, text: &str) -> Result<Value> {
+    if text.trim().is_empty() {
+        return Ok(Value::Object(Default::default()));
+    }
+    Ok(match format {
+        Format::Json => parse_json(text)?.value,
+        Format::Toml => toml_to_json(toml::Value::Table(toml::from_str(text)?)),
+        Format::Yaml => match serde_yaml::from_str(text)? {
+            // a YAML file holding only comments is an empty document
+            Value::Null => Value::Object(Default::default()),
+            value => value,
+        },
+    })
+}
+
+/// Convert a config value to the JSON data model edits compare with. TOML
+/// datetimes have no JSON counterpart and become strings.
+pub(crate) fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(arr) => Value::Array(arr.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// leading whitespace of the line containing `pos`
+fn line_indent(text: &str, pos: usize) -> &str {
+    let start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// the file's indentation step: its smallest indentation, or two spaces
+fn indent_unit(text: &str) -> String {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+        .filter(|indent| !indent.is_empty())
+        .min_by_key(|indent| indent.len())
+        .map_or_else(|| "  ".to_string(), |indent| indent.to_string())
+}
+
+// --- JSON ---
+
+/// a parsed JSON value with the byte spans needed to splice edits into the
+/// original text
+struct JsonNode {
+    value: Value,
+    span: Range<usize>,
+    /// object members in file order; empty for anything but objects
+    members: Vec<JsonMember>,
+}
+
+struct JsonMember {
+    key: String,
+    key_start: usize,
+    value: JsonNode,
+    /// position of the comma following the value
+    comma: Option<usize>,
+}
+
+fn parse_json(text: &str) -> Result<JsonNode> {
+    let mut scanner = JsonScanner { text, pos: 0 };
+    let node = scanner.node()?;
+    scanner.skip_trivia()?;
+    if scanner.pos < text.len() {
+        return Err(scanner.error("unexpected content after the top-level value"));
+    }
+    Ok(node)
+}
+
+/// recursive descent over JSON plus `//` and `/* */` comments and trailing
+/// commas
+struct JsonScanner<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl JsonScanner<'_> {
+    fn error(&self, msg: &str) -> eyre::Report {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        eyre!("{msg} at line {line}")
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_trivia(&mut self) -> Result<()> {
+        loop {
+            let rest = &self.text[self.pos..];
+            if rest.starts_with([' ', '\t', '\n', '\r']) {
+                self.pos += 1;
+            } else if rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                match comment.find("*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => return Err(self.error("unterminated comment")),
+                }
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn node(&mut self) -> Result<JsonNode> {
+        self.skip_trivia()?;
+        let start = self.pos;
+        let (value, members) = match self.peek() {
+            Some(b'{') => self.object()?,
+            Some(b'[') => (self.array()?, vec![]),
+            Some(b'"') => (Value::String(self.string()?), vec![]),
+            Some(_) => {
+                let len = self.text[start..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)))
+                    .unwrap_or(self.text.len() - start);
+                self.pos += len;
+                let value = serde_json::from_str(&self.text[start..self.pos])
+                    .map_err(|_| self.error("invalid value"))?;
+                (value, vec![])
+            }
+            None => return Err(self.error("unexpected end of file")),
+        };
+        Ok(JsonNode {
+            value,
+            span: start..self.pos,
+            members,
+        })
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => break,
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+        self.pos += 1;
+        serde_json::from_str(&self.text[start..self.pos]).map_err(|_| self.error("invalid string"))
+    }
+
+    fn object(&mut self) -> Result<(Value, Vec<JsonMember>)> {
+        self.pos += 1;
+        let mut map = serde_json::Map::new();
+        let mut members: Vec<JsonMember> = vec![];
+        loop {
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b'}') => break,
+                Some(b'"') if members.last().is_none_or(|m| m.comma.is_some()) => {
+                    let key_start = self.pos;
+                    let key = self.string()?;
+                    self.skip_trivia()?;
+                    if self.peek() != Some(b':') {
+                        return Err(self.error("expected ':'"));
+                    }
+                    self.pos += 1;
+                    let value = self.node()?;
+                    self.skip_trivia()?;
+                    let comma = (self.peek() == Some(b',')).then_some(self.pos);
+                    if comma.is_some() {
+                        self.pos += 1;
+                    }
+                    map.insert(key.clone(), value.value.clone());
+                    members.push(JsonMember {
+                        key,
+                        key_start,
+                        value,
+                        comma,
+                    });
+                }
+                _ => return Err(self.error("expected '}' or a key")),
+            }
+        }
+        self.pos += 1;
+        Ok((Value::Object(map), members))
+    }
+
+    fn array(&mut self) -> Result<Value> {
+        self.pos += 1;
+        let mut items = vec![];
+        let mut separated = true;
+        loop {
+            self.skip_trivia()?;
+            match self.peek() {
+                Some(b']') => break,
+                Some(_) if separated => {
+                    items.push(self.node()?.value);
+                    self.skip_trivia()?;
+                    separated = self.peek() == Some(b',');
+                    if separated {
+                        self.pos += 1;
+                    }
+                }
+                _ => return Err(self.error("expected ']' or ','")),
+            }
+        }
+        self.pos += 1;
+        Ok(Value::Array(items))
+    }
+}
+
+/// pretty-print `value` for insertion on a line indented by `indent`
+fn json_render(value: &Value, indent: &str, unit: &str) -> Result<String> {
+    let mut buf = vec![];
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(unit.as_bytes());
+    value.serialize(&mut serde_json::Serializer::with_formatter(
+        &mut buf, formatter,
+    ))?;
+    Ok(String::from_utf8(buf)?.replace('\n', &format!("\n{indent}")))
+}
+
+/// the deepest node along `path` and how many keys of it exist
+fn json_descend<'a>(root: &'a JsonNode, path: &[String]) -> (&'a JsonNode, usize) {
+    let mut node = root;
+    let mut depth = 0;
+    for key in path {
+        // duplicate keys: the last one wins, as in every JSON parser
+        match node.members.iter().rfind(|m| &m.key == key) {
+            Some(member) => node = &member.value,
+            None => break,
+        }
+        depth += 1;
+    }
+    (node, depth)
+}
+
+fn json_set(text: &str, path: &[String], value: &Value) -> Result<String> {
+    let mut text = if text.trim().is_empty() {
+        "{}\n".to_string()
+    } else {
+        text.to_string()
+    };
+    let root = parse_json(&text)?;
+    let unit = indent_unit(&text);
+    let (node, depth) = json_descend(&root, path);
+    if depth == path.len() {
+        let indent = line_indent(&text, node.span.start).to_string();
+        text.replace_range(node.span.clone(), &json_render(value, &indent, &unit)?);
+        return Ok(text);
+    }
+    if !node.value.is_object() {
+        bail!("{} is not an object", display_key(&path[..depth]));
+    }
+    // missing parents are inserted as one nested object
+    let value = path[depth + 1..]
+        .iter()
+        .rev()
+        .fold(value.clone(), |value, key| {
+            Value::Object([(key.clone(), value)].into_iter().collect())
+        });
+    let key = serde_json::to_string(&path[depth])?;
+    match node.members.last() {
+        Some(last) if is_first_on_line(&text, last.key_start) => {
+            let indent = line_indent(&text, last.key_start).to_string();
+            let member = format!("{key}: {}", json_render(&value, &indent, &unit)?);
+            let value_end = last.value.span.end;
+            let after = last.comma.map_or(value_end, |comma| comma + 1);
+            // keep a comment trailing the last member on its line
+            let line_end = text[after..].find('\n').map_or(text.len(), |i| after + i);
+            let rest = text[after..line_end].trim();
+            let pos = if rest.is_empty() || rest.starts_with("//") {
+                line_end
+            } else {
+                after
+            };
+            // follow the file's trailing comma style
+            let trailing = if last.comma.is_some() { "," } else { "" };
+            text.insert_str(pos, &format!("\n{indent}{member}{trailing}"));
+            if last.comma.is_none() {
+                text.insert(value_end, ',');
+            }
+        }
+        Some(last) => {
+            let member = format!("{key}: {}", serde_json::to_string(&value)?);
+            let value_end = last.value.span.end;
+            match last.comma {
+                Some(comma) => text.insert_str(comma + 1, &format!(" {member},")),
+                None => text.insert_str(value_end, &format!(", {member}")),
+            }
+        }
+        None => {
+            let parent = line_indent(&text, node.span.start).to_string();
+            let indent = format!("{parent}{unit}");
+            let member = format!("{key}: {}", json_render(&value, &indent, &unit)?);
+            let inner = node.span.start + 1..node.span.end - 1;
+            if text[inner.clone()].trim().is_empty() {
+                text.replace_range(inner, &format!("\n{indent}{member}\n{parent}"));
+            } else {
+                text.insert_str(inner.start, &format!("\n{indent}{member},"));
+            }
+        }
+    }
+    Ok(text)
+}
+
+fn json_remove(text: &str, path: &[String]) -> Result<String> {
+    let Some((key, parent)) = path.split_last() else {
+        return Ok(text.to_string());
+    };
+    if text.trim().is_empty() {
+        return Ok(text.to_string());
+    }
+    let root = parse_json(text)?;
+    let (node, depth) = json_descend(&root, parent);
+    if depth < parent.len() {
+        return Ok(text.to_string());
+    }
+    let Some(index) = node.members.iter().rposition(|m| &m.key == key) else {
+        return Ok(text.to_string());
+    };
+    let member = &node.members[index];
+    let mut text = text.to_string();
+    let mut start = member.key_start;
+    let mut end = member
+        .comma
+        .map_or(member.value.span.end, |comma| comma + 1);
+    if is_first_on_line(&text, start) {
+        start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[end..].find('\n').map_or(text.len(), |i| end + i);
+        let rest = text[end..line_end].trim();
+        if rest.is_empty() || rest.starts_with("//") {
+            end = (line_end + 1).min(text.len());
+        }
+    }
+    text.replace_range(start..end, "");
+    // the new last member must not keep a comma the file didn't have before
+    if member.comma.is_none()
+        && index > 0
+        && let Some(comma) = node.members[index - 1].comma
+    {
+        text.remove(comma);
+    }
+    Ok(text)
+}
+
+fn is_first_on_line(text: &str, pos: usize) -> bool {
+    let start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    text[start..pos].trim().is_empty()
+}
+
+// --- TOML ---
+
+fn toml_set(text: &str, path: &[String], value: &Value) -> Result<String> {
+    let mut doc = text.parse::<toml_edit::DocumentMut>()?;
+    let value = toml::Value::try_from(value)
+        .map_err(|err| eyre!("{} can't be written to TOML: {err}", display_key(path)))?;
+    let value = crate::config::config_file::mise_toml::toml_value_to_edit(value);
+    toml_set_in(doc.as_table_mut(), false, path, value)?;
+    Ok(doc.to_string())
+}
+
+fn toml_set_in(
+    table: &mut dyn TableLike,
+    inline: bool,
+    path: &[String],
+    value: toml_edit::Value,
+) -> Result<()> {
+    let (key, rest) = path.split_first().expect("key path");
+    if rest.is_empty() {
+        match table.get_mut(key) {
+            // keep the comments around a replaced value
+            Some(Item::Value(existing)) => {
+                let decor = existing.decor().clone();
+                *existing = value;
+                *existing.decor_mut() = decor;
+            }
+            _ => {
+                table.insert(key, Item::Value(value));
+            }
+        }
+        return Ok(());
+    }
+    if table.get(key).is_none() {
+        let item = if inline {
+            Item::Value(toml_edit::Value::InlineTable(Default::default()))
+        } else {
+            let mut child = toml_edit::Table::new();
+            child.set_implicit(true);
+            Item::Table(child)
+        };
+        table.insert(key, item);
+    }
+    let item = table.get_mut(key).expect("inserted above");
+    let inline = matches!(item, Item::Value(_));
+    match item.as_table_like_mut() {
+        Some(child) => toml_set_in(child, inline, rest, value),
+        None => bail!("{key} is not a table"),
+    }
+}
+
+fn toml_remove(text: &str, path: &[String]) -> Result<String> {
+    let mut doc = text.parse::<toml_edit::DocumentMut>()?;
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    let (key, parents) = path.split_last().expect("key path");
+    for parent in parents {
+        match table.get_mut(parent).and_then(Item::as_table_like_mut) {
+            Some(child) => table = child,
+            None => return Ok(text.to_string()),
+        }
+    }
+    table.remove(key);
+    Ok(doc.to_string())
+}
+
+// --- YAML ---
+
+/// one mapping entry of a YAML block: `key: value` plus the more indented
+/// lines that belong to it
+struct YamlEntry {
+    key: String,
+    line: usize,
+    /// one past the entry's last significant line
+    end: usize,
+    indent: usize,
+    /// byte offset of the inline value within the key line
+    value_start: usize,
+}
+
+fn yaml_significant(line: &str) -> bool {
+    let trimmed = line.trim();
+    !(trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---")
+}
+
+fn yaml_indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// parse `key:` at the start of `content`; returns the key and the offset
+/// just past the colon
+fn yaml_key(content: &str) -> Option<(String, usize)> {
+    let (key, after) = match content.chars().next()? {
+        '"' => {
+            let mut escaped = false;
+            let end = content[1..].find(|c: char| {
+                let end = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                end
+            })? + 2;
+            (serde_json::from_str(&content[..end]).ok()?, end)
+        }
+        '\'' => {
+            let mut end = 1;
+            loop {
+                end += content[end..].find('\'')? + 1;
+                if !content[end..].starts_with('\'') {
+                    break;
+                }
+                end += 1;
+            }
+            (content[1..end - 1].replace("''", "'"), end)
+        }
+        '-' if content == "-" || content.starts_with("- ") => return None,
+        _ => {
+            let colon = content
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|i| content[i + 1..].is_empty() || content[i + 1..].starts_with(' '))?;
+            (content[..colon].trim_end().to_string(), colon)
+        }
+    };
+    let colon = after + content[after..].len() - content[after..].trim_start().len();
+    if !content[colon..].starts_with(':') {
+        return None;
+    }
+    Some((key, colon + 1))
+}
+
+/// the entries of the block mapping spanning `lines[range]`, indented by
+/// more than `parent_indent`
+fn yaml_entries(
+    lines: &[String],
+    range: Range<usize>,
+    parent_indent: Option<usize>,
+) -> Result<Vec<YamlEntry>> {
+    let mut entries: Vec<YamlEntry> = vec![];
+    let mut block_indent = None;
+    for i in range {
+        let line = &lines[i];
+        if !yaml_significant(line) {
+            continue;
+        }
+        let line_indent = yaml_indent(line);
+        if parent_indent.is_some_and(|parent| line_indent <= parent) {
+            break;
+        }
+        let indent = *block_indent.get_or_insert(line_indent);
+        let content = &line[line_indent..];
+        if line_indent > indent || (content.starts_with('-') && !entries.is_empty()) {
+            // nested lines, or a sequence at the same indentation as its key
+            if let Some(entry) = entries.last_mut() {
+                entry.end = i + 1;
+            }
+            continue;
+        }
+        if line_indent < indent {
+            bail!("unexpected indentation at line {}", i + 1);
+        }
+        let Some((key, value_start)) = yaml_key(content) else {
+            bail!("only block mappings can be edited, found line {}", i + 1);
+        };
+        entries.push(YamlEntry {
+            key,
+            line: i,
+            end: i + 1,
+            indent: line_indent,
+            value_start: line_indent + value_start,
+        });
+    }
+    Ok(entries)
+}
+
+/// the inline value on an entry's key line, without a trailing comment
+fn yaml_inline_value(line: &str, entry: &YamlEntry) -> Range<usize> {
+    let rest = &line[entry.value_start..];
+    let start = entry.value_start + rest.len() - rest.trim_start().len();
+    let value = &line[start..];
+    let quoted_end = match value.chars().next() {
+        Some(quote @ ('"' | '\'')) => value[1..].find(quote).map_or(0, |i| i + 2),
+        _ => 0,
+    };
+    let end = value[quoted_end..]
+        .find(" #")
+        .map_or(value.len(), |i| quoted_end + i);
+    start..start + value[..end].trim_end().len()
+}
+
+fn yaml_render(value: &Value) -> Result<String> {
+    Ok(match value {
+        // flow style keeps a collection on the key's line
+        Value::Array(_) | Value::Object(_) => serde_json::to_string(value)?,
+        _ => serde_yaml::to_string(value)?.trim_end().to_string(),
+    })
+}
+
+fn yaml_render_key(key: &str) -> Result<String> {
+    let plain = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || "_-./".contains(c))
+        && !key.starts_with(['-', '.']);
+    Ok(if plain {
+        key.to_string()
+    } else {
+        serde_json::to_string(key)?
+    })
+}
+
+fn yaml_lines(text: &str) -> Vec<String> {
+    text.lines().map(|line| line.to_string()).collect()
+}
+
+fn yaml_join(lines: Vec<String>) -> String {
+    let mut out = lines.join("\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// the entries along `path` that exist, outermost first
+fn yaml_descend(lines: &[String], path: &[String]) -> Result<Vec<YamlEntry>> {
+    let mut found: Vec<YamlEntry> = vec![];
+    let mut range = 0..lines.len();
+    let mut parent_indent = None;
+    for key in path {
+        let entries = yaml_entries(lines, range.clone(), parent_indent)?;
+        let Some(entry) = entries.into_iter().rfind(|e| &e.key == key) else {
+            break;
+        };
+        range = entry.line + 1..entry.end;
+        parent_indent = Some(entry.indent);
+        let inline = &lines[entry.line][yaml_inline_value(&lines[entry.line], &entry)];
+        let scalar = !matches!(inline, "" | "{}" | "~" | "null");
+        found.push(entry);
+        if found.len() < path.len() && scalar {
+            bail!(
+                "{} is not a block mapping",
+                display_key(&path[..found.len()])
+            );
+        }
+    }
+    Ok(found)
+}
+
+fn yaml_set(text: &str, path: &[String], value: &Value) -> Result<String> {
+    let mut lines = yaml_lines(text);
+    let unit = indent_unit(text).len();
+    let found = yaml_descend(&lines, path)?;
+    if found.len() == path.len() {
+        let entry = found.last().unwrap();
+        let inline = yaml_inline_value(&lines[entry.line], entry);
+        let line = &mut lines[entry.line];
+        let separator = if inline.start == entry.value_start {
+            " "
+        } else {
+            ""
+        };
+        line.replace_range(inline, &format!("{separator}{}", yaml_render(value)?));
+        // a scalar replaces a nested block
+        lines.drain(entry.line + 1..entry.end);
+        return Ok(yaml_join(lines));
+    }
+    let (insert_at, indent) = match found.last() {
+        Some(parent) => {
+            // drop an explicit empty value so the new keys nest under the key
+            let inline = yaml_inline_value(&lines[parent.line], parent);
+            lines[parent.line].replace_range(inline, "");
+            let line = lines[parent.line].trim_end().to_string();
+            lines[parent.line] = line;
+            let children = yaml_entries(&lines, parent.line + 1..parent.end, Some(parent.indent))?;
+            let indent = children
+                .first()
+                .map_or(parent.indent + unit, |child| child.indent);
+            (parent.end, indent)
+        }
+        None => {
+            let entries = yaml_entries(&lines, 0..lines.len(), None)?;
+            let end = entries.last().map_or(lines.len(), |e| e.end);
+            (end, entries.first().map_or(0, |e| e.indent))
+        }
+    };
+    let missing = &path[found.len()..];
+    let mut new_lines = vec![];
+    for (depth, key) in missing.iter().enumerate() {
+        let pad = " ".repeat(indent + depth * unit);
+        let key = yaml_render_key(key)?;
+        if depth + 1 == missing.len() {
+            new_lines.push(format!("{pad}{key}: {}", yaml_render(value)?));
+        } else {
+            new_lines.push(format!("{pad}{key}:"));
+        }
+    }
+    lines.splice(insert_at..insert_at, new_lines);
+    Ok(yaml_join(lines))
+}
+
+fn yaml_remove(text: &str, path: &[String]) -> Result<String> {
+    let mut lines = yaml_lines(text);
+    let found = yaml_descend(&lines, path)?;
+    if found.len() < path.len() {
+        return Ok(text.to_string());
+    }
+    let entry = found.last().unwrap();
+    lines.drain(entry.line..entry.end);
+    Ok(yaml_join(lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn key(path: &str) -> Vec<String> {
+        path.split('.').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_json_set_preserves_comments() {
+        let text = r#"{
+    // editor
+    "editor.fontSize": 12, // small
+    "files.trimTrailingWhitespace": true
+}
+"#;
+        let out = json_set(text, &["editor.fontSize".into()], &json!(14)).unwrap();
+        assert!(out.contains(r#""editor.fontSize": 14, // small"#));
+        let out = json_set(&out, &key("remote.ssh"), &json!({"a": 1})).unwrap();
+        assert_eq!(
+            out,
+            r#"{
+    // editor
+    "editor.fontSize": 14, // small
+    "files.trimTrailingWhitespace": true,
+    "remote": {
+        "ssh": {
+            "a": 1
+        }
+    }
+}
+"#
+        );
+        let out = json_remove(&out, &key("remote")).unwrap();
+        let out = json_remove(&out, &["editor.fontSize".into()]).unwrap();
+        assert_eq!(
+            out,
+            "{\n    // editor\n    \"files.trimTrailingWhitespace\": true\n}\n"
+        );
+        assert_eq!(
+            get(Format::Json, &out, &["files.trimTrailingWhitespace".into()]).unwrap(),
+            Some(json!(true))
+        );
+    }
+
+    #[test]
+    fn test_json_set_into_empty_and_trailing_comma() {
+        assert_eq!(
+            json_set("", &key("credsStore"), &json!("pass")).unwrap(),
+            "{\n  \"credsStore\": \"pass\"\n}\n"
+        );
+        let out = json_set("{\n  \"a\": 1,\n}\n", &key("b"), &json!(2)).unwrap();
+        assert_eq!(out, "{\n  \"a\": 1,\n  \"b\": 2,\n}\n");
+        let out = json_set(r#"{"a": 1}"#, &key("b"), &json!(2)).unwrap();
+        assert_eq!(out, r#"{"a": 1, "b": 2}"#);
+        assert!(json_set("[1]", &key("a"), &json!(1)).is_err());
+    }
+
+    #[test]
+    fn test_toml_set_and_remove() {
+        let text = "# cargo\n[build]\njobs = 4 # cores\n";
+        let out = toml_set(text, &key("build.jobs"), &json!(8)).unwrap();
+        assert_eq!(out, "# cargo\n[build]\njobs = 8 # cores\n");
+        let out = toml_set(&out, &key("net.git-fetch-with-cli"), &json!(true)).unwrap();
+        assert_eq!(
+            out,
+            "# cargo\n[build]\njobs = 8 # cores\n\n[net]\ngit-fetch-with-cli = true\n"
+        );
+        let out = toml_remove(&out, &key("net.git-fetch-with-cli")).unwrap();
+        assert_eq!(
+            get(Format::Toml, &out, &key("net")).unwrap(),
+            Some(json!({}))
+        );
+    }
+
+    #[test]
+    fn test_yaml_set_and_remove() {
+        let text = "# compose\nservices:\n  web:\n    image: nginx # pinned\nversion: 3\n";
+        let out = yaml_set(text, &key("services.web.image"), &json!("caddy")).unwrap();
+        assert_eq!(
+            out,
+            "# compose\nservices:\n  web:\n    image: caddy # pinned\nversion: 3\n"
+        );
+        let out = yaml_set(&out, &key("services.db.image"), &json!("postgres")).unwrap();
+        assert_eq!(
+            out,
+            "# compose\nservices:\n  web:\n    image: caddy # pinned\n  db:\n    image: postgres\nversion: 3\n"
+        );
+        let out = yaml_set(&out, &key("ports"), &json!([80, 443])).unwrap();
+        assert!(out.ends_with("version: 3\nports: [80,443]\n"));
+        assert_eq!(
+            get(Format::Yaml, &out, &key("ports")).unwrap(),
+            Some(json!([80, 443]))
+        );
+        let out = yaml_remove(&out, &key("services.db")).unwrap();
+        let out = yaml_remove(&out, &key("ports")).unwrap();
+        assert_eq!(
+            out,
+            "# compose\nservices:\n  web:\n    image: caddy # pinned\nversion: 3\n"
+        );
+        assert!(yaml_set("a: {b: 1}\n", &key("a.c"), &json!(1)).is_err());
+    }
+}
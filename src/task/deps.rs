@@ -484,6 +484,16 @@ impl Deps {
         }
     }
 
+    /// Whether a task still in the graph waits on `task`. Post-dependencies don't
+    /// count since they run after it rather than alongside it.
+    pub(crate) fn has_dependents(&self, task: &Task) -> bool {
+        self.node_idx(task).is_some_and(|idx| {
+            self.graph
+                .neighbors_directed(idx, Direction::Incoming)
+                .any(|dependent| self.graph[dependent].run_phase != TaskRunPhase::Post)
+        })
+    }
+
     fn node_idx(&self, task: &Task) -> Option<petgraph::graph::NodeIndex> {
         self.graph
             .node_indices()
@@ -39,7 +39,9 @@ pub(crate) fn reset() {
 }
 
 /// Type alias for tracking failed tasks with their exit codes
-pub(crate) type FailedTasks = Arc<std::sync::Mutex<Vec<(Task, Option<i32>)>>>;
+/// Failed tasks with their exit status and, when there is more to it than the exit status, the
+/// reason they failed
+pub(crate) type FailedTasks = Arc<std::sync::Mutex<Vec<(Task, Option<i32>, Option<String>)>>>;
 
 mod deps;
 pub(crate) mod task_cache;
@@ -52,13 +54,17 @@ pub(crate) mod task_executor;
 pub(crate) mod task_fetcher;
 pub(crate) mod task_file_providers;
 pub(crate) mod task_helpers;
+pub(crate) mod task_history;
+pub(crate) mod task_include_lock;
 pub(crate) mod task_list;
 mod task_load_context;
 pub(crate) mod task_output;
 pub(crate) mod task_output_handler;
+pub(crate) mod task_report;
 pub(crate) mod task_results_display;
 pub(crate) mod task_scheduler;
 mod task_script_parser;
+pub(crate) mod task_service;
 pub(crate) mod task_source_checker;
 pub(crate) mod task_sources;
 pub(crate) mod task_template;
@@ -78,6 +84,7 @@ pub(crate) use task_load_context::{
 };
 pub(crate) use task_output::TaskOutput;
 pub(crate) use task_script_parser::{has_any_args_defined, has_any_usage_spec};
+pub(crate) use task_service::{TaskReadyProbe, TaskServices};
 pub(crate) use task_template::TaskTemplate;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
@@ -429,6 +436,7 @@ pub(crate) struct TaskTomlBoolPresence {
     interactive: bool,
     quiet: bool,
     silent: bool,
+    service: bool,
 }
 
 impl TaskTomlBoolPresence {
@@ -440,6 +448,7 @@ impl TaskTomlBoolPresence {
             "interactive" => self.interactive = true,
             "quiet" => self.quiet = true,
             "silent" => self.silent = true,
+            "service" => self.service = true,
             _ => {}
         }
     }
@@ -748,6 +757,13 @@ pub(crate) struct Task {
     pub usage: String,
     #[serde(default)]
     pub timeout: Option<String>,
+    /// Long-running task (e.g. a dev server or database). Dependents start once it is
+    /// ready rather than once it exits, and it is stopped when the run finishes.
+    #[serde(default)]
+    pub service: bool,
+    /// How to tell that a service task is ready to accept work
+    #[serde(default)]
+    pub ready: Option<TaskReadyProbe>,
 
     // normal type
     #[serde(default, deserialize_with = "deserialize_arr")]
@@ -817,6 +833,15 @@ pub(crate) struct Task {
     /// Preserve ambient env vars when env inheritance is denied without hashing their values
     #[serde(default)]
     pub pass_through_env: Vec<String>,
+    /// Limit memory usage, e.g. "2G" or "512MiB" (Linux only)
+    #[serde(default)]
+    pub limit_memory: Option<String>,
+    /// Limit CPU usage to this many CPUs (Linux only)
+    #[serde(default)]
+    pub limit_cpu: Option<f64>,
+    /// Limit the number of processes and threads (Linux only)
+    #[serde(default)]
+    pub limit_pids: Option<u64>,
 
     /// Name of the task template to extend
     #[serde(default)]
@@ -1412,6 +1437,14 @@ impl Task {
         task.raw = p.parse_bool("raw").unwrap_or_default();
         task.raw_args = p.parse_bool("raw_args").unwrap_or_default();
         task.interactive = p.parse_bool("interactive").unwrap_or_default();
+        task.service = p.parse_bool("service").unwrap_or_default();
+        task.ready = p
+            .get_raw("ready")
+            .map(|v| {
+                TaskReadyProbe::deserialize(v.clone())
+                    .map_err(|e| eyre!("failed to parse ready field in task header: {e}"))
+            })
+            .transpose()?;
         task.sources = p.parse_array("sources").unwrap_or_default();
         task.watch = p
             .get_raw("watch")
@@ -2582,6 +2615,14 @@ impl Task {
         if other.timeout.is_some() {
             self.timeout = other.timeout;
         }
+        merge_bool(
+            &mut self.service,
+            other.service,
+            other.toml_bool_presence.service,
+        );
+        if other.ready.is_some() {
+            self.ready = other.ready;
+        }
         if other.confirm.is_some() {
             self.confirm = other.confirm;
         }
@@ -2602,6 +2643,15 @@ impl Task {
         self.allow_net.extend(other.allow_net);
         self.allow_env.extend(other.allow_env);
         self.pass_through_env.extend(other.pass_through_env);
+        if other.limit_memory.is_some() {
+            self.limit_memory = other.limit_memory;
+        }
+        if other.limit_cpu.is_some() {
+            self.limit_cpu = other.limit_cpu;
+        }
+        if other.limit_pids.is_some() {
+            self.limit_pids = other.limit_pids;
+        }
     }
 
     fn has_render_templates(&self) -> bool {
@@ -3155,6 +3205,8 @@ impl Default for Task {
             tools: Default::default(),
             usage: "".to_string(),
             timeout: None,
+            service: false,
+            ready: None,
             remote_file_source: None,
             deny_all: false,
             deny_read: false,
@@ -3166,6 +3218,9 @@ impl Default for Task {
             allow_net: vec![],
             allow_env: vec![],
             pass_through_env: vec![],
+            limit_memory: None,
+            limit_cpu: None,
+            limit_pids: None,
             extends: None,
             show_args_in_prefix: false,
             depends_raw: None,
@@ -5079,6 +5134,8 @@ echo "hello world"
 #MISE raw=true
 #MISE raw_args=true
 #MISE interactive=true
+#MISE service=true
+#MISE ready={port=3000}
 #MISE sources=["src1.txt", "src2.txt"]
 #MISE watch={no_vcs_ignore=true}
 #MISE outputs=["out1.txt"]
@@ -5111,6 +5168,14 @@ echo "test"
         assert_eq!(task.raw, true);
         assert_eq!(task.raw_args, true);
         assert_eq!(task.interactive, true);
+        assert_eq!(task.service, true);
+        assert_eq!(
+            task.ready,
+            Some(TaskReadyProbe {
+                port: Some(3000),
+                ..Default::default()
+            })
+        );
         assert_eq!(task.sources, vec!["src1.txt", "src2.txt"]);
         assert_eq!(
             task.watch,
@@ -6,17 +6,20 @@ use crate::env_diff::EnvDiff;
 use crate::file::{
     can_execute_directly, canonicalize_or_self, display_path, replace_path, strip_utf8_bom,
 };
-use crate::sandbox::SandboxConfig;
+use crate::sandbox::{ResourceLimits, SandboxConfig};
 use crate::task::TaskArtifactCache;
 use crate::task::task_cache::{
     CommandInput, TaskCacheContext, TaskCacheMissReason, TaskCacheRestore,
 };
 use crate::task::task_context_builder::TaskContextBuilder;
+use crate::task::task_history::{CAPTURED_OUTPUT_LINES, TaskHistory, env_hash};
 use crate::task::task_list::split_task_spec;
 use crate::task::task_output::{TaskOutput, trunc};
 use crate::task::task_output_handler::OutputHandler;
+use crate::task::task_report::{TaskReport, TaskReportEntry, TaskReportStatus};
 use crate::task::task_scheduler::SchedMsg;
 use crate::task::task_script_parser::subcommand_name_from_parse;
+use crate::task::task_service::{TaskReadyContext, TaskServices, wait_until_ready};
 use crate::task::task_source_checker::{
     remove_auto_output, save_checksum, sources_are_fresh, task_cwd, task_source_match_root,
 };
@@ -52,7 +55,49 @@ use xx::file;
 /// Global lock for interactive task exclusivity.
 /// Interactive tasks acquire a write lock (exclusive), non-interactive tasks acquire a read lock (shared).
 static TASK_RUNTIME_LOCK: LazyLock<RwLock<()>> = LazyLock::new(|| RwLock::new(()));
-type TaskOutputCapture = Arc<StdMutex<Vec<TaskCacheOutput>>>;
+type TaskOutputCapture = Arc<StdMutex<CapturedOutput>>;
+
+/// Output lines captured from a task. Lines are numbered in the order they were captured, so
+/// readers can pick up where they left off after older lines were dropped.
+#[derive(Debug, Default)]
+pub(crate) struct CapturedOutput {
+    pub(crate) lines: Vec<TaskCacheOutput>,
+    /// Number of the oldest line kept, i.e. how many lines were dropped
+    first: usize,
+}
+
+impl CapturedOutput {
+    /// Appends a line, keeping at least the newest `limit` lines. Older lines are dropped in
+    /// batches once the capture reaches twice the limit, so it stays bounded without shifting
+    /// the whole buffer on every line.
+    pub(crate) fn push(&mut self, line: TaskCacheOutput, limit: Option<usize>) {
+        self.lines.push(line);
+        if let Some(limit) = limit
+            && self.lines.len() >= limit * 2
+        {
+            let dropped = self.lines.len() - limit;
+            self.lines.drain(..dropped);
+            self.first += dropped;
+        }
+    }
+
+    /// Number the next captured line will get
+    pub(crate) fn next_line(&self) -> usize {
+        self.first + self.lines.len()
+    }
+
+    /// The lines kept from line number `from` on
+    pub(crate) fn since(&self, from: usize) -> &[TaskCacheOutput] {
+        &self.lines[from.saturating_sub(self.first).min(self.lines.len())..]
+    }
+}
+
+/// What `run_task` collects for `--report` and the task history
+#[derive(Default)]
+struct TaskRunObservation {
+    capture: Option<TaskOutputCapture>,
+    env_hash: std::sync::OnceLock<String>,
+}
 const COMMAND_INPUT_TIMEOUT: Duration = Duration::from_secs(30);
 const COMMAND_INPUT_MAX_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
 
@@ -65,6 +110,8 @@ pub(crate) struct TaskRunContext<'a> {
     pub(crate) semaphore: Arc<Semaphore>,
     pub(crate) permit: &'a mut Option<OwnedSemaphorePermit>,
     pub(crate) allow_during_interruption: bool,
+    /// Fired once a service task passes its ready probe
+    pub(crate) on_ready: Option<oneshot::Sender<()>>,
 }
 
 #[derive(Clone, Copy)]
@@ -73,6 +120,8 @@ struct TaskExecContext<'a> {
     env: &'a BTreeMap<String, String>,
     prefix: &'a str,
     output_capture: Option<&'a TaskOutputCapture>,
+    /// Most recent lines kept in `output_capture`, unbounded when unset
+    output_capture_limit: Option<usize>,
     allow_during_interruption: bool,
 }
 
@@ -286,6 +335,10 @@ pub(crate) struct TaskExecutorConfig {
     pub cache_session: Option<crate::cache::session::CacheSessionEnvironment>,
     /// CLI-level sandbox overrides (merged with task-level sandbox config)
     pub sandbox: crate::sandbox::SandboxConfig,
+    /// Collects per-task results when `--report` is requested
+    pub report: Option<TaskReport>,
+    /// Persists per-task results and output under the state dir
+    pub history: Option<TaskHistory>,
 }
 
 /// Executes tasks with proper context, environment, and output handling
@@ -310,12 +363,20 @@ pub(crate) struct TaskExecutor {
     pub task_cache_explain_json: bool,
     pub cache_session: Option<crate::cache::session::CacheSessionEnvironment>,
     pub sandbox: crate::sandbox::SandboxConfig,
+    pub report: Option<TaskReport>,
+    pub history: Option<TaskHistory>,
+    /// Stop signal shared by every `service = true` task in this run
+    pub(crate) services: TaskServices,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub(crate) struct TaskRunOutcome {
     pub did_work: bool,
     pub cache_key: Option<String>,
+    /// The task was skipped without running (up-to-date sources or `task.skip`)
+    pub skipped: bool,
+    /// The task's result was served from the artifact cache
+    pub cached: bool,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -363,6 +424,9 @@ impl TaskExecutor {
             task_cache_explain_json: config.task_cache_explain_json,
             cache_session: config.cache_session,
             sandbox: config.sandbox,
+            report: config.report,
+            history: config.history,
+            services: TaskServices::default(),
         }
     }
 
@@ -385,9 +449,9 @@ impl TaskExecutor {
         Ok(())
     }
 
-    pub(crate) fn add_failed_task(&self, task: Task, status: Option<i32>) {
+    pub(crate) fn add_failed_task(&self, task: Task, status: Option<i32>, reason: Option<String>) {
         let mut failed = self.failed_tasks.lock().unwrap();
-        failed.push((task, status.or(Some(1))));
+        failed.push((task, status.or(Some(1)), reason));
     }
 
     fn eprint(&self, task: &Task, prefix: &str, line: &str) {
@@ -420,6 +484,20 @@ impl TaskExecutor {
         let task_base = task.dir(config).await?;
         let resolve_task_path =
             |p: &PathBuf| -> PathBuf { resolve_task_sandbox_path(p, task_base.as_deref()) };
+        let task_limits = ResourceLimits {
+            memory: task
+                .limit_memory
+                .as_deref()
+                .map(crate::sandbox::parse_memory)
+                .transpose()
+                .wrap_err_with(|| format!("invalid limit_memory for task {}", task.name))?,
+            cpu: task
+                .limit_cpu
+                .map(crate::sandbox::validate_cpu)
+                .transpose()
+                .wrap_err_with(|| format!("invalid limit_cpu for task {}", task.name))?,
+            pids: task.limit_pids,
+        };
         let mut sandbox = SandboxConfig {
             deny_read: task.deny_all || task.deny_read || self.sandbox.deny_read,
             deny_write: task.deny_all || task.deny_write || self.sandbox.deny_write,
@@ -449,6 +527,8 @@ impl TaskExecutor {
                 .chain(self.sandbox.allow_env.iter())
                 .cloned()
                 .collect(),
+            deny_read_paths: vec![],
+            deny_syscalls: false,
             pass_through_env: task
                 .pass_through_env
                 .iter()
@@ -463,6 +543,7 @@ impl TaskExecutor {
                 .chain(self.sandbox.cache_env.iter())
                 .cloned()
                 .collect(),
+            limits: task_limits.min(self.sandbox.limits),
         };
         if task.rust_cache.as_ref().is_some_and(|cache| cache.enabled)
             && let Some(session) = &self.cache_session
@@ -509,6 +590,71 @@ impl TaskExecutor {
     /// Run a task, returning whether it did work and any stable artifact identity
     /// it produced or reused.
     pub(crate) async fn run_task_sched(&self, ctx: TaskRunContext<'_>) -> Result<TaskRunOutcome> {
+        if self.report.is_none() && self.history.is_none() {
+            return self.run_task(ctx, &TaskRunObservation::default()).await;
+        }
+        let task = ctx.task;
+        // Raw tasks are attached directly to the terminal, so there is no output to capture.
+        // Every other output mode reads the task's lines, and each one is still written to the
+        // terminal as it is captured.
+        let observation = TaskRunObservation {
+            capture: (!self.raw(Some(task))).then(TaskOutputCapture::default),
+            ..Default::default()
+        };
+        let start = std::time::Instant::now();
+        let result = self.run_task(ctx, &observation).await;
+        let duration = start.elapsed();
+        if result
+            .as_ref()
+            .is_err_and(crate::errors::Error::is_task_interrupted_before_start)
+        {
+            return result;
+        }
+        let status = match &result {
+            Ok(outcome) if outcome.cached => TaskReportStatus::Cached,
+            Ok(outcome) if outcome.skipped => TaskReportStatus::Skipped,
+            Ok(_) => TaskReportStatus::Passed,
+            Err(_) => TaskReportStatus::Failed,
+        };
+        let mut entry = TaskReportEntry::new(task, status, duration);
+        match (&result, status) {
+            (Ok(_), TaskReportStatus::Passed) => entry.exit_code = Some(0),
+            (Ok(_), TaskReportStatus::Skipped) => {
+                entry.message = Some("sources up-to-date".to_string())
+            }
+            (Err(err), _) => {
+                entry.exit_code = crate::errors::Error::get_exit_status(err);
+                if entry.exit_code.is_none() || crate::errors::Error::failure_reason(err).is_some()
+                {
+                    entry.message = Some(format!("{err:#}"));
+                }
+            }
+            _ => {}
+        }
+        let output = observation
+            .capture
+            .map(|capture| capture.lock().unwrap().lines.clone())
+            .unwrap_or_default();
+        if let Some(history) = &self.history {
+            let cache_key = result.as_ref().ok().and_then(|o| o.cache_key.clone());
+            history.record(
+                &entry,
+                &output,
+                observation.env_hash.into_inner(),
+                cache_key,
+            );
+        }
+        if let Some(report) = &self.report {
+            report.record(entry.with_output(&output));
+        }
+        result
+    }
+
+    async fn run_task(
+        &self,
+        ctx: TaskRunContext<'_>,
+        observation: &TaskRunObservation,
+    ) -> Result<TaskRunOutcome> {
         let TaskRunContext {
             task,
             config,
@@ -518,6 +664,7 @@ impl TaskExecutor {
             semaphore,
             permit,
             allow_during_interruption,
+            on_ready,
         } = ctx;
         let prefix = task.estyled_prefix();
         let total_start = std::time::Instant::now();
@@ -526,7 +673,10 @@ impl TaskExecutor {
             if !self.quiet(Some(task)) {
                 self.eprint(task, &prefix, "skipping task");
             }
-            return Ok(TaskRunOutcome::default());
+            return Ok(TaskRunOutcome {
+                skipped: true,
+                ..Default::default()
+            });
         }
         // If any dependency executed or restored, skip the source freshness check
         // so that downstream tasks are invalidated by upstream changes.
@@ -540,7 +690,10 @@ impl TaskExecutor {
             if !self.quiet(Some(task)) {
                 self.eprint(task, &prefix, "sources up-to-date, skipping");
             }
-            return Ok(TaskRunOutcome::default());
+            return Ok(TaskRunOutcome {
+                skipped: true,
+                ..Default::default()
+            });
         }
 
         let PreparedTaskContext {
@@ -549,6 +702,9 @@ impl TaskExecutor {
             task_env,
             extra_vars,
         } = self.prepare_task_context(config, task).await?;
+        if self.history.is_some() {
+            let _ = observation.env_hash.set(env_hash(&env));
+        }
         let task_file = self
             .parse_task_usage(config, task, &mut env, extra_vars.clone())
             .await?;
@@ -637,6 +793,8 @@ impl TaskExecutor {
                         return Ok(TaskRunOutcome {
                             did_work: false,
                             cache_key: Some(cache.key().to_string()),
+                            skipped: false,
+                            cached: true,
                         });
                     }
                     if bypass_cache {
@@ -690,6 +848,8 @@ impl TaskExecutor {
                                     return Ok(TaskRunOutcome {
                                         did_work: true,
                                         cache_key: Some(cache.key().to_string()),
+                                        skipped: false,
+                                        cached: true,
                                     });
                                 }
                                 TaskCacheRestore::Miss(reason) => reason,
@@ -707,10 +867,18 @@ impl TaskExecutor {
         } else {
             None
         };
-        let output_capture = artifact_cache
-            .as_ref()
-            .filter(|_| self.task_cache.writes())
-            .map(|_| Arc::new(StdMutex::new(Vec::new())));
+        // Share the report and history capture with the artifact cache and a `ready.log` probe so
+        // output is only collected once.
+        let watches_log = task.service && task.ready.as_ref().is_some_and(|r| r.uses_log());
+        let caches_output = artifact_cache.is_some() && self.task_cache.writes();
+        let output_capture = if caches_output || watches_log {
+            Some(observation.capture.clone().unwrap_or_default())
+        } else {
+            observation.capture.clone()
+        };
+        // The artifact cache replays all of a task's output; everything else only reads the tail,
+        // so long-running tasks like watchers and services don't grow the capture forever.
+        let output_capture_limit = (!caches_output).then_some(CAPTURED_OUTPUT_LINES);
         let action_cache_run = if task.rust_cache.as_ref().is_some_and(|cache| cache.enabled)
             && let Some(session) = self.cache_session.as_ref()
         {
@@ -725,57 +893,92 @@ impl TaskExecutor {
             env: &env,
             prefix: &prefix,
             output_capture: output_capture.as_ref(),
+            output_capture_limit,
             allow_during_interruption,
         };
 
         let timer = std::time::Instant::now();
 
-        if let Some(file) = task_file {
-            let exec_start = std::time::Instant::now();
-            Self::check_interruption(allow_during_interruption)?;
-            remove_auto_output(task, config).await?;
-            self.exec_file(config, &file, confirm_guard, exec_ctx)
-                .await?;
-            trace!(
-                "task {} exec_file took {}ms (total {}ms)",
-                task.name,
-                exec_start.elapsed().as_millis(),
-                total_start.elapsed().as_millis()
-            );
-        } else {
-            let rendered_run_scripts = task
-                .render_run_scripts_with_args(
-                    config,
-                    self.cd.clone(),
-                    &task.args,
-                    &env,
-                    extra_vars.clone(),
+        // A service keeps running after it is ready, so it must not hold a job slot for its
+        // whole lifetime; the permit is released once the ready probe passes.
+        let service = task.service && !self.dry_run;
+        let service_permit = if service { permit.take() } else { None };
+        let exec = async {
+            if let Some(file) = task_file {
+                let exec_start = std::time::Instant::now();
+                Self::check_interruption(allow_during_interruption)?;
+                remove_auto_output(task, config).await?;
+                self.exec_file(config, &file, confirm_guard, exec_ctx)
+                    .await?;
+                trace!(
+                    "task {} exec_file took {}ms (total {}ms)",
+                    task.name,
+                    exec_start.elapsed().as_millis(),
+                    total_start.elapsed().as_millis()
+                );
+            } else {
+                let rendered_run_scripts = task
+                    .render_run_scripts_with_args(
+                        config,
+                        self.cd.clone(),
+                        &task.args,
+                        &env,
+                        extra_vars.clone(),
+                    )
+                    .await?;
+
+                let exec_start = std::time::Instant::now();
+                Self::check_interruption(allow_during_interruption)?;
+                remove_auto_output(task, config).await?;
+                self.exec_task_run_entries(
+                    rendered_run_scripts,
+                    TaskRunEntriesContext {
+                        config,
+                        exec: exec_ctx,
+                        task_env: &task_env,
+                        sched_tx,
+                        existing_guard: confirm_guard,
+                        completion_state: &completion_state,
+                        semaphore,
+                        permit,
+                    },
                 )
                 .await?;
-
-            let exec_start = std::time::Instant::now();
-            Self::check_interruption(allow_during_interruption)?;
-            remove_auto_output(task, config).await?;
-            self.exec_task_run_entries(
-                rendered_run_scripts,
-                TaskRunEntriesContext {
-                    config,
-                    exec: exec_ctx,
-                    task_env: &task_env,
-                    sched_tx,
-                    existing_guard: confirm_guard,
-                    completion_state: &completion_state,
-                    semaphore,
-                    permit,
-                },
-            )
-            .await?;
-            trace!(
-                "task {} exec_task_run_entries took {}ms (total {}ms)",
-                task.name,
-                exec_start.elapsed().as_millis(),
-                total_start.elapsed().as_millis()
-            );
+                trace!(
+                    "task {} exec_task_run_entries took {}ms (total {}ms)",
+                    task.name,
+                    exec_start.elapsed().as_millis(),
+                    total_start.elapsed().as_millis()
+                );
+            }
+            Ok::<(), Report>(())
+        };
+        if service {
+            let probe = task.ready.clone().unwrap_or_default();
+            let ready_ctx = TaskReadyContext {
+                env: &env,
+                dir: Some(task_cwd(task, config).await?),
+                shell: task.shell()?.unwrap_or(self.clone_default_inline_shell()?),
+                output: output_capture.as_ref(),
+            };
+            let mut exec = std::pin::pin!(exec);
+            tokio::select! {
+                result = &mut exec => {
+                    result?;
+                    eyre::bail!("service exited before it became ready");
+                }
+                ready = wait_until_ready(&probe, ready_ctx) => ready?,
+            }
+            drop(service_permit);
+            if !self.quiet(Some(task)) {
+                self.eprint(task, &prefix, "ready");
+            }
+            if let Some(on_ready) = on_ready {
+                let _ = on_ready.send(());
+            }
+            exec.await?;
+        } else {
+            exec.await?;
         }
 
         let execution_duration = timer.elapsed();
@@ -795,7 +998,7 @@ impl TaskExecutor {
         {
             let output = output_capture
                 .as_ref()
-                .map(|output| output.lock().unwrap().clone())
+                .map(|output| output.lock().unwrap().lines.clone())
                 .unwrap_or_default();
             match cache.store(task, &output, execution_duration).await {
                 Ok(()) => {
@@ -824,6 +1027,7 @@ impl TaskExecutor {
         Ok(TaskRunOutcome {
             did_work: true,
             cache_key,
+            ..Default::default()
         })
     }
 
@@ -1495,6 +1699,7 @@ impl TaskExecutor {
             env,
             prefix,
             output_capture,
+            output_capture_limit,
             allow_during_interruption,
         } = ctx;
         #[cfg(not(windows))]
@@ -1730,16 +1935,16 @@ impl TaskExecutor {
             let stderr = output_capture.clone();
             cmd = cmd
                 .with_stdout_observer(move |line| {
-                    stdout
-                        .lock()
-                        .unwrap()
-                        .push(TaskCacheOutput::Stdout(line.to_string()));
+                    stdout.lock().unwrap().push(
+                        TaskCacheOutput::Stdout(line.to_string()),
+                        output_capture_limit,
+                    );
                 })
                 .with_stderr_observer(move |line| {
-                    stderr
-                        .lock()
-                        .unwrap()
-                        .push(TaskCacheOutput::Stderr(line.to_string()));
+                    stderr.lock().unwrap().push(
+                        TaskCacheOutput::Stderr(line.to_string()),
+                        output_capture_limit,
+                    );
                 });
         }
         let dir = task_cwd(task, &config).await?;
@@ -1777,6 +1982,12 @@ impl TaskExecutor {
         if let Some(timeout) = effective_timeout {
             cmd = cmd.with_timeout(timeout);
         }
+        if task.service {
+            cmd = cmd.with_stop_signal(
+                self.services.stop_signal(),
+                Settings::get().task_service_stop_timeout()?,
+            );
+        }
         // Apply sandbox async (DNS resolution for macOS) before spawning.
         cmd.apply_sandbox().await?;
         let result = cmd
@@ -2441,6 +2652,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn captured_output_keeps_the_newest_lines() {
+        let mut output = CapturedOutput::default();
+        for i in 0..25 {
+            output.push(TaskCacheOutput::Stdout(i.to_string()), Some(5));
+            assert!(output.lines.len() < 10);
+        }
+        assert_eq!(
+            output.lines.last(),
+            Some(&TaskCacheOutput::Stdout("24".to_string()))
+        );
+        assert!(output.lines.len() >= 5);
+
+        let mut output = CapturedOutput::default();
+        for i in 0..25 {
+            output.push(TaskCacheOutput::Stdout(i.to_string()), None);
+        }
+        assert_eq!(output.lines.len(), 25);
+    }
+
+    #[test]
+    fn captured_output_numbers_lines_across_drops() {
+        let mut output = CapturedOutput::default();
+        for i in 0..8 {
+            output.push(TaskCacheOutput::Stdout(i.to_string()), Some(5));
+        }
+        let seen = output.next_line();
+        assert_eq!(seen, 8);
+        // the next push drops the oldest lines, shifting their indices
+        for i in 8..12 {
+            output.push(TaskCacheOutput::Stdout(i.to_string()), Some(5));
+        }
+        assert_eq!(
+            output.since(seen),
+            ["8", "9", "10", "11"]
+                .map(|l| TaskCacheOutput::Stdout(l.to_string()))
+                .as_slice()
+        );
+        assert_eq!(output.since(0).len(), output.lines.len());
+        assert!(output.since(output.next_line()).is_empty());
+    }
+
     #[test]
     fn task_cache_stats_saturate_and_accumulate() {
         let mut stats = TaskCacheStats::default();
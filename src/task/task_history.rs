@@ -0,0 +1,280 @@
+use crate::config::Settings;
+use crate::task::TaskCacheOutput;
+use crate::task::task_report::{TaskReportEntry, TaskReportStatus};
+use crate::{dirs, env, file};
+use eyre::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Most recent lines kept in the history log of a single task
+pub(crate) const CAPTURED_OUTPUT_LINES: usize = 10_000;
+
+pub(crate) fn history_dir() -> PathBuf {
+    dirs::STATE.join("task-history")
+}
+
+/// One `mise run` invocation as stored under `$MISE_STATE_DIR/task-history/<id>/run.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TaskHistoryRun {
+    pub id: u64,
+    /// Unix timestamp in seconds
+    pub started_at: u64,
+    /// Wall time of the whole run in seconds, unset while the run is in progress
+    #[serde(default)]
+    pub duration: Option<f64>,
+    pub dir: PathBuf,
+    pub command: String,
+    #[serde(default)]
+    pub tasks: Vec<TaskHistoryEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TaskHistoryEntry {
+    pub name: String,
+    pub status: TaskReportStatus,
+    /// Seconds
+    pub duration: f64,
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Hash of the resolved task environment, for spotting env differences between runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_key: Option<String>,
+    /// File name of the JSON-lines output log next to `run.json`
+    pub log: String,
+}
+
+impl TaskHistoryRun {
+    pub(crate) fn failed(&self) -> usize {
+        self.tasks
+            .iter()
+            .filter(|t| t.status == TaskReportStatus::Failed)
+            .count()
+    }
+
+    pub(crate) fn read_log(&self, entry: &TaskHistoryEntry) -> Result<Vec<TaskCacheOutput>> {
+        let path = history_dir().join(self.id.to_string()).join(&entry.log);
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+        file::read_to_string(&path)?
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| Ok(serde_json::from_str(l)?))
+            .collect()
+    }
+}
+
+impl TaskHistoryEntry {
+    /// Whether this entry is an execution of `task`, with or without arguments
+    pub(crate) fn is_task(&self, task: &str) -> bool {
+        self.name == task
+            || self
+                .name
+                .strip_prefix(task)
+                .is_some_and(|rest| rest.starts_with(' '))
+    }
+}
+
+/// Records the tasks of the current `mise run` to the state dir as they finish,
+/// so the output of a run can be inspected after it has scrolled away.
+#[derive(Debug, Clone)]
+pub(crate) struct TaskHistory {
+    dir: PathBuf,
+    start: Instant,
+    run: Arc<Mutex<TaskHistoryRun>>,
+}
+
+impl TaskHistory {
+    pub(crate) fn start() -> Result<Self> {
+        let root = history_dir();
+        file::create_dir_all(&root)?;
+        prune(&root);
+        let mut id = runs_in(&root).last().map(|(id, _)| id + 1).unwrap_or(1);
+        // Another `mise run` may claim the same id concurrently; `create_dir` only
+        // succeeds for one of them.
+        let dir = loop {
+            let dir = root.join(id.to_string());
+            match std::fs::create_dir(&dir) {
+                Ok(()) => break dir,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => id += 1,
+                Err(e) => bail!("failed to create {}: {e}", file::display_path(&dir)),
+            }
+        };
+        let run = TaskHistoryRun {
+            id,
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            duration: None,
+            dir: dirs::CWD.clone().unwrap_or_default(),
+            command: env::ARGS.read().unwrap().join(" "),
+            tasks: vec![],
+        };
+        let history = Self {
+            dir,
+            start: Instant::now(),
+            run: Arc::new(Mutex::new(run)),
+        };
+        history.save()?;
+        Ok(history)
+    }
+
+    pub(crate) fn record(
+        &self,
+        entry: &TaskReportEntry,
+        output: &[TaskCacheOutput],
+        env_hash: Option<String>,
+        cache_key: Option<String>,
+    ) {
+        let log = {
+            let mut run = self.run.lock().unwrap();
+            let log = format!("{}.log", run.tasks.len() + 1);
+            run.tasks.push(TaskHistoryEntry {
+                name: entry.name.clone(),
+                status: entry.status,
+                duration: entry.duration.as_secs_f64(),
+                exit_code: entry.exit_code,
+                message: entry.message.clone(),
+                env_hash,
+                cache_key,
+                log: log.clone(),
+            });
+            log
+        };
+        if let Err(err) = write_log(&self.dir.join(&log), output) {
+            warn!("failed to write task history for {}: {err}", entry.name);
+        }
+        if let Err(err) = self.save() {
+            warn!("failed to write task history: {err}");
+        }
+    }
+
+    pub(crate) fn finish(&self) {
+        self.run.lock().unwrap().duration = Some(self.start.elapsed().as_secs_f64());
+        if let Err(err) = self.save() {
+            warn!("failed to write task history: {err}");
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(&*self.run.lock().unwrap())?;
+        file::write_atomic(self.dir.join("run.json"), json)
+    }
+}
+
+fn write_log(path: &Path, output: &[TaskCacheOutput]) -> Result<()> {
+    let skip = output.len().saturating_sub(CAPTURED_OUTPUT_LINES);
+    let mut f = std::io::BufWriter::new(file::create(path)?);
+    for line in &output[skip..] {
+        serde_json::to_writer(&mut f, line)?;
+        f.write_all(b"\n")?;
+    }
+    f.flush()?;
+    Ok(())
+}
+
+/// Hash of a resolved task environment, stable across runs with the same env
+pub(crate) fn env_hash(env: &BTreeMap<String, String>) -> String {
+    let env = env
+        .iter()
+        .map(|(k, v)| format!("{k}={v}\n"))
+        .collect::<String>();
+    crate::hash::hash_blake3_to_str(&env)[..16].to_string()
+}
+
+fn runs_in(root: &Path) -> Vec<(u64, PathBuf)> {
+    let mut runs = file::dir_subdirs(root)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|name| Some((name.parse::<u64>().ok()?, root.join(name))))
+        .collect::<Vec<_>>();
+    runs.sort_by_key(|(id, _)| *id);
+    runs
+}
+
+/// Remove runs older than `cache_prune_age`
+fn prune(root: &Path) {
+    let Some(max_age) = Settings::get().cache_prune_age_duration() else {
+        return;
+    };
+    for (id, dir) in runs_in(root) {
+        if file::modified_duration(&dir).is_ok_and(|age| age > max_age) {
+            trace!("pruning task history run {id}");
+            if let Err(err) = file::remove_all(&dir) {
+                debug!("failed to prune task history run {id}: {err}");
+            }
+        }
+    }
+}
+
+/// All recorded runs, oldest first
+pub(crate) fn runs() -> Result<Vec<TaskHistoryRun>> {
+    let mut runs = vec![];
+    for (id, dir) in runs_in(&history_dir()) {
+        let path = dir.join("run.json");
+        if !path.exists() {
+            continue;
+        }
+        match serde_json::from_str::<TaskHistoryRun>(&file::read_to_string(&path)?) {
+            Ok(run) => runs.push(run),
+            Err(err) => debug!("skipping unreadable task history run {id}: {err}"),
+        }
+    }
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_is_task() {
+        let entry = TaskHistoryEntry {
+            name: "build --release".into(),
+            status: TaskReportStatus::Passed,
+            duration: 1.0,
+            exit_code: Some(0),
+            message: None,
+            env_hash: None,
+            cache_key: None,
+            log: "1.log".into(),
+        };
+        assert!(entry.is_task("build"));
+        assert!(entry.is_task("build --release"));
+        assert!(!entry.is_task("buil"));
+        assert!(!entry.is_task("build:all"));
+    }
+
+    #[test]
+    fn test_env_hash() {
+        let a = BTreeMap::from([("A".to_string(), "1".to_string())]);
+        let b = BTreeMap::from([("A".to_string(), "2".to_string())]);
+        assert_eq!(env_hash(&a), env_hash(&a.clone()));
+        assert_ne!(env_hash(&a), env_hash(&b));
+        assert_eq!(env_hash(&a).len(), 16);
+    }
+
+    #[test]
+    fn test_write_log_keeps_tail() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("1.log");
+        let output = (0..CAPTURED_OUTPUT_LINES + 5)
+            .map(|i| TaskCacheOutput::Stdout(i.to_string()))
+            .collect::<Vec<_>>();
+        write_log(&path, &output).unwrap();
+        let lines = file::read_to_string(&path).unwrap();
+        let lines = lines.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), CAPTURED_OUTPUT_LINES);
+        assert_eq!(lines[0], r#"{"stream":"stdout","line":"5"}"#);
+    }
+}
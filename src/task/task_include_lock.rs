@@ -0,0 +1,446 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use eyre::{Result, bail, eyre};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, Settings, resolve_git_url_to_path};
+use crate::file::display_path;
+use crate::git::{Git, looks_like_sha, tag_commits};
+use crate::http::HTTP;
+use crate::lockfile::{Lockfile, lockfile_path_for_config};
+use crate::remote_source::RemoteSource;
+use crate::task::task_file_providers::TaskFileArtifact;
+use crate::{dirs, file, hash};
+
+/// A remote `task_config.includes` entry pinned in the `[task-includes]` section of `mise.lock`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TaskIncludeLock {
+    /// Tag or branch the include's `ref` resolved to (git only)
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Commit checked out for the include (git only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Digest of the included file or directory, e.g. `sha256:<hex>`
+    pub checksum: String,
+}
+
+impl Display for TaskIncludeLock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (&self.git_ref, &self.commit) {
+            (Some(git_ref), Some(commit)) => write!(f, "{git_ref} ({})", short_sha(commit)),
+            (None, Some(commit)) => write!(f, "{}", short_sha(commit)),
+            _ => {
+                let checksum = &self.checksum;
+                let hex = checksum
+                    .split_once(':')
+                    .map_or(checksum.as_str(), |(_, h)| h);
+                write!(f, "{}", &hex[..hex.len().min(12)])
+            }
+        }
+    }
+}
+
+pub(crate) fn is_remote_task_include(include: &str) -> bool {
+    include.starts_with("git::") || RemoteSource::parse_http(include).is_some()
+}
+
+/// Lockfile that pins the remote includes declared in `config_path`
+pub(crate) fn task_include_lockfile(config: &Config, config_path: &Path) -> PathBuf {
+    lockfile_path_for_config(config_path, config.monorepo_lockfile_root().as_deref()).0
+}
+
+/// Fetches a remote task include, pinned to its entry in `mise.lock` when there is one.
+///
+/// Unpinned includes are resolved and recorded in the lockfile of the config that
+/// declared them whenever that lockfile exists or lockfile creation is enabled.
+pub(crate) async fn fetch_task_include(
+    config: &Arc<Config>,
+    include: &str,
+    config_path: Option<&Path>,
+) -> Result<TaskFileArtifact> {
+    let settings = Settings::get();
+    let Some(lockfile_path) = config_path
+        .filter(|_| settings.lockfile_enabled())
+        .map(|p| task_include_lockfile(config, p))
+    else {
+        return fetch_unlocked(include).await;
+    };
+    let lockfile = Lockfile::read(&lockfile_path)?;
+    if let Some(lock) = lockfile.get_task_include(include) {
+        return fetch_locked(include, lock).await;
+    }
+    if settings.locked {
+        bail!(
+            "task include {include} is not pinned in {}\nRun `mise tasks update-includes` to pin it",
+            display_path(&lockfile_path)
+        );
+    }
+    if !lockfile_path.exists() && !config.lockfile_creation_enabled() {
+        return fetch_unlocked(include).await;
+    }
+    let (artifact, lock) = resolve_task_include(include).await?;
+    debug!(
+        "pinning task include {include} to {lock} in {}",
+        display_path(&lockfile_path)
+    );
+    update_lockfile(&lockfile_path, |lockfile| {
+        lockfile.set_task_include(include, lock)
+    })?;
+    Ok(artifact)
+}
+
+/// Resolves a remote task include ignoring any pin: semver ranges are matched against
+/// the repository's tags, other refs resolved to their current commit, and HTTP
+/// includes downloaded again.
+pub(crate) async fn resolve_task_include(
+    include: &str,
+) -> Result<(TaskFileArtifact, TaskIncludeLock)> {
+    if let Some(source) = RemoteSource::parse_git(include) {
+        let (git_ref, commit) = resolve_git_ref(&source.url, source.git_ref.as_deref())?;
+        let artifact = resolve_git_url_to_path(&with_ref(include, &commit)).await?;
+        let checksum = digest(&artifact.path)?;
+        let lock = TaskIncludeLock {
+            git_ref,
+            commit: Some(commit),
+            checksum,
+        };
+        return Ok((artifact, lock));
+    }
+    let source = RemoteSource::parse_http(include)
+        .ok_or_else(|| eyre!("unsupported remote task include: {include}"))?;
+    let artifact = download(&source.url, None, true).await?;
+    let lock = TaskIncludeLock {
+        git_ref: None,
+        commit: None,
+        checksum: digest(&artifact.path)?,
+    };
+    Ok((artifact, lock))
+}
+
+async fn fetch_locked(include: &str, lock: &TaskIncludeLock) -> Result<TaskFileArtifact> {
+    if RemoteSource::parse_git(include).is_some() {
+        let commit = lock
+            .commit
+            .as_deref()
+            .ok_or_else(|| eyre!("locked task include {include} has no commit"))?;
+        let artifact = resolve_git_url_to_path(&with_ref(include, commit)).await?;
+        verify(include, &artifact.path, &lock.checksum)?;
+        return Ok(artifact);
+    }
+    let source = RemoteSource::parse_http(include)
+        .ok_or_else(|| eyre!("unsupported remote task include: {include}"))?;
+    download(&source.url, Some(&lock.checksum), false).await
+}
+
+async fn fetch_unlocked(include: &str) -> Result<TaskFileArtifact> {
+    if let Some(source) = RemoteSource::parse_git(include) {
+        if let Some(git_ref) = &source.git_ref
+            && let Some(req) = semver_requirement(git_ref)?
+        {
+            let refs = Git::ls_remote(&source.url, &["refs/tags/*"])?;
+            let (tag, _) = latest_matching_tag(&refs, &req)
+                .ok_or_else(|| eyre!("no tag of {} matches {git_ref}", source.url))?;
+            return resolve_git_url_to_path(&with_ref(include, &tag)).await;
+        }
+        return resolve_git_url_to_path(include).await;
+    }
+    let source = RemoteSource::parse_http(include)
+        .ok_or_else(|| eyre!("unsupported remote task include: {include}"))?;
+    download(&source.url, None, false).await
+}
+
+/// Downloads an HTTP include into the cache, keeping its file name so `.toml`
+/// task files are recognized. A cached copy is reused unless `fresh` is set or it
+/// does not match `checksum`.
+async fn download(url: &str, checksum: Option<&str>, fresh: bool) -> Result<TaskFileArtifact> {
+    let file_name = url::Url::parse(url)?
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("tasks.toml")
+        .to_string();
+    let path = dirs::CACHE
+        .join("remote-http-task-includes")
+        .join(hash::hash_sha256_to_str(url))
+        .join(file_name);
+    let no_cache = Settings::get().task.remote_no_cache.unwrap_or(false);
+    let cached = !fresh
+        && !no_cache
+        && path.is_file()
+        && match checksum {
+            Some(checksum) => digest(&path)? == checksum,
+            None => true,
+        };
+    if !cached {
+        trace!("downloading task include {url}");
+        file::create_dir_all(path.parent().unwrap())?;
+        HTTP.download_file(url, &path, None).await?;
+    }
+    if let Some(checksum) = checksum {
+        verify(url, &path, checksum)?;
+    }
+    Ok(TaskFileArtifact::persistent(path))
+}
+
+fn verify(include: &str, path: &Path, expected: &str) -> Result<()> {
+    let actual = digest(path)?;
+    if actual != expected {
+        bail!(
+            "checksum mismatch for task include {include}\n  expected: {expected}\n  actual:   {actual}\nRun `mise tasks update-includes` if the change is expected"
+        );
+    }
+    Ok(())
+}
+
+/// Digest of a task file, or of every file under a task directory with its relative path
+pub(crate) fn digest(path: &Path) -> Result<String> {
+    if path.is_file() {
+        return Ok(format!("sha256:{}", hash::file_hash_sha256(path, None)?));
+    }
+    let mut manifest = String::new();
+    let entries = walkdir::WalkDir::new(path)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git");
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(path)?;
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let sha = hash::file_hash_sha256(entry.path(), None)?;
+        manifest.push_str(&format!("{sha}  {rel}\n"));
+    }
+    Ok(format!("sha256:{}", hash::hash_sha256_to_str(&manifest)))
+}
+
+pub(crate) fn update_lockfile(path: &Path, f: impl FnOnce(&mut Lockfile)) -> Result<()> {
+    let _lock = crate::lock_file::LockFile::new(path)
+        .with_callback(|l| debug!("waiting for lock on {}", display_path(l)))
+        .lock()?;
+    let mut lockfile = Lockfile::read(path)?;
+    f(&mut lockfile);
+    lockfile.write(path)
+}
+
+/// Resolves the `ref` of a git include to `(ref, commit)`
+fn resolve_git_ref(url: &str, git_ref: Option<&str>) -> Result<(Option<String>, String)> {
+    let Some(git_ref) = git_ref else {
+        let refs = Git::ls_remote(url, &["HEAD"])?;
+        let commit = ref_commit(&refs, "HEAD").ok_or_else(|| eyre!("{url} has no HEAD"))?;
+        return Ok((None, commit));
+    };
+    if looks_like_sha(git_ref) {
+        return Ok((None, git_ref.to_string()));
+    }
+    if let Some(req) = semver_requirement(git_ref)? {
+        let refs = Git::ls_remote(url, &["refs/tags/*"])?;
+        let (tag, commit) = latest_matching_tag(&refs, &req)
+            .ok_or_else(|| eyre!("no tag of {url} matches {git_ref}"))?;
+        return Ok((Some(tag), commit));
+    }
+    let refs = Git::ls_remote(url, &[git_ref])?;
+    let commit =
+        ref_commit(&refs, git_ref).ok_or_else(|| eyre!("ref {git_ref} not found in {url}"))?;
+    Ok((Some(git_ref.to_string()), commit))
+}
+
+/// Parses refs like `^1.2`, `~1.4.0`, `>=1, <2`, `1.x` or `v2.*` as a version
+/// requirement. Plain tags and branch names return None.
+fn semver_requirement(git_ref: &str) -> Result<Option<semver::VersionReq>> {
+    let is_range = git_ref.starts_with(['^', '~', '=', '>', '<'])
+        || git_ref
+            .split(['.', ',', ' '])
+            .any(|part| matches!(part, "*" | "x" | "X"));
+    if !is_range {
+        return Ok(None);
+    }
+    let req = git_ref
+        .strip_prefix('v')
+        .filter(|r| r.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(git_ref)
+        .split('.')
+        .map(|part| {
+            if part == "x" || part == "X" {
+                "*"
+            } else {
+                part
+            }
+        })
+        .join(".");
+    match semver::VersionReq::parse(&req) {
+        Ok(req) => Ok(Some(req)),
+        Err(err) => bail!("invalid version range in task include ref {git_ref}: {err}"),
+    }
+}
+
+/// Newest release tag matching `req` as `(tag, commit)`
+fn latest_matching_tag(
+    refs: &[(String, String)],
+    req: &semver::VersionReq,
+) -> Option<(String, String)> {
+    tag_commits(refs)
+        .into_iter()
+        .filter_map(|(tag, commit)| {
+            let version = semver::Version::parse(tag.strip_prefix('v').unwrap_or(&tag)).ok()?;
+            req.matches(&version).then_some((version, tag, commit))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, tag, commit)| (tag, commit))
+}
+
+fn ref_commit(refs: &[(String, String)], git_ref: &str) -> Option<String> {
+    let candidates = [
+        format!("refs/tags/{git_ref}^{{}}"),
+        format!("refs/tags/{git_ref}"),
+        format!("refs/heads/{git_ref}"),
+        git_ref.to_string(),
+    ];
+    candidates.iter().find_map(|candidate| {
+        refs.iter()
+            .find(|(_, name)| name == candidate)
+            .map(|(sha, _)| sha.clone())
+    })
+}
+
+/// Replaces (or adds) the `?ref=` of a git include
+fn with_ref(include: &str, git_ref: &str) -> String {
+    match include.split_once("?ref=") {
+        Some((base, rest)) => {
+            let params = rest.find('&').map_or("", |i| &rest[i..]);
+            format!("{base}?ref={git_ref}{params}")
+        }
+        None => format!("{include}?ref={git_ref}"),
+    }
+}
+
+fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn refs(lines: &[(&str, &str)]) -> Vec<(String, String)> {
+        lines
+            .iter()
+            .map(|(sha, name)| (sha.to_string(), name.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_semver_requirement() {
+        for range in ["^1.2", "~1.4.0", ">=1, <2", "1.x", "v2.*", "=1.0.0"] {
+            assert!(
+                semver_requirement(range).unwrap().is_some(),
+                "{range} should be a range"
+            );
+        }
+        for git_ref in ["main", "v1.2.3", "1.2.3", "release/1.x-fixes"] {
+            assert!(
+                semver_requirement(git_ref).unwrap().is_none(),
+                "{git_ref} should not be a range"
+            );
+        }
+        assert!(semver_requirement("^one").is_err());
+    }
+
+    #[test]
+    fn test_latest_matching_tag() {
+        let refs = refs(&[
+            ("a1", "refs/tags/v1.2.0"),
+            ("b1", "refs/tags/v1.3.0"),
+            ("b2", "refs/tags/v1.3.0^{}"),
+            ("c1", "refs/tags/v1.4.0-rc.1"),
+            ("d1", "refs/tags/v2.0.0"),
+            ("e1", "refs/tags/nightly"),
+            ("f1", "refs/heads/v1.9.0"),
+        ]);
+        let req = semver_requirement("^1.2").unwrap().unwrap();
+        assert_eq!(
+            latest_matching_tag(&refs, &req),
+            Some(("v1.3.0".to_string(), "b2".to_string()))
+        );
+        let req = semver_requirement("^3").unwrap().unwrap();
+        assert_eq!(latest_matching_tag(&refs, &req), None);
+    }
+
+    #[test]
+    fn test_ref_commit() {
+        let refs = refs(&[
+            ("a1", "refs/heads/main"),
+            ("b1", "refs/tags/v1.0.0"),
+            ("b2", "refs/tags/v1.0.0^{}"),
+            ("c1", "HEAD"),
+        ]);
+        assert_eq!(ref_commit(&refs, "main"), Some("a1".to_string()));
+        assert_eq!(ref_commit(&refs, "v1.0.0"), Some("b2".to_string()));
+        assert_eq!(ref_commit(&refs, "HEAD"), Some("c1".to_string()));
+        assert_eq!(ref_commit(&refs, "missing"), None);
+    }
+
+    #[test]
+    fn test_with_ref() {
+        let base = "git::https://github.com/acme/tasks.git//tasks";
+        assert_eq!(with_ref(base, "abc"), format!("{base}?ref=abc"));
+        assert_eq!(
+            with_ref(&format!("{base}?ref=^1.2"), "abc"),
+            format!("{base}?ref=abc")
+        );
+        assert_eq!(
+            with_ref(&format!("{base}?ref=^1.2&depth=1"), "abc"),
+            format!("{base}?ref=abc&depth=1")
+        );
+    }
+
+    #[test]
+    fn test_digest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        file::write(dir.join("a.toml"), "[a]\nrun = 'a'\n").unwrap();
+        file::create_dir_all(dir.join("sub")).unwrap();
+        file::write(dir.join("sub/b"), "echo b\n").unwrap();
+        file::create_dir_all(dir.join(".git")).unwrap();
+        file::write(dir.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+
+        let before = digest(dir).unwrap();
+        assert!(before.starts_with("sha256:"));
+        file::write(dir.join(".git/HEAD"), "ref: refs/heads/other\n").unwrap();
+        assert_eq!(digest(dir).unwrap(), before);
+        file::write(dir.join("sub/b"), "echo changed\n").unwrap();
+        assert_ne!(digest(dir).unwrap(), before);
+        assert_eq!(
+            digest(&dir.join("a.toml")).unwrap(),
+            format!("sha256:{}", hash::hash_sha256_to_str("[a]\nrun = 'a'\n"))
+        );
+    }
+
+    #[test]
+    fn test_lockfile_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("mise.lock");
+        let include = "git::https://github.com/acme/tasks.git//tasks?ref=^1.2";
+        let lock = TaskIncludeLock {
+            git_ref: Some("v1.3.0".into()),
+            commit: Some("0123456789abcdef0123456789abcdef01234567".into()),
+            checksum: "sha256:abc".into(),
+        };
+        update_lockfile(&path, |lockfile| {
+            lockfile.set_task_include(include, lock.clone())
+        })
+        .unwrap();
+        let content = file::read_to_string(&path).unwrap();
+        assert!(content.contains("[task-includes."), "{content}");
+        assert!(content.contains("ref = \"v1.3.0\""), "{content}");
+        let lockfile = Lockfile::read(&path).unwrap();
+        assert_eq!(lockfile.get_task_include(include), Some(&lock));
+        assert_eq!(lock.to_string(), "v1.3.0 (0123456)");
+    }
+}
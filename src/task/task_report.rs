@@ -0,0 +1,357 @@
+use crate::file;
+use crate::task::{Task, TaskCacheOutput};
+use eyre::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Number of trailing lines kept per stream for each task in a report
+const OUTPUT_TAIL_LINES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum TaskReportFormat {
+    Junit,
+    Json,
+}
+
+/// A `--report <FORMAT>:<PATH>` destination for `mise run`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TaskReportTarget {
+    pub format: TaskReportFormat,
+    pub path: PathBuf,
+}
+
+impl FromStr for TaskReportTarget {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((format, path)) = s.split_once(':') else {
+            bail!("invalid report {s:?}, expected <FORMAT>:<PATH> e.g.: junit:results.xml");
+        };
+        let Ok(format) = format.parse() else {
+            bail!("invalid report format {format:?}, expected one of: junit, json");
+        };
+        if path.is_empty() {
+            bail!("report path is empty in {s:?}");
+        }
+        Ok(Self {
+            format,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum TaskReportStatus {
+    Passed,
+    Failed,
+    Skipped,
+    Cached,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct TaskReportEntry {
+    pub name: String,
+    pub status: TaskReportStatus,
+    #[serde(serialize_with = "serialize_secs")]
+    pub duration: Duration,
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl TaskReportEntry {
+    pub(crate) fn new(task: &Task, status: TaskReportStatus, duration: Duration) -> Self {
+        let name = if task.args.is_empty() {
+            task.display_name.clone()
+        } else {
+            format!("{} {}", task.display_name, task.args.join(" "))
+        };
+        Self {
+            name,
+            status,
+            duration,
+            exit_code: None,
+            message: None,
+            stdout: String::new(),
+            stderr: String::new(),
+        }
+    }
+
+    /// Keep the last lines of captured task output for the report
+    pub(crate) fn with_output(mut self, output: &[TaskCacheOutput]) -> Self {
+        let tail = |stderr: bool| {
+            let lines = output
+                .iter()
+                .filter_map(|line| match (line, stderr) {
+                    (TaskCacheOutput::Stdout(line), false)
+                    | (TaskCacheOutput::Stderr(line), true) => {
+                        Some(console::strip_ansi_codes(line).to_string())
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let skip = lines.len().saturating_sub(OUTPUT_TAIL_LINES);
+            lines[skip..].join("\n")
+        };
+        self.stdout = tail(false);
+        self.stderr = tail(true);
+        self
+    }
+}
+
+fn serialize_secs<S: serde::Serializer>(
+    d: &Duration,
+    s: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    s.serialize_f64(d.as_secs_f64())
+}
+
+/// Collects the outcome of every executed task in a `mise run` so it can be
+/// written out as a JUnit XML or JSON report once the run finishes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TaskReport {
+    entries: Arc<Mutex<Vec<TaskReportEntry>>>,
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    tests: usize,
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    cached: usize,
+    #[serde(serialize_with = "serialize_secs")]
+    duration: Duration,
+    tasks: &'a [TaskReportEntry],
+}
+
+impl TaskReport {
+    pub(crate) fn record(&self, entry: TaskReportEntry) {
+        self.entries.lock().unwrap().push(entry);
+    }
+
+    pub(crate) fn write(&self, targets: &[TaskReportTarget], duration: Duration) -> Result<()> {
+        let entries = self.entries.lock().unwrap().clone();
+        for target in targets {
+            let contents = match target.format {
+                TaskReportFormat::Junit => render_junit(&entries, duration),
+                TaskReportFormat::Json => render_json(&entries, duration)?,
+            };
+            if let Some(parent) = target.path.parent()
+                && !parent.as_os_str().is_empty()
+            {
+                file::create_dir_all(parent)?;
+            }
+            file::write(&target.path, contents)?;
+            debug!(
+                "wrote {} task report to {}",
+                target.format,
+                file::display_path(&target.path)
+            );
+        }
+        Ok(())
+    }
+}
+
+fn count(entries: &[TaskReportEntry], status: TaskReportStatus) -> usize {
+    entries.iter().filter(|e| e.status == status).count()
+}
+
+fn render_json(entries: &[TaskReportEntry], duration: Duration) -> Result<String> {
+    let report = JsonReport {
+        tests: entries.len(),
+        passed: count(entries, TaskReportStatus::Passed),
+        failed: count(entries, TaskReportStatus::Failed),
+        skipped: count(entries, TaskReportStatus::Skipped),
+        cached: count(entries, TaskReportStatus::Cached),
+        duration,
+        tasks: entries,
+    };
+    Ok(serde_json::to_string_pretty(&report)?)
+}
+
+fn render_junit(entries: &[TaskReportEntry], duration: Duration) -> String {
+    let tests = entries.len();
+    let failures = count(entries, TaskReportStatus::Failed);
+    let skipped = count(entries, TaskReportStatus::Skipped);
+    let time = duration.as_secs_f64();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="mise run" tests="{tests}" failures="{failures}" errors="0" skipped="{skipped}" time="{time:.3}">"#
+    );
+    let _ = writeln!(
+        xml,
+        r#"  <testsuite name="mise run" tests="{tests}" failures="{failures}" errors="0" skipped="{skipped}" time="{time:.3}">"#
+    );
+    for entry in entries {
+        let _ = writeln!(
+            xml,
+            r#"    <testcase name="{}" classname="mise.tasks" time="{:.3}">"#,
+            xml_escape(&entry.name),
+            entry.duration.as_secs_f64()
+        );
+        let _ = writeln!(xml, "      <properties>");
+        let _ = writeln!(
+            xml,
+            r#"        <property name="status" value="{}"/>"#,
+            entry.status
+        );
+        if let Some(code) = entry.exit_code {
+            let _ = writeln!(
+                xml,
+                r#"        <property name="exit_code" value="{code}"/>"#
+            );
+        }
+        let _ = writeln!(xml, "      </properties>");
+        match entry.status {
+            TaskReportStatus::Failed => {
+                let message = entry
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| match entry.exit_code {
+                        Some(code) => format!("exited with status {code}"),
+                        None => "failed".to_string(),
+                    });
+                let _ = writeln!(
+                    xml,
+                    r#"      <failure message="{}" type="failure"/>"#,
+                    xml_escape(&message)
+                );
+            }
+            TaskReportStatus::Skipped => {
+                let message = entry.message.as_deref().unwrap_or("skipped");
+                let _ = writeln!(xml, r#"      <skipped message="{}"/>"#, xml_escape(message));
+            }
+            TaskReportStatus::Passed | TaskReportStatus::Cached => {}
+        }
+        if !entry.stdout.is_empty() {
+            let _ = writeln!(
+                xml,
+                "      <system-out>{}</system-out>",
+                xml_escape(&entry.stdout)
+            );
+        }
+        if !entry.stderr.is_empty() {
+            let _ = writeln!(
+                xml,
+                "      <system-err>{}</system-err>",
+                xml_escape(&entry.stderr)
+            );
+        }
+        let _ = writeln!(xml, "    </testcase>");
+    }
+    let _ = writeln!(xml, "  </testsuite>");
+    let _ = writeln!(xml, "</testsuites>");
+    xml
+}
+
+/// Escape text for XML attributes and content, dropping characters XML 1.0 can't represent
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 || c == '\u{fffe}' || c == '\u{ffff}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry(name: &str, status: TaskReportStatus, exit_code: Option<i32>) -> TaskReportEntry {
+        TaskReportEntry {
+            name: name.to_string(),
+            status,
+            duration: Duration::from_millis(1500),
+            exit_code,
+            message: None,
+            stdout: String::new(),
+            stderr: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parse_report_target() {
+        let target: TaskReportTarget = "junit:out/results.xml".parse().unwrap();
+        assert_eq!(target.format, TaskReportFormat::Junit);
+        assert_eq!(target.path, PathBuf::from("out/results.xml"));
+        let target: TaskReportTarget = "json:C:\\results.json".parse().unwrap();
+        assert_eq!(target.format, TaskReportFormat::Json);
+        assert_eq!(target.path, PathBuf::from("C:\\results.json"));
+        assert!("results.xml".parse::<TaskReportTarget>().is_err());
+        assert!("tap:results.tap".parse::<TaskReportTarget>().is_err());
+        assert!("junit:".parse::<TaskReportTarget>().is_err());
+    }
+
+    #[test]
+    fn test_output_tail_keeps_last_lines_per_stream() {
+        let output = (0..OUTPUT_TAIL_LINES + 5)
+            .map(|i| TaskCacheOutput::Stdout(format!("out {i}")))
+            .chain([TaskCacheOutput::Stderr("\x1b[31merr\x1b[0m".to_string())])
+            .collect::<Vec<_>>();
+        let entry = entry("build", TaskReportStatus::Passed, Some(0)).with_output(&output);
+        assert_eq!(entry.stdout.lines().count(), OUTPUT_TAIL_LINES);
+        assert!(entry.stdout.starts_with("out 5\n"));
+        assert_eq!(entry.stderr, "err");
+    }
+
+    #[test]
+    fn test_render_junit() {
+        let mut failed = entry("test <unit>", TaskReportStatus::Failed, Some(2));
+        failed.stderr = "boom & \x07bell".to_string();
+        let entries = vec![
+            entry("build", TaskReportStatus::Passed, Some(0)),
+            failed,
+            entry("lint", TaskReportStatus::Skipped, None),
+            entry("fmt", TaskReportStatus::Cached, None),
+        ];
+        let xml = render_junit(&entries, Duration::from_secs(3));
+        assert!(xml.contains(
+            r#"<testsuite name="mise run" tests="4" failures="1" errors="0" skipped="1" time="3.000">"#
+        ));
+        assert!(xml.contains(
+            r#"<testcase name="test &lt;unit&gt;" classname="mise.tasks" time="1.500">"#
+        ));
+        assert!(xml.contains(r#"<failure message="exited with status 2" type="failure"/>"#));
+        assert!(xml.contains("<system-err>boom &amp; bell</system-err>"));
+        assert!(xml.contains(r#"<skipped message="skipped"/>"#));
+        assert!(xml.contains(r#"<property name="status" value="cached"/>"#));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let entries = vec![
+            entry("build", TaskReportStatus::Passed, Some(0)),
+            entry("test", TaskReportStatus::Failed, Some(1)),
+        ];
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&entries, Duration::from_secs(2)).unwrap()).unwrap();
+        assert_eq!(json["tests"], 2);
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["duration"], 2.0);
+        assert_eq!(json["tasks"][1]["name"], "test");
+        assert_eq!(json["tasks"][1]["status"], "failed");
+        assert_eq!(json["tasks"][1]["exit_code"], 1);
+        assert_eq!(json["tasks"][0]["duration"], 1.5);
+    }
+}
@@ -80,8 +80,12 @@ impl TaskResultsDisplay {
 
         let count = failed.len();
         safe_eprintln!("{} {} task(s) failed:", style::ered("ERROR"), count);
-        for (task, status) in &failed {
+        for (task, status, reason) in &failed {
             let prefix = task.estyled_prefix();
+            if let Some(reason) = reason {
+                self.eprint(task, &prefix, reason);
+                continue;
+            }
             let status_str = status
                 .map(|s| s.to_string())
                 .unwrap_or_else(|| "unknown".to_string());
@@ -91,13 +95,13 @@ impl TaskResultsDisplay {
 
     /// Request a failing exit status if any tasks failed
     fn exit_if_failed(&self) -> Result<()> {
-        if let Some((task, status)) = self.failed_tasks.lock().unwrap().first() {
+        if let Some((task, status, reason)) = self.failed_tasks.lock().unwrap().first() {
             let prefix = task.estyled_prefix();
-            self.eprint(
-                task,
-                &prefix,
-                &format!("{} task failed", style::ered("ERROR")),
-            );
+            let msg = match reason {
+                Some(reason) => format!("{} task failed: {reason}", style::ered("ERROR")),
+                None => format!("{} task failed", style::ered("ERROR")),
+            };
+            self.eprint(task, &prefix, &msg);
             return Err(request_exit(status.unwrap_or(1)));
         }
         Ok(())
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use eyre::{Result, bail, ensure, eyre};
+use regex::Regex;
+use serde::Deserialize;
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+
+use crate::duration;
+use crate::errors::Error;
+use crate::task::TaskCacheOutput;
+use crate::task::task_executor::CapturedOutput;
+use crate::ui::ctrlc;
+
+const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_READY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How mise decides that a `service = true` task is ready for its dependents.
+///
+/// Exactly one of `tcp`, `port`, `http`, `log` or `run` may be set. A service
+/// without a `ready` probe is considered ready as soon as it has started.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct TaskReadyProbe {
+    /// `host:port` that accepts TCP connections once the service is ready
+    pub tcp: Option<String>,
+    /// Shorthand for `tcp = "127.0.0.1:<port>"`
+    pub port: Option<u16>,
+    /// URL that responds with a 2xx or 3xx status once the service is ready
+    pub http: Option<String>,
+    /// Regex matched against each line the service writes to stdout or stderr
+    pub log: Option<String>,
+    /// Shell command that exits successfully once the service is ready
+    pub run: Option<String>,
+    /// How long to wait for the service to become ready (default: 60s)
+    pub timeout: Option<String>,
+    /// How long to wait between probe attempts (default: 250ms)
+    pub interval: Option<String>,
+}
+
+impl TaskReadyProbe {
+    pub(crate) fn validate(&self) -> Result<()> {
+        let kinds = [
+            self.tcp.is_some(),
+            self.port.is_some(),
+            self.http.is_some(),
+            self.log.is_some(),
+            self.run.is_some(),
+        ];
+        ensure!(
+            kinds.iter().filter(|k| **k).count() <= 1,
+            "ready probe must set only one of tcp, port, http, log or run"
+        );
+        if let Some(log) = &self.log {
+            Regex::new(log).map_err(|e| eyre!("invalid ready.log regex: {e}"))?;
+        }
+        if let Some(http) = &self.http {
+            url::Url::parse(http).map_err(|e| eyre!("invalid ready.http url: {e}"))?;
+        }
+        self.timeout()?;
+        self.interval()?;
+        Ok(())
+    }
+
+    pub(crate) fn uses_log(&self) -> bool {
+        self.log.is_some()
+    }
+
+    fn timeout(&self) -> Result<Duration> {
+        match &self.timeout {
+            Some(t) => duration::parse_duration(t),
+            None => Ok(DEFAULT_READY_TIMEOUT),
+        }
+    }
+
+    fn interval(&self) -> Result<Duration> {
+        match &self.interval {
+            Some(i) => duration::parse_duration(i),
+            None => Ok(DEFAULT_READY_INTERVAL),
+        }
+    }
+
+    fn tcp_addr(&self) -> Option<String> {
+        self.tcp
+            .clone()
+            .or_else(|| self.port.map(|port| format!("127.0.0.1:{port}")))
+    }
+
+    fn describe(&self) -> String {
+        if let Some(addr) = self.tcp_addr() {
+            format!("tcp {addr}")
+        } else if let Some(http) = &self.http {
+            format!("http {http}")
+        } else if let Some(log) = &self.log {
+            format!("log /{log}/")
+        } else if let Some(run) = &self.run {
+            format!("`{run}`")
+        } else {
+            "start".to_string()
+        }
+    }
+}
+
+/// Where a `run` probe executes and which output a `log` probe watches.
+pub(crate) struct TaskReadyContext<'a> {
+    pub(crate) env: &'a BTreeMap<String, String>,
+    pub(crate) dir: Option<PathBuf>,
+    pub(crate) shell: Vec<String>,
+    pub(crate) output: Option<&'a Arc<Mutex<CapturedOutput>>>,
+}
+
+/// Polls `probe` until it succeeds, the probe times out, or the run is interrupted.
+pub(crate) async fn wait_until_ready(
+    probe: &TaskReadyProbe,
+    ctx: TaskReadyContext<'_>,
+) -> Result<()> {
+    let timeout = probe.timeout()?;
+    let interval = probe.interval()?;
+    let log = probe.log.as_deref().map(Regex::new).transpose()?;
+    let http = match &probe.http {
+        Some(_) => Some(
+            reqwest::Client::builder()
+                .no_proxy()
+                .timeout(interval.max(Duration::from_secs(1)))
+                .build()?,
+        ),
+        None => None,
+    };
+    let deadline = Instant::now() + timeout;
+    let mut scanned = 0;
+    loop {
+        if ctrlc::is_cancelled() {
+            return Err(Error::TaskInterrupted.into());
+        }
+        let ready = if let Some(addr) = probe.tcp_addr() {
+            TcpStream::connect(addr.as_str()).await.is_ok()
+        } else if let (Some(url), Some(client)) = (&probe.http, &http) {
+            match client.get(url).send().await {
+                Ok(res) => res.status().is_success() || res.status().is_redirection(),
+                Err(err) => {
+                    trace!("ready probe {url}: {err}");
+                    false
+                }
+            }
+        } else if let Some(re) = &log {
+            // only lines captured since the last poll are scanned
+            ctx.output.is_some_and(|output| {
+                let output = output.lock().unwrap();
+                let found = output.since(scanned).iter().any(|line| match line {
+                    TaskCacheOutput::Stdout(l) | TaskCacheOutput::Stderr(l) => {
+                        re.is_match(&console::strip_ansi_codes(l))
+                    }
+                });
+                scanned = output.next_line();
+                found
+            })
+        } else if let Some(run) = &probe.run {
+            run_probe(run, &ctx).await
+        } else {
+            true
+        };
+        if ready {
+            debug!("service ready: {}", probe.describe());
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            bail!(
+                "service did not become ready ({}) within {:?}",
+                probe.describe(),
+                timeout
+            );
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+async fn run_probe(run: &str, ctx: &TaskReadyContext<'_>) -> bool {
+    let Some((program, args)) = ctx.shell.split_first() else {
+        return false;
+    };
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.args(args)
+        .arg(run)
+        .envs(ctx.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    if let Some(dir) = &ctx.dir {
+        cmd.current_dir(dir);
+    }
+    match cmd.status().await {
+        Ok(status) => status.success(),
+        Err(err) => {
+            trace!("ready probe `{run}`: {err}");
+            false
+        }
+    }
+}
+
+/// Service tasks started during one `mise run`.
+///
+/// Every service subscribes to the same stop signal, which is raised once the
+/// scheduler has nothing left to run (or the run was interrupted).
+#[derive(Debug, Clone)]
+pub(crate) struct TaskServices {
+    stop: Arc<watch::Sender<bool>>,
+}
+
+impl Default for TaskServices {
+    fn default() -> Self {
+        Self {
+            stop: Arc::new(watch::Sender::new(false)),
+        }
+    }
+}
+
+impl TaskServices {
+    pub(crate) fn stop_signal(&self) -> watch::Receiver<bool> {
+        self.stop.subscribe()
+    }
+
+    pub(crate) fn stop_all(&self) {
+        if !self.stop.send_replace(true) {
+            trace!("stopping service tasks");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ready_probe_parse() {
+        let probe: TaskReadyProbe =
+            toml::from_str("port = 8080\ntimeout = \"5s\"\ninterval = \"100ms\"").unwrap();
+        assert_eq!(probe.tcp_addr(), Some("127.0.0.1:8080".to_string()));
+        assert_eq!(probe.timeout().unwrap(), Duration::from_secs(5));
+        assert_eq!(probe.interval().unwrap(), Duration::from_millis(100));
+        probe.validate().unwrap();
+
+        let probe: TaskReadyProbe = toml::from_str("").unwrap();
+        assert_eq!(probe.timeout().unwrap(), DEFAULT_READY_TIMEOUT);
+        assert_eq!(probe.describe(), "start");
+
+        assert!(toml::from_str::<TaskReadyProbe>("socket = \"/tmp/x\"").is_err());
+    }
+
+    #[test]
+    fn test_ready_probe_validate() {
+        let probe: TaskReadyProbe =
+            toml::from_str("port = 8080\nhttp = \"http://localhost:8080\"").unwrap();
+        assert!(probe.validate().is_err());
+        let probe: TaskReadyProbe = toml::from_str("log = \"listening (\"").unwrap();
+        assert!(probe.validate().is_err());
+        let probe: TaskReadyProbe = toml::from_str("timeout = \"soon\"").unwrap();
+        assert!(probe.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_log() {
+        let output = Arc::new(Mutex::new(CapturedOutput::default()));
+        output
+            .lock()
+            .unwrap()
+            .push(TaskCacheOutput::Stdout("starting".into()), None);
+        output.lock().unwrap().push(
+            TaskCacheOutput::Stderr("\x1b[32mlistening on :3000\x1b[0m".into()),
+            None,
+        );
+        let probe: TaskReadyProbe = toml::from_str("log = \"listening on\"").unwrap();
+        let env = BTreeMap::new();
+        let ctx = TaskReadyContext {
+            env: &env,
+            dir: None,
+            shell: vec![],
+            output: Some(&output),
+        };
+        wait_until_ready(&probe, ctx).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_times_out() {
+        let probe: TaskReadyProbe =
+            toml::from_str("log = \"never\"\ntimeout = \"50ms\"\ninterval = \"10ms\"").unwrap();
+        let env = BTreeMap::new();
+        let output = Arc::new(Mutex::new(CapturedOutput::default()));
+        let ctx = TaskReadyContext {
+            env: &env,
+            dir: None,
+            shell: vec![],
+            output: Some(&output),
+        };
+        let err = wait_until_ready(&probe, ctx).await.unwrap_err();
+        assert!(err.to_string().contains("did not become ready"), "{err}");
+    }
+}
@@ -88,6 +88,15 @@ pub(crate) struct TaskTemplate {
     /// Preserve ambient env vars when env inheritance is denied without hashing their values
     #[serde(default)]
     pub pass_through_env: Vec<String>,
+    /// Limit memory usage, e.g. "2G" or "512MiB" (Linux only)
+    #[serde(default)]
+    pub limit_memory: Option<String>,
+    /// Limit CPU usage to this many CPUs (Linux only)
+    #[serde(default)]
+    pub limit_cpu: Option<f64>,
+    /// Limit the number of processes and threads (Linux only)
+    #[serde(default)]
+    pub limit_pids: Option<u64>,
 }
 
 impl Task {
@@ -246,6 +255,13 @@ impl Task {
         self.allow_env.splice(0..0, template.allow_env.clone());
         self.pass_through_env
             .splice(0..0, template.pass_through_env.clone());
+
+        // limits: use template only if local not set
+        if self.limit_memory.is_none() {
+            self.limit_memory = template.limit_memory.clone();
+        }
+        self.limit_cpu = self.limit_cpu.or(template.limit_cpu);
+        self.limit_pids = self.limit_pids.or(template.limit_pids);
     }
 }
 
@@ -557,4 +573,23 @@ mod tests {
             vec!["TEMPLATE_SECRET".to_string(), "TASK_SECRET".to_string()]
         );
     }
+
+    #[test]
+    fn test_merge_template_limits() {
+        let mut task = Task {
+            limit_memory: Some("1GiB".to_string()),
+            ..Default::default()
+        };
+        let template = TaskTemplate {
+            limit_memory: Some("4GiB".to_string()),
+            limit_cpu: Some(2.0),
+            ..Default::default()
+        };
+
+        task.merge_template(&template);
+
+        assert_eq!(task.limit_memory.as_deref(), Some("1GiB"));
+        assert_eq!(task.limit_cpu, Some(2.0));
+        assert_eq!(task.limit_pids, None);
+    }
 }
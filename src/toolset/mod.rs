@@ -63,6 +63,7 @@ mod tool_version_options;
 mod toolset_env;
 mod toolset_install;
 mod toolset_paths;
+pub(crate) mod upgrade_policy;
 
 #[derive(Debug, Clone, Serialize)]
 pub(crate) struct ToolInfo {
@@ -141,7 +142,9 @@ impl Toolset {
             .collect::<Vec<_>>();
         let tvls = parallel::parallel(versions, |(config, ba, mut tvl, opts)| async move {
             if let Err(err) = tvl.resolve(&config, &opts).await {
-                if Error::is_required_channel_resolution_err(&err) {
+                if Error::is_required_channel_resolution_err(&err)
+                    || Error::is_policy_denied_err(&err)
+                {
                     return Err(err);
                 }
                 // warn_once: a command may resolve the same toolset more than
@@ -1,5 +1,9 @@
+use crate::backend::release_notes::ReleaseNote;
+use crate::file;
+use crate::install_before::resolve_before_date_for_tool;
 use crate::semver::{chunkify_version, split_version_prefix};
 use crate::toolset;
+use crate::toolset::upgrade_policy::UpgradePolicy;
 use crate::toolset::{ResolveOptions, ToolRequest, ToolSource, ToolVersion};
 use crate::{Result, backend::ABackend, config::Config};
 use serde::Serialize;
@@ -8,6 +12,7 @@ use std::{
     fmt::{Display, Formatter},
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 use tabled::Tabled;
 use versions::Version;
@@ -28,6 +33,10 @@ pub(crate) struct OutdatedInfo {
     pub bump: Option<String>,
     pub latest: String,
     pub source: ToolSource,
+    /// Release notes from `current` to `latest`, when requested with `--changelog`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[tabled(skip)]
+    pub changelog: Option<Vec<ReleaseNote>>,
 }
 
 impl OutdatedInfo {
@@ -43,10 +52,37 @@ impl OutdatedInfo {
             tool_version: tv,
             bump: None,
             latest,
+            changelog: None,
         };
         Ok(oi)
     }
 
+    /// Fetches the release notes between the current and latest version. A
+    /// failed request is only warned about so the rest of the output still shows.
+    pub(crate) async fn load_changelog(&mut self) {
+        let notes = match self.tool_version.backend() {
+            Ok(backend) => {
+                backend
+                    .release_notes(self.current.as_deref(), &self.latest)
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        self.changelog = Some(notes.unwrap_or_else(|err| {
+            warn!("failed to fetch release notes for {}: {err:#}", self.name);
+            vec![]
+        }));
+    }
+
+    /// [`Self::load_changelog`] for each tool, fetched in parallel.
+    pub(crate) async fn load_changelogs(outdated: Vec<Self>) -> Result<Vec<Self>> {
+        crate::parallel::parallel(outdated, |mut o| async move {
+            o.load_changelog().await;
+            Ok(o)
+        })
+        .await
+    }
+
     fn current_version(
         config: &Arc<Config>,
         backend: &ABackend,
@@ -134,6 +170,59 @@ impl OutdatedInfo {
             let options = oi.tool_request.options();
             oi.tool_request = ToolRequest::new_opts(backend, &oi.latest, options, source)?;
         }
+        let policy = match UpgradePolicy::from_options(&oi.tool_request.options()) {
+            Ok(policy) => policy,
+            Err(e) => {
+                warn!("{t}: ignoring upgrade policy: {e:#}");
+                UpgradePolicy::default()
+            }
+        };
+        if !policy.is_due(oi.installed_for()) {
+            trace!("skipping {}, upgrade_schedule is not due", oi.tool_version);
+            return Ok(None);
+        }
+        if policy.limits_versions() {
+            let query = match prefixed_latest_query(&prefix, &prefix_version) {
+                Some(query) if use_backend_latest => query,
+                None if use_backend_latest => "latest".to_string(),
+                _ => oi.tool_version.request.version(),
+            };
+            let base = oi
+                .current
+                .clone()
+                .unwrap_or_else(|| oi.tool_version.request.version());
+            let selection_opts = config.get_tool_opts_with_overrides(t.ba()).await?;
+            let before_date = resolve_before_date_for_tool(
+                t.ba(),
+                opts.before_date,
+                oi.tool_request.options().minimum_release_age(),
+            )?;
+            let candidates = t
+                .list_versions_matching_with_selection_options(
+                    config,
+                    &query,
+                    &selection_opts,
+                    before_date,
+                    false,
+                )
+                .await?;
+            // requests like "lts" don't match the version list, so the policy
+            // can only apply to versions it can see
+            if !candidates.is_empty() {
+                let Some(latest) = candidates
+                    .into_iter()
+                    .filter(|v| policy.allows(&base, v))
+                    .next_back()
+                else {
+                    trace!(
+                        "skipping {}, no version allowed by its upgrade policy",
+                        oi.tool_version
+                    );
+                    return Ok(None);
+                };
+                oi.latest = latest;
+            }
+        }
         if oi
             .current
             .as_ref()
@@ -198,6 +287,13 @@ impl OutdatedInfo {
         Ok(Some(oi))
     }
 
+    /// How long ago the current version was installed.
+    fn installed_for(&self) -> Option<Duration> {
+        let current = self.current.clone()?;
+        let tv = ToolVersion::new(self.tool_request.clone(), current);
+        file::modified_duration(&tv.install_path()).ok()
+    }
+
     fn display_current(current: &Option<String>) -> String {
         if let Some(current) = current {
             current.to_string()
@@ -14,6 +14,7 @@ use crate::file;
 use crate::hash::hash_to_str;
 use crate::install_before::{BeforeDateSource, resolve_before_date_for_tool_with_source};
 use crate::lockfile::{CondaPackageInfo, LockfileTool, PkgxPackageInfo, PlatformInfo};
+use crate::policy;
 use crate::runtime_symlinks::is_runtime_symlink;
 use crate::toolset::{ToolRequest, ToolSource, ToolVersionOptions, tool_request};
 use crate::{dirs, env};
@@ -107,7 +108,11 @@ impl ToolVersion {
             && !has_linked_version(request.ba())
             && let Some(lt) = request.lockfile_resolve(config)?
         {
-            return Ok(Self::from_lockfile(request.clone(), lt).with_before_date(opts.before_date));
+            let tv = Self::from_lockfile(request.clone(), lt).with_before_date(opts.before_date);
+            if !opts.skip_policy {
+                policy::enforce(&tv)?;
+            }
+            return Ok(tv);
         }
         let backend = request.ba().backend()?;
         if let Some(plugin) = backend.plugin()
@@ -133,6 +138,9 @@ impl ToolVersion {
         };
         let tv = tv.with_before_date(opts.before_date);
         trace!("resolved: {tv}");
+        if !opts.skip_policy {
+            policy::enforce(&tv)?;
+        }
         Ok(tv)
     }
 
@@ -300,6 +308,7 @@ impl ToolVersion {
             offline: base_opts.offline,
             refresh_remote_versions: base_opts.refresh_remote_versions,
             inactive: base_opts.inactive,
+            skip_policy: base_opts.skip_policy,
         };
         let tv = self.request.resolve(config, &opts).await?;
         Ok(tv.version)
@@ -858,6 +867,9 @@ pub(crate) struct ResolveOptions {
     /// (for example `ToolSource::Unknown`) when resolving tools for flows like
     /// outdated/upgrade checks.
     pub inactive: bool,
+    /// Resolve versions the tool policy denies, for commands that report on them
+    /// (`mise policy check`, `mise doctor`) rather than use them.
+    pub skip_policy: bool,
 }
 
 impl Default for ResolveOptions {
@@ -873,6 +885,7 @@ impl Default for ResolveOptions {
             offline: false,
             refresh_remote_versions: false,
             inactive: false,
+            skip_policy: false,
         }
     }
 }
@@ -986,6 +999,9 @@ impl Display for ResolveOptions {
         if self.refresh_remote_versions {
             opts.push("refresh_remote_versions".to_string());
         }
+        if self.skip_policy {
+            opts.push("skip_policy".to_string());
+        }
         write!(f, "({})", opts.join(", "))
     }
 }
@@ -14,6 +14,9 @@ pub(crate) const EPHEMERAL_OPT_KEYS: &[&str] = &[
     "install_before",
     "minimum_release_age",
     "version_order",
+    "upgrade",
+    "upgrade_schedule",
+    "ignore",
 ];
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
@@ -0,0 +1,213 @@
+//! Per-tool upgrade policies
+//!
+//! Tool options that limit what `mise outdated` and `mise upgrade` offer:
+//!
+//! ```toml
+//! [tools]
+//! node = { version = "20", upgrade = "minor", upgrade_schedule = "weekly", ignore = ["20.9.x"] }
+//! ```
+
+use std::time::Duration;
+
+use eyre::{Result, bail};
+
+use crate::duration::parse_duration;
+use crate::semver::split_version_prefix;
+use crate::toolset::ToolVersionOptions;
+
+/// The largest semver component an upgrade may change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum UpgradeLevel {
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct UpgradePolicy {
+    /// `upgrade`: stay on the current major (`minor`) or major.minor (`patch`).
+    pub level: Option<UpgradeLevel>,
+    /// `upgrade_schedule`: how long an installed version is kept before an
+    /// upgrade is offered again.
+    pub schedule: Option<Duration>,
+    /// `ignore`: versions never upgraded to, e.g. `21.x` or `20.3.1`.
+    pub ignore: Vec<String>,
+}
+
+impl UpgradePolicy {
+    pub(crate) fn from_options(opts: &ToolVersionOptions) -> Result<Self> {
+        let level = match opts.get("upgrade") {
+            Some(level) => match level.parse() {
+                Ok(level) => Some(level),
+                Err(_) => bail!("invalid upgrade option {level:?}, expected patch, minor or major"),
+            },
+            None => None,
+        };
+        let schedule = match opts.get("upgrade_schedule") {
+            Some("daily") => Some(Duration::from_secs(60 * 60 * 24)),
+            Some("weekly") => Some(Duration::from_secs(60 * 60 * 24 * 7)),
+            Some("monthly") => Some(Duration::from_secs(60 * 60 * 24 * 30)),
+            Some(schedule) => Some(parse_duration(schedule).map_err(|_| {
+                eyre::eyre!(
+                    "invalid upgrade_schedule option {schedule:?}, expected daily, weekly, monthly or a duration like 14d"
+                )
+            })?),
+            None => None,
+        };
+        let ignore = match opts.opts.get("ignore") {
+            Some(toml::Value::String(pattern)) => vec![pattern.clone()],
+            Some(toml::Value::Array(patterns)) => patterns
+                .iter()
+                .map(|p| match p.as_str() {
+                    Some(p) => Ok(p.to_string()),
+                    None => bail!("ignore must be a string or an array of strings"),
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => bail!("ignore must be a string or an array of strings"),
+            None => vec![],
+        };
+        Ok(Self {
+            level,
+            schedule,
+            ignore,
+        })
+    }
+
+    /// Whether the policy limits which versions can be upgraded to.
+    pub(crate) fn limits_versions(&self) -> bool {
+        self.level.is_some_and(|l| l != UpgradeLevel::Major) || !self.ignore.is_empty()
+    }
+
+    /// Whether `version` may be upgraded to from `base`, the installed version
+    /// or, when nothing is installed, the requested one.
+    pub(crate) fn allows(&self, base: &str, version: &str) -> bool {
+        if self.ignore.iter().any(|p| matches_pattern(p, version)) {
+            return false;
+        }
+        let same = match self.level {
+            Some(UpgradeLevel::Patch) => 2,
+            Some(UpgradeLevel::Minor) => 1,
+            Some(UpgradeLevel::Major) | None => 0,
+        };
+        let base = components(base);
+        let version = components(version);
+        base.iter()
+            .take(same)
+            .enumerate()
+            .all(|(i, c)| version.get(i) == Some(c))
+    }
+
+    /// Whether an upgrade is due for a version installed `installed_for` ago.
+    pub(crate) fn is_due(&self, installed_for: Option<Duration>) -> bool {
+        match (self.schedule, installed_for) {
+            (Some(schedule), Some(installed_for)) => installed_for >= schedule,
+            _ => true,
+        }
+    }
+}
+
+/// The numeric-ish components of a version, without a prefix like `v` or
+/// `temurin-`: "v20.1.0-rc.1" -> ["20", "1", "0", "rc", "1"].
+fn components(version: &str) -> Vec<String> {
+    split_version_prefix(version)
+        .1
+        .split(['.', '-', '+'])
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether `version` matches an `ignore` pattern. `x` and `*` components are
+/// wildcards and a pattern matches every version it is a prefix of, so `21.x`
+/// and `21` both ignore all 21 releases.
+fn matches_pattern(pattern: &str, version: &str) -> bool {
+    let is_wildcard = |c: &String| c == "x" || c == "X" || c == "*";
+    let mut pattern = components(pattern);
+    while pattern.last().is_some_and(is_wildcard) {
+        pattern.pop();
+    }
+    let version = components(version);
+    !pattern.is_empty()
+        && pattern.len() <= version.len()
+        && pattern
+            .iter()
+            .zip(&version)
+            .all(|(p, v)| is_wildcard(p) || p == v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn policy(level: Option<UpgradeLevel>, ignore: &[&str]) -> UpgradePolicy {
+        UpgradePolicy {
+            level,
+            schedule: None,
+            ignore: ignore.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_from_options() {
+        let mut opts = ToolVersionOptions::default();
+        opts.opts.insert("upgrade".into(), "minor".into());
+        opts.opts.insert("upgrade_schedule".into(), "weekly".into());
+        opts.opts.insert(
+            "ignore".into(),
+            toml::Value::Array(vec!["21.x".into(), "20.3.1".into()]),
+        );
+        assert_eq!(
+            UpgradePolicy::from_options(&opts).unwrap(),
+            UpgradePolicy {
+                level: Some(UpgradeLevel::Minor),
+                schedule: Some(Duration::from_secs(60 * 60 * 24 * 7)),
+                ignore: vec!["21.x".into(), "20.3.1".into()],
+            }
+        );
+        opts.opts.insert("upgrade_schedule".into(), "14d".into());
+        assert_eq!(
+            UpgradePolicy::from_options(&opts).unwrap().schedule,
+            Some(Duration::from_secs(60 * 60 * 24 * 14))
+        );
+        opts.opts.insert("upgrade".into(), "minr".into());
+        assert!(UpgradePolicy::from_options(&opts).is_err());
+    }
+
+    #[test]
+    fn test_allows_level() {
+        let patch = policy(Some(UpgradeLevel::Patch), &[]);
+        assert!(patch.allows("20.1.0", "20.1.5"));
+        assert!(!patch.allows("20.1.0", "20.2.0"));
+        let minor = policy(Some(UpgradeLevel::Minor), &[]);
+        assert!(minor.allows("v20.1.0", "20.9.0"));
+        assert!(!minor.allows("20.1.0", "21.0.0"));
+        assert!(minor.allows("20", "20.9.0"));
+        assert!(minor.allows("temurin-17.0.1+12", "temurin-17.0.9+9"));
+        assert!(!minor.allows("temurin-17.0.1+12", "temurin-21.0.1+12"));
+        let major = policy(Some(UpgradeLevel::Major), &[]);
+        assert!(major.allows("20.1.0", "22.0.0"));
+        assert!(!major.limits_versions());
+    }
+
+    #[test]
+    fn test_allows_ignore() {
+        let p = policy(None, &["21.x", "20.3.1", "1.*.0"]);
+        assert!(!p.allows("20.1.0", "21.4.0"));
+        assert!(!p.allows("20.1.0", "20.3.1"));
+        assert!(p.allows("20.1.0", "20.3.2"));
+        assert!(p.allows("20.1.0", "210.0.0"));
+        assert!(!p.allows("1.0.0", "1.5.0"));
+        assert!(p.allows("1.0.0", "1.5.1"));
+    }
+
+    #[test]
+    fn test_is_due() {
+        let mut p = policy(None, &[]);
+        assert!(p.is_due(Some(Duration::ZERO)));
+        p.schedule = Some(Duration::from_secs(60));
+        assert!(!p.is_due(Some(Duration::from_secs(30))));
+        assert!(p.is_due(Some(Duration::from_secs(60))));
+        assert!(p.is_due(None));
+    }
+}
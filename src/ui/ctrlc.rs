@@ -1,11 +1,14 @@
+use std::sync::LazyLock;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::cmd::CmdLineRunner;
 use console::Term;
+use tokio::sync::watch;
 
 static EXIT: AtomicBool = AtomicBool::new(true);
 static SHOW_CURSOR: AtomicBool = AtomicBool::new(false);
 static CANCELLED: AtomicBool = AtomicBool::new(false);
+static INTERRUPTED: LazyLock<watch::Sender<bool>> = LazyLock::new(|| watch::Sender::new(false));
 // static HANDLERS: OnceCell<Vec<Box<dyn Fn() + Send + Sync + 'static>>> = OnceCell::new();
 
 pub(crate) async fn exit_signal() -> i32 {
@@ -18,17 +21,27 @@ pub(crate) async fn exit_signal() -> i32 {
         // their exit handlers can distinguish cancellation from task failure.
         let should_exit = EXIT.load(Ordering::Relaxed) || CANCELLED.swap(true, Ordering::Relaxed);
         vfox::cancel_http_requests();
-        CmdLineRunner::kill_all(nix::sys::signal::SIGINT);
+        INTERRUPTED.send_replace(true);
         if should_exit {
+            CmdLineRunner::kill_all(nix::sys::signal::SIGINT);
             debug!("Ctrl-C pressed, exiting...");
             return 1;
         }
+        // service tasks are stopped by their runners (SIGTERM, then SIGKILL after
+        // the grace period) rather than interrupted with everything else
+        CmdLineRunner::kill_all_except_stoppable(nix::sys::signal::SIGINT);
     }
 }
 
 pub(crate) fn exit_on_ctrl_c(do_exit: bool) {
     EXIT.store(do_exit, Ordering::Relaxed);
     CANCELLED.store(false, Ordering::Relaxed);
+    INTERRUPTED.send_replace(false);
+}
+
+/// Becomes true when Ctrl-C is pressed
+pub(crate) fn interrupted() -> watch::Receiver<bool> {
+    INTERRUPTED.subscribe()
 }
 
 /// Returns true if ctrl-c has been received
@@ -10,5 +10,11 @@ pub(crate) fn is_cancelled() -> bool {
     false
 }
 
+pub(crate) fn interrupted() -> tokio::sync::watch::Receiver<bool> {
+    static INTERRUPTED: std::sync::LazyLock<tokio::sync::watch::Sender<bool>> =
+        std::sync::LazyLock::new(|| tokio::sync::watch::Sender::new(false));
+    INTERRUPTED.subscribe()
+}
+
 /// ensures cursor is displayed on ctrl-c
 pub(crate) fn show_cursor_after_ctrl_c() {}
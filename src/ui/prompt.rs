@@ -1,6 +1,6 @@
 use std::sync::Mutex;
 
-use demand::{Confirm, Dialog, DialogButton};
+use demand::{Confirm, DemandOption, Dialog, DialogButton, Select};
 
 use crate::env;
 use crate::ui::ctrlc;
@@ -70,3 +70,24 @@ pub(crate) fn confirm_with_all<S: Into<String>>(message: S) -> eyre::Result<bool
     };
     Ok(result)
 }
+
+/// Ask the user to pick one of `options`, given as `(value, label)` pairs.
+/// Returns `None` when not running interactively.
+pub(crate) fn select<T, S: Into<String>>(
+    message: S,
+    options: Vec<(T, &str)>,
+) -> eyre::Result<Option<T>> {
+    let _lock = MUTEX.lock().unwrap(); // Prevent multiple prompts at once
+    ctrlc::show_cursor_after_ctrl_c();
+
+    if !console::user_attended_stderr() || env::__USAGE.is_some() {
+        return Ok(None);
+    }
+    let _progress_pause = MultiProgressReport::try_get().map(|report| report.pause_progress());
+    let theme = get_theme();
+    let mut select = Select::new(message).theme(&theme);
+    for (value, label) in options {
+        select = select.option(DemandOption::with_label(label, value));
+    }
+    Ok(Some(select.run()?))
+}